@@ -0,0 +1,266 @@
+//! Measures the round-trip latency and throughput of the communication primitives that
+//! Interprocess provides, so that they can be compared on the machine at hand.
+//!
+//! ```text
+//! cargo run --release --example ipc_bench -- [--sizes 64,4096,65536] [--round-trips 10000]
+//!     [--volume 256] [--buffer-size BYTES]
+//! ```
+//!
+//! - `--sizes`: message sizes to measure with, in bytes.
+//! - `--round-trips`: the number of messages sent back and forth to measure latency.
+//! - `--volume`: the amount of data sent in one direction to measure throughput, in MiB.
+//! - `--buffer-size`: the send and receive buffer sizes to request for local sockets.
+//!
+//! Local sockets are measured both with plain and with vectored sends. On Windows, named pipes
+//! in message mode are measured as well.
+
+use {
+    interprocess::{
+        local_socket::{prelude::*, GenericNamespaced, ListenerOptions, Stream},
+        unnamed_pipe,
+    },
+    std::{
+        env,
+        io::{self, prelude::*, IoSlice},
+        process,
+        str::FromStr,
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+const MIB: u32 = 1024 * 1024;
+
+#[derive(Clone, Debug)]
+struct Config {
+    sizes: Vec<usize>,
+    round_trips: u32,
+    volume_mib: u32,
+    buffer_size: Option<usize>,
+}
+impl Config {
+    fn messages_for_volume(&self, size: usize) -> usize {
+        usize::try_from(self.volume_mib.saturating_mul(MIB))
+            .unwrap_or(usize::MAX)
+            .checked_div(size)
+            .unwrap_or(0)
+            .max(1)
+    }
+}
+
+struct Measurement {
+    latency: Duration,
+    throughput_mib: f64,
+}
+
+fn main() -> io::Result<()> {
+    let cfg = parse_args()?;
+    println!("{:<26} {:>8} {:>12} {:>14}", "primitive", "size", "round trip", "throughput");
+    for &size in &cfg.sizes {
+        report("local socket", size, bench_local_socket(&cfg, size, false)?);
+        report("local socket, vectored", size, bench_local_socket(&cfg, size, true)?);
+        report("unnamed pipe", size, bench_unnamed_pipe(&cfg, size)?);
+        #[cfg(windows)]
+        report("named pipe, messages", size, windows::bench_message_pipe(&cfg, size)?);
+    }
+    Ok(())
+}
+
+fn report(primitive: &str, size: usize, m: Measurement) {
+    let latency = format!("{:.2?}", m.latency);
+    let throughput = format!("{:.1} MiB/s", m.throughput_mib);
+    println!("{primitive:<26} {size:>8} {latency:>12} {throughput:>14}");
+}
+
+fn parse_args() -> io::Result<Config> {
+    let mut cfg = Config {
+        sizes: vec![64, 4096, 65536],
+        round_trips: 10_000,
+        volume_mib: 256,
+        buffer_size: None,
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let val = args.next().ok_or_else(|| invalid(format!("{arg} requires a value")))?;
+        match arg.as_str() {
+            "--sizes" => cfg.sizes = val.split(',').map(parse).collect::<Result<_, _>>()?,
+            "--round-trips" => cfg.round_trips = parse(&val)?,
+            "--volume" => cfg.volume_mib = parse(&val)?,
+            "--buffer-size" => cfg.buffer_size = Some(parse(&val)?),
+            _ => return Err(invalid(format!("unknown option {arg}"))),
+        }
+    }
+    if cfg.sizes.contains(&0) || cfg.round_trips == 0 {
+        return Err(invalid("sizes and the number of round trips must be nonzero".to_owned()));
+    }
+    Ok(cfg)
+}
+fn parse<T: FromStr>(s: &str) -> io::Result<T> {
+    s.trim().parse().map_err(|_| invalid(format!("invalid number: {s}")))
+}
+fn invalid(msg: String) -> io::Error { io::Error::new(io::ErrorKind::InvalidInput, msg) }
+
+fn bench_local_socket(cfg: &Config, size: usize, vectored: bool) -> io::Result<Measurement> {
+    let name = format!("ipc-bench-{}.sock", process::id()).to_ns_name::<GenericNamespaced>()?;
+    let listener = ListenerOptions::new().name(name.borrow()).create_sync()?;
+    let server = thread::spawn({
+        let cfg = cfg.clone();
+        move || {
+            let conn = listener.accept()?;
+            set_buffer_size(&conn, &cfg);
+            serve(conn, &cfg, size)
+        }
+    });
+    let conn = Stream::connect(name)?;
+    set_buffer_size(&conn, cfg);
+    let m = measure(conn, cfg, size, vectored)?;
+    server.join().unwrap_or_else(|e| std::panic::resume_unwind(e))?;
+    Ok(m)
+}
+fn set_buffer_size(conn: &Stream, cfg: &Config) {
+    let Some(size) = cfg.buffer_size else { return };
+    let rslt = conn.set_send_buffer_size(size).and_then(|()| conn.set_recv_buffer_size(size));
+    if let Err(e) = rslt {
+        eprintln!("warning: could not set buffer sizes: {e}");
+    }
+}
+
+fn bench_unnamed_pipe(cfg: &Config, size: usize) -> io::Result<Measurement> {
+    let (to_server_tx, to_server_rx) = unnamed_pipe::pipe()?;
+    let (to_client_tx, to_client_rx) = unnamed_pipe::pipe()?;
+    let server = thread::spawn({
+        let cfg = cfg.clone();
+        move || serve(Duplex(to_server_rx, to_client_tx), &cfg, size)
+    });
+    let m = measure(Duplex(to_client_rx, to_server_tx), cfg, size, false)?;
+    server.join().unwrap_or_else(|e| std::panic::resume_unwind(e))?;
+    Ok(m)
+}
+
+/// Combines two unidirectional channels into a bidirectional one.
+struct Duplex<R, W>(R, W);
+impl<R: Read, W> Read for Duplex<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> { self.0.read(buf) }
+}
+impl<R, W: Write> Write for Duplex<R, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.1.write(buf) }
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.1.write_vectored(bufs)
+    }
+    fn flush(&mut self) -> io::Result<()> { self.1.flush() }
+}
+
+/// Echoes `cfg.round_trips` messages back, then receives the throughput measurement's worth of
+/// data and acknowledges it with a single byte.
+fn serve(mut conn: impl Read + Write, cfg: &Config, size: usize) -> io::Result<()> {
+    let mut buf = vec![0; size];
+    for _ in 0..cfg.round_trips {
+        conn.read_exact(&mut buf)?;
+        conn.write_all(&buf)?;
+        conn.flush()?;
+    }
+    for _ in 0..cfg.messages_for_volume(size) {
+        conn.read_exact(&mut buf)?;
+    }
+    conn.write_all(&[1])?;
+    conn.flush()
+}
+
+fn measure(
+    mut conn: impl Read + Write,
+    cfg: &Config,
+    size: usize,
+    vectored: bool,
+) -> io::Result<Measurement> {
+    let msg = vec![0x55; size];
+    let mut buf = vec![0; size];
+
+    let start = Instant::now();
+    for _ in 0..cfg.round_trips {
+        send(&mut conn, &msg, vectored)?;
+        conn.flush()?;
+        conn.read_exact(&mut buf)?;
+    }
+    let latency = start.elapsed() / cfg.round_trips;
+
+    let messages = cfg.messages_for_volume(size);
+    let start = Instant::now();
+    for _ in 0..messages {
+        send(&mut conn, &msg, vectored)?;
+    }
+    conn.flush()?;
+    conn.read_exact(&mut [0])?;
+    let elapsed = start.elapsed().as_secs_f64();
+    let volume = u32::try_from(messages.saturating_mul(size)).unwrap_or(u32::MAX);
+    let throughput_mib = f64::from(volume) / f64::from(MIB) / elapsed;
+    Ok(Measurement { latency, throughput_mib })
+}
+
+/// Sends the message, either in one piece or in two halves in a single vectored send, the way a
+/// header and a payload that are stored separately would be sent.
+fn send(conn: &mut impl Write, msg: &[u8], vectored: bool) -> io::Result<()> {
+    if !vectored {
+        return conn.write_all(msg);
+    }
+    let (head, tail) = msg.split_at(msg.len() / 2);
+    let sent = conn.write_vectored(&[IoSlice::new(head), IoSlice::new(tail)])?;
+    conn.write_all(msg.get(sent..).unwrap_or_default())
+}
+
+#[cfg(windows)]
+mod windows {
+    use {
+        super::*,
+        interprocess::os::windows::named_pipe::{
+            pipe_mode, DuplexPipeStream, PipeListenerOptions, PipeMode,
+        },
+        recvmsg::{MsgBuf, RecvMsg},
+        std::path::Path,
+    };
+
+    pub(super) fn bench_message_pipe(cfg: &Config, size: usize) -> io::Result<Measurement> {
+        let path = format!(r"\\.\pipe\ipc-bench-{}", process::id());
+        let listener = PipeListenerOptions::new()
+            .path(Path::new(&path))
+            .mode(PipeMode::Messages)
+            .create_duplex::<pipe_mode::Messages>()?;
+        let server = thread::spawn({
+            let cfg = cfg.clone();
+            move || serve(MessageStream::new(listener.accept()?), &cfg, size)
+        });
+        let conn = DuplexPipeStream::<pipe_mode::Messages>::connect_by_path(path.as_str())?;
+        let m = measure(MessageStream::new(conn), cfg, size, false)?;
+        server.join().unwrap_or_else(|e| std::panic::resume_unwind(e))?;
+        Ok(m)
+    }
+
+    /// Sends every write as one message, and hands out received messages piece by piece, which
+    /// is all that the measurements need.
+    struct MessageStream {
+        conn: DuplexPipeStream<pipe_mode::Messages>,
+        buf: MsgBuf<'static>,
+        pos: usize,
+    }
+    impl MessageStream {
+        fn new(conn: DuplexPipeStream<pipe_mode::Messages>) -> Self {
+            Self { conn, buf: MsgBuf::from(Vec::new()), pos: 0 }
+        }
+    }
+    impl Read for MessageStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.pos >= self.buf.filled_part().len() {
+                self.conn.recv_msg(&mut self.buf, None)?;
+                self.pos = 0;
+            }
+            let avail = self.buf.filled_part().get(self.pos..).unwrap_or_default();
+            let n = avail.len().min(buf.len());
+            buf.iter_mut().zip(avail).for_each(|(dst, src)| *dst = *src);
+            self.pos = self.pos.saturating_add(n);
+            Ok(n)
+        }
+    }
+    impl Write for MessageStream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.conn.send(buf) }
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+}
@@ -2,8 +2,10 @@
 fn main() -> std::io::Result<()> {
     //}
     use {
-        interprocess::local_socket::{prelude::*, GenericNamespaced, ListenerOptions, Stream},
-        std::io::{self, prelude::*, BufReader},
+        interprocess::local_socket::{
+            prelude::*, BufStream, GenericNamespaced, ListenerOptions, Stream,
+        },
+        std::io::{self, prelude::*},
     };
 
     // Define a function that checks for errors in incoming connections. We'll use this to filter
@@ -56,9 +58,9 @@ fn main() -> std::io::Result<()> {
     let mut buffer = String::with_capacity(128);
 
     for conn in listener.incoming().filter_map(handle_error) {
-        // Wrap the connection into a buffered receiver right away
+        // Wrap the connection into a buffered stream right away
         // so that we could receive a single line from it.
-        let mut conn = BufReader::new(conn);
+        let mut conn = BufStream::new(conn);
         println!("Incoming connection!");
 
         // Since our client example sends first, the server should receive a line and only then
@@ -68,9 +70,9 @@ fn main() -> std::io::Result<()> {
         conn.read_line(&mut buffer)?;
 
         // Now that the receive has come through and the client is waiting on the server's send, do
-        // it. (`.get_mut()` is to get the sender, `BufReader` doesn't implement a pass-through
-        // `Write`.)
-        conn.get_mut().write_all(b"Hello from server!\n")?;
+        // it, flushing the send buffer so that the message doesn't linger in it.
+        conn.write_all(b"Hello from server!\n")?;
+        conn.flush()?;
 
         // Print out the result, getting the newline for free!
         print!("Client answered: {buffer}");
@@ -2,8 +2,10 @@
 fn main() -> std::io::Result<()> {
     //}
     use {
-        interprocess::local_socket::{prelude::*, GenericFilePath, GenericNamespaced, Stream},
-        std::io::{prelude::*, BufReader},
+        interprocess::local_socket::{
+            prelude::*, BufStream, GenericFilePath, GenericNamespaced, Stream,
+        },
+        std::io::prelude::*,
     };
 
     // Pick a name.
@@ -23,13 +25,13 @@ fn main() -> std::io::Result<()> {
     // refused" response, but that will take twice the ping, the roundtrip time, to reach the
     // client.
     let conn = Stream::connect(name)?;
-    // Wrap it into a buffered reader right away so that we could receive a single line out of it.
-    let mut conn = BufReader::new(conn);
+    // Wrap it into a buffered stream right away so that we could receive a single line out of it.
+    let mut conn = BufStream::new(conn);
 
-    // Send our message into the stream. This will finish either when the whole message has been
-    // sent or if a send operation returns an error. (`.get_mut()` is to get the sender,
-    // `BufReader` doesn't implement pass-through `Write`.)
-    conn.get_mut().write_all(b"Hello from client!\n")?;
+    // Send our message into the stream. The message ends up in the send buffer, so we flush it to
+    // make sure it actually reaches the server before we start waiting for a response.
+    conn.write_all(b"Hello from client!\n")?;
+    conn.flush()?;
 
     // We now employ the buffer we allocated prior and receive a single line, interpreting a
     // newline character as an end-of-file (because local sockets cannot be portably shut down),
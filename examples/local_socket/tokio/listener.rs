@@ -1,7 +1,7 @@
 //{
-#[cfg(not(feature = "tokio"))]
+#[cfg(not(feature = "local-socket-tokio"))]
 fn main() {}
-#[cfg(feature = "tokio")]
+#[cfg(feature = "local-socket-tokio")]
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     //}
@@ -1,7 +1,7 @@
 //{
-#[cfg(not(all(windows, feature = "tokio")))]
+#[cfg(not(all(windows, feature = "local-socket-tokio")))]
 fn main() {}
-#[cfg(all(windows, feature = "tokio"))]
+#[cfg(all(windows, feature = "local-socket-tokio"))]
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     //}
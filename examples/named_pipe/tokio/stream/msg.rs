@@ -1,8 +1,8 @@
 //{
 // TODO(2.3.0)..?
-#[cfg(not(all(windows, feature = "tokio")))]
+#[cfg(not(all(windows, feature = "local-socket-tokio")))]
 fn main() {}
-#[cfg(all(windows, feature = "tokio"))]
+#[cfg(all(windows, feature = "local-socket-tokio"))]
 fn main() -> std::io::Result<()> {
     //}
     //{
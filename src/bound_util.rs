@@ -1,7 +1,7 @@
 //! Trait bound utilities.
 
 use std::io::prelude::*;
-#[cfg(feature = "tokio")]
+#[cfg(feature = "local-socket-tokio")]
 use tokio::io::{AsyncRead as TokioAsyncRead, AsyncWrite as TokioAsyncWrite};
 
 pub(crate) trait Is<T: ?Sized> {}
@@ -47,7 +47,7 @@ bound_util! {
     RefWrite of Write with Write mtd as_write
 }
 
-#[cfg(feature = "tokio")]
+#[cfg(feature = "local-socket-tokio")]
 bound_util! {
     /// [Tokio's `AsyncRead`](TokioAsyncRead) by reference.
     RefTokioAsyncRead  of TokioAsyncRead  with Read  mtd as_tokio_async_read
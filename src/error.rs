@@ -193,3 +193,25 @@ impl<R: Debug, S: Debug> Error for ReuniteError<R, S> {}
 
 /// Result type of `.reunite()` on splittable stream types.
 pub type ReuniteResult<T, R, S> = Result<T, ReuniteError<R, S>>;
+
+/// Error payload of operations which were cancelled before they could complete, such as
+/// [`accept_cancellable()`](crate::local_socket::tokio::Listener::accept_cancellable).
+///
+/// It is delivered inside an [`io::Error`] of kind [`Other`](io::ErrorKind::Other), which
+/// [`Cancelled::is()`] can tell apart from errors reported by the OS.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cancelled;
+impl Cancelled {
+    /// Returns whether the given error was produced by a cancelled operation.
+    #[inline]
+    pub fn is(e: &io::Error) -> bool { e.get_ref().is_some_and(|e| e.is::<Self>()) }
+}
+impl Display for Cancelled {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { f.write_str("operation cancelled") }
+}
+impl Error for Cancelled {}
+impl From<Cancelled> for io::Error {
+    #[inline]
+    fn from(c: Cancelled) -> Self { io::Error::other(c) }
+}
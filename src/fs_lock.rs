@@ -0,0 +1,145 @@
+//! Advisory file locks.
+//!
+//! Lock files are the traditional way of making sure that only one instance of a program runs at
+//! a time, or that only one process works on a set of files at a time – for instance, a server
+//! might hold an exclusive lock on a file next to its local socket for as long as it runs.
+//!
+//! The locks are advisory: they only keep out processes which take locks themselves, not ones
+//! which simply access the file.
+//!
+//! # Example
+//! ```no_run
+//! use interprocess::fs_lock::{FileLock, LockKind};
+//!
+//! let mut lock = FileLock::open("/tmp/example.lock")?;
+//! let Some(_guard) = lock.try_lock(LockKind::Exclusive)? else {
+//!     eprintln!("another instance is already running");
+//!     return Ok(());
+//! };
+//! // ...the program proper...
+//! # std::io::Result::Ok(())
+//! ```
+
+impmod! {fs_lock, lock as lock_file, unlock as unlock_file}
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How often timed and asynchronous lock operations retry.
+const RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The kind of lock to acquire.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LockKind {
+    /// A lock which can be held by any number of owners at once, as long as nobody holds an
+    /// exclusive lock. Typically used for reading.
+    Shared,
+    /// A lock which can only be held by one owner at a time. Typically used for writing.
+    Exclusive,
+}
+impl LockKind {
+    #[inline]
+    fn is_exclusive(self) -> bool { self == Self::Exclusive }
+}
+
+/// A file which can be locked, covering the whole file.
+///
+/// Locks belong to the opened file rather than to the process, so two `FileLock`s for the same
+/// path exclude each other even within the same process. Locking takes `&mut self`, which keeps
+/// a lock from being acquired twice through the same `FileLock`.
+///
+/// # Platform-specific behavior
+/// ## Unix
+/// Open file description locks (`F_OFD_SETLK`) are used on Linux and Android, and `flock()`
+/// elsewhere. Both are shared with duplicates of the file descriptor, including ones inherited
+/// by child processes.
+///
+/// ## Windows
+/// The locks are taken with `LockFileEx()` and are mandatory rather than advisory: while one is
+/// held, other handles to the file cannot read or write the locked range, which is all of it.
+#[derive(Debug)]
+pub struct FileLock(File);
+impl FileLock {
+    /// Opens the file at the given path for reading and writing, creating it if it doesn't
+    /// exist. Its contents are left untouched.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut opts = OpenOptions::new();
+        opts.read(true).write(true).create(true).truncate(false);
+        opts.open(path).map(Self)
+    }
+    /// Borrows the file.
+    #[inline]
+    pub fn file(&self) -> &File { &self.0 }
+    /// Unwraps the file. Locks are released when it is closed.
+    #[inline]
+    pub fn into_inner(self) -> File { self.0 }
+
+    /// Acquires a lock, blocking until it's available.
+    pub fn lock(&mut self, kind: LockKind) -> io::Result<FileLockGuard<'_>> {
+        lock_file(&self.0, kind.is_exclusive(), true)?;
+        Ok(FileLockGuard(self))
+    }
+    /// Acquires a lock if it's available, returning `None` otherwise.
+    pub fn try_lock(&mut self, kind: LockKind) -> io::Result<Option<FileLockGuard<'_>>> {
+        Ok(if lock_file(&self.0, kind.is_exclusive(), false)? {
+            Some(FileLockGuard(self))
+        } else {
+            None
+        })
+    }
+    /// Acquires a lock, blocking until it's available or the timeout expires, in which case
+    /// `None` is returned.
+    ///
+    /// None of the platforms offer locks with timeouts, so this retries every 10 milliseconds.
+    pub fn lock_timeout(
+        &mut self,
+        kind: LockKind,
+        timeout: Duration,
+    ) -> io::Result<Option<FileLockGuard<'_>>> {
+        let start = Instant::now();
+        loop {
+            if lock_file(&self.0, kind.is_exclusive(), false)? {
+                return Ok(Some(FileLockGuard(self)));
+            }
+            let remaining = timeout.saturating_sub(start.elapsed());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            thread::sleep(remaining.min(RETRY_INTERVAL));
+        }
+    }
+    /// Acquires a lock, waiting asynchronously until it's available.
+    ///
+    /// This retries every 10 milliseconds, sleeping with Tokio's timer in between.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+    pub async fn lock_async(&mut self, kind: LockKind) -> io::Result<FileLockGuard<'_>> {
+        while !lock_file(&self.0, kind.is_exclusive(), false)? {
+            tokio::time::sleep(RETRY_INTERVAL).await;
+        }
+        Ok(FileLockGuard(self))
+    }
+}
+impl From<File> for FileLock {
+    #[inline]
+    fn from(file: File) -> Self { Self(file) }
+}
+
+/// Proof of holding a lock on a [`FileLock`], which releases it on drop.
+#[derive(Debug)]
+pub struct FileLockGuard<'l>(&'l mut FileLock);
+impl FileLockGuard<'_> {
+    /// Borrows the locked file.
+    #[inline]
+    pub fn file(&self) -> &File { &self.0 .0 }
+}
+impl Drop for FileLockGuard<'_> {
+    fn drop(&mut self) {
+        // Only fails if the file is not locked, which the guard rules out.
+        let _ = unlock_file(&self.0 .0);
+    }
+}
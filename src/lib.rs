@@ -18,7 +18,24 @@ mod macros;
 
 pub mod bound_util;
 pub mod error;
+#[cfg(any(unix, windows))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(any(unix, windows))))]
+pub mod fs_lock;
 pub mod local_socket;
+pub mod process;
+#[cfg(any(unix, windows))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(any(unix, windows))))]
+pub mod shared_memory;
+#[cfg(any(unix, windows))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(any(unix, windows))))]
+pub mod signal;
+pub mod single_instance;
+#[cfg(any(unix, windows))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(any(unix, windows))))]
+pub mod sync;
+#[cfg(feature = "test-util")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "test-util")))]
+pub mod testing;
 pub mod unnamed_pipe;
 
 /// Platform-specific functionality for various interprocess communication primitives.
@@ -44,7 +61,8 @@ pub use try_clone::*;
 
 mod atomic_enum;
 mod misc;
-pub(crate) use {atomic_enum::*, misc::*};
+mod nonblocking_cache;
+pub(crate) use {atomic_enum::*, misc::*, nonblocking_cache::*};
 
 #[cfg(test)]
 #[path = "../tests/index.rs"]
@@ -24,14 +24,41 @@
 #[macro_use]
 mod enumdef;
 
+pub mod audit;
+pub mod framing;
+#[cfg(feature = "handshake")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "handshake")))]
+pub mod handshake;
+
+#[macro_use]
+mod broadcaster;
+mod buf_stream;
+#[cfg(feature = "serde")]
+mod channel;
+#[cfg(feature = "compression")]
+mod compressed;
+#[cfg(any(unix, windows))]
+mod hybrid;
 mod name;
+mod peer_credentials;
+#[cfg(feature = "noise")]
+mod secure;
+mod throttled;
+mod version;
+mod writer_handle;
 mod stream {
+    #[cfg(feature = "bytes")]
+    pub(super) mod bytes;
+    pub(super) mod connecting;
     pub(super) mod r#enum;
+    pub(super) mod flush_mode;
+    pub(super) mod options;
     pub(super) mod r#trait;
 }
 mod listener {
     pub(super) mod r#enum;
     pub(super) mod options;
+    pub(super) mod stats;
     pub(super) mod r#trait;
 }
 
@@ -42,26 +69,55 @@ pub mod traits {
         stream::r#trait::*,
     };
     /// Traits for the Tokio variants of local socket objects.
-    #[cfg(feature = "tokio")]
-    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+    #[cfg(feature = "local-socket-tokio")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "local-socket-tokio")))]
     pub mod tokio {
         pub use super::super::tokio::{listener::r#trait::*, stream::r#trait::*};
     }
 }
 
 pub use {
-    listener::{options::ListenerOptions, r#enum::*, r#trait::Incoming},
+    broadcaster::Broadcaster,
+    buf_stream::BufStream,
+    listener::{
+        options::{ListenerOptions, NamePolicy},
+        r#enum::*,
+        r#trait::Incoming,
+        stats::ListenerStats,
+    },
     name::*,
-    stream::r#enum::*,
+    peer_credentials::{PeerCredentials, PeerInfo},
+    stream::{
+        connecting::{ConnectStatus, Connecting},
+        flush_mode::FlushMode,
+        options::ConnectOptions,
+        r#enum::*,
+    },
+    throttled::Throttled,
     traits::ListenerNonblockingMode,
+    version::{negotiate_version, ProtocolVersion, VersionMismatch},
+    writer_handle::WriterHandle,
 };
+pub(crate) use listener::stats::StatsCounters;
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "serde")))]
+pub use channel::*;
+#[cfg(feature = "compression")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "compression")))]
+pub use compressed::CompressedStream;
+#[cfg(feature = "noise")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "noise")))]
+pub use secure::{SecureStream, StaticKeypair};
+#[cfg(any(unix, windows))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(any(unix, windows))))]
+pub use hybrid::HybridStream;
 
 /// Re-exports of [traits] done in a way that doesn't pollute the scope, as well as of the
 /// enum-dispatch types with their names prefixed with `LocalSocket`.
 pub mod prelude {
     pub use super::{
         name::{NameType as _, ToFsName as _, ToNsName as _},
-        traits::{Listener as _, ListenerExt as _, Stream as _},
+        traits::{Listener as _, ListenerExt as _, Stream as _, StreamCommon as _},
         Listener as LocalSocketListener, Stream as LocalSocketStream,
     };
 }
@@ -75,25 +131,53 @@ pub mod prelude {
 /// Types from this module will *not* work with other async runtimes, such as `async-std` or `smol`,
 /// since the Tokio types' methods will panic whenever they're called outside of a Tokio runtime
 /// context. Open an issue if you'd like to see other runtimes supported as well.
-#[cfg(feature = "tokio")]
-#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+#[cfg(feature = "local-socket-tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "local-socket-tokio")))]
 pub mod tokio {
     pub(super) mod listener {
         pub(in super::super) mod r#enum;
+        pub(in super::super) mod incoming;
         pub(in super::super) mod r#trait;
+        pub(in super::super) mod tracking;
     }
     pub(super) mod stream {
+        #[cfg(feature = "bytes")]
+        mod bytes;
         pub(in super::super) mod r#enum;
         pub(in super::super) mod r#trait;
     }
-    pub use {listener::r#enum::*, stream::r#enum::*};
+    mod broadcaster;
+    mod cancel;
+    mod deadline;
+    mod writer_handle;
+    pub use {
+        broadcaster::Broadcaster,
+        cancel::with_cancel,
+        listener::{
+            incoming::Incoming,
+            r#enum::*,
+            tracking::{Tracked, TrackingListener},
+        },
+        stream::r#enum::*,
+        writer_handle::WriterHandle,
+    };
+    pub(crate) use deadline::Deadlines;
+
+    /// Tokio counterpart of the [sync `BufStream`](super::BufStream), provided by Tokio itself.
+    ///
+    /// As with the sync version, the send buffer must be flushed before waiting for a response
+    /// from the other side.
+    pub type BufStream<S = Stream> = ::tokio::io::BufStream<S>;
 
     /// Like the [sync local socket prelude](super::prelude), but for Tokio local sockets.
     pub mod prelude {
         pub use super::{
             super::{
                 name::{NameType as _, ToFsName as _, ToNsName as _},
-                traits::tokio::{Listener as _, Stream as _},
+                traits::{
+                    tokio::{Listener as _, Stream as _},
+                    StreamCommon as _,
+                },
             },
             Listener as LocalSocketListener, Stream as LocalSocketStream,
         };
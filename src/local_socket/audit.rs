@@ -0,0 +1,89 @@
+//! A process-wide hook that is told about every connect and accept.
+//!
+//! Applications that are subject to security monitoring often need a record of who talked to
+//! whom over IPC, and adding logging around every connect and accept in a large codebase – let
+//! alone in its dependencies – is impractical. Instead, a single hook can be
+//! [installed](set_hook) at startup, which every local socket connect performed through
+//! [`ConnectOptions`](super::ConnectOptions) and every accept of the local socket listeners of
+//! this crate reports to, sync and Tokio alike.
+//!
+//! The hook is called synchronously on the thread that performed the operation, right before
+//! its result is returned, so it should be quick – forwarding the event to a logging framework
+//! or a channel is the intended use. Accepts that fail with
+//! [`WouldBlock`](io::ErrorKind::WouldBlock) on nonblocking listeners are not reported.
+//!
+//! # Example
+//! ```no_run
+//! use interprocess::local_socket::audit::{self, Direction};
+//!
+//! audit::set_hook(|event| {
+//!     let verb = match event.direction {
+//!         Direction::Connect => "connect to",
+//!         Direction::Accept => "accept on",
+//!     };
+//!     match event.result {
+//!         Ok(()) => eprintln!("{verb} {:?} by {:?}", event.name, event.peer),
+//!         Err(e) => eprintln!("failed {verb} {:?}: {e}", event.name),
+//!     }
+//! });
+//! ```
+
+use {
+    super::{traits::StreamCommon, Name, PeerCredentials},
+    std::{
+        io,
+        sync::{Arc, PoisonError, RwLock},
+    },
+};
+
+/// Which side of a connection an [`AuditEvent`] was reported by.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// A client connected to a server.
+    Connect,
+    /// A listener accepted a client.
+    Accept,
+}
+
+/// A connect or accept, as reported to the [audit hook](set_hook).
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct AuditEvent<'a> {
+    /// Whether this is a connect or an accept.
+    pub direction: Direction,
+    /// For connects, the name that was connected to. For accepts, the name of the listener, if
+    /// it is known.
+    pub name: Option<Name<'a>>,
+    /// Credentials of the process on the other side, if the operation succeeded and the platform
+    /// reports them. See [`PeerCredentials`] for which fields are filled in where.
+    pub peer: Option<PeerCredentials>,
+    /// Whether the operation succeeded, and the error that it failed with if it didn't.
+    pub result: Result<(), &'a io::Error>,
+}
+
+type Hook = Arc<dyn Fn(&AuditEvent<'_>) + Send + Sync>;
+static HOOK: RwLock<Option<Hook>> = RwLock::new(None);
+
+/// Installs the audit hook, replacing the one installed before, if any.
+pub fn set_hook(hook: impl Fn(&AuditEvent<'_>) + Send + Sync + 'static) {
+    *HOOK.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(hook));
+}
+/// Removes the audit hook, if one is installed.
+pub fn clear_hook() { *HOOK.write().unwrap_or_else(PoisonError::into_inner) = None; }
+
+/// Reports the outcome of a connect or accept to the hook, if one is installed.
+pub(crate) fn record<S: StreamCommon>(
+    direction: Direction,
+    name: Option<Name<'_>>,
+    rslt: &io::Result<S>,
+) {
+    // The lock is not held while the hook runs, so that it may replace itself.
+    let Some(hook) = HOOK.read().unwrap_or_else(PoisonError::into_inner).clone() else {
+        return;
+    };
+    if matches!(rslt, Err(e) if e.kind() == io::ErrorKind::WouldBlock) {
+        return;
+    }
+    let peer = rslt.as_ref().ok().and_then(|s| s.peer_credentials().ok());
+    hook(&AuditEvent { direction, name, peer, result: rslt.as_ref().map(|_| ()) });
+}
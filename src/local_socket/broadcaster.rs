@@ -0,0 +1,107 @@
+use {
+    super::Stream,
+    std::{
+        io::{self, prelude::*},
+        mem,
+    },
+};
+
+/// Defines a broadcaster struct and the peer bookkeeping shared by the sync and Tokio versions.
+macro_rules! broadcaster_common {
+    ($(#[$attr:meta])* $ty:ident) => {
+        $(#[$attr])*
+        #[derive(Debug)]
+        pub struct $ty<S = Stream> {
+            peers: Vec<S>,
+        }
+        impl<S> $ty<S> {
+            /// Creates an empty broadcaster.
+            #[inline]
+            pub const fn new() -> Self { Self { peers: Vec::new() } }
+            /// Adds a peer to the broadcaster.
+            #[inline]
+            pub fn add(&mut self, peer: S) { self.peers.push(peer) }
+            /// Returns the number of peers that are currently connected.
+            #[inline]
+            pub fn len(&self) -> usize { self.peers.len() }
+            /// Returns `true` if there are no peers.
+            #[inline]
+            pub fn is_empty(&self) -> bool { self.peers.is_empty() }
+            /// Borrows the peers.
+            #[inline]
+            pub fn peers(&self) -> &[S] { &self.peers }
+            /// Mutably borrows the peers.
+            #[inline]
+            pub fn peers_mut(&mut self) -> &mut Vec<S> { &mut self.peers }
+            /// Unwraps the peers.
+            #[inline]
+            pub fn into_inner(self) -> Vec<S> { self.peers }
+        }
+        impl<S> Default for $ty<S> {
+            #[inline]
+            fn default() -> Self { Self::new() }
+        }
+        impl<S> From<Vec<S>> for $ty<S> {
+            #[inline]
+            fn from(peers: Vec<S>) -> Self { Self { peers } }
+        }
+        impl<S> Extend<S> for $ty<S> {
+            #[inline]
+            fn extend<T: IntoIterator<Item = S>>(&mut self, iter: T) { self.peers.extend(iter) }
+        }
+        impl<S> FromIterator<S> for $ty<S> {
+            #[inline]
+            fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+                Self { peers: iter.into_iter().collect() }
+            }
+        }
+    };
+}
+
+broadcaster_common! {
+    /// A collection of connected streams to which messages can be sent all at once.
+    ///
+    /// This is the building block of event notification servers, which accept connections from
+    /// subscribers and then relay every event to all of them. Each message is sent to every peer
+    /// in its entirety and the streams are flushed afterwards; peers for which sending fails are
+    /// considered dead and are removed from the broadcaster.
+    ///
+    /// Peers are sent to one after another, so a slow or unresponsive peer holds up delivery to
+    /// the ones after it. If that is a concern, the Tokio broadcaster sends to all peers
+    /// concurrently. Messages are sent as-is; if peers need to tell them apart, encode them with
+    /// a [`Framer`](super::framing::Framer) beforehand.
+    Broadcaster
+}
+
+impl<S: Write> Broadcaster<S> {
+    /// Sends `msg` to all peers, waiting until it has been sent to each of them in its entirety.
+    ///
+    /// The peers for which sending failed are removed from the broadcaster and returned along
+    /// with the errors that occurred, so that they can be logged or inspected. An empty return
+    /// value means that the message was delivered to everyone.
+    pub fn broadcast(&mut self, msg: &[u8]) -> Vec<(S, io::Error)> {
+        let results = self.peers.iter_mut().map(|peer| send(peer, msg)).collect();
+        prune(&mut self.peers, results)
+    }
+}
+
+fn send(peer: &mut impl Write, msg: &[u8]) -> io::Result<()> {
+    peer.write_all(msg)?;
+    peer.flush()
+}
+
+/// Keeps the peers for which the corresponding result is `Ok` and returns the rest along with
+/// their errors.
+pub(super) fn prune<S>(
+    peers: &mut Vec<S>,
+    results: Vec<io::Result<()>>,
+) -> Vec<(S, io::Error)> {
+    let mut failed = Vec::new();
+    for (peer, result) in mem::take(peers).into_iter().zip(results) {
+        match result {
+            Ok(()) => peers.push(peer),
+            Err(e) => failed.push((peer, e)),
+        }
+    }
+    failed
+}
@@ -0,0 +1,115 @@
+use {
+    super::Stream,
+    std::{
+        fmt::{self, Debug, Formatter},
+        io::{self, prelude::*, BufReader, BufWriter, IoSlice, IoSliceMut},
+    },
+};
+
+/// A stream with both a receive buffer and a send buffer.
+///
+/// Wrapping a stream in [`BufReader`] and sending through `.get_mut()` or a reference to the
+/// stream bypasses buffering on the send side, while wrapping it in both [`BufReader`] and
+/// [`BufWriter`] is not possible without splitting. `BufStream` provides [`BufRead`] and buffered
+/// [`Write`] on the same object.
+///
+/// Data that has been sent is held in the send buffer until it fills up or until
+/// [`.flush()`](Write::flush) is called, which means that **the send buffer must be flushed
+/// before waiting for a response from the other side**, or the two sides will deadlock. The send
+/// buffer is also flushed when the `BufStream` is dropped, with errors being ignored.
+///
+/// While this type is geared towards local sockets, it can be used with any type that implements
+/// both [`Read`] and [`Write`], such as `&Stream`.
+pub struct BufStream<S: Read + Write = Stream>(BufReader<Inner<S>>);
+
+/// The default capacity of the buffers, same as that of [`BufReader`] and [`BufWriter`].
+const DEFAULT_CAPACITY: usize = 8192;
+
+impl<S: Read + Write> BufStream<S> {
+    /// Wraps the given stream, allocating buffers of the default capacity (currently 8 KiB each).
+    #[inline]
+    pub fn new(inner: S) -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY, DEFAULT_CAPACITY, inner)
+    }
+    /// Wraps the given stream, allocating buffers of the specified capacities.
+    pub fn with_capacity(recv_capacity: usize, send_capacity: usize, inner: S) -> Self {
+        let writer = Inner(BufWriter::with_capacity(send_capacity, inner));
+        Self(BufReader::with_capacity(recv_capacity, writer))
+    }
+
+    /// Borrows the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &S { self.0.get_ref().0.get_ref() }
+    /// Mutably borrows the underlying stream.
+    ///
+    /// Receiving from or sending to the stream directly will bypass the buffers. In particular,
+    /// data sent this way may arrive before that which is still held in the send buffer.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut S { self.0.get_mut().0.get_mut() }
+
+    /// Returns the data currently held in the receive buffer.
+    #[inline]
+    pub fn recv_buffer(&self) -> &[u8] { self.0.buffer() }
+    /// Returns the data currently held in the send buffer.
+    #[inline]
+    pub fn send_buffer(&self) -> &[u8] { self.0.get_ref().0.buffer() }
+
+    /// Flushes the send buffer and unwraps the underlying stream.
+    ///
+    /// Any data left in the receive buffer is lost. If the flush fails, the stream is dropped and
+    /// the error is returned.
+    pub fn into_inner(self) -> io::Result<S> {
+        self.0.into_inner().0.into_inner().map_err(io::IntoInnerError::into_error)
+    }
+}
+
+impl<S: Read + Write> Read for BufStream<S> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> { self.0.read(buf) }
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+}
+impl<S: Read + Write> BufRead for BufStream<S> {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> { self.0.fill_buf() }
+    #[inline]
+    fn consume(&mut self, amt: usize) { self.0.consume(amt) }
+}
+impl<S: Read + Write> Write for BufStream<S> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.0.get_mut().0.write(buf) }
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.0.get_mut().0.write_vectored(bufs)
+    }
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> { self.0.get_mut().0.flush() }
+}
+
+impl<S: Read + Write> From<S> for BufStream<S> {
+    #[inline]
+    fn from(inner: S) -> Self { Self::new(inner) }
+}
+
+impl<S: Read + Write + Debug> Debug for BufStream<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufStream")
+            .field("inner", self.get_ref())
+            .field("recv_buffer_len", &self.recv_buffer().len())
+            .field("send_buffer_len", &self.send_buffer().len())
+            .finish()
+    }
+}
+
+/// `BufWriter` with pass-through `Read`, so that it can be wrapped in a `BufReader`.
+struct Inner<S: Write>(BufWriter<S>);
+impl<S: Read + Write> Read for Inner<S> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> { self.0.get_mut().read(buf) }
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.0.get_mut().read_vectored(bufs)
+    }
+}
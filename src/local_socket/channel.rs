@@ -0,0 +1,175 @@
+use {
+    super::{
+        framing::{Framed, LengthPrefixed},
+        prelude::*,
+        Listener, ListenerOptions, Name, Stream,
+    },
+    serde::{de::DeserializeOwned, Serialize},
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+        iter::FusedIterator,
+        marker::PhantomData,
+    },
+};
+
+/// Creates a listener for a typed channel of messages of type `T`, along with a [`Connector`]
+/// which connects to it.
+///
+/// This is a shorthand for binding a [`Listener`] and wrapping the streams on both ends in
+/// [`TypedStream`]s, which serialize messages with [`bincode`](https://docs.rs/bincode) and frame
+/// them with [`LengthPrefixed`]. Other processes can connect by creating a `Connector` for the
+/// same name with [`Connector::new()`].
+///
+/// # Example
+/// ```no_run
+/// use interprocess::local_socket::{channel, prelude::*, GenericNamespaced};
+///
+/// let name = "example-channel.sock".to_ns_name::<GenericNamespaced>()?;
+/// let (listener, connector) = channel::<(u32, String)>(name)?;
+///
+/// std::thread::spawn(move || {
+///     connector.connect()?.send(&(1, "Hello".to_owned()))
+/// });
+///
+/// let mut conn = listener.accept()?;
+/// assert_eq!(conn.recv()?, Some((1, "Hello".to_owned())));
+/// # std::io::Result::Ok(())
+/// ```
+pub fn channel<T: Serialize + DeserializeOwned>(
+    name: Name<'_>,
+) -> io::Result<(TypedListener<T>, Connector<T>)> {
+    let listener = ListenerOptions::new().name(name.borrow()).create_sync()?;
+    Ok((TypedListener::from(listener), Connector::new(name.into_owned())))
+}
+
+/// A local socket listener which produces [`TypedStream`]s, created by [`channel()`].
+pub struct TypedListener<T> {
+    listener: Listener,
+    _phantom: PhantomData<fn(T) -> T>,
+}
+impl<T> TypedListener<T> {
+    /// Accepts a connection, blocking until a client connects.
+    #[inline]
+    pub fn accept(&self) -> io::Result<TypedStream<T>> {
+        self.listener.accept().map(TypedStream::from)
+    }
+    /// Creates an iterator which accepts connections, blocking each time `next()` is called until
+    /// a client connects.
+    #[inline]
+    pub fn incoming(&self) -> TypedIncoming<'_, T> { TypedIncoming(self) }
+    /// Borrows the underlying listener.
+    #[inline]
+    pub fn get_ref(&self) -> &Listener { &self.listener }
+    /// Unwraps the underlying listener.
+    #[inline]
+    pub fn into_inner(self) -> Listener { self.listener }
+}
+impl<T> From<Listener> for TypedListener<T> {
+    #[inline]
+    fn from(listener: Listener) -> Self { Self { listener, _phantom: PhantomData } }
+}
+impl<T> Debug for TypedListener<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedListener").field(&self.listener).finish()
+    }
+}
+
+/// An infinite iterator over connections to a [`TypedListener`], created by
+/// [`.incoming()`](TypedListener::incoming).
+pub struct TypedIncoming<'a, T>(&'a TypedListener<T>);
+impl<T> Iterator for TypedIncoming<'_, T> {
+    type Item = io::Result<TypedStream<T>>;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> { Some(self.0.accept()) }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { (usize::MAX, None) }
+}
+impl<T> FusedIterator for TypedIncoming<'_, T> {}
+impl<T> Debug for TypedIncoming<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedIncoming").field(&self.0).finish()
+    }
+}
+
+/// Connects to a [`TypedListener`], created by [`channel()`] or by [`Connector::new()`] in
+/// processes that only act as clients.
+pub struct Connector<T> {
+    name: Name<'static>,
+    _phantom: PhantomData<fn(T) -> T>,
+}
+impl<T> Connector<T> {
+    /// Creates a connector for the channel with the given name.
+    #[inline]
+    pub fn new(name: Name<'static>) -> Self { Self { name, _phantom: PhantomData } }
+    /// Connects to the listener.
+    #[inline]
+    pub fn connect(&self) -> io::Result<TypedStream<T>> {
+        Stream::connect(self.name.borrow()).map(TypedStream::from)
+    }
+    /// Returns the name of the channel.
+    #[inline]
+    pub fn name(&self) -> Name<'_> { self.name.borrow() }
+}
+impl<T> Clone for Connector<T> {
+    #[inline]
+    fn clone(&self) -> Self { Self::new(self.name.clone()) }
+}
+impl<T> Debug for Connector<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Connector").field(&self.name).finish()
+    }
+}
+
+/// A local socket stream which sends and receives messages of type `T`.
+///
+/// Both ends of the connection must, of course, use the same `T`. Messages which fail to
+/// deserialize are reported as [`InvalidData`](io::ErrorKind::InvalidData) errors.
+pub struct TypedStream<T> {
+    framed: Framed<Stream, LengthPrefixed>,
+    _phantom: PhantomData<fn(T) -> T>,
+}
+impl<T: Serialize> TypedStream<T> {
+    /// Sends one message, blocking until it has been sent in its entirety.
+    pub fn send(&mut self, msg: &T) -> io::Result<()> {
+        let bytes = bincode::serialize(msg)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        self.framed.send(&bytes)
+    }
+}
+impl<T: DeserializeOwned> TypedStream<T> {
+    /// Receives one message, blocking until it arrives in its entirety.
+    ///
+    /// `Ok(None)` is returned if the peer has closed the connection.
+    pub fn recv(&mut self) -> io::Result<Option<T>> {
+        let Some(bytes) = self.framed.recv()? else { return Ok(None) };
+        bincode::deserialize(&bytes)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+impl<T> TypedStream<T> {
+    /// Borrows the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &Stream { self.framed.get_ref() }
+    /// Unwraps the underlying stream. Buffered data that does not yet form a complete message is
+    /// lost.
+    #[inline]
+    pub fn into_inner(self) -> Stream { self.framed.into_inner().0 }
+}
+impl<T> From<Stream> for TypedStream<T> {
+    #[inline]
+    fn from(stream: Stream) -> Self {
+        Self { framed: Framed::new(stream, LengthPrefixed::new()), _phantom: PhantomData }
+    }
+}
+impl<T: DeserializeOwned> Iterator for TypedStream<T> {
+    type Item = io::Result<T>;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> { self.recv().transpose() }
+}
+impl<T> Debug for TypedStream<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedStream").field(&self.framed).finish()
+    }
+}
@@ -0,0 +1,228 @@
+use {
+    super::Stream,
+    std::{
+        fmt::{self, Debug, Formatter},
+        io::{self, prelude::*},
+        mem::ManuallyDrop,
+        ptr,
+    },
+};
+
+/// Sent by both sides when the stream is set up, followed by a byte that says whether the side
+/// wants compression.
+const MAGIC: [u8; 4] = *b"IPZ\x01";
+/// The amount of data that is compressed at once, which is also the largest frame size that is
+/// accepted from the other side.
+const FRAME_SIZE: usize = 64 * 1024;
+/// Kind byte, payload length and uncompressed length.
+const HEADER_LEN: usize = 9;
+const KIND_STORED: u8 = 0;
+const KIND_LZ4: u8 = 1;
+
+/// A stream which compresses the data that it sends and decompresses the data that it receives,
+/// if both sides agree to it.
+///
+/// Compression is [negotiated](Self::negotiate) when the stream is set up: both sides say whether
+/// they want it, and it's used in both directions if they both do. Otherwise, the stream passes
+/// data through untouched, so that a program can fall back to uncompressed communication with
+/// peers that don't support it, or turn it off for connections where it doesn't pay off.
+///
+/// Data is compressed with [LZ4](https://lz4.org) in blocks of up to 64 KiB, which trades some
+/// compression ratio for being fast enough not to become the bottleneck of local IPC – it's
+/// mainly worth it for highly compressible data like JSON telemetry squeezed through a slow
+/// link, such as the boundary of a virtual machine.
+///
+/// Like with [`BufStream`](super::BufStream), sent data is held in a buffer until it fills up
+/// or until [`.flush()`](Write::flush) is called, which means that **the send buffer must be
+/// flushed before waiting for a response from the other side**. The send buffer is also flushed
+/// when the `CompressedStream` is dropped, with errors being ignored.
+///
+/// # Example
+/// ```no_run
+/// use {
+///     interprocess::local_socket::{prelude::*, CompressedStream, GenericNamespaced, Stream},
+///     std::io::prelude::*,
+/// };
+///
+/// let stream = Stream::connect("telemetry.sock".to_ns_name::<GenericNamespaced>()?)?;
+/// let mut stream = CompressedStream::negotiate(stream, true)?;
+/// stream.write_all(br#"{"cpu": 0.25, "mem": 1048576}"#)?;
+/// stream.flush()?;
+/// # std::io::Result::Ok(())
+/// ```
+pub struct CompressedStream<S: Read + Write = Stream> {
+    inner: S,
+    enabled: bool,
+    recv_buf: Vec<u8>,
+    recv_pos: usize,
+    send_buf: Vec<u8>,
+}
+impl<S: Read + Write> CompressedStream<S> {
+    /// Tells the other side whether this side wants compression, and waits for it to do the
+    /// same. Compression is enabled if both sides want it.
+    ///
+    /// Both sides must call this at the start of the connection before anything else is sent.
+    ///
+    /// # Errors
+    /// Fails with [`InvalidData`](io::ErrorKind::InvalidData) if the other side did not send a
+    /// valid negotiation message, which usually means that it's not using `CompressedStream`.
+    pub fn negotiate(mut inner: S, want_compression: bool) -> io::Result<Self> {
+        let [m0, m1, m2, m3] = MAGIC;
+        inner.write_all(&[m0, m1, m2, m3, u8::from(want_compression)])?;
+        inner.flush()?;
+        let mut peer = [0; 5];
+        inner.read_exact(&mut peer)?;
+        let [magic @ .., flag] = peer;
+        if magic != MAGIC || flag > 1 {
+            return Err(invalid("malformed compression negotiation message"));
+        }
+        Ok(Self::with_negotiated(inner, want_compression && flag == 1))
+    }
+    /// Wraps a stream on which compression has already been negotiated by other means, such as
+    /// a protocol version check, skipping the negotiation message.
+    ///
+    /// Both sides must agree on `enabled`, or the data will be garbled.
+    #[inline]
+    pub fn with_negotiated(inner: S, enabled: bool) -> Self {
+        Self { inner, enabled, recv_buf: Vec::new(), recv_pos: 0, send_buf: Vec::new() }
+    }
+
+    /// Returns whether compression is in use.
+    #[inline]
+    pub fn is_compressed(&self) -> bool { self.enabled }
+    /// Borrows the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &S { &self.inner }
+    /// Mutably borrows the underlying stream.
+    ///
+    /// Receiving from or sending to the stream directly will desynchronize the framing if
+    /// compression is in use.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut S { &mut self.inner }
+    /// Flushes the send buffer and unwraps the underlying stream.
+    ///
+    /// Any decompressed data that has not been received yet is lost.
+    pub fn into_inner(mut self) -> io::Result<S> {
+        self.flush()?;
+        let mut slf = ManuallyDrop::new(self);
+        // SAFETY: the fields are moved or dropped out of `slf` exactly once, and `slf` itself is
+        // never dropped, which keeps the `Drop` impl from flushing into a moved-out stream
+        unsafe {
+            ptr::drop_in_place(&mut slf.recv_buf);
+            ptr::drop_in_place(&mut slf.send_buf);
+            Ok(ptr::read(&slf.inner))
+        }
+    }
+
+    fn send_frame(&mut self) -> io::Result<()> {
+        if self.send_buf.is_empty() {
+            return Ok(());
+        }
+        let compressed = lz4_flex::block::compress(&self.send_buf);
+        let (kind, payload) = if compressed.len() < self.send_buf.len() {
+            (KIND_LZ4, compressed.as_slice())
+        } else {
+            (KIND_STORED, self.send_buf.as_slice())
+        };
+        let mut header = [0; HEADER_LEN];
+        header[0] = kind;
+        header[1..5].copy_from_slice(&len_u32(payload.len())?.to_be_bytes());
+        header[5..9].copy_from_slice(&len_u32(self.send_buf.len())?.to_be_bytes());
+        self.inner.write_all(&header)?;
+        self.inner.write_all(payload)?;
+        self.send_buf.clear();
+        Ok(())
+    }
+    /// Receives one frame into the receive buffer, returning `false` on end of file on a frame
+    /// boundary.
+    fn recv_frame(&mut self) -> io::Result<bool> {
+        let mut header = [0; HEADER_LEN];
+        match self.inner.read(&mut header)? {
+            0 => return Ok(false),
+            n => self.inner.read_exact(header.get_mut(n..).unwrap_or_default())?,
+        }
+        let [kind, p0, p1, p2, p3, r0, r1, r2, r3] = header;
+        let payload_len = frame_len(u32::from_be_bytes([p0, p1, p2, p3]))?;
+        let raw_len = frame_len(u32::from_be_bytes([r0, r1, r2, r3]))?;
+        let mut payload = vec![0; payload_len];
+        self.inner.read_exact(&mut payload)?;
+        self.recv_buf = match kind {
+            KIND_STORED if payload_len == raw_len => payload,
+            KIND_LZ4 => {
+                let mut raw = vec![0; raw_len];
+                let n = lz4_flex::block::decompress_into(&payload, &mut raw)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                if n != raw_len {
+                    return Err(invalid("decompressed frame has the wrong length"));
+                }
+                raw
+            }
+            _ => return Err(invalid("malformed compressed frame header")),
+        };
+        self.recv_pos = 0;
+        Ok(true)
+    }
+}
+
+impl<S: Read + Write> Read for CompressedStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.enabled {
+            return self.inner.read(buf);
+        }
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while self.recv_pos >= self.recv_buf.len() {
+            if !self.recv_frame()? {
+                return Ok(0);
+            }
+        }
+        let avail = self.recv_buf.get(self.recv_pos..).unwrap_or_default();
+        let n = avail.len().min(buf.len());
+        buf.iter_mut().zip(avail).for_each(|(dst, src)| *dst = *src);
+        self.recv_pos = self.recv_pos.saturating_add(n);
+        Ok(n)
+    }
+}
+impl<S: Read + Write> Write for CompressedStream<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !self.enabled {
+            return self.inner.write(buf);
+        }
+        if self.send_buf.len() >= FRAME_SIZE {
+            self.send_frame()?;
+        }
+        let room = FRAME_SIZE.saturating_sub(self.send_buf.len());
+        let n = room.min(buf.len());
+        self.send_buf.extend_from_slice(buf.get(..n).unwrap_or(buf));
+        Ok(n)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.send_frame()?;
+        self.inner.flush()
+    }
+}
+impl<S: Read + Write> Drop for CompressedStream<S> {
+    fn drop(&mut self) { let _ = self.send_frame(); }
+}
+
+impl<S: Read + Write + Debug> Debug for CompressedStream<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompressedStream")
+            .field("inner", &self.inner)
+            .field("compressed", &self.enabled)
+            .field("send_buffer_len", &self.send_buf.len())
+            .finish()
+    }
+}
+
+fn invalid(msg: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, msg) }
+fn len_u32(len: usize) -> io::Result<u32> {
+    u32::try_from(len).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame too long"))
+}
+fn frame_len(len: u32) -> io::Result<usize> {
+    usize::try_from(len)
+        .ok()
+        .filter(|len| *len <= FRAME_SIZE)
+        .ok_or_else(|| invalid("compressed frame exceeds the size limit"))
+}
@@ -1,4 +1,7 @@
 /// Only dispatches with `&self` and `&mut self`.
+///
+/// Since exactly one of the arms exists on any given platform, the expression has the concrete
+/// type of that arm, which means that futures returned through `dispatch!` never need boxing.
 macro_rules! dispatch {
     (@$arm:ident $nm:ident $e:expr) => {{
         let mut _arm2 = $arm;
@@ -0,0 +1,22 @@
+//! Message framing over byte streams.
+//!
+//! Local socket streams are byte streams, which means that they do not preserve the boundaries
+//! between individual sends. This module provides [framers](Framer) that define how messages are
+//! delimited within a byte stream, as well as wrappers that use them to send and receive whole
+//! messages over any stream: [`Framed`] for blocking I/O, where receiving is also exposed as an
+//! iterator, and [`AsyncFramed`] for Tokio, which implements the `Stream` and `Sink` traits from
+//! the `futures` ecosystem.
+//!
+//! Two framers are provided: [`LengthPrefixed`], which precedes each message with its length, and
+//! [`Delimited`], which terminates each message with a delimiter byte. Both sides of the
+//! connection must, of course, use the same framer.
+
+mod framer;
+mod sync;
+#[cfg(feature = "local-socket-tokio")]
+mod tokio;
+
+#[cfg(feature = "local-socket-tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "local-socket-tokio")))]
+pub use self::tokio::AsyncFramed;
+pub use {framer::*, sync::*};
@@ -0,0 +1,183 @@
+use std::{io, mem};
+
+/// A method of delimiting messages within a byte stream.
+///
+/// Framers are stateful to allow them to remember how far they've gotten into decoding a message
+/// that hasn't been received in its entirety yet, but are expected to be cheap to create.
+pub trait Framer {
+    /// Appends the framed form of `msg` to `dst`.
+    ///
+    /// # Errors
+    /// Implementations should return [`InvalidInput`](io::ErrorKind::InvalidInput) if `msg`
+    /// cannot be framed.
+    fn encode(&mut self, msg: &[u8], dst: &mut Vec<u8>) -> io::Result<()>;
+
+    /// Attempts to extract one message from the start of `src`, removing it and its framing from
+    /// the buffer. `Ok(None)` is returned if `src` does not yet contain a complete message.
+    ///
+    /// # Errors
+    /// Implementations should return [`InvalidData`](io::ErrorKind::InvalidData) if the contents
+    /// of `src` are malformed.
+    fn decode(&mut self, src: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>>;
+}
+impl<F: Framer + ?Sized> Framer for &mut F {
+    #[inline]
+    fn encode(&mut self, msg: &[u8], dst: &mut Vec<u8>) -> io::Result<()> {
+        (**self).encode(msg, dst)
+    }
+    #[inline]
+    fn decode(&mut self, src: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        (**self).decode(src)
+    }
+}
+
+/// The default value for the maximum message length of the provided framers, 16 MiB.
+pub const DEFAULT_MAX_LEN: usize = 16 * 1024 * 1024;
+
+fn too_long(kind: io::ErrorKind) -> io::Error {
+    io::Error::new(kind, "message length exceeds the limit set on the framer")
+}
+
+/// Splits off the first `len` bytes of `src`, leaving only the bytes after `len + skip` in it.
+#[allow(clippy::arithmetic_side_effects)] // Callers ensure len + skip <= src.len()
+fn take_front(src: &mut Vec<u8>, len: usize, skip: usize) -> Vec<u8> {
+    let rest = src.split_off(len + skip);
+    let mut msg = mem::replace(src, rest);
+    msg.truncate(len);
+    msg
+}
+
+/// Frames messages by preceding each one with its length as a 32-bit big-endian integer.
+///
+/// This framer is binary-transparent: messages can contain arbitrary bytes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LengthPrefixed {
+    max_len: usize,
+}
+impl LengthPrefixed {
+    const HEADER_LEN: usize = 4;
+
+    /// Creates a length-prefixed framer with the [default maximum length](DEFAULT_MAX_LEN).
+    #[inline]
+    pub const fn new() -> Self { Self { max_len: DEFAULT_MAX_LEN } }
+
+    builder_setters! {
+        /// Sets the maximum length of a message, in bytes, not including the length prefix.
+        ///
+        /// Sending a message that exceeds this length fails with
+        /// [`InvalidInput`](io::ErrorKind::InvalidInput), and receiving one fails with
+        /// [`InvalidData`](io::ErrorKind::InvalidData) as soon as its length prefix arrives,
+        /// without waiting for the message itself. Since the length prefix is 32 bits wide,
+        /// messages can never exceed 4 GiB, regardless of this setting.
+        max_len: usize,
+    }
+}
+impl Default for LengthPrefixed {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+impl Framer for LengthPrefixed {
+    fn encode(&mut self, msg: &[u8], dst: &mut Vec<u8>) -> io::Result<()> {
+        let len = u32::try_from(msg.len())
+            .ok()
+            .filter(|_| msg.len() <= self.max_len)
+            .ok_or_else(|| too_long(io::ErrorKind::InvalidInput))?;
+        dst.reserve(Self::HEADER_LEN.saturating_add(msg.len()));
+        dst.extend_from_slice(&len.to_be_bytes());
+        dst.extend_from_slice(msg);
+        Ok(())
+    }
+    fn decode(&mut self, src: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        let Some(header) = src.get(..Self::HEADER_LEN).and_then(|h| <[u8; 4]>::try_from(h).ok())
+        else {
+            return Ok(None);
+        };
+        let len = usize::try_from(u32::from_be_bytes(header))
+            .ok()
+            .filter(|len| *len <= self.max_len)
+            .ok_or_else(|| too_long(io::ErrorKind::InvalidData))?;
+        if src.len().saturating_sub(Self::HEADER_LEN) < len {
+            return Ok(None);
+        }
+        src.drain(..Self::HEADER_LEN);
+        Ok(Some(take_front(src, len, 0)))
+    }
+}
+
+/// Frames messages by terminating each one with a delimiter byte, such as a newline.
+///
+/// Messages cannot contain the delimiter: attempting to send such a message fails with
+/// [`InvalidInput`](io::ErrorKind::InvalidInput).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Delimited {
+    delimiter: u8,
+    max_len: usize,
+    scanned: usize,
+}
+impl Delimited {
+    /// Creates a framer with the given delimiter and the
+    /// [default maximum length](DEFAULT_MAX_LEN).
+    #[inline]
+    pub const fn new(delimiter: u8) -> Self {
+        Self { delimiter, max_len: DEFAULT_MAX_LEN, scanned: 0 }
+    }
+    /// Creates a framer which uses `\n` as the delimiter, producing messages that are lines of
+    /// text.
+    ///
+    /// Note that `\r` is not stripped from received messages.
+    #[inline]
+    pub const fn lines() -> Self { Self::new(b'\n') }
+    /// Returns the delimiter used by the framer.
+    #[inline]
+    pub const fn delimiter(&self) -> u8 { self.delimiter }
+
+    builder_setters! {
+        /// Sets the maximum length of a message, in bytes, not including the delimiter.
+        ///
+        /// Sending a message that exceeds this length fails with
+        /// [`InvalidInput`](io::ErrorKind::InvalidInput), and receiving one fails with
+        /// [`InvalidData`](io::ErrorKind::InvalidData) as soon as this many bytes are buffered
+        /// without a delimiter among them.
+        max_len: usize,
+    }
+}
+impl Framer for Delimited {
+    fn encode(&mut self, msg: &[u8], dst: &mut Vec<u8>) -> io::Result<()> {
+        if msg.len() > self.max_len {
+            return Err(too_long(io::ErrorKind::InvalidInput));
+        }
+        if msg.contains(&self.delimiter) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "message contains the delimiter used by the framer",
+            ));
+        }
+        dst.reserve(msg.len().saturating_add(1));
+        dst.extend_from_slice(msg);
+        dst.push(self.delimiter);
+        Ok(())
+    }
+    fn decode(&mut self, src: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        // Don't rescan the part of the buffer that was already checked during previous calls.
+        let start = self.scanned.min(src.len());
+        let unscanned = src.get(start..).unwrap_or_default();
+        match unscanned.iter().position(|b| *b == self.delimiter) {
+            Some(pos) => {
+                self.scanned = 0;
+                let len = start.saturating_add(pos);
+                if len > self.max_len {
+                    return Err(too_long(io::ErrorKind::InvalidData));
+                }
+                Ok(Some(take_front(src, len, 1)))
+            }
+            None if src.len() > self.max_len => {
+                self.scanned = 0;
+                Err(too_long(io::ErrorKind::InvalidData))
+            }
+            None => {
+                self.scanned = src.len();
+                Ok(None)
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use {
+    super::Framer,
+    std::{
+        io::{self, prelude::*},
+        iter::FusedIterator,
+    },
+};
+
+/// Size of the chunks in which data is received from the underlying stream.
+pub(super) const RECV_CHUNK_SIZE: usize = 4096;
+
+/// A blocking stream wrapper that sends and receives whole messages using a [`Framer`].
+///
+/// Receiving is also available through the [`Iterator`] implementation, which ends when the
+/// underlying stream reaches end of file on a message boundary.
+///
+/// Works with any type that implements [`Read`], [`Write`] or both, including the local socket
+/// [`Stream`](crate::local_socket::Stream), references to it and its halves.
+#[derive(Debug)]
+pub struct Framed<S, F> {
+    inner: S,
+    framer: F,
+    recv_buf: Vec<u8>,
+    send_buf: Vec<u8>,
+    eof: bool,
+}
+impl<S, F: Framer> Framed<S, F> {
+    /// Wraps the given stream, using the given framer.
+    #[inline]
+    pub fn new(inner: S, framer: F) -> Self {
+        Self { inner, framer, recv_buf: Vec::new(), send_buf: Vec::new(), eof: false }
+    }
+    /// Borrows the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &S { &self.inner }
+    /// Mutably borrows the underlying stream.
+    ///
+    /// Receiving from the stream directly may desynchronize the framing.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut S { &mut self.inner }
+    /// Borrows the framer.
+    #[inline]
+    pub fn framer(&self) -> &F { &self.framer }
+    /// Returns the data that has been received but does not yet form a complete message.
+    #[inline]
+    pub fn recv_buffer(&self) -> &[u8] { &self.recv_buf }
+    /// Unwraps the underlying stream and the framer. Buffered data that does not yet form a
+    /// complete message is lost.
+    #[inline]
+    pub fn into_inner(self) -> (S, F) { (self.inner, self.framer) }
+}
+
+impl<S: Read, F: Framer> Framed<S, F> {
+    /// Receives one message, blocking until it arrives in its entirety.
+    ///
+    /// `Ok(None)` is returned if the stream reaches end of file on a message boundary. If end of
+    /// file is reached in the middle of a message, an
+    /// [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error is returned instead.
+    pub fn recv(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let mut chunk = [0; RECV_CHUNK_SIZE];
+        loop {
+            if let Some(msg) = self.framer.decode(&mut self.recv_buf)? {
+                return Ok(Some(msg));
+            }
+            if self.eof {
+                return Ok(None);
+            }
+            let incr = match self.inner.read(&mut chunk) {
+                Ok(incr) => incr,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if incr == 0 {
+                self.eof = true;
+                if !self.recv_buf.is_empty() {
+                    return Err(io::ErrorKind::UnexpectedEof.into());
+                }
+                return Ok(None);
+            }
+            self.recv_buf.extend_from_slice(chunk.get(..incr).unwrap_or(&chunk));
+        }
+    }
+}
+
+impl<S: Write, F: Framer> Framed<S, F> {
+    /// Sends one message, blocking until it has been sent in its entirety, then flushes the
+    /// stream.
+    pub fn send(&mut self, msg: &[u8]) -> io::Result<()> {
+        self.send_buf.clear();
+        self.framer.encode(msg, &mut self.send_buf)?;
+        self.inner.write_all(&self.send_buf)?;
+        self.inner.flush()
+    }
+}
+
+impl<S: Read, F: Framer> Iterator for Framed<S, F> {
+    type Item = io::Result<Vec<u8>>;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> { self.recv().transpose() }
+}
+impl<S: Read, F: Framer> FusedIterator for Framed<S, F> {}
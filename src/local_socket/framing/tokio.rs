@@ -0,0 +1,173 @@
+use {
+    super::{sync::RECV_CHUNK_SIZE, Framer},
+    futures_core::Stream,
+    futures_sink::Sink,
+    std::{
+        future, io,
+        pin::Pin,
+        task::{ready, Context, Poll},
+    },
+    tokio::io::{AsyncRead, AsyncWrite, ReadBuf},
+};
+
+/// A Tokio stream wrapper that sends and receives whole messages using a [`Framer`].
+///
+/// Receiving is available through the [`Stream`] implementation and sending through the [`Sink`]
+/// implementation, both from the `futures` ecosystem, as well as through the inherent
+/// [`.recv()`](Self::recv) and [`.send()`](Self::send) methods.
+///
+/// Works with any type that implements [`AsyncRead`], [`AsyncWrite`] or both, including the Tokio
+/// local socket [`Stream`](crate::local_socket::tokio::Stream), references to it and its halves.
+#[derive(Debug)]
+pub struct AsyncFramed<S, F> {
+    inner: S,
+    framer: F,
+    recv_buf: Vec<u8>,
+    send_buf: Vec<u8>,
+    send_pos: usize,
+    eof: bool,
+}
+impl<S, F: Framer> AsyncFramed<S, F> {
+    /// Wraps the given stream, using the given framer.
+    #[inline]
+    pub fn new(inner: S, framer: F) -> Self {
+        Self {
+            inner,
+            framer,
+            recv_buf: Vec::new(),
+            send_buf: Vec::new(),
+            send_pos: 0,
+            eof: false,
+        }
+    }
+    /// Borrows the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &S { &self.inner }
+    /// Mutably borrows the underlying stream.
+    ///
+    /// Receiving from the stream directly may desynchronize the framing, while sending to it
+    /// directly may interleave with messages that have not been flushed yet.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut S { &mut self.inner }
+    /// Borrows the framer.
+    #[inline]
+    pub fn framer(&self) -> &F { &self.framer }
+    /// Returns the data that has been received but does not yet form a complete message.
+    #[inline]
+    pub fn recv_buffer(&self) -> &[u8] { &self.recv_buf }
+    /// Unwraps the underlying stream and the framer. Buffered data that does not yet form a
+    /// complete message is lost, as are messages that have not yet been flushed.
+    #[inline]
+    pub fn into_inner(self) -> (S, F) { (self.inner, self.framer) }
+}
+
+impl<S: AsyncRead + Unpin, F: Framer + Unpin> AsyncFramed<S, F> {
+    /// Receives one message, waiting until it arrives in its entirety.
+    ///
+    /// `Ok(None)` is returned if the stream reaches end of file on a message boundary. If end of
+    /// file is reached in the middle of a message, an
+    /// [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error is returned instead.
+    ///
+    /// This method is cancel-safe: if its future is dropped before completion, no data is lost.
+    #[inline]
+    pub async fn recv(&mut self) -> io::Result<Option<Vec<u8>>> {
+        future::poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Option<Vec<u8>>>> {
+        let mut chunk = [0; RECV_CHUNK_SIZE];
+        loop {
+            if let Some(msg) = self.framer.decode(&mut self.recv_buf)? {
+                return Poll::Ready(Ok(Some(msg)));
+            }
+            if self.eof {
+                return Poll::Ready(Ok(None));
+            }
+            let mut readbuf = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut self.inner).poll_read(cx, &mut readbuf))?;
+            let filled = readbuf.filled();
+            if filled.is_empty() {
+                self.eof = true;
+                if !self.recv_buf.is_empty() {
+                    return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+                }
+                return Poll::Ready(Ok(None));
+            }
+            self.recv_buf.extend_from_slice(filled);
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin, F: Framer + Unpin> AsyncFramed<S, F> {
+    /// Sends one message, waiting until it and all previously queued messages have been sent in
+    /// their entirety, then flushes the stream.
+    ///
+    /// # Cancel safety
+    /// The whole message is queued before any of it is sent, and how much of the queue has been
+    /// written is tracked by the `AsyncFramed` rather than by the future. If the future is
+    /// dropped before completion, the rest of the message stays queued and is sent ahead of the
+    /// next message (or by flushing the [`Sink`]), so the peer never sees a partial frame for as
+    /// long as the `AsyncFramed` keeps being used.
+    ///
+    /// Dropping the `AsyncFramed` or [unwrapping](Self::into_inner) it after a cancelled send,
+    /// however, leaves a truncated frame on the stream, which desynchronizes the peer. The
+    /// stream should then be closed rather than used for anything else.
+    #[inline]
+    pub async fn send(&mut self, msg: &[u8]) -> io::Result<()> {
+        self.queue(msg)?;
+        future::poll_fn(|cx| self.poll_send_flush(cx)).await
+    }
+
+    fn queue(&mut self, msg: &[u8]) -> io::Result<()> {
+        self.framer.encode(msg, &mut self.send_buf)
+    }
+
+    fn poll_send_flush(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while let Some(rem) = self.send_buf.get(self.send_pos..).filter(|r| !r.is_empty()) {
+            let incr = ready!(Pin::new(&mut self.inner).poll_write(cx, rem))?;
+            if incr == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.send_pos = self.send_pos.saturating_add(incr);
+        }
+        self.send_buf.clear();
+        self.send_pos = 0;
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+}
+
+impl<S: AsyncRead + Unpin, F: Framer + Unpin> Stream for AsyncFramed<S, F> {
+    type Item = io::Result<Vec<u8>>;
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_recv(cx).map(Result::transpose)
+    }
+}
+
+/// Messages are only queued by `start_send()`. They are written to the stream when the sink is
+/// flushed, or in `poll_ready()` if a previous message is still pending.
+impl<S: AsyncWrite + Unpin, F: Framer + Unpin, M: AsRef<[u8]>> Sink<M> for AsyncFramed<S, F> {
+    type Error = io::Error;
+    #[inline]
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let slf = self.get_mut();
+        if slf.send_buf.is_empty() {
+            return Poll::Ready(Ok(()));
+        }
+        slf.poll_send_flush(cx)
+    }
+    #[inline]
+    fn start_send(self: Pin<&mut Self>, item: M) -> io::Result<()> {
+        self.get_mut().queue(item.as_ref())
+    }
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_send_flush(cx)
+    }
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let slf = self.get_mut();
+        ready!(slf.poll_send_flush(cx))?;
+        Pin::new(&mut slf.inner).poll_shutdown(cx)
+    }
+}
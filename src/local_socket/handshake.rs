@@ -0,0 +1,197 @@
+//! Mutual authentication of both ends of a connection by a shared secret.
+//!
+//! When OS-level access control is unavailable or insufficient – a socket directory that is
+//! shared between containers, or a named pipe that must be reachable by several accounts – a
+//! server and its clients can instead be given a secret, for instance by storing it in a file
+//! that only they can read. Before the stream is handed to application code, each side then
+//! proves that it knows the secret with an HMAC-SHA256 challenge-response, without the secret
+//! itself ever being sent.
+//!
+//! The handshake is most conveniently enabled with
+//! [`ListenerOptions::shared_secret()`](super::ListenerOptions::shared_secret) and
+//! [`ConnectOptions::shared_secret()`](super::ConnectOptions::shared_secret), which make
+//! [`.accept()`](super::traits::Listener::accept) and
+//! [`.connect_sync()`](super::ConnectOptions::connect_sync) perform it. It can also be performed
+//! over any other stream with [`authenticate_server()`] and [`authenticate_client()`].
+//!
+//! Since `.accept()` waits for each client to complete the handshake before it returns, a client
+//! that is slow to do so holds up every client behind it. Servers that can be reached by parties
+//! they don't trust should use [`Listener::accept_pending()`](super::Listener::accept_pending)
+//! instead, and complete each [`PendingHandshake`] on a thread of its own.
+//!
+//! The handshake only authenticates the peer; it does not protect the data sent afterwards.
+//!
+//! # Protocol
+//! 1. The server sends a random 32-byte challenge.
+//! 2. The client replies with a random 32-byte challenge of its own, followed by
+//!    `HMAC(secret, "interprocess client" ‖ server challenge ‖ client challenge)`.
+//! 3. The server checks the client's proof, closing the connection if it is wrong, and replies
+//!    with `HMAC(secret, "interprocess server" ‖ client challenge ‖ server challenge)`.
+//! 4. The client checks the server's proof.
+
+#[cfg(any(unix, target_vendor = "wasmer"))]
+use crate::os::unix::uds_local_socket::authenticate_within;
+#[cfg(windows)]
+use crate::os::windows::named_pipe::local_socket::authenticate_within;
+use {
+    super::{traits::Stream as _, Stream},
+    hmac::{Hmac, Mac},
+    sha2::Sha256,
+    std::{
+        fmt::{self, Debug, Formatter},
+        io::{self, prelude::*},
+        sync::Arc,
+        time::Duration,
+    },
+};
+
+/// How long [`.accept()`](super::traits::Listener::accept) and [`PendingHandshake::finish()`]
+/// wait for a client to complete the handshake by default, unless
+/// [configured](super::ListenerOptions::handshake_timeout) otherwise.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+const CHALLENGE_LEN: usize = 32;
+const PROOF_LEN: usize = 32;
+const CLIENT_LABEL: &[u8] = b"interprocess client";
+const SERVER_LABEL: &[u8] = b"interprocess server";
+
+/// A secret known to both the server and its clients, cheaply clonable.
+///
+/// The secret should be long and random – 32 bytes from a cryptographically secure source is
+/// plenty – since anyone who can connect to the socket can try to guess it.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SharedSecret(Arc<[u8]>);
+impl SharedSecret {
+    /// Wraps the given secret.
+    #[inline]
+    pub fn new(secret: impl Into<Arc<[u8]>>) -> Self { Self(secret.into()) }
+    fn proof(&self, label: &[u8], first: &[u8], second: &[u8]) -> io::Result<Hmac<Sha256>> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.0)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        mac.update(label);
+        mac.update(first);
+        mac.update(second);
+        Ok(mac)
+    }
+}
+/// The secret itself is not shown.
+impl Debug for SharedSecret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedSecret").finish_non_exhaustive()
+    }
+}
+
+/// A connection accepted by [`Listener::accept_pending()`](super::Listener::accept_pending),
+/// the client of which has yet to prove that it knows the shared secret.
+///
+/// [`.finish()`](Self::finish) performs the handshake, blocking for up to the
+/// [handshake timeout](super::ListenerOptions::handshake_timeout) of the listener. If the
+/// listener has no shared secret, there is nothing to do, and `.finish()` returns right away.
+#[derive(Debug)]
+pub struct PendingHandshake {
+    stream: Stream,
+    handshake: Option<(SharedSecret, Duration)>,
+    nonblocking: bool,
+}
+impl PendingHandshake {
+    /// For a stream in blocking mode, to be put in nonblocking mode after the handshake if
+    /// `nonblocking` is set.
+    pub(crate) fn new(
+        stream: impl Into<Stream>,
+        secret: SharedSecret,
+        timeout: Duration,
+        nonblocking: bool,
+    ) -> Self {
+        Self { stream: stream.into(), handshake: Some((secret, timeout)), nonblocking }
+    }
+    /// For a stream that is ready to be used as is.
+    pub(crate) fn ready(stream: impl Into<Stream>) -> Self {
+        Self { stream: stream.into(), handshake: None, nonblocking: false }
+    }
+    /// Performs the server side of the handshake, returning the stream once the client has been
+    /// authenticated.
+    ///
+    /// # Errors
+    /// Fails with [`PermissionDenied`](io::ErrorKind::PermissionDenied) if the client does not
+    /// know the secret, and with [`TimedOut`](io::ErrorKind::TimedOut) if it doesn't complete the
+    /// handshake in time, in addition to any errors from the stream. The connection is closed
+    /// on failure.
+    pub fn finish(self) -> io::Result<Stream> {
+        let Some((secret, timeout)) = &self.handshake else { return Ok(self.stream) };
+        dispatch!(Stream: x in &self.stream => authenticate_within(*x, secret, *timeout))?;
+        if self.nonblocking {
+            self.stream.set_nonblocking(true)?;
+        }
+        Ok(self.stream)
+    }
+}
+
+/// Performs the server side of the handshake over the given stream, which must be in blocking
+/// mode.
+///
+/// # Errors
+/// Fails with [`PermissionDenied`](io::ErrorKind::PermissionDenied) if the client does not know
+/// the secret, in addition to any errors from the stream.
+pub fn authenticate_server(
+    stream: &mut (impl Read + Write),
+    secret: &SharedSecret,
+) -> io::Result<()> {
+    let server_challenge = challenge()?;
+    stream.write_all(&server_challenge)?;
+    stream.flush()?;
+
+    let mut reply = [0; CHALLENGE_LEN + PROOF_LEN];
+    stream.read_exact(&mut reply)?;
+    let (client_challenge, client_proof) = reply.split_at(CHALLENGE_LEN);
+    secret
+        .proof(CLIENT_LABEL, &server_challenge, client_challenge)?
+        .verify_slice(client_proof)
+        .map_err(|_| denied("client does not know the shared secret"))?;
+
+    let server_proof = secret.proof(SERVER_LABEL, client_challenge, &server_challenge)?;
+    stream.write_all(&server_proof.finalize().into_bytes())?;
+    stream.flush()
+}
+
+/// Performs the client side of the handshake over the given stream, which must be in blocking
+/// mode.
+///
+/// # Errors
+/// Fails with [`PermissionDenied`](io::ErrorKind::PermissionDenied) if the server does not know
+/// the secret, or if it hangs up during the handshake, which is what it does when it doesn't
+/// accept the client's proof.
+pub fn authenticate_client(
+    stream: &mut (impl Read + Write),
+    secret: &SharedSecret,
+) -> io::Result<()> {
+    let mut server_challenge = [0; CHALLENGE_LEN];
+    stream.read_exact(&mut server_challenge)?;
+
+    let client_challenge = challenge()?;
+    let client_proof = secret.proof(CLIENT_LABEL, &server_challenge, &client_challenge)?;
+    let mut reply = [0; CHALLENGE_LEN + PROOF_LEN];
+    let (reply_challenge, reply_proof) = reply.split_at_mut(CHALLENGE_LEN);
+    reply_challenge.copy_from_slice(&client_challenge);
+    reply_proof.copy_from_slice(&client_proof.finalize().into_bytes());
+    stream.write_all(&reply)?;
+    stream.flush()?;
+
+    let mut server_proof = [0; PROOF_LEN];
+    stream.read_exact(&mut server_proof).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof | io::ErrorKind::ConnectionReset => {
+            denied("server rejected the shared secret")
+        }
+        _ => e,
+    })?;
+    secret
+        .proof(SERVER_LABEL, &client_challenge, &server_challenge)?
+        .verify_slice(&server_proof)
+        .map_err(|_| denied("server does not know the shared secret"))
+}
+
+fn challenge() -> io::Result<[u8; CHALLENGE_LEN]> {
+    let mut challenge = [0; CHALLENGE_LEN];
+    getrandom::getrandom(&mut challenge).map_err(io::Error::from)?;
+    Ok(challenge)
+}
+fn denied(msg: &str) -> io::Error { io::Error::new(io::ErrorKind::PermissionDenied, msg) }
@@ -0,0 +1,240 @@
+impmod! {shared_memory,
+    Segment,
+    SegmentQueue,
+    claim_segment,
+    recv_with_segments,
+    segment_token,
+    send_with_segment,
+}
+use {
+    super::{framing::DEFAULT_MAX_LEN, Stream},
+    std::{
+        io::{self, prelude::*},
+        mem, ptr,
+    },
+};
+
+const TAG_INLINE: u8 = 0;
+const TAG_SHARED: u8 = 1;
+/// Tag, then length, then the token of the shared memory object, which is only present if the
+/// tag is [`TAG_SHARED`].
+const INLINE_HEADER_LEN: usize = 1 + 8;
+const SHARED_HEADER_LEN: usize = INLINE_HEADER_LEN + 8;
+const RECV_CHUNK_SIZE: usize = 4096;
+
+/// A message-oriented wrapper around a local socket [`Stream`] which moves large messages
+/// through shared memory instead of copying them through the kernel's socket buffers.
+///
+/// Messages no longer than the [threshold](Self::set_threshold) are sent over the stream as
+/// usual. Longer ones are written into a freshly created anonymous shared memory object, of
+/// which only a reference is sent over the stream, and the receiving side copies the message
+/// out of it. Both ends of the connection must use `HybridStream`, but their thresholds need not
+/// match.
+///
+/// # Platform-specific behavior
+/// ## Unix
+/// The shared memory object is a `memfd` on Linux and Android and an immediately unlinked
+/// `shm_open()` object elsewhere. Its file descriptor is passed with `SCM_RIGHTS`.
+///
+/// On Linux and Android, the size of the `memfd` is sealed before it is sent, and objects whose
+/// size is not sealed are rejected by the receiver, since the sender could otherwise shrink one
+/// while it is being read from, killing the receiver with `SIGBUS`. Other Unix systems provide
+/// no such protection, and `HybridStream` should only be used with trusted peers on them.
+///
+/// ## Windows
+/// The shared memory object is a file mapping backed by the paging file. The sender leaves a
+/// handle to it in its own process, which the receiver duplicates into its own process, closing
+/// the original. This requires the receiver to be able to open the sender with
+/// `PROCESS_DUP_HANDLE` access – as is the case for processes of the same user at the same
+/// integrity level. If the receiver never gets to receive the message, the handle remains open
+/// in the sender's process until it exits.
+#[derive(Debug)]
+pub struct HybridStream {
+    stream: Stream,
+    threshold: usize,
+    max_inline_len: usize,
+    max_shared_len: usize,
+    recv_buf: Vec<u8>,
+    segments: SegmentQueue,
+    eof: bool,
+}
+impl HybridStream {
+    /// The default threshold, 64 KiB.
+    pub const DEFAULT_THRESHOLD: usize = 64 * 1024;
+    /// The default maximum length of messages received through shared memory, 1 GiB.
+    pub const DEFAULT_MAX_SHARED_LEN: usize = 1024 * 1024 * 1024;
+
+    /// Wraps the given stream, using the [default threshold](Self::DEFAULT_THRESHOLD).
+    #[inline]
+    pub fn new(stream: Stream) -> Self {
+        Self {
+            stream,
+            threshold: Self::DEFAULT_THRESHOLD,
+            max_inline_len: DEFAULT_MAX_LEN,
+            max_shared_len: Self::DEFAULT_MAX_SHARED_LEN,
+            recv_buf: Vec::new(),
+            segments: SegmentQueue::default(),
+            eof: false,
+        }
+    }
+    /// Sets the length above which messages are sent through shared memory.
+    #[inline]
+    pub fn set_threshold(&mut self, threshold: usize) { self.threshold = threshold }
+    /// Returns the length above which messages are sent through shared memory.
+    #[inline]
+    pub fn threshold(&self) -> usize { self.threshold }
+    /// Sets the maximum length of messages received over the stream rather than through shared
+    /// memory, [16 MiB](DEFAULT_MAX_LEN) by default. Longer ones are rejected with
+    /// [`InvalidData`](io::ErrorKind::InvalidData) before any memory is allocated for them.
+    #[inline]
+    pub fn set_max_inline_len(&mut self, max_len: usize) { self.max_inline_len = max_len }
+    /// Sets the maximum length of messages received through shared memory,
+    /// [1 GiB](Self::DEFAULT_MAX_SHARED_LEN) by default. Longer ones are rejected with
+    /// [`InvalidData`](io::ErrorKind::InvalidData) before any memory is allocated for them, and
+    /// their shared memory objects are closed.
+    #[inline]
+    pub fn set_max_shared_len(&mut self, max_len: usize) { self.max_shared_len = max_len }
+    /// Borrows the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &Stream { &self.stream }
+    /// Unwraps the underlying stream. Buffered data that does not yet form a complete message is
+    /// lost.
+    #[inline]
+    pub fn into_inner(self) -> Stream { self.stream }
+
+    /// Sends one message, blocking until it has been sent in its entirety.
+    pub fn send(&mut self, msg: &[u8]) -> io::Result<()> {
+        let len = u64::try_from(msg.len()).map_err(|_| too_long())?.to_le_bytes();
+        if msg.len() <= self.threshold {
+            let mut header = [TAG_INLINE; INLINE_HEADER_LEN];
+            header.iter_mut().skip(1).zip(len).for_each(|(dst, src)| *dst = src);
+            let mut stream = &self.stream;
+            stream.write_all(&header)?;
+            stream.write_all(msg)?;
+            return stream.flush();
+        }
+
+        let seg = Segment::create_anonymous(msg.len())?;
+        {
+            let mapping = seg.map()?;
+            // SAFETY: the mapping is at least as long as the segment, which is exactly as long as
+            // the message, and nobody else can access the segment yet
+            unsafe { ptr::copy_nonoverlapping(msg.as_ptr(), mapping.as_ptr(), msg.len()) };
+        }
+        let token = segment_token(&self.stream, &seg)?.to_le_bytes();
+        let mut header = [TAG_SHARED; SHARED_HEADER_LEN];
+        header.iter_mut().skip(1).zip(len.into_iter().chain(token)).for_each(|(dst, src)| {
+            *dst = src;
+        });
+        send_with_segment(&self.stream, &header, &seg)
+    }
+
+    /// Receives one message, blocking until it arrives in its entirety.
+    ///
+    /// `Ok(None)` is returned if the stream reaches end of file on a message boundary.
+    pub fn recv(&mut self) -> io::Result<Option<Vec<u8>>> {
+        if !self.fill(INLINE_HEADER_LEN)? {
+            return Ok(None);
+        }
+        let tag = self.recv_buf.first().copied().unwrap_or(TAG_INLINE);
+        let len = read_u64(&self.recv_buf, 1);
+        let len = usize::try_from(len).map_err(|_| too_long())?;
+        match tag {
+            TAG_INLINE => {
+                if len > self.max_inline_len {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "inline message exceeds the maximum length",
+                    ));
+                }
+                let total = INLINE_HEADER_LEN.saturating_add(len);
+                self.demand(total)?;
+                let rest = self.recv_buf.split_off(total);
+                let mut msg = mem::replace(&mut self.recv_buf, rest);
+                msg.drain(..INLINE_HEADER_LEN);
+                Ok(Some(msg))
+            }
+            TAG_SHARED => {
+                self.demand(SHARED_HEADER_LEN)?;
+                let token = read_u64(&self.recv_buf, INLINE_HEADER_LEN);
+                self.recv_buf.drain(..SHARED_HEADER_LEN);
+                // Claimed even if the message is rejected, so as to not leave the object behind.
+                let seg = claim_segment(&self.stream, token, len, &mut self.segments)?;
+                if len > self.max_shared_len {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "shared memory message exceeds the maximum length",
+                    ));
+                }
+                let mapping = seg.map()?;
+                let mut msg = Vec::with_capacity(len);
+                // SAFETY: the mapping is at least `len` bytes long, and so is the spare capacity
+                // of the vector
+                unsafe {
+                    ptr::copy_nonoverlapping(mapping.as_ptr(), msg.as_mut_ptr(), len);
+                    msg.set_len(len);
+                }
+                Ok(Some(msg))
+            }
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "unknown message type")),
+        }
+    }
+
+    /// Like [`fill()`](Self::fill), but treats end of file as an error.
+    fn demand(&mut self, len: usize) -> io::Result<()> {
+        if self.fill(len)? {
+            Ok(())
+        } else {
+            Err(io::ErrorKind::UnexpectedEof.into())
+        }
+    }
+    /// Receives until at least `len` bytes are buffered. Returns `false` if end of file is
+    /// reached on a message boundary.
+    fn fill(&mut self, len: usize) -> io::Result<bool> {
+        while self.recv_buf.len() < len {
+            if self.eof {
+                return self.eof_result();
+            }
+            self.recv_buf.reserve(RECV_CHUNK_SIZE);
+            let spare = self.recv_buf.spare_capacity_mut();
+            let incr = match recv_with_segments(&self.stream, spare, &mut self.segments) {
+                Ok(incr) => incr.min(spare.len()),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if incr == 0 {
+                self.eof = true;
+                continue;
+            }
+            // SAFETY: the OS has initialized this many bytes of the spare capacity
+            unsafe { self.recv_buf.set_len(self.recv_buf.len().saturating_add(incr)) };
+        }
+        Ok(true)
+    }
+    fn eof_result(&self) -> io::Result<bool> {
+        if self.recv_buf.is_empty() {
+            Ok(false)
+        } else {
+            Err(io::ErrorKind::UnexpectedEof.into())
+        }
+    }
+}
+impl From<Stream> for HybridStream {
+    #[inline]
+    fn from(stream: Stream) -> Self { Self::new(stream) }
+}
+impl Iterator for HybridStream {
+    type Item = io::Result<Vec<u8>>;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> { self.recv().transpose() }
+}
+
+fn read_u64(buf: &[u8], at: usize) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.iter_mut().zip(buf.iter().skip(at)).for_each(|(dst, src)| *dst = *src);
+    u64::from_le_bytes(bytes)
+}
+
+fn too_long() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "message is too long for this platform")
+}
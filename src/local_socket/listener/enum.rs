@@ -4,9 +4,11 @@ use crate::os::unix::uds_local_socket as uds_impl;
 use crate::os::windows::named_pipe::local_socket as np_impl;
 use {
     super::{options::ListenerOptions, r#trait},
-    crate::local_socket::{ListenerNonblockingMode, Stream},
+    crate::local_socket::{ListenerNonblockingMode, ListenerStats, Name, Stream},
     std::{io, iter::FusedIterator},
 };
+#[cfg(feature = "handshake")]
+use crate::local_socket::handshake::PendingHandshake;
 
 impmod! {local_socket::dispatch_sync as dispatch}
 
@@ -32,14 +34,17 @@ mkenum!(
 /// Identially, the automatic name reclamation mechanism can be opted out of via
 /// [`.do_not_reclaim_name_on_drop()`](trait::Listener::do_not_reclaim_name_on_drop) on the listener
 /// or [`.reclaim_name(false)`](super::options::ListenerOptions::reclaim_name) on the builder.
+/// Other ways of dealing with the socket file can be selected with
+/// [`.name_policy()`](super::options::ListenerOptions::name_policy).
 ///
 /// Note that the socket file can be unlinked by other programs at any time, retaining the inode the
 /// listener is bound to but making it inaccessible to peers if it was at its last hardlink. If that
 /// happens and another listener takes the same path before the first one performs name reclamation,
 /// the socket file deletion wouldn't correspond to the listener being closed, instead deleting the
-/// socket file of the second listener. If the second listener also performs name reclamation, the
-/// ensuing deletion will silently fail. Due to the awful design of Unix, this issue cannot be
-/// mitigated.
+/// socket file of the second listener. The
+/// [`UnlinkIfOwned`](super::options::NamePolicy::UnlinkIfOwned) policy guards against this by
+/// checking the inode of the socket file before deleting it, which leaves only a narrow window
+/// between the check and the deletion.
 ///
 /// [`create_sync()`]: super::options::ListenerOptions::create_sync
 ///
@@ -67,13 +72,59 @@ impl r#trait::Listener for Listener {
         dispatch!(Self: x in self => x.set_nonblocking(nonblocking))
     }
     #[inline]
+    fn local_name(&self) -> Option<Name<'_>> { dispatch!(Self: x in self => x.local_name()) }
+    #[inline]
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+        dispatch!(Self: x in self => x.set_inheritable(inheritable))
+    }
+    #[inline]
+    fn backlog(&self) -> Option<u32> { dispatch!(Self: x in self => x.backlog()) }
+    #[inline]
+    fn set_backlog(&self, backlog: u32) -> io::Result<()> {
+        dispatch!(Self: x in self => x.set_backlog(backlog))
+    }
+    #[inline]
+    fn stats(&self) -> ListenerStats { dispatch!(Self: x in self => x.stats()) }
+    #[inline]
     fn do_not_reclaim_name_on_drop(&mut self) {
         dispatch!(Self: x in self => x.do_not_reclaim_name_on_drop())
     }
 }
+impl Listener {
+    /// Accepts a connection like [`.accept()`](r#trait::Listener::accept), but leaves the
+    /// [shared secret handshake](super::options::ListenerOptions::shared_secret) to be performed
+    /// with [`PendingHandshake::finish()`].
+    ///
+    /// Handing each pending handshake off to a thread of its own (or to a pool) keeps clients
+    /// that are slow to authenticate, or that never do, from holding up the ones that connect
+    /// after them, which is what happens when `.accept()` performs the handshake itself.
+    ///
+    /// The connection is counted in the [stats](r#trait::Listener::stats) and reported to the
+    /// [audit hook](crate::local_socket::audit) once it has been accepted, before the client is
+    /// authenticated; the outcome of the handshake is not.
+    #[cfg(feature = "handshake")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "handshake")))]
+    #[inline]
+    pub fn accept_pending(&self) -> io::Result<PendingHandshake> {
+        dispatch!(Self: x in self => x.accept_pending())
+    }
+}
+
 impl Iterator for Listener {
     type Item = io::Result<Stream>;
     #[inline(always)]
     fn next(&mut self) -> Option<Self::Item> { Some(r#trait::Listener::accept(self)) }
 }
 impl FusedIterator for Listener {}
+
+/// Borrows the file descriptor of the listener, for example to register it with a `polling`
+/// `Poller` or any other readiness API.
+#[cfg(any(unix, target_vendor = "wasmer"))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(any(unix, target_vendor = "wasmer"))))]
+impl std::os::fd::AsFd for Listener {
+    #[inline]
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        let Self::UdSocket(x) = self;
+        std::os::fd::AsFd::as_fd(x)
+    }
+}
@@ -1,7 +1,15 @@
-#[cfg(feature = "tokio")]
+#[cfg(feature = "local-socket-tokio")]
 use crate::local_socket::tokio::Listener as TokioListener;
+#[cfg(feature = "handshake")]
+use {
+    crate::local_socket::handshake::{self, SharedSecret},
+    std::time::Duration,
+};
 #[cfg(windows)]
-use crate::os::windows::security_descriptor::SecurityDescriptor;
+use crate::os::windows::{
+    named_pipe::WaitTimeout,
+    security_descriptor::{RawSecurityAttributes, SecurityDescriptor},
+};
 use {
     crate::{
         local_socket::{traits, Listener, ListenerNonblockingMode, Name},
@@ -10,16 +18,57 @@ use {
     std::io,
 };
 
+/// What a listener does with its name when it is dropped, as part of
+/// [name reclamation](Listener#name-reclamation).
+///
+/// This only matters for Unix domain sockets bound to filesystem paths, whose socket files
+/// outlive the listener unless they are dealt with. Other names are released by the OS when the
+/// listener is closed, regardless of the policy.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum NamePolicy {
+    /// Unlink the socket file, so that the name can be reused right away.
+    #[default]
+    Unlink,
+    /// Leave the socket file in place, as is appropriate for sockets whose files are owned by
+    /// someone else, such as a service manager performing socket activation.
+    Keep,
+    /// Unlink the socket file, but only if it is still the one that the listener created. The
+    /// device and inode numbers of the file are recorded when the listener is created and
+    /// compared to those of the file at the path on drop, which keeps a listener from deleting
+    /// the socket file of another one that has taken its path in the meantime, as can happen
+    /// during a supervised restart.
+    UnlinkIfOwned,
+    /// Rename the socket file by appending `.old` to its name, replacing any previous file of
+    /// that name. This frees the name while leaving the socket file around for inspection.
+    RenameAside,
+}
+
 /// A builder for [local socket listeners](traits::Listener), including [`Listener`].
 #[derive(Debug)]
 pub struct ListenerOptions<'n> {
     pub(crate) name: Name<'n>,
     pub(crate) nonblocking: ListenerNonblockingMode,
-    pub(crate) reclaim_name: bool,
+    pub(crate) name_policy: NamePolicy,
+    pub(crate) backlog: Option<u32>,
+    #[cfg(feature = "handshake")]
+    pub(crate) shared_secret: Option<SharedSecret>,
+    #[cfg(feature = "handshake")]
+    pub(crate) handshake_timeout: Duration,
     #[cfg(any(unix, target_vendor = "wasmer"))]
     pub(crate) mode: Option<libc::mode_t>,
+    #[cfg(any(unix, target_vendor = "wasmer"))]
+    pub(crate) audit_directory: bool,
     #[cfg(windows)]
     pub(crate) security_descriptor: Option<SecurityDescriptor>,
+    #[cfg(windows)]
+    pub(crate) extra_open_mode: u32,
+    #[cfg(windows)]
+    pub(crate) extra_pipe_mode: u32,
+    #[cfg(windows)]
+    pub(crate) wait_timeout: WaitTimeout,
+    #[cfg(windows)]
+    pub(crate) raw_security_attributes: Option<RawSecurityAttributes>,
 }
 impl Sealed for ListenerOptions<'_> {}
 
@@ -28,15 +77,30 @@ impl TryClone for ListenerOptions<'_> {
         Ok(Self {
             name: self.name.clone(),
             nonblocking: self.nonblocking,
-            reclaim_name: self.reclaim_name,
+            name_policy: self.name_policy,
+            backlog: self.backlog,
+            #[cfg(feature = "handshake")]
+            shared_secret: self.shared_secret.clone(),
+            #[cfg(feature = "handshake")]
+            handshake_timeout: self.handshake_timeout,
             #[cfg(any(unix, target_vendor = "wasmer"))]
             mode: self.mode,
+            #[cfg(any(unix, target_vendor = "wasmer"))]
+            audit_directory: self.audit_directory,
             #[cfg(windows)]
             security_descriptor: self
                 .security_descriptor
                 .as_ref()
                 .map(TryClone::try_clone)
                 .transpose()?,
+            #[cfg(windows)]
+            extra_open_mode: self.extra_open_mode,
+            #[cfg(windows)]
+            extra_pipe_mode: self.extra_pipe_mode,
+            #[cfg(windows)]
+            wait_timeout: self.wait_timeout,
+            #[cfg(windows)]
+            raw_security_attributes: self.raw_security_attributes,
         })
     }
 }
@@ -49,11 +113,26 @@ impl ListenerOptions<'_> {
         Self {
             name: Name::invalid(),
             nonblocking: ListenerNonblockingMode::Neither,
-            reclaim_name: true,
+            name_policy: NamePolicy::Unlink,
+            backlog: None,
+            #[cfg(feature = "handshake")]
+            shared_secret: None,
+            #[cfg(feature = "handshake")]
+            handshake_timeout: handshake::DEFAULT_TIMEOUT,
             #[cfg(any(unix, target_vendor = "wasmer"))]
             mode: None,
+            #[cfg(any(unix, target_vendor = "wasmer"))]
+            audit_directory: false,
             #[cfg(windows)]
             security_descriptor: None,
+            #[cfg(windows)]
+            extra_open_mode: 0,
+            #[cfg(windows)]
+            extra_pipe_mode: 0,
+            #[cfg(windows)]
+            wait_timeout: WaitTimeout::DEFAULT,
+            #[cfg(windows)]
+            raw_security_attributes: None,
         }
     }
 }
@@ -67,10 +146,78 @@ impl<'n> ListenerOptions<'n> {
         ///
         /// The default value is `Neither`.
         nonblocking: ListenerNonblockingMode,
-        /// Sets whether [name reclamation](Listener#name-reclamation) is to happen or not.
+        /// Sets what is to happen to the name when the listener is dropped.
         ///
-        /// This is enabled by default.
-        reclaim_name: bool,
+        /// The default value is [`Unlink`](NamePolicy::Unlink).
+        name_policy: NamePolicy,
+    }
+    /// Sets whether [name reclamation](Listener#name-reclamation) is to happen or not. Shorthand
+    /// for setting the [name policy](Self::name_policy) to [`Unlink`](NamePolicy::Unlink) or
+    /// [`Keep`](NamePolicy::Keep).
+    ///
+    /// This is enabled by default.
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn reclaim_name(mut self, reclaim_name: bool) -> Self {
+        self.name_policy = if reclaim_name { NamePolicy::Unlink } else { NamePolicy::Keep };
+        self
+    }
+    /// Sets the accept backlog – the number of clients which may wait to be accepted, beyond
+    /// which further ones are refused or have to retry, depending on the platform. The OS may
+    /// clamp the value, such as to `net.core.somaxconn` on Linux.
+    ///
+    /// By default, the largest backlog that the platform allows is requested. This has no effect
+    /// on Windows, where named pipes have no backlog.
+    ///
+    /// The backlog can be queried and changed on the resulting listener with
+    /// [`.backlog()`](traits::Listener::backlog) and
+    /// [`.set_backlog()`](traits::Listener::set_backlog).
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn backlog(mut self, backlog: u32) -> Self {
+        self.backlog = Some(backlog);
+        self
+    }
+    /// Makes [`.accept()`](traits::Listener::accept) authenticate every client by the given
+    /// secret with a [challenge-response handshake](crate::local_socket::handshake), failing
+    /// with [`PermissionDenied`](io::ErrorKind::PermissionDenied) for clients that don't know it.
+    ///
+    /// The handshake is performed by `.accept()` itself, with a
+    /// [timeout](Self::handshake_timeout), which makes every client wait for the ones before it
+    /// to authenticate. [`.accept_pending()`](crate::local_socket::Listener::accept_pending)
+    /// hands the handshake over to the caller instead, so that it can be performed for each
+    /// client separately.
+    ///
+    /// The handshake is only supported by sync listeners – creating a Tokio listener with a
+    /// shared secret set fails with [`Unsupported`](io::ErrorKind::Unsupported).
+    #[cfg(feature = "handshake")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "handshake")))]
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn shared_secret(mut self, secret: SharedSecret) -> Self {
+        self.shared_secret = Some(secret);
+        self
+    }
+    /// Sets how long [`.accept()`](traits::Listener::accept) waits for a client to complete the
+    /// [shared secret handshake](Self::shared_secret), [5 seconds](handshake::DEFAULT_TIMEOUT)
+    /// by default. The timeout covers the whole handshake rather than each read and write of it.
+    /// `.accept()` fails with [`TimedOut`](io::ErrorKind::TimedOut) for clients which take
+    /// longer, disconnecting them.
+    ///
+    /// Since the handshake is performed in blocking mode, a client that connects and then stays
+    /// silent holds up the thread calling `.accept()` for up to this long. This is the case even
+    /// if the listener is in [nonblocking accept mode](ListenerNonblockingMode::Accept), which
+    /// only keeps `.accept()` from waiting for clients to connect. Servers which cannot afford
+    /// that should use [`.accept_pending()`](crate::local_socket::Listener::accept_pending),
+    /// which applies the same timeout to
+    /// [`PendingHandshake::finish()`](handshake::PendingHandshake::finish) instead.
+    #[cfg(feature = "handshake")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "handshake")))]
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.handshake_timeout = timeout;
+        self
     }
 }
 
@@ -91,15 +238,22 @@ impl ListenerOptions<'_> {
     /// On platforms where there are multiple available implementations, this dispatches to the
     /// appropriate implementation based on where the name points to.
     #[inline]
-    #[cfg(feature = "tokio")]
+    #[cfg(feature = "local-socket-tokio")]
     pub fn create_tokio(self) -> io::Result<TokioListener> {
         self.create_tokio_as::<TokioListener>()
     }
     /// Creates the given [type of listener](traits::tokio::Listener), binding it to the specified
     /// local socket name.
     #[inline]
-    #[cfg(feature = "tokio")]
+    #[cfg(feature = "local-socket-tokio")]
     pub fn create_tokio_as<L: traits::tokio::Listener>(self) -> io::Result<L> {
+        #[cfg(feature = "handshake")]
+        if self.shared_secret.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the shared secret handshake is not supported by Tokio listeners",
+            ));
+        }
         L::from_options(self)
     }
 }
@@ -0,0 +1,44 @@
+use std::{
+    io,
+    sync::atomic::{AtomicU64, Ordering::Relaxed},
+};
+
+/// Counters kept by a local socket listener, as returned by
+/// [`.stats()`](super::super::traits::Listener::stats).
+///
+/// The counters start at zero when the listener is created, including when it's converted
+/// between its sync and Tokio variants, and only ever go up. Taking the difference between two
+/// snapshots gives the rates that health endpoints typically report.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct ListenerStats {
+    /// How many connections [`.accept()`](super::super::traits::Listener::accept) has returned.
+    pub accepted: u64,
+    /// How many times `.accept()` has failed, including because the client failed a shared secret
+    /// handshake, which isn't counted when it is performed by `PendingHandshake::finish()`
+    /// instead. Calls that fail with [`WouldBlock`](io::ErrorKind::WouldBlock) on nonblocking
+    /// listeners are not counted.
+    pub accept_errors: u64,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct StatsCounters {
+    accepted: AtomicU64,
+    accept_errors: AtomicU64,
+}
+impl StatsCounters {
+    /// Counts the outcome of a call to `.accept()`.
+    pub(crate) fn record<T>(&self, rslt: &io::Result<T>) {
+        match rslt {
+            Ok(..) => self.accepted.fetch_add(1, Relaxed),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => return,
+            Err(..) => self.accept_errors.fetch_add(1, Relaxed),
+        };
+    }
+    pub(crate) fn snapshot(&self) -> ListenerStats {
+        ListenerStats {
+            accepted: self.accepted.load(Relaxed),
+            accept_errors: self.accept_errors.load(Relaxed),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use {
     crate::{
-        local_socket::{stream::r#trait::Stream, ListenerOptions},
+        local_socket::{stream::r#trait::Stream, ListenerOptions, ListenerStats, Name, PeerInfo},
         Sealed,
     },
     std::{io, iter::FusedIterator},
@@ -42,6 +42,51 @@ pub trait Listener:
     /// [`.incoming()`]: ListenerExt::incoming
     fn set_nonblocking(&self, nonblocking: ListenerNonblockingMode) -> io::Result<()>;
 
+    /// Returns the name the listener is bound to.
+    ///
+    /// For listeners created from [`ListenerOptions`], this is the name that was passed to the
+    /// builder, except for abstract namespace names on Linux, which are read back from the socket
+    /// to reflect the address that was actually bound. `None` is returned if the listener was
+    /// created from a raw file descriptor or handle and its name could not be determined.
+    fn local_name(&self) -> Option<Name<'_>>;
+
+    /// Sets whether the listener is to be inherited by child processes spawned after this call.
+    ///
+    /// Listeners are not inheritable by default. On Unix, this clears or sets the `FD_CLOEXEC`
+    /// flag of the socket. On Windows, it sets or clears the `HANDLE_FLAG_INHERIT` flag on the
+    /// instance of the named pipe that is currently waiting for a client, and the same setting is
+    /// used for all instances created afterwards.
+    ///
+    /// Streams returned by [`.accept()`](Self::accept) do not take after the listener in this
+    /// regard on Unix, but do on Windows, where they are made out of the listener's instances.
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()>;
+
+    /// Returns the accept backlog that was last requested for the listener, either with
+    /// [`ListenerOptions::backlog()`] or with [`.set_backlog()`](Self::set_backlog).
+    ///
+    /// `None` is returned if the platform default is in effect, if the listener was created from
+    /// a raw file descriptor, and on Windows, where named pipes have no backlog.
+    #[inline]
+    fn backlog(&self) -> Option<u32> { None }
+
+    /// Changes the accept backlog of the listener while it runs, as load-shedding servers may
+    /// want to do under pressure.
+    ///
+    /// On Unix, this calls `listen()` again with the new backlog, which Linux, the BSDs and macOS
+    /// honor. Fails with [`Unsupported`](io::ErrorKind::Unsupported) on Windows.
+    #[inline]
+    fn set_backlog(&self, backlog: u32) -> io::Result<()> {
+        let _ = backlog;
+        Err(io::Error::new(io::ErrorKind::Unsupported, "listener has no backlog"))
+    }
+
+    /// Returns a snapshot of the listener's [counters](ListenerStats): how many connections have
+    /// been accepted, and how many times accepting has failed.
+    ///
+    /// Neither Unix domain sockets nor named pipes report how many clients are waiting to be
+    /// accepted, so that is not among the counters.
+    fn stats(&self) -> ListenerStats;
+
     /// Disables [name reclamation](super::enum::Listener#name-reclamation) on the listener.
     fn do_not_reclaim_name_on_drop(&mut self);
 }
@@ -76,6 +121,49 @@ pub trait ListenerExt: Listener {
     /// socket server.
     #[inline]
     fn incoming(&self) -> Incoming<'_, Self> { self.into() }
+
+    /// Accepts a connection like [`.accept()`](Listener::accept), and gathers
+    /// [information about the peer](PeerInfo) right away, before returning the stream.
+    ///
+    /// Credentials are recorded by the OS when the connection is established on most Unix
+    /// systems, but have to be looked up while the client is still connected on other platforms,
+    /// including Windows. Looking them up as part of accepting the connection captures them even
+    /// if the peer exits immediately after connecting, which matters when they are used for
+    /// authorization.
+    ///
+    /// # Errors
+    /// In addition to the errors of `.accept()`, fails if looking up the credentials fails for a
+    /// reason other than the platform not supporting it.
+    fn accept_with_info(&self) -> io::Result<(Self::Stream, PeerInfo)> {
+        let stream = self.accept()?;
+        let info = PeerInfo::of(&stream)?;
+        Ok((stream, info))
+    }
+
+    /// Accepts connections like [`.accept_with_info()`](Self::accept_with_info) until one whose
+    /// peer satisfies the given predicate arrives, closing all others right away without sending
+    /// them anything.
+    ///
+    /// Rejected clients only see their connection closed, which happens before the application
+    /// gets to do anything with it. This makes allow-list servers simple to write, and keeps the
+    /// server from leaking why it rejected a client, or how long it took to decide, through error
+    /// messages or response timing.
+    ///
+    /// # Errors
+    /// Same as with `.accept_with_info()`. In nonblocking mode, this fails with
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) once there are no clients waiting, even if some
+    /// were rejected before that.
+    fn accept_filtered(
+        &self,
+        mut filter: impl FnMut(&PeerInfo) -> bool,
+    ) -> io::Result<(Self::Stream, PeerInfo)> {
+        loop {
+            let (stream, info) = self.accept_with_info()?;
+            if filter(&info) {
+                return Ok((stream, info));
+            }
+        }
+    }
 }
 impl<T: Listener> ListenerExt for T {}
 
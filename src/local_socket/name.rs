@@ -1,9 +1,10 @@
 mod inner;
+mod static_name;
 pub(super) mod to_name;
 pub(super) mod r#type;
 
 pub(crate) use self::inner::*;
-pub use {r#type::*, to_name::*};
+pub use {r#type::*, static_name::*, to_name::*};
 
 /// Name for a local socket.
 ///
@@ -0,0 +1,75 @@
+use {
+    super::{
+        r#type::{GenericFilePath, GenericNamespaced},
+        to_name::{ToFsName, ToNsName},
+        Name,
+    },
+    std::{io, sync::OnceLock},
+};
+
+/// A local socket name that can be declared as a `static` or `const` item and is converted to a
+/// [`Name`] only once.
+///
+/// Creating a [`Name`] from a string validates it and, on Windows, re-encodes it as UTF-16 into a
+/// freshly allocated buffer. Programs that connect to the same well-known name over and over can
+/// declare it as a `StaticName` instead, which performs the conversion the first time
+/// [`.name()`](Self::name) is called and hands out borrowed names from then on, without
+/// allocating.
+///
+/// The string is interpreted as if by [`GenericFilePath`] or [`GenericNamespaced`], depending on
+/// the constructor.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::local_socket::{prelude::*, StaticName, Stream};
+///
+/// static SERVER: StaticName = StaticName::namespaced("example.sock");
+///
+/// for _ in 0..1000 {
+///     let conn = Stream::connect(SERVER.name()?)?;
+///     // ...
+/// #   drop(conn);
+/// }
+/// # std::io::Result::Ok(())
+/// ```
+#[derive(Debug)]
+pub struct StaticName {
+    string: &'static str,
+    namespaced: bool,
+    converted: OnceLock<Name<'static>>,
+}
+impl StaticName {
+    /// Creates a name which refers to the given filesystem path.
+    #[inline]
+    pub const fn path(path: &'static str) -> Self {
+        Self { string: path, namespaced: false, converted: OnceLock::new() }
+    }
+    /// Creates a name in the local socket namespace of the platform.
+    #[inline]
+    pub const fn namespaced(name: &'static str) -> Self {
+        Self { string: name, namespaced: true, converted: OnceLock::new() }
+    }
+
+    /// Returns the string that the name was created from.
+    #[inline]
+    pub const fn as_str(&self) -> &'static str { self.string }
+
+    /// Returns the name, converting it if this is the first call to succeed.
+    ///
+    /// # Errors
+    /// Fails in the same cases as [`to_fs_name()`](ToFsName::to_fs_name) and
+    /// [`to_ns_name()`](ToNsName::to_ns_name), such as if the name isn't supported by the
+    /// platform. Since failed conversions aren't remembered, every call fails in that case.
+    pub fn name(&self) -> io::Result<Name<'_>> {
+        if let Some(name) = self.converted.get() {
+            return Ok(name.borrow());
+        }
+        let name = if self.namespaced {
+            self.string.to_ns_name::<GenericNamespaced>()?
+        } else {
+            self.string.to_fs_name::<GenericFilePath>()?
+        };
+        // Another thread may have gotten there first, in which case its result is used.
+        Ok(self.converted.get_or_init(|| name).borrow())
+    }
+}
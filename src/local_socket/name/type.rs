@@ -75,6 +75,17 @@ tag_enum!(
 ///
 /// ### Unix
 /// Resolves to filesystem paths to Unix domain sockets without performing any transformations.
+///
+/// Paths are limited by the size of `sun_path`, which is between 92 and 108 bytes depending on
+/// the platform. On Linux and Android, longer paths are still accepted as long as the file name
+/// is short enough: the parent directory is opened and the socket is reached through
+/// `/proc/self/fd`, which requires procfs to be mounted. Paths which exceed these limits are
+/// rejected by the conversion, with an error stating the limit.
+///
+/// Other Unix systems have no equivalent of `/proc/self/fd`, and the only portable way of
+/// reaching such a socket, changing the working directory to its parent for the duration of
+/// the call, would change it for every thread of the process. Overlong paths are therefore
+/// rejected on them, pending support for the `bindat()` and `connectat()` calls of FreeBSD.
 GenericFilePath);
 impl NameType for GenericFilePath {
     fn is_supported() -> bool { true }
@@ -99,11 +110,17 @@ tag_enum!(
 /// ## Platform-specific behavior
 /// ### Windows
 /// Resolves to named pipe names by prepending `\\.\pipe\` (thus, only local named pipes are
-/// addressable).
+/// addressable), unless the name already starts with it. Names which cannot be those of named
+/// pipes, such as ones containing backslashes, are rejected by the conversion, as described for
+/// the Windows-specific `NamedPipe` name type.
 ///
-/// ### Linux
+/// ### Linux and Android
 /// Resolves to the abstract namespace with no string transformations and thus has a maximum length
-/// of 107 bytes.
+/// of 107 bytes, which is checked by the conversion.
+///
+/// This is the name type to use on Android, where SELinux policy permits apps to use abstract
+/// sockets but prevents them from using filesystem-bound ones outside of their own data
+/// directory. Permission errors caused by the latter carry a hint to that effect.
 ///
 /// ### Other Unices
 /// Resolves to filesystem paths by prepending `/tmp/`.
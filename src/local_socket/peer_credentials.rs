@@ -0,0 +1,54 @@
+use {
+    super::{traits::StreamCommon, Name},
+    std::io,
+};
+
+/// Identity of the process on the other side of a local socket connection, as reported by the
+/// OS.
+///
+/// Each platform provides a different subset of this information through a different mechanism,
+/// so every field is optional:
+/// - On Linux, Android and Haiku, all fields are filled in from `SO_PEERCRED`.
+/// - On NetBSD, all fields are filled in from `LOCAL_PEEREID`.
+/// - On illumos and Solaris, all fields are filled in from `getpeerucred()`.
+/// - On Apple platforms, FreeBSD, DragonFly BSD, OpenBSD and AIX, the user and group IDs come
+///   from `getpeereid()`. Apple platforms also report the process ID via `LOCAL_PEERPID`.
+/// - On Windows, only the process ID is available, using `GetNamedPipeClientProcessId` on the
+///   server side and `GetNamedPipeServerProcessId` on the client side.
+///
+/// On Unix, the user and group IDs are the effective ones the peer had at the time the
+/// connection was established (or, for the server, when it started listening), not necessarily
+/// the ones it has now.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct PeerCredentials {
+    /// Process ID of the peer.
+    pub pid: Option<u32>,
+    /// Effective user ID of the peer.
+    pub uid: Option<u32>,
+    /// Effective group ID of the peer.
+    pub gid: Option<u32>,
+}
+
+/// Information about the process on the other side of a connection, gathered by
+/// [`.accept_with_info()`](super::traits::ListenerExt::accept_with_info) as soon as the
+/// connection is accepted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PeerInfo {
+    /// Credentials of the peer, with every field set to `None` on platforms that provide no way
+    /// of querying them.
+    pub credentials: PeerCredentials,
+    /// Name the peer is bound to, as returned by
+    /// [`.peer_name()`](super::traits::StreamCommon::peer_name).
+    pub name: Option<Name<'static>>,
+}
+impl PeerInfo {
+    pub(crate) fn of(stream: &impl StreamCommon) -> io::Result<Self> {
+        let credentials = match stream.peer_credentials() {
+            Err(e) if e.kind() == io::ErrorKind::Unsupported => PeerCredentials::default(),
+            rslt => rslt?,
+        };
+        Ok(Self { credentials, name: stream.peer_name() })
+    }
+}
@@ -0,0 +1,260 @@
+use {
+    super::Stream,
+    snow::{params::NoiseParams, Builder, HandshakeState, TransportState},
+    std::{
+        fmt::{self, Debug, Formatter},
+        io::{self, prelude::*},
+    },
+};
+
+/// Interactive handshake with a known responder key, which lets the responder learn the
+/// initiator's static key from the first message and decide whether to go on.
+const PARAMS: &str = "Noise_IK_25519_ChaChaPoly_BLAKE2s";
+/// The largest Noise message, and thus frame, that can be sent or received.
+const MAX_MSG_LEN: usize = 65535;
+/// Size of the ChaCha20-Poly1305 authentication tag added to every message.
+const TAG_LEN: usize = 16;
+const MAX_PLAINTEXT_LEN: usize = MAX_MSG_LEN - TAG_LEN;
+const KEY_LEN: usize = 32;
+
+/// A static X25519 keypair that identifies one side of a [`SecureStream`].
+///
+/// Public keys are to be distributed to peers ahead of time, for instance by writing them into a
+/// file that only the intended peers can read, while the private key never leaves the process
+/// (or its own protected storage).
+#[derive(Clone, PartialEq, Eq)]
+pub struct StaticKeypair {
+    private: [u8; KEY_LEN],
+    public: [u8; KEY_LEN],
+}
+impl StaticKeypair {
+    /// Generates a new keypair from the operating system's source of randomness.
+    pub fn generate() -> io::Result<Self> {
+        let keypair = Builder::new(params()?).generate_keypair().map_err(to_io_error)?;
+        Self::from_keys(&keypair.private, &keypair.public)
+    }
+    /// Restores a keypair from its private and public keys, as previously returned by
+    /// [`.private_key()`](Self::private_key) and [`.public_key()`](Self::public_key).
+    ///
+    /// The keys are not checked to belong together – a mismatched pair makes every handshake
+    /// fail.
+    pub fn from_keys(private: &[u8], public: &[u8]) -> io::Result<Self> {
+        Ok(Self { private: key_from_slice(private)?, public: key_from_slice(public)? })
+    }
+    /// Returns the private key.
+    #[inline]
+    pub fn private_key(&self) -> &[u8; KEY_LEN] { &self.private }
+    /// Returns the public key, which is what peers need to know to authenticate this side.
+    #[inline]
+    pub fn public_key(&self) -> &[u8; KEY_LEN] { &self.public }
+}
+/// The private key is not shown.
+impl Debug for StaticKeypair {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticKeypair").field("public", &self.public).finish_non_exhaustive()
+    }
+}
+
+/// An encrypted and mutually authenticated channel over a byte stream, using the
+/// [Noise protocol framework](https://noiseprotocol.org).
+///
+/// Local sockets are normally protected by OS-level access control alone. When a socket is
+/// reachable by parties that should not be trusted with the traffic – other users on a shared
+/// machine, or containers that share a socket directory – `SecureStream` adds defense in depth:
+/// both sides prove possession of a [static key](StaticKeypair) known to the other, and
+/// everything sent afterwards is encrypted and integrity-protected.
+///
+/// The client needs to know the server's public key in advance, and the server decides which
+/// clients to let in by their public keys. The handshake is `Noise_IK_25519_ChaChaPoly_BLAKE2s`,
+/// after which every [`.write()`](Write::write) of up to 65519 bytes is sent as one encrypted
+/// message. Sends are not buffered, so there is nothing to flush, but many small sends each carry
+/// 18 bytes of overhead.
+///
+/// # Errors
+/// The handshake fails with [`PermissionDenied`](io::ErrorKind::PermissionDenied) if the other
+/// side's static key is not the expected one, in which case the stream is closed and the other
+/// side typically sees [`UnexpectedEof`](io::ErrorKind::UnexpectedEof). Tampered or otherwise
+/// malformed messages fail reception with [`InvalidData`](io::ErrorKind::InvalidData), after
+/// which the stream is unusable.
+///
+/// # Example
+/// ```no_run
+/// use {
+///     interprocess::local_socket::{prelude::*, GenericNamespaced, SecureStream, StaticKeypair},
+///     std::io::prelude::*,
+/// };
+///
+/// # fn load_keys() -> std::io::Result<(StaticKeypair, [u8; 32])> { unimplemented!() }
+/// let (client_keys, server_public) = load_keys()?;
+/// let stream = LocalSocketStream::connect("secure.sock".to_ns_name::<GenericNamespaced>()?)?;
+/// let mut stream = SecureStream::connect(stream, &client_keys, &server_public)?;
+/// stream.write_all(b"for the server's eyes only")?;
+/// # std::io::Result::Ok(())
+/// ```
+pub struct SecureStream<S: Read + Write = Stream> {
+    inner: S,
+    transport: TransportState,
+    remote_key: [u8; KEY_LEN],
+    recv_buf: Vec<u8>,
+    recv_pos: usize,
+    /// Received message which is yet to be decrypted.
+    frame_buf: Vec<u8>,
+    /// Encrypted message which is about to be sent.
+    send_buf: Vec<u8>,
+}
+impl<S: Read + Write> SecureStream<S> {
+    /// Performs the client side of the handshake, authenticating with `keys` and expecting the
+    /// server to hold the private key for `server_public_key`.
+    pub fn connect(
+        mut inner: S,
+        keys: &StaticKeypair,
+        server_public_key: &[u8; KEY_LEN],
+    ) -> io::Result<Self> {
+        let mut hs = Builder::new(params()?)
+            .local_private_key(&keys.private)
+            .remote_public_key(server_public_key)
+            .build_initiator()
+            .map_err(to_io_error)?;
+        send_handshake(&mut inner, &mut hs)?;
+        recv_handshake(&mut inner, &mut hs)?;
+        Self::finish(inner, hs)
+    }
+    /// Performs the server side of the handshake, authenticating with `keys` and letting the
+    /// client in only if its public key is among `client_public_keys`.
+    pub fn accept(
+        mut inner: S,
+        keys: &StaticKeypair,
+        client_public_keys: &[[u8; KEY_LEN]],
+    ) -> io::Result<Self> {
+        let mut hs = Builder::new(params()?)
+            .local_private_key(&keys.private)
+            .build_responder()
+            .map_err(to_io_error)?;
+        recv_handshake(&mut inner, &mut hs)?;
+        let authorized = hs.get_remote_static().is_some_and(|key| {
+            client_public_keys.iter().any(|allowed| allowed.as_slice() == key)
+        });
+        if !authorized {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "client is not authorized to connect",
+            ));
+        }
+        send_handshake(&mut inner, &mut hs)?;
+        Self::finish(inner, hs)
+    }
+    fn finish(inner: S, hs: HandshakeState) -> io::Result<Self> {
+        let remote_key = key_from_slice(hs.get_remote_static().unwrap_or_default())?;
+        let transport = hs.into_transport_mode().map_err(to_io_error)?;
+        Ok(Self {
+            inner,
+            transport,
+            remote_key,
+            recv_buf: Vec::new(),
+            recv_pos: 0,
+            frame_buf: Vec::new(),
+            send_buf: vec![0; MAX_MSG_LEN],
+        })
+    }
+
+    /// Returns the static public key that the other side has authenticated with.
+    #[inline]
+    pub fn remote_public_key(&self) -> &[u8; KEY_LEN] { &self.remote_key }
+    /// Borrows the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &S { &self.inner }
+    /// Unwraps the underlying stream, abandoning the secure channel.
+    #[inline]
+    pub fn into_inner(self) -> S { self.inner }
+}
+
+impl<S: Read + Write> Read for SecureStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while self.recv_pos >= self.recv_buf.len() {
+            let Some(msg) = recv_frame(&mut self.inner, &mut self.frame_buf)? else {
+                return Ok(0);
+            };
+            self.recv_buf.resize(MAX_MSG_LEN, 0);
+            let n = self.transport.read_message(msg, &mut self.recv_buf).map_err(to_io_error)?;
+            self.recv_buf.truncate(n);
+            self.recv_pos = 0;
+        }
+        let avail = self.recv_buf.get(self.recv_pos..).unwrap_or_default();
+        let n = avail.len().min(buf.len());
+        buf.iter_mut().zip(avail).for_each(|(dst, src)| *dst = *src);
+        self.recv_pos = self.recv_pos.saturating_add(n);
+        Ok(n)
+    }
+}
+impl<S: Read + Write> Write for SecureStream<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let plaintext = buf.get(..MAX_PLAINTEXT_LEN).unwrap_or(buf);
+        let len =
+            self.transport.write_message(plaintext, &mut self.send_buf).map_err(to_io_error)?;
+        send_frame(&mut self.inner, self.send_buf.get(..len).unwrap_or_default())?;
+        Ok(plaintext.len())
+    }
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
+}
+
+impl<S: Read + Write + Debug> Debug for SecureStream<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecureStream")
+            .field("inner", &self.inner)
+            .field("remote_public_key", &self.remote_key)
+            .finish_non_exhaustive()
+    }
+}
+
+fn send_handshake(inner: &mut impl Write, hs: &mut HandshakeState) -> io::Result<()> {
+    let mut msg_buf = vec![0; MAX_MSG_LEN];
+    let len = hs.write_message(&[], &mut msg_buf).map_err(to_io_error)?;
+    send_frame(inner, msg_buf.get(..len).unwrap_or_default())?;
+    inner.flush()
+}
+fn recv_handshake(inner: &mut impl Read, hs: &mut HandshakeState) -> io::Result<()> {
+    let mut frame_buf = Vec::new();
+    let msg = recv_frame(inner, &mut frame_buf)?
+        .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+    let mut payload = vec![0; MAX_MSG_LEN];
+    hs.read_message(msg, &mut payload).map_err(|e| match e {
+        // A key mismatch shows up as a message that fails to decrypt.
+        snow::Error::Decrypt => io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "secure channel handshake failed: the peer's static key is not the expected one",
+        ),
+        e => to_io_error(e),
+    })?;
+    Ok(())
+}
+
+/// Sends a message preceded by its length as a 16-bit big-endian integer.
+fn send_frame(inner: &mut impl Write, msg: &[u8]) -> io::Result<()> {
+    let len = u16::try_from(msg.len()).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+    inner.write_all(&len.to_be_bytes())?;
+    inner.write_all(msg)
+}
+/// Receives a message sent by [`send_frame()`] into `buf`, returning `None` on end of file on a
+/// message boundary.
+fn recv_frame<'b>(inner: &mut impl Read, buf: &'b mut Vec<u8>) -> io::Result<Option<&'b [u8]>> {
+    let mut len = [0; 2];
+    match inner.read(&mut len)? {
+        0 => return Ok(None),
+        1 => inner.read_exact(&mut len[1..])?,
+        _ => {}
+    }
+    buf.resize(usize::from(u16::from_be_bytes(len)), 0);
+    inner.read_exact(buf)?;
+    Ok(Some(buf))
+}
+
+fn params() -> io::Result<NoiseParams> { PARAMS.parse().map_err(to_io_error) }
+fn key_from_slice(key: &[u8]) -> io::Result<[u8; KEY_LEN]> {
+    key.try_into()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "keys must be 32 bytes long"))
+}
+fn to_io_error(e: snow::Error) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, e) }
@@ -0,0 +1,71 @@
+use {
+    super::r#enum::{RecvHalf, SendHalf, Stream},
+    ::bytes::{Buf, BufMut, BytesMut},
+    std::{
+        io::{self, prelude::*, IoSlice},
+        mem::MaybeUninit,
+    },
+};
+
+/// How much `read_bytes()` grows the buffer by if it has no spare capacity left.
+const RESERVE_CHUNK: usize = 8 * 1024;
+/// The maximum number of chunks of a [`Buf`] passed to a single vectored write.
+const MAX_CHUNKS: usize = 16;
+
+/// Grows `buf` if it has no spare capacity left and returns its spare capacity.
+pub(crate) fn reserve_spare(buf: &mut BytesMut) -> &mut [MaybeUninit<u8>] {
+    if buf.len() == buf.capacity() {
+        buf.reserve(RESERVE_CHUNK);
+    }
+    buf.spare_capacity_mut()
+}
+
+/// Lets `f` send the chunks of `buf` and advances it past what was sent.
+fn write_buf_with(
+    buf: &mut impl Buf,
+    f: impl FnOnce(&[IoSlice<'_>]) -> io::Result<usize>,
+) -> io::Result<usize> {
+    let mut chunks = [IoSlice::new(&[]); MAX_CHUNKS];
+    let cnt = buf.chunks_vectored(&mut chunks);
+    let sent = f(chunks.get(..cnt).unwrap_or_default())?.min(buf.remaining());
+    buf.advance(sent);
+    Ok(sent)
+}
+
+macro_rules! read_bytes {
+    ($ty:ident) => {
+        #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytes")))]
+        impl $ty {
+            /// Receives data into the spare capacity of `buf`, growing it first if it has none,
+            /// and returns the number of bytes received, which are appended to the buffer.
+            ///
+            /// The spare capacity is not zeroed out beforehand, making this a cheaper alternative
+            /// to resizing the buffer and then receiving into it.
+            pub fn read_bytes(&self, buf: &mut BytesMut) -> io::Result<usize> {
+                let received = self.read_to_uninit(reserve_spare(buf))?;
+                // SAFETY: read_to_uninit() has initialized that many bytes of the spare capacity
+                unsafe { buf.advance_mut(received) };
+                Ok(received)
+            }
+        }
+    };
+}
+macro_rules! write_buf {
+    ($ty:ident) => {
+        #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytes")))]
+        impl $ty {
+            /// Sends data from `buf`, advancing it past the bytes that were sent and returning
+            /// their number.
+            ///
+            /// Noncontiguous buffers are sent with a single vectored write.
+            pub fn write_buf(&self, buf: &mut impl Buf) -> io::Result<usize> {
+                write_buf_with(buf, |chunks| (&*self).write_vectored(chunks))
+            }
+        }
+    };
+}
+
+read_bytes!(Stream);
+read_bytes!(RecvHalf);
+write_buf!(Stream);
+write_buf!(SendHalf);
@@ -0,0 +1,83 @@
+#[cfg(any(unix, target_vendor = "wasmer"))]
+use crate::os::unix::uds_local_socket::{Connecting as ConnectingImpl, Stream as StreamImpl};
+#[cfg(windows)]
+use crate::os::windows::named_pipe::local_socket::{
+    Connecting as ConnectingImpl, Stream as StreamImpl,
+};
+use {
+    crate::local_socket::{Name, Stream},
+    std::io,
+};
+
+impl Stream {
+    /// Starts connecting to the given name without blocking, for integration into event loops
+    /// other than the ones that Interprocess supports out of the box.
+    ///
+    /// Returns the stream right away if the connection could be established immediately, and a
+    /// [`Connecting`] object to [finish](Connecting::finish) the connection with later
+    /// otherwise. Either way, the resulting stream is in nonblocking mode.
+    ///
+    /// When to call `.finish()` depends on [`.must_retry()`](Connecting::must_retry). If it
+    /// returns `false`, the connection is in progress, and the socket, which is exposed via
+    /// [`AsFd`](std::os::unix::io::AsFd) on Unix, becomes writable once it's time to call
+    /// `.finish()`. If it returns `true`, there is nothing to wait on, and `.finish()` is to be
+    /// retried periodically instead.
+    ///
+    /// # Platform-specific behavior
+    /// ## Unix
+    /// On Linux, a connection attempt is not started at all if the backlog of the server is
+    /// full. The socket then polls as writable right away, so waiting for that would spin, which
+    /// is why `.must_retry()` returns `true` in that case.
+    ///
+    /// ## Windows
+    /// The connection can only be pending if all instances of the named pipe were busy serving
+    /// other clients, so `.must_retry()` always returns `true`.
+    pub fn connect_nonblocking(name: Name<'_>) -> io::Result<ConnectStatus> {
+        ConnectingImpl::start(name).map(ConnectStatus::new)
+    }
+}
+
+/// The outcome of a nonblocking connection attempt, started with
+/// [`Stream::connect_nonblocking()`].
+#[derive(Debug)]
+pub enum ConnectStatus {
+    /// The connection has been established.
+    Connected(Stream),
+    /// The connection has not been established yet.
+    InProgress(Connecting),
+}
+impl ConnectStatus {
+    fn new(rslt: Result<StreamImpl, ConnectingImpl>) -> Self {
+        match rslt {
+            Ok(stream) => Self::Connected(stream.into()),
+            Err(connecting) => Self::InProgress(Connecting(connecting)),
+        }
+    }
+}
+
+/// A nonblocking connection attempt which has not completed yet.
+///
+/// See [`Stream::connect_nonblocking()`] for how to tell when to [finish](Self::finish) it.
+#[derive(Debug)]
+pub struct Connecting(ConnectingImpl);
+impl Connecting {
+    /// Checks whether the connection has been established, returning the stream if so. Fails
+    /// with the error that the connection attempt ended with, such as
+    /// [`ConnectionRefused`](io::ErrorKind::ConnectionRefused).
+    #[inline]
+    pub fn finish(self) -> io::Result<ConnectStatus> {
+        self.0.finish().map(ConnectStatus::new)
+    }
+    /// Returns whether there is nothing to wait on before calling [`.finish()`](Self::finish)
+    /// again, in which case it should be retried periodically. Otherwise, the
+    /// [file descriptor](std::os::unix::io::AsFd) becomes writable once it's time to call it.
+    ///
+    /// See [`Stream::connect_nonblocking()`] for when this is the case.
+    #[inline]
+    pub fn must_retry(&self) -> bool { self.0.must_retry() }
+}
+#[cfg(any(unix, target_vendor = "wasmer"))]
+impl std::os::unix::io::AsFd for Connecting {
+    #[inline]
+    fn as_fd(&self) -> std::os::unix::io::BorrowedFd<'_> { self.0.as_fd() }
+}
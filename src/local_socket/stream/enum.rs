@@ -2,13 +2,22 @@
 use crate::os::unix::uds_local_socket as uds_impl;
 #[cfg(windows)]
 use crate::os::windows::named_pipe::local_socket as np_impl;
+#[cfg(any(unix, windows))]
+use crate::shared_memory::{self, ShmRegion};
 use {
     super::r#trait,
-    crate::{local_socket::Name, TryClone},
-    std::io::{self, prelude::*, IoSlice, IoSliceMut},
+    crate::{
+        local_socket::{ConnectOptions, FlushMode, Name, PeerCredentials, WriterHandle},
+        TryClone,
+    },
+    std::{
+        io::{self, prelude::*, IoSlice, IoSliceMut},
+        mem::MaybeUninit,
+    },
 };
 
 impmod! {local_socket::dispatch_sync}
+impmod! {process, from_inherited as from_inherited_impl}
 
 macro_rules! dispatch_read {
     (@iw $ty:ident) => {
@@ -28,6 +37,16 @@ macro_rules! dispatch_read {
         impl Read for $ty {
             dispatch_read!(@iw $ty);
         }
+        impl $ty {
+            /// Same as `.read()` from the [`Read`] trait, but accepts an uninitialized buffer,
+            /// sparing the cost of zeroing it out beforehand for large transfers.
+            ///
+            /// The returned number of bytes at the beginning of `buf` are initialized.
+            #[inline]
+            pub fn read_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+                dispatch!($ty: x in self => x.read_to_uninit(buf))
+            }
+        }
     };
 }
 macro_rules! dispatch_write {
@@ -68,12 +87,186 @@ mkenum!(
 #[doc = doctest_file::include_doctest!("examples/local_socket/sync/stream.rs")]
 /// ```
 Stream);
+impl Stream {
+    /// Splits the stream into a receive half and a cheaply clonable [`WriterHandle`], which can
+    /// be used to send messages from multiple threads without interleaving their bytes.
+    #[inline]
+    pub fn writer_handle(self) -> (RecvHalf, WriterHandle) {
+        let (rh, sh) = r#trait::Stream::split(self);
+        (rh, WriterHandle::new(sh))
+    }
+    /// Claims the end of the channel that was inherited from the parent process, which must
+    /// have spawned this process with
+    /// [`spawn_with_channel()`](crate::process::spawn_with_channel).
+    ///
+    /// The inherited stream can only be claimed once; subsequent calls fail with
+    /// [`AlreadyExists`](io::ErrorKind::AlreadyExists). If the process was not spawned with a
+    /// channel, [`NotFound`](io::ErrorKind::NotFound) is returned.
+    ///
+    /// Once claimed, the channel's [environment variable](crate::process::CHANNEL_ENV_VAR) is
+    /// removed from the environment of the process, so that processes which it spawns in turn
+    /// don't inherit it. Like [`std::env::remove_var()`], this is not synchronized with other
+    /// threads reading the environment by means other than the standard library, so the
+    /// channel is best claimed early, before such threads are started.
+    #[inline]
+    pub fn from_inherited() -> io::Result<Self> { from_inherited_impl() }
+    /// Sets what [`.flush()`](Write::flush) does, choosing between strict and fast semantics on
+    /// platforms where flushing is a real operation. See [`FlushMode`] for the details.
+    ///
+    /// The default is [`OnClose`](FlushMode::OnClose). Halves obtained by
+    /// [splitting](r#trait::Stream::split) the stream do not inherit its flush mode.
+    pub fn set_flush_mode(&self, mode: FlushMode) {
+        match self {
+            #[cfg(windows)]
+            Self::NamedPipe(s) => s.set_flush_mode(mode),
+            #[cfg(any(unix, target_vendor = "wasmer"))]
+            Self::UdSocket(..) => {
+                let _ = mode;
+            }
+        }
+    }
+}
+
+/// File descriptor passing.
+impl Stream {
+    /// Returns whether [`.send_fds()`](Self::send_fds) and [`.recv_fds()`](Self::recv_fds) are
+    /// supported on the current platform, which they are on Unix. Named pipes cannot carry
+    /// handles, so on Windows they fail with [`Unsupported`](io::ErrorKind::Unsupported).
+    ///
+    /// This lets portable code pick between passing file descriptors and some other way of
+    /// sharing resources at runtime, rather than with `cfg` attributes.
+    #[inline]
+    pub const fn supports_fd_passing() -> bool { cfg!(unix) }
+    /// Sends `buf` along with the given file descriptors, which the peer receives with
+    /// [`.recv_fds()`](Self::recv_fds). Returns how much of `buf` was sent.
+    ///
+    /// The file descriptors arrive with the first byte of `buf`, which must not be empty unless
+    /// `fds` is. Up to 8 file descriptors can be sent at once. The peer must not be reading
+    /// through a buffered reader, or the file descriptors will arrive with a read that doesn't
+    /// collect them and be closed.
+    pub fn send_fds(&self, buf: &[u8], fds: &[BorrowedDescriptor<'_>]) -> io::Result<usize> {
+        match self {
+            #[cfg(unix)]
+            Self::UdSocket(s) => s.send_fds(buf, fds),
+            #[cfg(not(unix))]
+            _ => {
+                let _ = (buf, fds);
+                Err(fd_passing_unsupported())
+            }
+        }
+    }
+    /// Receives data into `buf`, appending the file descriptors that arrive with it to `fds`.
+    ///
+    /// The received file descriptors are not inherited by child processes. If the peer sends
+    /// more of them at once than [`.max_recv_fds()`](Self::max_recv_fds), the excess ones are
+    /// closed, which is reported by [`.take_fds_truncated()`](Self::take_fds_truncated)
+    /// rather than by failing, since the data has been received regardless.
+    pub fn recv_fds(&self, buf: &mut [u8], fds: &mut Vec<OwnedDescriptor>) -> io::Result<usize> {
+        match self {
+            #[cfg(unix)]
+            Self::UdSocket(s) => s.recv_fds(buf, fds),
+            #[cfg(not(unix))]
+            _ => {
+                let _ = (buf, fds);
+                Err(fd_passing_unsupported())
+            }
+        }
+    }
+    /// Sets the maximum number of file descriptors that [`.recv_fds()`](Self::recv_fds) accepts
+    /// per message, protecting against peers that try to exhaust this process's file descriptor
+    /// table. The limit also applies to [shared memory regions](Self::recv_region), each of which
+    /// arrives as a file descriptor.
+    ///
+    /// The default, and the largest limit that can be set, is 8. Larger values are clamped. Does
+    /// nothing on platforms that don't [support](Self::supports_fd_passing) file descriptor
+    /// passing.
+    pub fn set_max_recv_fds(&self, max: usize) {
+        match self {
+            #[cfg(unix)]
+            Self::UdSocket(s) => s.set_max_recv_fds(max),
+            #[cfg(not(unix))]
+            _ => {
+                let _ = max;
+            }
+        }
+    }
+    /// Returns the limit set by [`.set_max_recv_fds()`](Self::set_max_recv_fds), which is 0 on
+    /// platforms that don't support file descriptor passing.
+    pub fn max_recv_fds(&self) -> usize {
+        match self {
+            #[cfg(unix)]
+            Self::UdSocket(s) => s.max_recv_fds(),
+            #[cfg(not(unix))]
+            _ => 0,
+        }
+    }
+    /// Returns whether any file descriptors sent by the peer have been closed instead of being
+    /// received since the last call, and resets the flag. This happens when the peer sends more
+    /// of them at once than [`.max_recv_fds()`](Self::max_recv_fds) allows.
+    ///
+    /// Always returns `false` on platforms that don't [support](Self::supports_fd_passing) file
+    /// descriptor passing.
+    pub fn take_fds_truncated(&self) -> bool {
+        match self {
+            #[cfg(unix)]
+            Self::UdSocket(s) => s.take_fds_truncated(),
+            #[cfg(not(unix))]
+            _ => false,
+        }
+    }
+}
+#[cfg(not(unix))]
+fn fd_passing_unsupported() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "file descriptor passing is not supported")
+}
+
+/// Borrowed file descriptor that can be passed with [`Stream::send_fds()`]: `BorrowedFd` on
+/// Unix, and `BorrowedHandle` on Windows, where it only exists so that portable code compiles.
+#[cfg(not(windows))]
+pub type BorrowedDescriptor<'a> = std::os::fd::BorrowedFd<'a>;
+/// Borrowed file descriptor that can be passed with [`Stream::send_fds()`]: `BorrowedFd` on
+/// Unix, and `BorrowedHandle` on Windows, where it only exists so that portable code compiles.
+#[cfg(windows)]
+pub type BorrowedDescriptor<'a> = std::os::windows::io::BorrowedHandle<'a>;
+/// Owned file descriptor received by [`Stream::recv_fds()`]: `OwnedFd` on Unix, and
+/// `OwnedHandle` on Windows, where it only exists so that portable code compiles.
+#[cfg(not(windows))]
+pub type OwnedDescriptor = std::os::fd::OwnedFd;
+/// Owned file descriptor received by [`Stream::recv_fds()`]: `OwnedFd` on Unix, and
+/// `OwnedHandle` on Windows, where it only exists so that portable code compiles.
+#[cfg(windows)]
+pub type OwnedDescriptor = std::os::windows::io::OwnedHandle;
+
+#[cfg(any(unix, windows))]
+impl Stream {
+    /// Hands the shared memory region over to the peer, which receives it with
+    /// [`.recv_region()`](Self::recv_region).
+    ///
+    /// Only the object itself is sent, not its contents, so the cost of this does not depend on
+    /// the size of the region. The region is unmapped from this process.
+    ///
+    /// Regions must not be sent while other data is partially written, and the peer must not be
+    /// reading through a buffered reader, since the shared memory object is delivered along with
+    /// a short message that `.recv_region()` expects to find at the front of the stream.
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(any(unix, windows))))]
+    #[inline]
+    pub fn send_region(&self, region: ShmRegion) -> io::Result<()> {
+        shared_memory::send_region(self, region)
+    }
+    /// Receives a shared memory region sent by the peer with
+    /// [`.send_region()`](Self::send_region) and maps it into this process.
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(any(unix, windows))))]
+    #[inline]
+    pub fn recv_region(&self) -> io::Result<ShmRegion> { shared_memory::recv_region(self) }
+}
 impl r#trait::Stream for Stream {
     type RecvHalf = RecvHalf;
     type SendHalf = SendHalf;
 
     #[inline]
-    fn connect(name: Name<'_>) -> io::Result<Self> { dispatch_sync::connect(name) }
+    fn from_options(options: &ConnectOptions<'_>) -> io::Result<Self> {
+        dispatch_sync::connect(options)
+    }
     #[inline]
     fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
         dispatch!(Self: x in self => x.set_nonblocking(nonblocking))
@@ -107,6 +300,47 @@ impl r#trait::Stream for Stream {
         }
     }
 }
+impl r#trait::StreamCommon for Stream {
+    #[inline]
+    fn peer_name(&self) -> Option<Name<'static>> { dispatch!(Self: x in self => x.peer_name()) }
+    #[inline]
+    fn peer_credentials(&self) -> io::Result<PeerCredentials> {
+        dispatch!(Self: x in self => x.peer_credentials())
+    }
+    #[inline]
+    fn send_buffer_size(&self) -> io::Result<usize> {
+        dispatch!(Self: x in self => x.send_buffer_size())
+    }
+    #[inline]
+    fn recv_buffer_size(&self) -> io::Result<usize> {
+        dispatch!(Self: x in self => x.recv_buffer_size())
+    }
+    #[inline]
+    fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        dispatch!(Self: x in self => x.set_send_buffer_size(size))
+    }
+    #[inline]
+    fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        dispatch!(Self: x in self => x.set_recv_buffer_size(size))
+    }
+    #[inline]
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+        dispatch!(Self: x in self => x.set_inheritable(inheritable))
+    }
+}
+
+/// Borrows the file descriptor of the stream, for example to register it with a `polling`
+/// `Poller` or any other readiness API.
+#[cfg(any(unix, target_vendor = "wasmer"))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(any(unix, target_vendor = "wasmer"))))]
+impl std::os::fd::AsFd for Stream {
+    #[inline]
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        let Self::UdSocket(x) = self;
+        std::os::fd::AsFd::as_fd(x)
+    }
+}
+
 impl TryClone for Stream {
     fn try_clone(&self) -> io::Result<Self> {
         dispatch!(Self: x in self => x.try_clone()).map(From::from)
@@ -126,6 +360,18 @@ impl r#trait::RecvHalf for RecvHalf {
 }
 dispatch_read!(RecvHalf);
 
+/// Borrows the file descriptor of the receive half, for example to register it with a `polling`
+/// `Poller` or any other readiness API.
+#[cfg(any(unix, target_vendor = "wasmer"))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(any(unix, target_vendor = "wasmer"))))]
+impl std::os::fd::AsFd for RecvHalf {
+    #[inline]
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        let Self::UdSocket(x) = self;
+        std::os::fd::AsFd::as_fd(x)
+    }
+}
+
 mkenum!(
 /// Send half of a local socket stream, obtained by splitting a [`Stream`].
 "local_socket::" SendHalf);
@@ -134,6 +380,18 @@ impl r#trait::SendHalf for SendHalf {
 }
 dispatch_write!(SendHalf);
 
+/// Borrows the file descriptor of the send half, for example to register it with a `polling`
+/// `Poller` or any other readiness API.
+#[cfg(any(unix, target_vendor = "wasmer"))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(any(unix, target_vendor = "wasmer"))))]
+impl std::os::fd::AsFd for SendHalf {
+    #[inline]
+    fn as_fd(&self) -> std::os::fd::BorrowedFd<'_> {
+        let Self::UdSocket(x) = self;
+        std::os::fd::AsFd::as_fd(x)
+    }
+}
+
 /// [`ReuniteError`](crate::error::ReuniteError) for [`Stream`].
 pub type ReuniteError = crate::error::ReuniteError<RecvHalf, SendHalf>;
 
@@ -0,0 +1,24 @@
+/// What [`.flush()`](std::io::Write::flush) on a [`Stream`](super::super::Stream) does, as set by
+/// [`.set_flush_mode()`](super::super::Stream::set_flush_mode).
+///
+/// This only makes a difference with named pipes on Windows, where flushing maps to
+/// `FlushFileBuffers`, which blocks until the other side has received everything that has been
+/// sent – a round trip through the other process that is costly to make after every message.
+/// Unix domain sockets have nothing to flush, so all modes behave the same with them: sent data
+/// is in the kernel and is delivered even if the stream is closed right away.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum FlushMode {
+    /// Flushing waits for the other side to receive everything that has been sent.
+    Os,
+    /// Flushing does nothing, and neither does closing, so data that the other side has not
+    /// received yet when the stream is dropped may be discarded by the OS. This is the fastest
+    /// mode, for protocols in which the other side always acknowledges what it receives.
+    NoOp,
+    /// Flushing does nothing, but dropping the stream makes sure that data that the other side
+    /// has not received yet is not discarded, by flushing it in the background before the pipe
+    /// is closed. This is the default.
+    #[default]
+    OnClose,
+}
+unsafe impl crate::ReprU8 for FlushMode {}
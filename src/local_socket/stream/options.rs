@@ -0,0 +1,280 @@
+#[cfg(feature = "handshake")]
+use crate::local_socket::handshake::{self, SharedSecret};
+#[cfg(feature = "local-socket-tokio")]
+use crate::local_socket::tokio::Stream as TokioStream;
+use {
+    crate::{
+        local_socket::{
+            audit::{self, Direction},
+            traits, Name, Stream,
+        },
+        Sealed,
+    },
+    std::io,
+};
+#[cfg(windows)]
+use {
+    crate::os::windows::{
+        c_wrappers,
+        local_socket::{ServerProcess, ServerVerifier},
+    },
+    std::sync::Arc,
+    widestring::U16CString,
+};
+
+/// A builder for [local socket streams](traits::Stream), including [`Stream`].
+#[derive(Clone, Debug)]
+pub struct ConnectOptions<'n> {
+    pub(crate) name: Name<'n>,
+    #[cfg(any(unix, target_vendor = "wasmer"))]
+    pub(crate) bind_name: Option<Name<'n>>,
+    #[cfg(any(unix, target_vendor = "wasmer"))]
+    pub(crate) audit_directory: bool,
+    #[cfg(any(unix, target_vendor = "wasmer"))]
+    pub(crate) socket_owner: Option<u32>,
+    #[cfg(any(unix, target_vendor = "wasmer"))]
+    pub(crate) forbidden_socket_mode: libc::mode_t,
+    #[cfg(feature = "handshake")]
+    pub(crate) shared_secret: Option<SharedSecret>,
+    #[cfg(unix)]
+    pub(crate) required_server_uid: Option<u32>,
+    #[cfg(windows)]
+    pub(crate) required_server_sid: Option<String>,
+    #[cfg(windows)]
+    pub(crate) server_verifier: Option<ServerVerifier>,
+}
+impl Sealed for ConnectOptions<'_> {}
+
+/// Creation.
+impl ConnectOptions<'_> {
+    /// Creates an options table with default values.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            name: Name::invalid(),
+            #[cfg(any(unix, target_vendor = "wasmer"))]
+            bind_name: None,
+            #[cfg(any(unix, target_vendor = "wasmer"))]
+            audit_directory: false,
+            #[cfg(any(unix, target_vendor = "wasmer"))]
+            socket_owner: None,
+            #[cfg(any(unix, target_vendor = "wasmer"))]
+            forbidden_socket_mode: 0,
+            #[cfg(feature = "handshake")]
+            shared_secret: None,
+            #[cfg(unix)]
+            required_server_uid: None,
+            #[cfg(windows)]
+            required_server_sid: None,
+            #[cfg(windows)]
+            server_verifier: None,
+        }
+    }
+}
+
+/// Option setters.
+impl<'n> ConnectOptions<'n> {
+    builder_setters! {
+        /// Sets the name of the server to connect to.
+        name: Name<'n>,
+    }
+    /// Makes the client prove to the server that it knows the given secret, and have the server
+    /// prove the same, with a [challenge-response handshake](crate::local_socket::handshake)
+    /// right after connecting. The server must have been created with the same
+    /// [shared secret](crate::local_socket::ListenerOptions::shared_secret).
+    ///
+    /// The handshake is only supported by sync streams – connecting a Tokio stream with a shared
+    /// secret set fails with [`Unsupported`](io::ErrorKind::Unsupported).
+    #[cfg(feature = "handshake")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "handshake")))]
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn shared_secret(mut self, secret: SharedSecret) -> Self {
+        self.shared_secret = Some(secret);
+        self
+    }
+
+    /// Makes connecting fail with [`PermissionDenied`](io::ErrorKind::PermissionDenied) unless
+    /// the server is run by the user with the given ID, as reported by
+    /// [`.peer_credentials()`](traits::StreamCommon::peer_credentials).
+    ///
+    /// Anyone who can create files in the directory that a socket resides in, or in the
+    /// namespace that it is named in, can bind a socket of that name before the real server
+    /// does, and have clients connect to it instead. Requiring a particular user – typically
+    /// `0` for system daemons – rules out such impostors, as long as the user is one that the
+    /// attacker cannot run programs as.
+    ///
+    /// The check is made right after connecting and before any data is sent, including the
+    /// [shared secret handshake](Self::shared_secret).
+    #[cfg(unix)]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(unix)))]
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn require_server_uid(mut self, uid: u32) -> Self {
+        self.required_server_uid = Some(uid);
+        self
+    }
+    /// Makes connecting fail with [`PermissionDenied`](io::ErrorKind::PermissionDenied) unless
+    /// the server process runs as the user identified by the given string SID, such as
+    /// `S-1-5-18` for LocalSystem.
+    ///
+    /// This guards against pipe name squatting, in which another program creates a pipe of the
+    /// same name before the real server does; see the Unix counterpart,
+    /// `require_server_uid()`, for more. The user is looked up in the access token of the
+    /// process that [`GetNamedPipeServerProcessId`] reports, which must allow
+    /// `PROCESS_QUERY_LIMITED_INFORMATION` access to the client. If it doesn't, or if the SID
+    /// is malformed, connecting fails with the corresponding error.
+    ///
+    /// [`GetNamedPipeServerProcessId`]: https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-getnamedpipeserverprocessid
+    #[cfg(windows)]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(windows)))]
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn require_server_sid(mut self, sid: impl Into<String>) -> Self {
+        self.required_server_sid = Some(sid.into());
+        self
+    }
+    /// Makes connecting fail with [`PermissionDenied`](io::ErrorKind::PermissionDenied) unless
+    /// the given callback approves of the process on the server side of the pipe.
+    ///
+    /// The callback is given the process ID and the path of the executable of the server, which
+    /// it can compare against the expected installation directory, or use to verify the
+    /// signature of the executable, for instance with `WinVerifyTrust`. Like
+    /// [`require_server_sid()`](Self::require_server_sid), this guards against other programs
+    /// creating a pipe of the same name before the real server does, and is checked right after
+    /// connecting, before any data is sent. If the server process cannot be opened with
+    /// `PROCESS_QUERY_LIMITED_INFORMATION` access, connecting fails with the corresponding
+    /// error without calling the callback.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # #[cfg(windows)] {
+    /// use {
+    ///     interprocess::local_socket::{prelude::*, ConnectOptions, GenericNamespaced},
+    ///     std::path::Path,
+    /// };
+    ///
+    /// let conn = ConnectOptions::new()
+    ///     .name("example-service".to_ns_name::<GenericNamespaced>()?)
+    ///     .verify_server(|server| {
+    ///         server.image_path.starts_with(Path::new(r"C:\Program Files\Example"))
+    ///     })
+    ///     .connect_sync()?;
+    /// # }
+    /// # std::io::Result::Ok(())
+    /// ```
+    #[cfg(windows)]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(windows)))]
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn verify_server(
+        mut self,
+        verifier: impl Fn(&ServerProcess) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.server_verifier = Some(ServerVerifier(Arc::new(verifier)));
+        self
+    }
+}
+
+/// Stream constructors.
+impl ConnectOptions<'_> {
+    /// Creates a [`Stream`] by connecting to the specified local socket name.
+    ///
+    /// On platforms where there are multiple available implementations, this dispatches to the
+    /// appropriate implementation based on where the name points to.
+    #[inline]
+    pub fn connect_sync(&self) -> io::Result<Stream> { self.connect_sync_as::<Stream>() }
+    /// Creates the given [type of stream](traits::Stream) by connecting to the specified local
+    /// socket name.
+    #[inline]
+    pub fn connect_sync_as<S: traits::Stream>(&self) -> io::Result<S> {
+        let rslt = self.connect_sync_unaudited();
+        audit::record(Direction::Connect, Some(self.name.borrow()), &rslt);
+        rslt
+    }
+    fn connect_sync_unaudited<S: traits::Stream>(&self) -> io::Result<S> {
+        #[allow(unused_mut)]
+        let mut stream = S::from_options(self)?;
+        self.check_server_identity(&stream)?;
+        #[cfg(feature = "handshake")]
+        if let Some(secret) = &self.shared_secret {
+            handshake::authenticate_client(&mut stream, secret)?;
+        }
+        Ok(stream)
+    }
+    /// Creates a [`Stream`](TokioStream) by connecting to the specified local socket name.
+    ///
+    /// On platforms where there are multiple available implementations, this dispatches to the
+    /// appropriate implementation based on where the name points to.
+    #[inline]
+    #[cfg(feature = "local-socket-tokio")]
+    pub async fn connect_tokio(&self) -> io::Result<TokioStream> {
+        self.connect_tokio_as::<TokioStream>().await
+    }
+    /// Creates the given [type of stream](traits::tokio::Stream) by connecting to the specified
+    /// local socket name.
+    #[inline]
+    #[cfg(feature = "local-socket-tokio")]
+    pub async fn connect_tokio_as<S: traits::tokio::Stream>(&self) -> io::Result<S> {
+        let rslt = self.connect_tokio_unaudited().await;
+        audit::record(Direction::Connect, Some(self.name.borrow()), &rslt);
+        rslt
+    }
+    #[cfg(feature = "local-socket-tokio")]
+    async fn connect_tokio_unaudited<S: traits::tokio::Stream>(&self) -> io::Result<S> {
+        #[cfg(feature = "handshake")]
+        if self.shared_secret.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the shared secret handshake is not supported by Tokio streams",
+            ));
+        }
+        let stream = S::from_options(self).await?;
+        self.check_server_identity(&stream)?;
+        Ok(stream)
+    }
+
+    /// Enforces the requirements on the identity of the server.
+    #[allow(unused_variables)]
+    fn check_server_identity(&self, stream: &impl traits::StreamCommon) -> io::Result<()> {
+        #[cfg(unix)]
+        if let Some(required) = self.required_server_uid {
+            match stream.peer_credentials()?.uid {
+                Some(uid) if uid == required => {}
+                Some(uid) => {
+                    return Err(denied(format!("server is run by user {uid}, not {required}")))
+                }
+                None => return Err(denied("could not determine the user of the server".into())),
+            }
+        }
+        #[cfg(windows)]
+        if let Some(sid) = &self.required_server_sid {
+            let pid = stream.peer_credentials()?.pid.ok_or(io::ErrorKind::Unsupported)?;
+            let wide = U16CString::from_str(sid)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            if !c_wrappers::process_runs_as(pid, &wide)? {
+                return Err(denied(format!("server process {pid} does not run as user {sid}")));
+            }
+        }
+        #[cfg(windows)]
+        if let Some(ServerVerifier(verifier)) = &self.server_verifier {
+            let pid = stream.peer_credentials()?.pid.ok_or(io::ErrorKind::Unsupported)?;
+            let server = ServerProcess { pid, image_path: c_wrappers::process_image_path(pid)? };
+            if !verifier(&server) {
+                return Err(denied(format!(
+                    "server process {pid} ({}) was rejected",
+                    server.image_path.display()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(any(unix, windows))]
+fn denied(msg: String) -> io::Error { io::Error::new(io::ErrorKind::PermissionDenied, msg) }
+
+impl Default for ConnectOptions<'_> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
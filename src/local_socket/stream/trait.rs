@@ -3,8 +3,8 @@
 use {
     crate::{
         bound_util::{RefRead, RefWrite},
-        local_socket::Name,
-        Sealed,
+        local_socket::{ConnectOptions, Name, PeerCredentials},
+        Sealed, TryClone,
     },
     std::io::{self, prelude::*},
 };
@@ -15,14 +15,28 @@ use {
 /// [`Stream` enum](super::enum::Stream). In addition, it is implemented on `Stream` itself, which
 /// makes it a trait object of sorts. See its documentation for more on the semantics of the methods
 /// seen here.
-pub trait Stream: Read + RefRead + Write + RefWrite + Send + Sync + Sized + Sealed {
+///
+/// All local socket streams can be [cloned](TryClone) by duplicating the underlying handle or
+/// file descriptor, which allows one connection to be received from on one thread and sent to
+/// from another using only blocking I/O and without [splitting](Stream::split).
+pub trait Stream:
+    Read + RefRead + Write + RefWrite + StreamCommon + TryClone + Send + Sync + Sized + Sealed
+{
     /// Receive half type returned by [`.split()`](Stream::split).
     type RecvHalf: RecvHalf<Stream = Self>;
     /// Send half type returned by [`.split()`](Stream::split).
     type SendHalf: SendHalf<Stream = Self>;
 
     /// Connects to a remote local socket server.
-    fn connect(name: Name<'_>) -> io::Result<Self>;
+    ///
+    /// This is a shorthand for [`ConnectOptions`] with only the name set.
+    #[inline]
+    fn connect(name: Name<'_>) -> io::Result<Self> {
+        ConnectOptions::new().name(name).connect_sync_as::<Self>()
+    }
+
+    /// Connects to a remote local socket server using the specified options.
+    fn from_options(options: &ConnectOptions<'_>) -> io::Result<Self>;
 
     /// Enables or disables the nonblocking mode for the stream. By default, it is disabled.
     ///
@@ -43,9 +57,66 @@ pub trait Stream: Read + RefRead + Write + RefWrite + Send + Sync + Sized + Seal
     /// method on streams that haven't been split to begin with).
     fn reunite(rh: Self::RecvHalf, sh: Self::SendHalf) -> ReuniteResult<Self>;
 
-    // Do not add methods to this trait that aren't directly tied to non-async streams. A new trait,
-    // which should be called StreamExtra or StreamCommon or something along those lines, is to be
-    // created for features like impersonation (ones that are instantaneous in nature).
+    // Do not add methods to this trait that aren't directly tied to non-async streams. Features
+    // that are instantaneous in nature (like impersonation) go in StreamCommon instead.
+}
+
+/// Functionality shared by [`Stream`] and its [Tokio counterpart](super::super::tokio::Stream)
+/// that doesn't perform I/O on the stream itself.
+///
+/// Types on which this trait is implemented are variants of the sync and Tokio `Stream` enums, as
+/// well as the enums themselves.
+pub trait StreamCommon: Sized + Sealed {
+    /// Returns the name the peer of the stream is bound to, if the OS provides it.
+    ///
+    /// On Unix, this is the address reported by `getpeername()`: client-side streams get the name
+    /// of the server, while server-side streams only get a name if the client bound its socket to
+    /// one before connecting. On Windows, both ends report the path of the named pipe.
+    ///
+    /// `None` is returned if the peer is unnamed or if the name could not be determined.
+    fn peer_name(&self) -> Option<Name<'static>>;
+
+    /// Returns the [credentials](PeerCredentials) of the process on the other side of the
+    /// connection.
+    ///
+    /// # Errors
+    /// Fails with [`Unsupported`](io::ErrorKind::Unsupported) on platforms that provide no way of
+    /// querying them.
+    fn peer_credentials(&self) -> io::Result<PeerCredentials>;
+
+    /// Sets whether the stream is to be inherited by child processes spawned after this call.
+    ///
+    /// Streams are not inheritable by default. On Unix, this clears or sets the `FD_CLOEXEC` flag
+    /// of the socket; on Windows, it sets or clears the `HANDLE_FLAG_INHERIT` flag of the pipe
+    /// handle. The child also needs to be told which file descriptor or handle it has inherited,
+    /// which [`spawn_with_channel()`](crate::process::spawn_with_channel) takes care of.
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()>;
+
+    /// Returns the size of the kernel buffer for data sent to the peer, in bytes.
+    ///
+    /// On Unix, this is the `SO_SNDBUF` socket option. On Windows, this is the size of the named
+    /// pipe buffer in the direction the stream sends in, as reported by `GetNamedPipeInfo`, with
+    /// zero meaning that the buffer is allocated as needed.
+    fn send_buffer_size(&self) -> io::Result<usize>;
+    /// Returns the size of the kernel buffer for data received from the peer, in bytes.
+    ///
+    /// On Unix, this is the `SO_RCVBUF` socket option. See
+    /// [`.send_buffer_size()`](Self::send_buffer_size) for Windows.
+    fn recv_buffer_size(&self) -> io::Result<usize>;
+    /// Sets the size of the kernel buffer for data sent to the peer, in bytes.
+    ///
+    /// The OS may round or clamp the size, so the actual size should be queried afterwards if it
+    /// matters; Linux, for example, doubles the requested value to account for bookkeeping
+    /// overhead.
+    ///
+    /// # Errors
+    /// On Windows, this always fails with [`Unsupported`](io::ErrorKind::Unsupported), since the
+    /// buffer sizes of a named pipe are fixed when the server creates it.
+    fn set_send_buffer_size(&self, size: usize) -> io::Result<()>;
+    /// Sets the size of the kernel buffer for data received from the peer, in bytes.
+    ///
+    /// The same caveats as with [`.set_send_buffer_size()`](Self::set_send_buffer_size) apply.
+    fn set_recv_buffer_size(&self, size: usize) -> io::Result<()>;
 }
 
 /// Receive halves of [`Stream`]s, obtained through [`.split()`](Stream::split).
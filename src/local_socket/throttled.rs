@@ -0,0 +1,251 @@
+use {
+    super::Stream,
+    std::{
+        io::{self, prelude::*},
+        num::NonZeroU64,
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// A stream which is only allowed to receive and send so many bytes per second.
+///
+/// Both directions are limited separately, each by a token bucket which holds up to a second's
+/// worth of bytes: a quiet stream can use up that allowance in one burst, after which it falls
+/// back to the configured rate. Reception and sending block (or, with Tokio, wait
+/// asynchronously) for as long as their bucket is empty.
+///
+/// `Throttled` implements [`Read`] and [`Write`] when the wrapped stream does, and, with the
+/// `local-socket-tokio` feature, Tokio's `AsyncRead` and `AsyncWrite` likewise. Only the wrapped
+/// stream is slowed down, so it's suited for keeping low-priority traffic such as log shipping
+/// out of the way of everything else.
+///
+/// # Example
+/// ```no_run
+/// use {
+///     interprocess::local_socket::{prelude::*, GenericNamespaced, Stream, Throttled},
+///     std::{io::prelude::*, num::NonZeroU64},
+/// };
+///
+/// let stream = Stream::connect("example.sock".to_ns_name::<GenericNamespaced>()?)?;
+/// let mut logs = Throttled::new(stream, NonZeroU64::new(64 * 1024).unwrap());
+/// logs.write_all(b"a log line which arrives no faster than 64 KiB/s\n")?;
+/// # std::io::Result::Ok(())
+/// ```
+#[derive(Debug)]
+pub struct Throttled<S = Stream> {
+    inner: S,
+    recv: Option<Bucket>,
+    send: Option<Bucket>,
+}
+impl<S> Throttled<S> {
+    /// Wraps the given stream, limiting both directions to the given number of bytes per second.
+    #[inline]
+    pub fn new(inner: S, bytes_per_sec: NonZeroU64) -> Self {
+        Self::with_rates(inner, Some(bytes_per_sec), Some(bytes_per_sec))
+    }
+    /// Wraps the given stream, limiting reception and sending to the given numbers of bytes per
+    /// second. `None` leaves the corresponding direction unlimited.
+    #[inline]
+    pub fn with_rates(
+        inner: S,
+        recv_bytes_per_sec: Option<NonZeroU64>,
+        send_bytes_per_sec: Option<NonZeroU64>,
+    ) -> Self {
+        Self {
+            inner,
+            recv: recv_bytes_per_sec.map(Bucket::new),
+            send: send_bytes_per_sec.map(Bucket::new),
+        }
+    }
+
+    /// Changes the limit on reception, starting over with a full bucket.
+    #[inline]
+    pub fn set_recv_rate(&mut self, bytes_per_sec: Option<NonZeroU64>) {
+        self.recv = bytes_per_sec.map(Bucket::new);
+    }
+    /// Changes the limit on sending, starting over with a full bucket.
+    #[inline]
+    pub fn set_send_rate(&mut self, bytes_per_sec: Option<NonZeroU64>) {
+        self.send = bytes_per_sec.map(Bucket::new);
+    }
+    /// Returns the limit on reception, in bytes per second.
+    #[inline]
+    pub fn recv_rate(&self) -> Option<NonZeroU64> { self.recv.as_ref().map(|b| b.rate) }
+    /// Returns the limit on sending, in bytes per second.
+    #[inline]
+    pub fn send_rate(&self) -> Option<NonZeroU64> { self.send.as_ref().map(|b| b.rate) }
+
+    /// Borrows the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &S { &self.inner }
+    /// Mutably borrows the underlying stream.
+    ///
+    /// Receiving from or sending to the stream directly bypasses the limits, and is not counted
+    /// against them.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut S { &mut self.inner }
+    /// Unwraps the underlying stream.
+    #[inline]
+    pub fn into_inner(self) -> S { self.inner }
+}
+
+impl<S: Read> Read for Throttled<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some(bucket) = &mut self.recv else { return self.inner.read(buf) };
+        let allowed = bucket.wait_sync(buf.len());
+        let n = self.inner.read(buf.get_mut(..allowed).unwrap_or_default())?;
+        bucket.take(n);
+        Ok(n)
+    }
+}
+impl<S: Write> Write for Throttled<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let Some(bucket) = &mut self.send else { return self.inner.write(buf) };
+        let allowed = bucket.wait_sync(buf.len());
+        let n = self.inner.write(buf.get(..allowed).unwrap_or(buf))?;
+        bucket.take(n);
+        Ok(n)
+    }
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
+}
+
+/// Token bucket holding up to a second's worth of bytes.
+#[derive(Debug)]
+struct Bucket {
+    rate: NonZeroU64,
+    tokens: u64,
+    refilled_at: Instant,
+    #[cfg(feature = "local-socket-tokio")]
+    sleep: Option<std::pin::Pin<Box<tokio::time::Sleep>>>,
+}
+impl Bucket {
+    fn new(rate: NonZeroU64) -> Self {
+        Self {
+            rate,
+            tokens: rate.get(),
+            refilled_at: Instant::now(),
+            #[cfg(feature = "local-socket-tokio")]
+            sleep: None,
+        }
+    }
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled_at).as_nanos();
+        let earned = elapsed.saturating_mul(self.rate.get().into()) / NANOS_PER_SEC;
+        if earned > 0 {
+            let earned = u64::try_from(earned).unwrap_or(u64::MAX);
+            self.tokens = self.tokens.saturating_add(earned).min(self.rate.get());
+            self.refilled_at = now;
+        }
+    }
+    /// Returns how many bytes may be transferred right away, out of the `wanted` ones, or how
+    /// long to wait for the bucket to hold enough of them to be worth the wakeup.
+    fn poll_allowance(&mut self, wanted: usize) -> Result<usize, Duration> {
+        self.refill(Instant::now());
+        let wanted64 = u64::try_from(wanted).unwrap_or(u64::MAX);
+        if wanted == 0 || self.tokens > 0 {
+            let allowed = wanted64.min(self.tokens);
+            return Ok(usize::try_from(allowed).unwrap_or(wanted));
+        }
+        // Waiting for up to a tenth of a second's worth of bytes, rather than for a single one,
+        // keeps high rates from being spent on a wakeup per byte.
+        let target = wanted64.min((self.rate.get() / 10).max(1));
+        let nanos = u128::from(target).saturating_mul(NANOS_PER_SEC);
+        let nanos = nanos.div_ceil(self.rate.get().into());
+        Err(Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX)))
+    }
+    fn wait_sync(&mut self, wanted: usize) -> usize {
+        loop {
+            match self.poll_allowance(wanted) {
+                Ok(allowed) => return allowed,
+                Err(wait) => thread::sleep(wait),
+            }
+        }
+    }
+    fn take(&mut self, n: usize) {
+        self.tokens = self.tokens.saturating_sub(u64::try_from(n).unwrap_or(u64::MAX));
+    }
+}
+
+#[cfg(feature = "local-socket-tokio")]
+mod tokio_impl {
+    use {
+        super::{Bucket, Throttled},
+        std::{
+            future::Future,
+            io,
+            pin::Pin,
+            task::{ready, Context, Poll},
+        },
+        tokio::{
+            io::{AsyncRead, AsyncWrite, ReadBuf},
+            time,
+        },
+    };
+
+    impl Bucket {
+        fn poll_wait(&mut self, cx: &mut Context<'_>, wanted: usize) -> Poll<usize> {
+            loop {
+                if let Some(sleep) = &mut self.sleep {
+                    ready!(sleep.as_mut().poll(cx));
+                    self.sleep = None;
+                }
+                match self.poll_allowance(wanted) {
+                    Ok(allowed) => return Poll::Ready(allowed),
+                    Err(wait) => self.sleep = Some(Box::pin(time::sleep(wait))),
+                }
+            }
+        }
+    }
+
+    impl<S: AsyncRead + Unpin> AsyncRead for Throttled<S> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            let slf = self.get_mut();
+            let Some(bucket) = &mut slf.recv else {
+                return Pin::new(&mut slf.inner).poll_read(cx, buf);
+            };
+            let allowed = ready!(bucket.poll_wait(cx, buf.remaining()));
+            let mut limited = buf.take(allowed);
+            ready!(Pin::new(&mut slf.inner).poll_read(cx, &mut limited))?;
+            let n = limited.filled().len();
+            // SAFETY: the first `n` bytes of the unfilled part of `buf` have just been filled
+            // through `limited`, which refers to the same memory
+            unsafe { buf.assume_init(n) };
+            buf.advance(n);
+            bucket.take(n);
+            Poll::Ready(Ok(()))
+        }
+    }
+    impl<S: AsyncWrite + Unpin> AsyncWrite for Throttled<S> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            let slf = self.get_mut();
+            let Some(bucket) = &mut slf.send else {
+                return Pin::new(&mut slf.inner).poll_write(cx, buf);
+            };
+            let allowed = ready!(bucket.poll_wait(cx, buf.len()));
+            let limited = buf.get(..allowed).unwrap_or(buf);
+            let n = ready!(Pin::new(&mut slf.inner).poll_write(cx, limited))?;
+            bucket.take(n);
+            Poll::Ready(Ok(n))
+        }
+        #[inline]
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+        }
+        #[inline]
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use {
+    super::Stream,
+    crate::local_socket::broadcaster::prune,
+    std::{
+        future, io,
+        pin::Pin,
+        task::{ready, Context, Poll},
+    },
+    tokio::io::AsyncWrite,
+};
+
+broadcaster_common! {
+    /// Tokio counterpart of the [sync `Broadcaster`](crate::local_socket::Broadcaster).
+    ///
+    /// Messages are sent to all peers concurrently on the current task, without spawning, so
+    /// that a single slow or unresponsive peer does not hold up delivery to everyone else.
+    Broadcaster
+}
+
+impl<S: AsyncWrite + Unpin> Broadcaster<S> {
+    /// Sends `msg` to all peers, waiting until it has been sent to each of them in its entirety.
+    ///
+    /// The peers for which sending failed are removed from the broadcaster and returned along
+    /// with the errors that occurred, so that they can be logged or inspected. An empty return
+    /// value means that the message was delivered to everyone.
+    ///
+    /// This method is not cancel-safe: if its future is dropped before completion, some peers may
+    /// have received only part of the message.
+    pub async fn broadcast(&mut self, msg: &[u8]) -> Vec<(S, io::Error)> {
+        let mut progress = self.peers.iter().map(|_| Progress::Writing(0)).collect::<Vec<_>>();
+        future::poll_fn(|cx| {
+            let mut pending = false;
+            for (peer, prog) in self.peers.iter_mut().zip(progress.iter_mut()) {
+                pending |= poll_send(peer, cx, msg, prog).is_pending();
+            }
+            if pending {
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+        .await;
+        let results = progress.into_iter().map(Progress::into_result).collect();
+        prune(&mut self.peers, results)
+    }
+}
+
+enum Progress {
+    Writing(usize),
+    Flushing,
+    Done(io::Result<()>),
+}
+impl Progress {
+    fn into_result(self) -> io::Result<()> {
+        match self {
+            Self::Done(result) => result,
+            Self::Writing(..) | Self::Flushing => Ok(()),
+        }
+    }
+}
+
+fn poll_send<S: AsyncWrite + Unpin>(
+    peer: &mut S,
+    cx: &mut Context<'_>,
+    msg: &[u8],
+    progress: &mut Progress,
+) -> Poll<()> {
+    loop {
+        match progress {
+            Progress::Writing(pos) => {
+                let Some(rem) = msg.get(*pos..).filter(|rem| !rem.is_empty()) else {
+                    *progress = Progress::Flushing;
+                    continue;
+                };
+                match ready!(Pin::new(&mut *peer).poll_write(cx, rem)) {
+                    Ok(0) => *progress = Progress::Done(Err(io::ErrorKind::WriteZero.into())),
+                    Ok(incr) => *pos = pos.saturating_add(incr),
+                    Err(e) => *progress = Progress::Done(Err(e)),
+                }
+            }
+            Progress::Flushing => {
+                let result = ready!(Pin::new(&mut *peer).poll_flush(cx));
+                *progress = Progress::Done(result);
+            }
+            Progress::Done(..) => return Poll::Ready(()),
+        }
+    }
+}
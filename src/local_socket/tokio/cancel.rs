@@ -0,0 +1,70 @@
+use {
+    super::{Listener, Stream},
+    crate::{
+        error::Cancelled,
+        local_socket::{
+            traits::tokio::{Listener as _, Stream as _},
+            Name,
+        },
+    },
+    std::{
+        future::{poll_fn, Future},
+        io,
+        pin::pin,
+        task::Poll,
+    },
+};
+
+/// Runs the given I/O operation until it completes or the `cancel` future does, whichever comes
+/// first, failing with [`Cancelled`] in the latter case.
+///
+/// `cancel` is typically the `.cancelled()` future of a `CancellationToken` from `tokio-util`,
+/// but can be any future – a timer, a shutdown signal, the receiving end of a channel. It is
+/// polled first, so an operation that could complete right away is still cancelled if
+/// cancellation has already been requested.
+///
+/// The operation is dropped on cancellation, so this is only as cancel-safe as the operation
+/// itself. Accepting and connecting, which is what it's mostly meant for, are cancel-safe: see
+/// [`Listener::accept_cancellable()`] and [`Stream::connect_cancellable()`].
+pub async fn with_cancel<T>(
+    op: impl Future<Output = io::Result<T>>,
+    cancel: impl Future,
+) -> io::Result<T> {
+    let mut op = pin!(op);
+    let mut cancel = pin!(cancel);
+    poll_fn(|cx| {
+        if cancel.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(Err(Cancelled.into()));
+        }
+        op.as_mut().poll(cx)
+    })
+    .await
+}
+
+impl Listener {
+    /// Like [`.accept()`](crate::local_socket::traits::tokio::Listener::accept), but gives up
+    /// with [`Cancelled`] once the `cancel` future completes. See [`with_cancel()`].
+    ///
+    /// # Cancel safety
+    /// No connection is lost when the accept is cancelled: one that arrives afterwards waits to
+    /// be accepted by the next call, as if the cancelled one was never made. This holds just as
+    /// well for dropping the future returned by `.accept()` inside `tokio::select!`, which is
+    /// what this method does under the hood.
+    #[inline]
+    pub async fn accept_cancellable(&self, cancel: impl Future) -> io::Result<Stream> {
+        with_cancel(self.accept(), cancel).await
+    }
+}
+
+impl Stream {
+    /// Like [`.connect()`](crate::local_socket::traits::tokio::Stream::connect), but gives up
+    /// with [`Cancelled`] once the `cancel` future completes. See [`with_cancel()`].
+    ///
+    /// # Cancel safety
+    /// A connection which was in the process of being established when the operation was
+    /// cancelled is closed, so the server sees a client that connected and immediately hung up.
+    #[inline]
+    pub async fn connect_cancellable(name: Name<'_>, cancel: impl Future) -> io::Result<Self> {
+        with_cancel(Self::connect(name), cancel).await
+    }
+}
@@ -0,0 +1,45 @@
+use {
+    std::{
+        future::Future,
+        io,
+        pin::Pin,
+        sync::{Mutex, MutexGuard, PoisonError},
+        task::Context,
+        time::Instant,
+    },
+    tokio::time::{self, Sleep},
+};
+
+/// The read and write deadlines of a Tokio stream.
+#[derive(Debug, Default)]
+pub(crate) struct Deadlines {
+    pub read: Deadline,
+    pub write: Deadline,
+}
+
+/// A point in time past which operations fail with [`TimedOut`](io::ErrorKind::TimedOut).
+///
+/// The timer is kept around between operations and only replaced when the deadline is changed,
+/// so that checking it is cheap.
+#[derive(Debug, Default)]
+pub(crate) struct Deadline(Mutex<Option<Pin<Box<Sleep>>>>);
+impl Deadline {
+    fn timer(&self) -> MutexGuard<'_, Option<Pin<Box<Sleep>>>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    pub fn set(&self, deadline: Option<Instant>) {
+        *self.timer() = deadline.map(|d| Box::pin(time::sleep_until(d.into())));
+    }
+    pub fn get(&self) -> Option<Instant> {
+        self.timer().as_ref().map(|timer| timer.deadline().into_std())
+    }
+    /// Fails if the deadline has passed, otherwise registers `cx` to be woken up when it does.
+    pub fn check(&self, cx: &mut Context<'_>) -> io::Result<()> {
+        match &mut *self.timer() {
+            Some(timer) if timer.as_mut().poll(cx).is_ready() => Err(expired()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn expired() -> io::Error { io::Error::new(io::ErrorKind::TimedOut, "deadline has passed") }
@@ -3,9 +3,11 @@ use crate::os::unix::uds_local_socket::tokio as uds_impl;
 #[cfg(windows)]
 use crate::os::windows::named_pipe::local_socket::tokio as np_impl;
 use {
-    super::r#trait,
-    crate::local_socket::{tokio::Stream, ListenerOptions},
-    std::io,
+    super::{incoming::Incoming, r#trait},
+    crate::local_socket::{
+        tokio::Stream, Listener as SyncListener, ListenerOptions, ListenerStats, Name,
+    },
+    std::{io, num::NonZeroU32},
 };
 
 impmod! {local_socket::dispatch_tokio as dispatch}
@@ -26,6 +28,64 @@ mkenum!(
 /// ```
 Listener);
 
+/// Conversion to and from [sync listeners](SyncListener).
+impl Listener {
+    /// Registers a sync listener with the Tokio runtime. The listener is put in nonblocking mode,
+    /// as are the streams it produces.
+    ///
+    /// # Errors
+    /// Fails if called outside of a Tokio runtime. On Windows, this always fails with
+    /// [`Unsupported`](io::ErrorKind::Unsupported), since sync named pipe listeners do not create
+    /// instances for overlapped I/O, which Tokio requires.
+    pub fn from_std(sync: SyncListener) -> io::Result<Self> {
+        match sync {
+            #[cfg(windows)]
+            SyncListener::NamedPipe(..) => Err(super::super::stream::r#enum::no_std_conversion()),
+            #[cfg(any(unix, target_vendor = "wasmer"))]
+            SyncListener::UdSocket(l) => uds_impl::Listener::try_from(l).map(Self::from),
+        }
+    }
+    /// Deregisters the listener from the Tokio runtime and turns it into a sync listener. The
+    /// listener is put back in blocking mode, as are the streams it produces.
+    ///
+    /// # Errors
+    /// On Windows, this always fails with [`Unsupported`](io::ErrorKind::Unsupported), since the
+    /// named pipe cannot be dissociated from the runtime's I/O completion port.
+    pub fn into_std(self) -> io::Result<SyncListener> {
+        match self {
+            #[cfg(windows)]
+            Self::NamedPipe(..) => Err(super::super::stream::r#enum::no_std_conversion()),
+            #[cfg(any(unix, target_vendor = "wasmer"))]
+            Self::UdSocket(l) => l.try_into().map(SyncListener::UdSocket),
+        }
+    }
+}
+
+impl Listener {
+    /// Moves the listener into a background task which accepts connections ahead of time,
+    /// returning a stream that yields them.
+    ///
+    /// Up to `queue_depth` (but at least one) connections are kept in a bounded queue, waiting
+    /// to be received from the stream. Once it is full, the task stops accepting, leaving further
+    /// clients to wait in the OS backlog until the queue has room again. If `limit` is `Some`, no
+    /// more than that many connections are accepted per second, smoothing out bursts of incoming
+    /// clients.
+    ///
+    /// Errors from accepting are yielded by the stream as well. After one, the task waits before
+    /// accepting again, starting at a millisecond and doubling with every consecutive error up to
+    /// a second, so that persistent errors like running out of file descriptors don't make it
+    /// spin.
+    ///
+    /// The background task is spawned on the current Tokio runtime and stopped when the returned
+    /// stream is dropped.
+    ///
+    /// # Panics
+    /// If called outside of a Tokio runtime.
+    pub fn incoming_with(self, limit: Option<NonZeroU32>, queue_depth: usize) -> Incoming {
+        Incoming::new(self, limit, queue_depth)
+    }
+}
+
 impl r#trait::Listener for Listener {
     type Stream = Stream;
 
@@ -38,6 +98,20 @@ impl r#trait::Listener for Listener {
         dispatch!(Self: x in self => x.accept()).await.map(Stream::from)
     }
     #[inline]
+    fn local_name(&self) -> Option<Name<'_>> { dispatch!(Self: x in self => x.local_name()) }
+    #[inline]
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+        dispatch!(Self: x in self => x.set_inheritable(inheritable))
+    }
+    #[inline]
+    fn backlog(&self) -> Option<u32> { dispatch!(Self: x in self => x.backlog()) }
+    #[inline]
+    fn set_backlog(&self, backlog: u32) -> io::Result<()> {
+        dispatch!(Self: x in self => x.set_backlog(backlog))
+    }
+    #[inline]
+    fn stats(&self) -> ListenerStats { dispatch!(Self: x in self => x.stats()) }
+    #[inline]
     fn do_not_reclaim_name_on_drop(&mut self) {
         dispatch!(Self: x in self => x.do_not_reclaim_name_on_drop())
     }
@@ -0,0 +1,100 @@
+use {
+    super::{r#enum::Listener, r#trait::Listener as _},
+    crate::local_socket::tokio::Stream,
+    futures_core::Stream as AsyncIterator,
+    std::{
+        io,
+        num::NonZeroU32,
+        pin::Pin,
+        task::{Context, Poll},
+        time::Duration,
+    },
+    tokio::{
+        sync::mpsc,
+        task::JoinHandle,
+        time::{self, MissedTickBehavior},
+    },
+};
+
+/// A stream of incoming client connections of a Tokio [`Listener`], accepted ahead of time into
+/// a bounded queue.
+///
+/// This stream is created by [`Listener::incoming_with()`] – see its documentation for more.
+/// Dropping it stops the accept loop and closes the listener.
+#[derive(Debug)]
+pub struct Incoming {
+    queue: mpsc::Receiver<io::Result<Stream>>,
+    task: JoinHandle<()>,
+}
+impl Incoming {
+    pub(super) fn new(listener: Listener, limit: Option<NonZeroU32>, queue_depth: usize) -> Self {
+        let (tx, queue) = mpsc::channel(queue_depth.max(1));
+        let task = tokio::spawn(async move {
+            let mut interval = limit.map(|limit| {
+                // Limits above a billion per second would round the period down to zero, which
+                // `interval()` panics on.
+                let period = (Duration::from_secs(1) / limit.get()).max(Duration::from_nanos(1));
+                let mut interval = time::interval(period);
+                interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                interval
+            });
+            let mut backoff = Duration::ZERO;
+            loop {
+                if let Some(interval) = &mut interval {
+                    interval.tick().await;
+                }
+                if !backoff.is_zero() {
+                    time::sleep(backoff).await;
+                }
+                // Waiting for room in the queue before accepting is what makes the backpressure
+                // reach the OS, which then holds clients in its own backlog.
+                let Ok(permit) = tx.reserve().await else { break };
+                let conn = listener.accept().await;
+                backoff = match conn {
+                    Ok(..) => Duration::ZERO,
+                    Err(..) => backoff.saturating_mul(2).clamp(MIN_BACKOFF, MAX_BACKOFF),
+                };
+                permit.send(conn);
+            }
+        });
+        Self { queue, task }
+    }
+    /// Receives the next connection, waiting for one to be accepted if the queue is empty.
+    ///
+    /// This method is cancel-safe.
+    #[inline]
+    pub async fn next(&mut self) -> io::Result<Stream> {
+        match self.queue.recv().await {
+            Some(conn) => conn,
+            None => Err(accept_loop_died()),
+        }
+    }
+    /// Returns the number of connections that have been accepted but not yet received.
+    #[inline]
+    pub fn queued(&self) -> usize { self.queue.len() }
+}
+impl AsyncIterator for Incoming {
+    type Item = io::Result<Stream>;
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut()
+            .queue
+            .poll_recv(cx)
+            .map(|conn| Some(conn.unwrap_or_else(|| Err(accept_loop_died()))))
+    }
+}
+impl Drop for Incoming {
+    #[inline]
+    fn drop(&mut self) { self.task.abort() }
+}
+
+/// How long the accept loop waits after an error before trying again, doubling with every
+/// consecutive error up to [`MAX_BACKOFF`]. Errors that persist, such as running out of file
+/// descriptors, would otherwise make it spin.
+const MIN_BACKOFF: Duration = Duration::from_millis(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The accept loop only stops if it panics or its runtime shuts down.
+fn accept_loop_died() -> io::Error {
+    io::Error::other("accept loop of the listener has stopped")
+}
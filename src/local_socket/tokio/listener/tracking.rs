@@ -0,0 +1,154 @@
+use {
+    super::{r#enum::Listener, r#trait::Listener as _},
+    crate::local_socket::{tokio::Stream, Name},
+    std::{
+        io,
+        ops::{Deref, DerefMut},
+        pin::{pin, Pin},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll},
+        time::Duration,
+    },
+    tokio::{
+        io::{AsyncRead, AsyncWrite, ReadBuf},
+        sync::Notify,
+    },
+};
+
+#[derive(Debug, Default)]
+struct Tracker {
+    open: AtomicUsize,
+    closed: Notify,
+}
+impl Tracker {
+    async fn wait_for_zero(&self) {
+        loop {
+            let mut closed = pin!(self.closed.notified());
+            // Registers the waiter before checking, so that a close in between is not missed.
+            closed.as_mut().enable();
+            if self.open.load(Ordering::Acquire) == 0 {
+                return;
+            }
+            closed.await;
+        }
+    }
+}
+
+/// A Tokio [`Listener`] which keeps count of the connections it has accepted that are still
+/// open, so that it can be [drained](Self::drain) on shutdown.
+///
+/// Created by [`Listener::tracking()`].
+#[derive(Debug)]
+pub struct TrackingListener {
+    listener: Listener,
+    tracker: Arc<Tracker>,
+}
+impl TrackingListener {
+    /// Accepts a connection, which is counted as open until the returned [`Tracked`] stream is
+    /// dropped.
+    pub async fn accept(&self) -> io::Result<Tracked<Stream>> {
+        let stream = self.listener.accept().await?;
+        self.tracker.open.fetch_add(1, Ordering::AcqRel);
+        Ok(Tracked { inner: stream, tracker: Arc::clone(&self.tracker) })
+    }
+    /// Returns the number of accepted connections that are still open.
+    #[inline]
+    pub fn open_connections(&self) -> usize { self.tracker.open.load(Ordering::Acquire) }
+    /// Returns the name that the listener is bound to.
+    #[inline]
+    pub fn local_name(&self) -> Option<Name<'_>> { self.listener.local_name() }
+    /// Borrows the underlying listener.
+    ///
+    /// Connections accepted through it directly are not counted.
+    #[inline]
+    pub fn get_ref(&self) -> &Listener { &self.listener }
+
+    /// Stops accepting connections by closing the listener, then waits until all accepted
+    /// connections have been closed or the grace period has expired, whichever comes first.
+    ///
+    /// Returns the number of connections which were still open when the grace period expired,
+    /// which is zero if all of them closed in time. They are left open, to be closed forcibly,
+    /// for instance by exiting, or to be waited on some more.
+    ///
+    /// # Panics
+    /// If called outside of a Tokio runtime with the timer enabled.
+    pub async fn drain(self, grace: Duration) -> usize {
+        let Self { listener, tracker } = self;
+        drop(listener);
+        let _ = tokio::time::timeout(grace, tracker.wait_for_zero()).await;
+        tracker.open.load(Ordering::Acquire)
+    }
+}
+
+impl Listener {
+    /// Wraps the listener into one which keeps count of the connections that it has accepted,
+    /// in preparation for [draining](TrackingListener::drain) it on shutdown.
+    #[inline]
+    pub fn tracking(self) -> TrackingListener {
+        TrackingListener { listener: self, tracker: Arc::default() }
+    }
+}
+
+/// A connection accepted by a [`TrackingListener`], which counts as open until dropped.
+///
+/// Dereferences to the stream, and forwards [`AsyncRead`] and [`AsyncWrite`] to it.
+#[derive(Debug)]
+pub struct Tracked<S> {
+    inner: S,
+    tracker: Arc<Tracker>,
+}
+impl<S> Tracked<S> {
+    /// Borrows the stream.
+    #[inline]
+    pub fn get_ref(&self) -> &S { &self.inner }
+    /// Mutably borrows the stream.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut S { &mut self.inner }
+}
+impl<S> Deref for Tracked<S> {
+    type Target = S;
+    #[inline]
+    fn deref(&self) -> &S { &self.inner }
+}
+impl<S> DerefMut for Tracked<S> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut S { &mut self.inner }
+}
+impl<S> Drop for Tracked<S> {
+    fn drop(&mut self) {
+        if self.tracker.open.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.tracker.closed.notify_waiters();
+        }
+    }
+}
+impl<S: AsyncRead + Unpin> AsyncRead for Tracked<S> {
+    #[inline]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
+    }
+}
+impl<S: AsyncWrite + Unpin> AsyncWrite for Tracked<S> {
+    #[inline]
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+    #[inline]
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
@@ -1,6 +1,6 @@
 use {
     crate::{
-        local_socket::{tokio::stream::r#trait::Stream, ListenerOptions},
+        local_socket::{tokio::stream::r#trait::Stream, ListenerOptions, ListenerStats, Name},
         Sealed,
     },
     std::{future::Future, io},
@@ -24,6 +24,35 @@ pub trait Listener: Send + Sync + Sized + Sealed {
     /// finishes only when a client is connected.
     fn accept(&self) -> impl Future<Output = io::Result<Self::Stream>> + Send + Sync;
 
+    /// Returns the name the listener is bound to. See
+    /// [the sync counterpart](crate::local_socket::traits::Listener::local_name) for details.
+    fn local_name(&self) -> Option<Name<'_>>;
+
+    /// Sets whether the listener is to be inherited by child processes spawned after this call.
+    /// See [the sync counterpart](crate::local_socket::traits::Listener::set_inheritable) for
+    /// details.
+    ///
+    /// On Windows, if an `.accept()` call is in progress, the instance it is waiting on is left
+    /// unchanged, and so is the stream it produces.
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()>;
+
+    /// Returns the accept backlog that was last requested for the listener. See
+    /// [the sync counterpart](crate::local_socket::traits::Listener::backlog) for details.
+    #[inline]
+    fn backlog(&self) -> Option<u32> { None }
+
+    /// Changes the accept backlog of the listener. See
+    /// [the sync counterpart](crate::local_socket::traits::Listener::set_backlog) for details.
+    #[inline]
+    fn set_backlog(&self, backlog: u32) -> io::Result<()> {
+        let _ = backlog;
+        Err(io::Error::new(io::ErrorKind::Unsupported, "listener has no backlog"))
+    }
+
+    /// Returns a snapshot of the listener's counters. See
+    /// [the sync counterpart](crate::local_socket::traits::Listener::stats) for details.
+    fn stats(&self) -> ListenerStats;
+
     /// Disables [name reclamation](super::enum::Listener#name-reclamation) on the listener.
     fn do_not_reclaim_name_on_drop(&mut self);
 }
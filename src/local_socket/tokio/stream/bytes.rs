@@ -0,0 +1,58 @@
+use {
+    super::r#enum::{RecvHalf, SendHalf, Stream},
+    crate::local_socket::stream::bytes::reserve_spare,
+    ::bytes::{Buf, BufMut, BytesMut},
+    std::{
+        future, io,
+        task::{ready, Poll},
+    },
+    tokio::io::ReadBuf,
+};
+
+macro_rules! read_bytes {
+    ($ty:ident) => {
+        #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytes")))]
+        impl $ty {
+            /// Receives data into the spare capacity of `buf`, growing it first if it has none,
+            /// and returns the number of bytes received, which are appended to the buffer.
+            ///
+            /// The spare capacity is not zeroed out beforehand, making this a cheaper alternative
+            /// to resizing the buffer and then receiving into it.
+            ///
+            /// This method is cancel-safe.
+            pub async fn read_bytes(&self, buf: &mut BytesMut) -> io::Result<usize> {
+                future::poll_fn(|cx| {
+                    let mut rb = ReadBuf::uninit(reserve_spare(buf));
+                    ready!(self.poll_read(cx, &mut rb))?;
+                    let received = rb.filled().len();
+                    // SAFETY: poll_read() has initialized that many bytes of the spare capacity
+                    unsafe { buf.advance_mut(received) };
+                    Poll::Ready(Ok(received))
+                })
+                .await
+            }
+        }
+    };
+}
+macro_rules! write_buf {
+    ($ty:ident) => {
+        #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytes")))]
+        impl $ty {
+            /// Sends data from `buf`, advancing it past the bytes that were sent and returning
+            /// their number.
+            ///
+            /// This method is cancel-safe.
+            pub async fn write_buf(&self, buf: &mut impl Buf) -> io::Result<usize> {
+                let sent = future::poll_fn(|cx| self.poll_write(cx, buf.chunk())).await?;
+                let sent = sent.min(buf.remaining());
+                buf.advance(sent);
+                Ok(sent)
+            }
+        }
+    };
+}
+
+read_bytes!(Stream);
+read_bytes!(RecvHalf);
+write_buf!(Stream);
+write_buf!(SendHalf);
@@ -1,16 +1,22 @@
 #[cfg(any(unix, target_vendor = "wasmer"))]
-use crate::os::unix::uds_local_socket::tokio as uds_impl;
+use {crate::os::unix::uds_local_socket::tokio as uds_impl, tokio::net::UnixStream};
 #[cfg(windows)]
-use crate::os::windows::named_pipe::local_socket::tokio as np_impl;
+use {
+    crate::{error::ConversionError, os::windows::named_pipe::local_socket::tokio as np_impl},
+    tokio::net::windows::named_pipe::{NamedPipeClient, NamedPipeServer},
+};
 use {
     super::r#trait,
-    crate::local_socket::Name,
+    crate::local_socket::{
+        tokio::WriterHandle, ConnectOptions, Name, PeerCredentials, Stream as SyncStream,
+    },
     std::{
-        io,
+        io::{self, IoSliceMut},
         pin::Pin,
         task::{Context, Poll},
+        time::Instant,
     },
-    tokio::io::{AsyncRead, AsyncWrite, ReadBuf},
+    tokio::io::{AsyncRead, AsyncWrite, Interest, ReadBuf, Ready},
 };
 
 impmod! {local_socket::dispatch_tokio as dispatch}
@@ -29,6 +35,26 @@ macro_rules! dispatch_read {
         impl AsyncRead for $ty {
             dispatch_read!(@iw $ty);
         }
+        impl $ty {
+            /// Attempts to receive data into `buf`, without having to pin the object or go
+            /// through [`AsyncRead`].
+            ///
+            /// If no data is available, `Poll::Pending` is returned and the waker from `cx` is
+            /// registered to be woken up once there is.
+            ///
+            /// # Cancel safety
+            /// No data is consumed unless `Poll::Ready` is returned, so nothing is lost if the
+            /// caller stops polling after `Poll::Pending`. Only the waker from the most recent
+            /// call is woken up.
+            #[inline]
+            pub fn poll_read(
+                &self,
+                cx: &mut Context<'_>,
+                buf: &mut ReadBuf<'_>,
+            ) -> Poll<io::Result<()>> {
+                Pin::new(&mut &*self).poll_read(cx, buf)
+            }
+        }
     };
 }
 macro_rules! dispatch_write {
@@ -55,6 +81,35 @@ macro_rules! dispatch_write {
         impl AsyncWrite for $ty {
             dispatch_write!(@iw $ty);
         }
+        impl $ty {
+            /// Attempts to send data from `buf`, without having to pin the object or go through
+            /// [`AsyncWrite`].
+            ///
+            /// If there is no room in the send buffer, `Poll::Pending` is returned and the waker
+            /// from `cx` is registered to be woken up once there is.
+            ///
+            /// # Cancel safety
+            /// No data is sent unless `Poll::Ready` is returned, so the caller can stop polling
+            /// after `Poll::Pending` without having to account for a partial send. Only the waker
+            /// from the most recent call is woken up.
+            #[inline]
+            pub fn poll_write(
+                &self,
+                cx: &mut Context<'_>,
+                buf: &[u8],
+            ) -> Poll<io::Result<usize>> {
+                Pin::new(&mut &*self).poll_write(cx, buf)
+            }
+            /// Flushes the stream, without having to pin the object or go through
+            /// [`AsyncWrite`].
+            ///
+            /// Local socket streams are unbuffered, so this is always a successful no-op, and is
+            /// thus trivially cancel-safe.
+            #[inline]
+            pub fn poll_flush(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Pin::new(&mut &*self).poll_flush(cx)
+            }
+        }
     };
 }
 
@@ -62,6 +117,11 @@ mkenum!(
 /// Tokio-based local socket byte stream, obtained either from [`Listener`](super::super::Listener)
 /// or by connecting to an existing local socket.
 ///
+/// # Concurrent receiving and sending
+/// `&Stream` implements [`AsyncRead`] and [`AsyncWrite`] on every platform, so receiving and
+/// sending can be done concurrently on the same task (with `tokio::join!`, for instance) by using
+/// two shared references, without having to [split](r#trait::Stream::split) the stream.
+///
 /// # Examples
 ///
 /// ## Basic client
@@ -70,12 +130,233 @@ mkenum!(
 /// ```
 Stream);
 
+impl Stream {
+    /// Splits the stream into a receive half and a cheaply clonable [`WriterHandle`], which can
+    /// be used to send messages from multiple tasks without interleaving their bytes.
+    #[inline]
+    pub fn writer_handle(self) -> (RecvHalf, WriterHandle) {
+        let (rh, sh) = r#trait::Stream::split(self);
+        (rh, WriterHandle::new(sh))
+    }
+}
+/// Readiness.
+impl Stream {
+    /// Waits for the stream to become ready for any of the given kinds of I/O, returning the
+    /// kinds that it is ready for.
+    ///
+    /// This is the building block of receive and send loops that don't go through
+    /// [`AsyncRead`] and [`AsyncWrite`]: wait for readiness, then perform nonblocking operations
+    /// until one fails with [`WouldBlock`](io::ErrorKind::WouldBlock), then wait again.
+    /// Readiness can be spurious, so the operation that follows may fail with `WouldBlock` right
+    /// away.
+    ///
+    /// # Cancel safety
+    /// This method is cancel-safe.
+    #[inline]
+    pub async fn ready(&self, interest: Interest) -> io::Result<Ready> {
+        dispatch!(Self: x in self => x.ready(interest).await)
+    }
+    /// Waits for the stream to become readable. Shorthand for `.ready(Interest::READABLE)`.
+    #[inline]
+    pub async fn readable(&self) -> io::Result<()> {
+        self.ready(Interest::READABLE).await.map(drop)
+    }
+    /// Waits for the stream to become writable. Shorthand for `.ready(Interest::WRITABLE)`.
+    #[inline]
+    pub async fn writable(&self) -> io::Result<()> {
+        self.ready(Interest::WRITABLE).await.map(drop)
+    }
+
+    /// Receives data into `buf` if any is available right away, failing with
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) otherwise, without waiting. End of file is
+    /// reported as `Ok(0)`.
+    ///
+    /// With named pipes, this checks whether a read started on the pipe completes immediately.
+    #[inline]
+    pub fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        dispatch!(Self: x in self => x.try_read(buf))
+    }
+    /// Like [`.try_read()`](Self::try_read), but receives into multiple buffers.
+    #[inline]
+    pub fn try_read_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        dispatch!(Self: x in self => x.try_read_vectored(bufs))
+    }
+    /// Sends as much of `buf` as can be sent right away, failing with
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) if none of it can, without waiting.
+    #[inline]
+    pub fn try_write(&self, buf: &[u8]) -> io::Result<usize> {
+        dispatch!(Self: x in self => x.try_write(buf))
+    }
+}
+/// Closing.
+impl Stream {
+    /// Closes the stream in an orderly fashion, making sure that everything which has been sent
+    /// reaches the other side.
+    ///
+    /// Dropping a Tokio stream closes it right away, without waiting for anything: sent data
+    /// that the other side has not received yet may be lost on Windows, where named pipes are
+    /// flushed in the background after being dropped, which does not happen if the program exits
+    /// first. This method performs the whole shutdown sequence instead:
+    /// - With Unix domain sockets, the sending direction is shut down, so that the other side
+    ///   sees end of file, after which this waits for the other side to close its end in
+    ///   response. Any data that it sends in the meantime is discarded.
+    /// - With named pipes, this waits for the other side to receive everything that has been
+    ///   sent, which is what the pipe acknowledges in place of end of file.
+    ///
+    /// The wait can take arbitrarily long if the other side does not cooperate. The
+    /// [read deadline](Self::set_read_deadline) applies to it on Unix, and the whole operation
+    /// can be bounded with `tokio::time::timeout()`.
+    pub async fn close(self) -> io::Result<()> {
+        match self {
+            #[cfg(windows)]
+            Self::NamedPipe(s) => s.close().await,
+            #[cfg(any(unix, target_vendor = "wasmer"))]
+            Self::UdSocket(s) => s.close().await,
+        }
+    }
+}
+/// Deadlines.
+impl Stream {
+    /// Sets the point in time past which receiving fails with
+    /// [`TimedOut`](io::ErrorKind::TimedOut), or lifts the deadline if `None` is given.
+    ///
+    /// The deadline applies to every receive operation from then on. Once it has passed, all of
+    /// them fail until it is moved or lifted, which makes it easy to bound a whole exchange
+    /// instead of every single call in it. Operations which are already waiting for data when
+    /// the deadline is changed only pick up the new one the next time they are polled.
+    ///
+    /// Halves obtained by [splitting](r#trait::Stream::split) the stream do not inherit its
+    /// deadlines.
+    ///
+    /// # Panics
+    /// If a deadline is given outside of a Tokio runtime with the timer enabled.
+    #[inline]
+    pub fn set_read_deadline(&self, deadline: Option<Instant>) {
+        dispatch!(Self: x in self => x.deadlines().read.set(deadline))
+    }
+    /// Returns the deadline for receiving, as set by
+    /// [`.set_read_deadline()`](Self::set_read_deadline).
+    #[inline]
+    pub fn read_deadline(&self) -> Option<Instant> {
+        dispatch!(Self: x in self => x.deadlines().read.get())
+    }
+    /// Sets the point in time past which sending fails with
+    /// [`TimedOut`](io::ErrorKind::TimedOut), or lifts the deadline if `None` is given.
+    ///
+    /// Works like [`.set_read_deadline()`](Self::set_read_deadline) otherwise.
+    ///
+    /// # Panics
+    /// If a deadline is given outside of a Tokio runtime with the timer enabled.
+    #[inline]
+    pub fn set_write_deadline(&self, deadline: Option<Instant>) {
+        dispatch!(Self: x in self => x.deadlines().write.set(deadline))
+    }
+    /// Returns the deadline for sending, as set by
+    /// [`.set_write_deadline()`](Self::set_write_deadline).
+    #[inline]
+    pub fn write_deadline(&self) -> Option<Instant> {
+        dispatch!(Self: x in self => x.deadlines().write.get())
+    }
+}
+/// Conversion to and from [sync streams](SyncStream).
+impl Stream {
+    /// Registers a sync stream with the Tokio runtime, allowing connections to be established
+    /// before a runtime exists. The stream is put in nonblocking mode.
+    ///
+    /// # Errors
+    /// Fails if called outside of a Tokio runtime. On Windows, this always fails with
+    /// [`Unsupported`](io::ErrorKind::Unsupported), since sync named pipes are not opened for
+    /// overlapped I/O, which Tokio requires.
+    pub fn from_std(sync: SyncStream) -> io::Result<Self> {
+        match sync {
+            #[cfg(windows)]
+            SyncStream::NamedPipe(..) => Err(no_std_conversion()),
+            #[cfg(any(unix, target_vendor = "wasmer"))]
+            SyncStream::UdSocket(s) => uds_impl::Stream::try_from(s).map(Self::from),
+        }
+    }
+    /// Deregisters the stream from the Tokio runtime and turns it into a sync stream, for
+    /// handing it off to blocking code. The stream is put back in blocking mode.
+    ///
+    /// # Errors
+    /// On Windows, this always fails with [`Unsupported`](io::ErrorKind::Unsupported), since the
+    /// named pipe cannot be dissociated from the runtime's I/O completion port.
+    pub fn into_std(self) -> io::Result<SyncStream> {
+        match self {
+            #[cfg(windows)]
+            Self::NamedPipe(..) => Err(no_std_conversion()),
+            #[cfg(any(unix, target_vendor = "wasmer"))]
+            Self::UdSocket(s) => s.try_into().map(SyncStream::UdSocket),
+        }
+    }
+}
+
+#[cfg(any(unix, target_vendor = "wasmer"))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(unix, target_vendor = "wasmer")))]
+impl From<UnixStream> for Stream {
+    #[inline]
+    fn from(s: UnixStream) -> Self { Self::UdSocket(uds_impl::Stream::from(s)) }
+}
+#[cfg(any(unix, target_vendor = "wasmer"))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(unix, target_vendor = "wasmer")))]
+impl From<Stream> for UnixStream {
+    #[inline]
+    fn from(s: Stream) -> Self {
+        let Stream::UdSocket(s) = s;
+        s.into()
+    }
+}
+#[cfg(windows)]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(windows)))]
+impl From<NamedPipeServer> for Stream {
+    #[inline]
+    fn from(s: NamedPipeServer) -> Self { Self::NamedPipe(np_impl::Stream::from(s)) }
+}
+#[cfg(windows)]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(windows)))]
+impl From<NamedPipeClient> for Stream {
+    #[inline]
+    fn from(s: NamedPipeClient) -> Self { Self::NamedPipe(np_impl::Stream::from(s)) }
+}
+/// Fails if the stream is client-side, returning it back.
+#[cfg(windows)]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(windows)))]
+impl TryFrom<Stream> for NamedPipeServer {
+    type Error = ConversionError<Stream>;
+    #[inline]
+    fn try_from(s: Stream) -> Result<Self, Self::Error> {
+        let Stream::NamedPipe(s) = s;
+        s.try_into().map_err(|e: ConversionError<_>| e.map_source(Stream::from))
+    }
+}
+/// Fails if the stream is server-side, returning it back.
+#[cfg(windows)]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(windows)))]
+impl TryFrom<Stream> for NamedPipeClient {
+    type Error = ConversionError<Stream>;
+    #[inline]
+    fn try_from(s: Stream) -> Result<Self, Self::Error> {
+        let Stream::NamedPipe(s) = s;
+        s.try_into().map_err(|e: ConversionError<_>| e.map_source(Stream::from))
+    }
+}
+
+#[cfg(windows)]
+pub(crate) fn no_std_conversion() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "named pipes cannot be converted between their sync and Tokio variants",
+    )
+}
+
 impl r#trait::Stream for Stream {
     type RecvHalf = RecvHalf;
     type SendHalf = SendHalf;
 
     #[inline]
-    async fn connect(name: Name<'_>) -> io::Result<Self> { dispatch::connect(name).await }
+    async fn from_options(options: &ConnectOptions<'_>) -> io::Result<Self> {
+        dispatch::connect(options).await
+    }
     fn split(self) -> (RecvHalf, SendHalf) {
         match self {
             #[cfg(windows)]
@@ -105,6 +386,34 @@ impl r#trait::Stream for Stream {
         }
     }
 }
+impl crate::local_socket::traits::StreamCommon for Stream {
+    #[inline]
+    fn peer_name(&self) -> Option<Name<'static>> { dispatch!(Self: x in self => x.peer_name()) }
+    #[inline]
+    fn peer_credentials(&self) -> io::Result<PeerCredentials> {
+        dispatch!(Self: x in self => x.peer_credentials())
+    }
+    #[inline]
+    fn send_buffer_size(&self) -> io::Result<usize> {
+        dispatch!(Self: x in self => x.send_buffer_size())
+    }
+    #[inline]
+    fn recv_buffer_size(&self) -> io::Result<usize> {
+        dispatch!(Self: x in self => x.recv_buffer_size())
+    }
+    #[inline]
+    fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        dispatch!(Self: x in self => x.set_send_buffer_size(size))
+    }
+    #[inline]
+    fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        dispatch!(Self: x in self => x.set_recv_buffer_size(size))
+    }
+    #[inline]
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+        dispatch!(Self: x in self => x.set_inheritable(inheritable))
+    }
+}
 multimacro! {
     Stream,
     dispatch_read,
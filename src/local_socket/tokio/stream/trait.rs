@@ -3,7 +3,7 @@
 use {
     crate::{
         bound_util::{RefTokioAsyncRead, RefTokioAsyncWrite},
-        local_socket::Name,
+        local_socket::{traits::StreamCommon, ConnectOptions, Name},
         Sealed,
     },
     std::{future::Future, io},
@@ -17,7 +17,15 @@ use {
 /// makes it a trait object of sorts. See its documentation for more on the semantics of the methods
 /// seen here.
 pub trait Stream:
-    AsyncRead + RefTokioAsyncRead + AsyncWrite + RefTokioAsyncWrite + Send + Sync + Sized + Sealed
+    AsyncRead
+    + RefTokioAsyncRead
+    + AsyncWrite
+    + RefTokioAsyncWrite
+    + StreamCommon
+    + Send
+    + Sync
+    + Sized
+    + Sealed
 {
     /// Receive half type returned by [`.split()`](Stream::split).
     type RecvHalf: RecvHalf<Stream = Self>;
@@ -25,7 +33,17 @@ pub trait Stream:
     type SendHalf: SendHalf<Stream = Self>;
 
     /// Asynchronously connects to a remote local socket server.
-    fn connect(name: Name<'_>) -> impl Future<Output = io::Result<Self>> + Send + Sync;
+    ///
+    /// This is a shorthand for [`ConnectOptions`] with only the name set.
+    #[inline]
+    fn connect(name: Name<'_>) -> impl Future<Output = io::Result<Self>> + Send + Sync {
+        async move { ConnectOptions::new().name(name).connect_tokio_as::<Self>().await }
+    }
+
+    /// Asynchronously connects to a remote local socket server using the specified options.
+    fn from_options(
+        options: &ConnectOptions<'_>,
+    ) -> impl Future<Output = io::Result<Self>> + Send + Sync;
 
     /// Splits a stream into a receive half and a send half, which can be used to receive from and
     /// send to the stream concurrently from different Tokio tasks, entailing a memory allocation.
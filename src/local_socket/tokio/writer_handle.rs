@@ -0,0 +1,57 @@
+use {
+    super::SendHalf,
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+        sync::Arc,
+    },
+    tokio::{
+        io::{AsyncWrite, AsyncWriteExt},
+        sync::Mutex,
+    },
+};
+
+/// Tokio counterpart of the [sync `WriterHandle`](crate::local_socket::WriterHandle).
+///
+/// Sends are serialized with an asynchronous mutex, which is held for the entire duration of each
+/// send, so that tasks waiting for their turn do not block the runtime.
+pub struct WriterHandle<S: AsyncWrite + Unpin = SendHalf> {
+    inner: Arc<Mutex<S>>,
+}
+impl<S: AsyncWrite + Unpin> WriterHandle<S> {
+    /// Wraps the given send half or stream.
+    #[inline]
+    pub fn new(inner: S) -> Self { Self { inner: Arc::new(Mutex::new(inner)) } }
+
+    /// Sends `msg` in its entirety, waiting until no other clone of the handle is sending and
+    /// then until all of the message has been sent.
+    ///
+    /// This method is not cancel-safe: if its future is dropped while the message is being sent,
+    /// the peer may receive only part of it. Dropping the future while waiting for other clones
+    /// to finish sending is harmless, however.
+    pub async fn send(&self, msg: &[u8]) -> io::Result<()> {
+        let mut inner = self.inner.lock().await;
+        inner.write_all(msg).await?;
+        inner.flush().await
+    }
+
+    /// Unwraps the send half or stream if this is the only clone of the handle, returning the
+    /// handle back otherwise.
+    pub fn try_into_inner(self) -> Result<S, Self> {
+        Arc::try_unwrap(self.inner).map(Mutex::into_inner).map_err(|inner| Self { inner })
+    }
+}
+
+impl<S: AsyncWrite + Unpin> Clone for WriterHandle<S> {
+    #[inline]
+    fn clone(&self) -> Self { Self { inner: Arc::clone(&self.inner) } }
+}
+impl<S: AsyncWrite + Unpin> From<S> for WriterHandle<S> {
+    #[inline]
+    fn from(inner: S) -> Self { Self::new(inner) }
+}
+impl<S: AsyncWrite + Unpin + Debug> Debug for WriterHandle<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WriterHandle").field(&self.inner).finish()
+    }
+}
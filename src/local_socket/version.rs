@@ -0,0 +1,150 @@
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    io::{self, prelude::*},
+};
+
+/// Sent by both sides before their protocol version, so that a peer which does not perform the
+/// negotiation at all is told apart from one that speaks a different version.
+const MAGIC: [u8; 4] = *b"IPV\x01";
+/// Magic, major version, minor version and length of the protocol name.
+const HEADER_LEN: usize = 9;
+
+/// Identifier of an application-level protocol and the version of it that one side of a
+/// connection speaks, as exchanged by [`negotiate_version()`].
+///
+/// Two versions are [compatible](Self::is_compatible_with) if they are of the same protocol and
+/// have the same major version. The minor version is there for backwards-compatible additions,
+/// which both sides can agree on by using the lower of their two minor versions.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct ProtocolVersion {
+    /// Name of the protocol, such as the name of the program or service. At most 255 bytes long.
+    pub name: Cow<'static, str>,
+    /// Major version, which must be the same on both sides.
+    pub major: u16,
+    /// Minor version, which may differ between the two sides.
+    pub minor: u16,
+}
+impl ProtocolVersion {
+    /// Creates a protocol identifier from its name and version.
+    #[inline]
+    pub fn new(name: impl Into<Cow<'static, str>>, major: u16, minor: u16) -> Self {
+        Self { name: name.into(), major, minor }
+    }
+    /// Returns whether the two sides can talk to each other.
+    #[inline]
+    pub fn is_compatible_with(&self, other: &Self) -> bool {
+        self.name == other.name && self.major == other.major
+    }
+}
+impl Display for ProtocolVersion {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}.{}", self.name, self.major, self.minor)
+    }
+}
+
+/// Error payload of [`negotiate_version()`] when the two sides speak incompatible versions of
+/// the protocol, or different protocols altogether.
+///
+/// It is delivered inside an [`io::Error`] of kind [`InvalidData`](io::ErrorKind::InvalidData),
+/// from which [`VersionMismatch::find()`] retrieves it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionMismatch {
+    /// The version that this side speaks.
+    pub local: ProtocolVersion,
+    /// The version that the other side speaks.
+    pub remote: ProtocolVersion,
+}
+impl VersionMismatch {
+    /// Returns the version mismatch which caused the given error, if that's what caused it.
+    #[inline]
+    pub fn find(e: &io::Error) -> Option<&Self> { e.get_ref().and_then(|e| e.downcast_ref()) }
+}
+impl Display for VersionMismatch {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "protocol version mismatch: this side speaks {}, ", self.local)?;
+        write!(f, "the other side speaks {}", self.remote)
+    }
+}
+impl Error for VersionMismatch {}
+impl From<VersionMismatch> for io::Error {
+    #[inline]
+    fn from(e: VersionMismatch) -> Self { io::Error::new(io::ErrorKind::InvalidData, e) }
+}
+
+/// Tells the other side which protocol and version this side speaks, and waits for it to do the
+/// same, returning the version that the other side speaks.
+///
+/// Both sides must call this at the start of the connection, before anything else is sent and
+/// before any other handshake that relies on framing of its own, so that a daemon and a client
+/// built from different releases fail fast instead of misinterpreting each other's data. The
+/// stream must be in blocking mode.
+///
+/// # Errors
+/// Fails with an [`InvalidData`](io::ErrorKind::InvalidData) error carrying a
+/// [`VersionMismatch`] if the versions are not [compatible](ProtocolVersion::is_compatible_with),
+/// and with a plain `InvalidData` error if the other side did not send a valid negotiation
+/// message, which usually means that it's not performing the negotiation. Also fails with
+/// [`InvalidInput`](io::ErrorKind::InvalidInput) if the protocol name is longer than 255 bytes.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::local_socket::{
+///     negotiate_version, prelude::*, GenericNamespaced, ProtocolVersion, Stream,
+///     VersionMismatch,
+/// };
+///
+/// let mut stream = Stream::connect("daemon.sock".to_ns_name::<GenericNamespaced>()?)?;
+/// let local = ProtocolVersion::new("my-daemon", 2, 1);
+/// match negotiate_version(&mut stream, &local) {
+///     Ok(remote) => println!("talking to {remote}"),
+///     Err(e) => match VersionMismatch::find(&e) {
+///         Some(mismatch) => eprintln!("please upgrade: {mismatch}"),
+///         None => return Err(e),
+///     },
+/// }
+/// # std::io::Result::Ok(())
+/// ```
+pub fn negotiate_version(
+    stream: &mut (impl Read + Write),
+    local: &ProtocolVersion,
+) -> io::Result<ProtocolVersion> {
+    let name = local.name.as_bytes();
+    let name_len = u8::try_from(name.len()).map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidInput, "protocol name longer than 255 bytes")
+    })?;
+    let [m0, m1, m2, m3] = MAGIC;
+    let [ma0, ma1] = local.major.to_be_bytes();
+    let [mi0, mi1] = local.minor.to_be_bytes();
+    let mut msg = Vec::with_capacity(HEADER_LEN.saturating_add(name.len()));
+    msg.extend_from_slice(&[m0, m1, m2, m3, ma0, ma1, mi0, mi1, name_len]);
+    msg.extend_from_slice(name);
+    stream.write_all(&msg)?;
+    stream.flush()?;
+
+    let mut header = [0; HEADER_LEN];
+    stream.read_exact(&mut header)?;
+    let [p0, p1, p2, p3, ma0, ma1, mi0, mi1, name_len] = header;
+    if [p0, p1, p2, p3] != MAGIC {
+        return Err(invalid("malformed protocol version negotiation message"));
+    }
+    let mut name = vec![0; usize::from(name_len)];
+    stream.read_exact(&mut name)?;
+    let name = String::from_utf8(name).map_err(|_| invalid("protocol name is not UTF-8"))?;
+    let remote = ProtocolVersion::new(
+        name,
+        u16::from_be_bytes([ma0, ma1]),
+        u16::from_be_bytes([mi0, mi1]),
+    );
+
+    if !local.is_compatible_with(&remote) {
+        return Err(VersionMismatch { local: local.clone(), remote }.into());
+    }
+    Ok(remote)
+}
+
+fn invalid(msg: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, msg) }
@@ -0,0 +1,77 @@
+use {
+    super::SendHalf,
+    std::{
+        fmt::{self, Debug, Formatter},
+        io::{self, prelude::*},
+        sync::{Arc, Mutex, PoisonError},
+    },
+};
+
+/// A cheaply clonable handle for sending to a stream from multiple threads without interleaving
+/// the bytes of concurrent messages.
+///
+/// Every send through the handle takes a lock for its entire duration, so that each message is
+/// sent in its entirety before the next one starts. Cloning the handle only clones an [`Arc`].
+///
+/// Created by [`Stream::writer_handle()`](super::Stream::writer_handle) or, for other kinds of
+/// send halves and streams, by [`WriterHandle::new()`].
+pub struct WriterHandle<S: Write = SendHalf> {
+    inner: Arc<Mutex<S>>,
+}
+impl<S: Write> WriterHandle<S> {
+    /// Wraps the given send half or stream.
+    #[inline]
+    pub fn new(inner: S) -> Self { Self { inner: Arc::new(Mutex::new(inner)) } }
+
+    /// Sends `msg` in its entirety, blocking until no other clone of the handle is sending and
+    /// then until all of the message has been sent.
+    ///
+    /// If a previous send panicked, the lock is taken regardless.
+    pub fn send(&self, msg: &[u8]) -> io::Result<()> {
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        inner.write_all(msg)?;
+        inner.flush()
+    }
+
+    /// Unwraps the send half or stream if this is the only clone of the handle, returning the
+    /// handle back otherwise.
+    pub fn try_into_inner(self) -> Result<S, Self> {
+        Arc::try_unwrap(self.inner)
+            .map(|mtx| mtx.into_inner().unwrap_or_else(PoisonError::into_inner))
+            .map_err(|inner| Self { inner })
+    }
+}
+
+/// Every call to `.write()` sends the whole buffer as one message, as if by
+/// [`.send()`](WriterHandle::send).
+impl<S: Write> Write for &WriterHandle<S> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.send(buf)?;
+        Ok(buf.len())
+    }
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+/// Every call to `.write()` sends the whole buffer as one message, as if by
+/// [`.send()`](WriterHandle::send).
+impl<S: Write> Write for WriterHandle<S> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> { (&*self).write(buf) }
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+impl<S: Write> Clone for WriterHandle<S> {
+    #[inline]
+    fn clone(&self) -> Self { Self { inner: Arc::clone(&self.inner) } }
+}
+impl<S: Write> From<S> for WriterHandle<S> {
+    #[inline]
+    fn from(inner: S) -> Self { Self::new(inner) }
+}
+impl<S: Write + Debug> Debug for WriterHandle<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WriterHandle").field(&self.inner).finish()
+    }
+}
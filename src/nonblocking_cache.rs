@@ -0,0 +1,40 @@
+use {
+    crate::poison_error,
+    std::{
+        io,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// The last known nonblocking mode of an OS object, which lets wrappers skip the system calls
+/// that would leave it unchanged.
+///
+/// Duplicated handles and file descriptors share their nonblocking mode with the original, which
+/// is why clones of the wrapper must [share](Self::share) the cache. Changes made to the mode of
+/// the object itself, bypassing the wrapper, are not seen; objects that come from outside start
+/// out with an unknown mode.
+#[derive(Debug)]
+pub(crate) struct NonblockingCache(Arc<Mutex<Option<bool>>>);
+impl NonblockingCache {
+    #[inline]
+    pub fn unknown() -> Self { Self(Arc::new(Mutex::new(None))) }
+    #[inline]
+    #[cfg_attr(windows, allow(dead_code))] // Named pipes start out with an unknown mode
+    pub fn known(nonblocking: bool) -> Self { Self(Arc::new(Mutex::new(Some(nonblocking)))) }
+    #[inline]
+    pub fn share(&self) -> Self { Self(Arc::clone(&self.0)) }
+
+    /// Calls `set` to change the mode, unless it's already the requested one. The lock is held
+    /// for the duration of the call, so that concurrent changes can't leave the cache out of
+    /// sync with the object.
+    pub fn set(&self, nonblocking: bool, set: impl FnOnce() -> io::Result<()>) -> io::Result<()> {
+        let mut known = self.0.lock().map_err(poison_error)?;
+        if *known == Some(nonblocking) {
+            return Ok(());
+        }
+        *known = None;
+        set()?;
+        *known = Some(nonblocking);
+        Ok(())
+    }
+}
@@ -12,12 +12,27 @@
 pub(crate) mod imports;
 
 mod c_wrappers;
+#[cfg(unix)]
+pub(crate) mod condvar;
 mod fdops;
+#[cfg(unix)]
+pub(crate) mod fs_lock;
+#[cfg(unix)]
+pub(crate) mod mutex;
+pub(crate) mod process;
+#[cfg(unix)]
+pub(crate) mod signal;
 // Exported into child modules specifically, not this file.
 use fdops::*;
 
 pub mod fifo_file;
 pub mod local_socket;
+#[cfg(unix)]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(unix)))]
+pub mod semaphore;
+#[cfg(unix)]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(unix)))]
+pub mod shared_memory;
 pub mod uds_local_socket;
 pub mod unnamed_pipe;
 
@@ -6,12 +6,15 @@ use std::os::android::net::SocketAddrExt;
 use std::os::linux::net::SocketAddrExt;
 use {
     super::unixprelude::*,
-    crate::AsPtr,
+    crate::{local_socket::PeerCredentials, AsMutPtr, AsPtr},
     libc::{sockaddr_un, AF_UNIX},
     std::{
-        io,
-        mem::{transmute, zeroed},
+        ffi::{CStr, CString},
+        fs::{self, OpenOptions},
+        io::{self, IoSlice},
+        mem::{size_of, transmute, zeroed, MaybeUninit},
         os::unix::net::SocketAddr,
+        path::Path,
     },
 };
 
@@ -81,9 +84,15 @@ fn get_flflags(fd: BorrowedFd<'_>) -> io::Result<c_int> {
 fn set_flflags(fd: BorrowedFd<'_>, flags: c_int) -> io::Result<()> {
     unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFL, flags) != -1 }.true_val_or_errno(())
 }
+/// Sets or clears `O_NONBLOCK`, leaving the other flags alone and skipping the `F_SETFL` if the
+/// flag is already in the requested state.
 pub(super) fn set_nonblocking(fd: BorrowedFd<'_>, nonblocking: bool) -> io::Result<()> {
-    let old_flags = get_flflags(fd)? & libc::O_NONBLOCK;
-    set_flflags(fd, old_flags | if nonblocking { libc::O_NONBLOCK } else { 0 })
+    let old_flags = get_flflags(fd)?;
+    if (old_flags & libc::O_NONBLOCK != 0) == nonblocking {
+        return Ok(());
+    }
+    let other_flags = old_flags & !libc::O_NONBLOCK;
+    set_flflags(fd, other_flags | if nonblocking { libc::O_NONBLOCK } else { 0 })
 }
 
 cfg_no_atomic_cloexec! {
@@ -92,6 +101,13 @@ cfg_no_atomic_cloexec! {
     }
 }
 
+/// Sets or clears `FD_CLOEXEC`. Only performs async-signal-safe operations.
+pub(super) fn set_inheritable(fd: BorrowedFd<'_>, inheritable: bool) -> io::Result<()> {
+    let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFD, 0) }.fd_or_errno()?;
+    let flags = if inheritable { flags & !libc::FD_CLOEXEC } else { flags | libc::FD_CLOEXEC };
+    unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, flags) != -1 }.true_val_or_errno(())
+}
+
 pub(super) fn set_socket_mode(fd: BorrowedFd<'_>, mode: mode_t) -> io::Result<()> {
     unsafe { libc::fchmod(fd.as_raw_fd(), mode) != -1 }.true_val_or_errno(())
 }
@@ -173,7 +189,7 @@ const SUN_PATH_OFFSET: usize = unsafe {
 };
 
 #[allow(clippy::indexing_slicing, clippy::arithmetic_side_effects, clippy::as_conversions)]
-fn bind(fd: BorrowedFd<'_>, addr: &SocketAddr) -> io::Result<()> {
+fn addr_to_raw(addr: &SocketAddr) -> (sockaddr_un, libc::socklen_t) {
     let (path, extra) = addr_to_slice(addr);
     let path = unsafe { transmute::<&[u8], &[libc::c_char]>(path) };
 
@@ -182,20 +198,24 @@ fn bind(fd: BorrowedFd<'_>, addr: &SocketAddr) -> io::Result<()> {
     addr.sun_path[extra..(extra + path.len())].copy_from_slice(path);
 
     let len = path.len() + extra + SUN_PATH_OFFSET;
+    // It's impossible for this to exceed socklen_t::MAX, since it came from a valid SocketAddr
+    (addr, len as _)
+}
 
-    unsafe {
-        libc::bind(
-            fd.as_raw_fd(),
-            addr.as_ptr().cast(),
-            // It's impossible for this to exceed socklen_t::MAX, since it came from a valid
-            // SocketAddr
-            len as _,
-        ) != -1
-    }
-    .true_val_or_errno(())
+fn bind(fd: BorrowedFd<'_>, addr: &SocketAddr) -> io::Result<()> {
+    let (addr, len) = addr_to_raw(addr);
+    unsafe { libc::bind(fd.as_raw_fd(), addr.as_ptr().cast(), len) != -1 }.true_val_or_errno(())
+}
+
+fn connect(fd: BorrowedFd<'_>, addr: &SocketAddr) -> io::Result<()> {
+    let (addr, len) = addr_to_raw(addr);
+    unsafe { libc::connect(fd.as_raw_fd(), addr.as_ptr().cast(), len) != -1 }
+        .true_val_or_errno(())
 }
 
-fn listen(fd: BorrowedFd<'_>) -> io::Result<()> {
+/// Puts the socket into the listening state, or changes the backlog of one that already is in it,
+/// which most platforms allow. `None` requests the largest backlog that the platform allows.
+pub(super) fn listen(fd: BorrowedFd<'_>, backlog: Option<u32>) -> io::Result<()> {
     // The standard library does this
     #[cfg(any(
         target_os = "windows",
@@ -222,7 +242,8 @@ fn listen(fd: BorrowedFd<'_>) -> io::Result<()> {
         target_os = "horizon"
     )))]
     const BACKLOG: libc::c_int = libc::SOMAXCONN;
-    unsafe { libc::listen(fd.as_raw_fd(), BACKLOG) != -1 }.true_val_or_errno(())
+    let backlog = backlog.map_or(BACKLOG, |b| c_int::try_from(b).unwrap_or(c_int::MAX));
+    unsafe { libc::listen(fd.as_raw_fd(), backlog) != -1 }.true_val_or_errno(())
 }
 
 struct WithUmask {
@@ -245,6 +266,7 @@ pub(super) fn create_server(
     addr: &SocketAddr,
     nonblocking: bool,
     mode: Option<mode_t>,
+    backlog: Option<u32>,
 ) -> io::Result<OwnedFd> {
     let dg = if let Some(mode) = mode {
         // This used to forbid modes with the executable bit set, but no longer does. That is the
@@ -253,8 +275,16 @@ pub(super) fn create_server(
         if can_fchmod_sockets() {
             let sock = create_socket(ty, nonblocking)?;
             match set_socket_mode(sock.as_fd(), mode) {
-                Ok(()) => return bind_and_listen(sock, addr, ()),
-                Err(e) if e.kind() == io::ErrorKind::InvalidInput => can_not_fchmod_sockets(),
+                Ok(()) => return bind_and_listen(sock, addr, Some(mode), backlog, ()),
+                // Most platforms report EINVAL, but some (such as Haiku) report ENOTSUP.
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::InvalidInput | io::ErrorKind::Unsupported
+                    ) =>
+                {
+                    can_not_fchmod_sockets()
+                }
                 Err(e) => return Err(e),
             }
         }
@@ -272,16 +302,192 @@ pub(super) fn create_server(
     // the socket gets its mode solely from the umask.
 
     let sock = create_socket(ty, nonblocking)?;
-    bind_and_listen(sock, addr, dg)
+    bind_and_listen(sock, addr, mode, backlog, dg)
 }
 
-fn bind_and_listen<T>(sock: OwnedFd, addr: &SocketAddr, drop_guard: T) -> io::Result<OwnedFd> {
+fn bind_and_listen<T>(
+    sock: OwnedFd,
+    addr: &SocketAddr,
+    mode: Option<mode_t>,
+    backlog: Option<u32>,
+    drop_guard: T,
+) -> io::Result<OwnedFd> {
     bind(sock.as_fd(), addr)?;
     drop(drop_guard); // Revert umask as soon as possible
-    listen(sock.as_fd())?;
+    if let (Some(mode), Some(path)) = (mode, addr.as_pathname()) {
+        // The file was created with at most the requested permissions, but the umask may have
+        // stripped some of them. Nobody can connect until listen() is called, so setting the mode
+        // now makes it exactly the requested one by the time the socket becomes connectable.
+        chmod_socket_file(path, mode)?;
+    }
+    listen(sock.as_fd(), backlog)?;
     Ok(sock)
 }
 
+/// Sets the mode of the socket file that has just been bound at `path`, without following a
+/// symlink that someone with write access to its directory may have put in its place, and
+/// removes the socket file if that fails.
+///
+/// The file is looked up relative to a descriptor of the directory, so that the directory cannot
+/// be swapped out from under it. If the file turns out not to be a socket, it is left alone,
+/// since it isn't the one that was bound.
+fn chmod_socket_file(path: &Path, mode: mode_t) -> io::Result<()> {
+    let name = path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "socket path does not end in a file name")
+    })?;
+    let name = CString::new(name.as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let dir = OpenOptions::new().read(true).custom_flags(libc::O_DIRECTORY).open(dir)?;
+    chmod_socket_at(dir.as_fd(), &name, mode).map_err(|e| {
+        if e.kind() != io::ErrorKind::AlreadyExists && is_socket_at(dir.as_fd(), &name) {
+            unsafe { libc::unlinkat(dir.as_raw_fd(), name.as_ptr(), 0) };
+        }
+        e
+    })
+}
+
+/// Linux has no way to change the mode of a file without following symlinks by its name – its
+/// `fchmodat()` ignores `AT_SYMLINK_NOFOLLOW` – and `fchmod()` doesn't work on the `O_PATH`
+/// descriptors that can be opened without following them. The `/proc/self/fd` entry of such a
+/// descriptor, however, refers to the file that it was opened for, rather than to whatever has
+/// the same name by the time it is used.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn chmod_socket_at(dir: BorrowedFd<'_>, name: &CStr, mode: mode_t) -> io::Result<()> {
+    let flags = libc::O_PATH | libc::O_NOFOLLOW | libc::O_CLOEXEC;
+    let fd = unsafe { libc::openat(dir.as_raw_fd(), name.as_ptr(), flags) }.fd_or_errno()?;
+    // SAFETY: we just opened this file descriptor
+    let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+    let mut st = unsafe { zeroed::<libc::stat>() };
+    unsafe { libc::fstat(fd.as_raw_fd(), st.as_mut_ptr()) != -1 }.true_val_or_errno(())?;
+    ensure_socket(&st)?;
+    let path = CString::new(format!("/proc/self/fd/{}", fd.as_raw_fd()))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    unsafe { libc::chmod(path.as_ptr(), mode) != -1 }.true_val_or_errno(())
+}
+/// The check only leaves a window for the file to be replaced by something other than a
+/// symlink, which the change of mode then applies to, since `AT_SYMLINK_NOFOLLOW` makes it
+/// apply to the symlink itself otherwise.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn chmod_socket_at(dir: BorrowedFd<'_>, name: &CStr, mode: mode_t) -> io::Result<()> {
+    let mut st = unsafe { zeroed::<libc::stat>() };
+    let flags = libc::AT_SYMLINK_NOFOLLOW;
+    unsafe { libc::fstatat(dir.as_raw_fd(), name.as_ptr(), st.as_mut_ptr(), flags) != -1 }
+        .true_val_or_errno(())?;
+    ensure_socket(&st)?;
+    unsafe { libc::fchmodat(dir.as_raw_fd(), name.as_ptr(), mode, flags) != -1 }
+        .true_val_or_errno(())
+}
+
+fn ensure_socket(st: &libc::stat) -> io::Result<()> {
+    if st.st_mode & libc::S_IFMT != libc::S_IFSOCK {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "socket file was replaced by something else before its mode could be set",
+        ));
+    }
+    Ok(())
+}
+fn is_socket_at(dir: BorrowedFd<'_>, name: &CStr) -> bool {
+    let mut st = unsafe { zeroed::<libc::stat>() };
+    let flags = libc::AT_SYMLINK_NOFOLLOW;
+    unsafe { libc::fstatat(dir.as_raw_fd(), name.as_ptr(), st.as_mut_ptr(), flags) != -1 }
+        && ensure_socket(&st).is_ok()
+}
+
+/// Creates a socket of the given type, binds it to `local` and connects it to `remote`.
+///
+/// If connecting fails, the socket file that binding created is removed, so that the same
+/// `local` address can be bound to again on the next attempt.
+pub(super) fn create_client(
+    ty: c_int,
+    local: &SocketAddr,
+    remote: &SocketAddr,
+) -> io::Result<OwnedFd> {
+    let sock = create_socket(ty, false)?;
+    bind(sock.as_fd(), local)?;
+    connect(sock.as_fd(), remote).map_err(|e| unbind(local, e))?;
+    Ok(sock)
+}
+/// Removes the socket file that a client socket has been bound to, if any, for when the
+/// connection that it was bound for has failed with `e`.
+pub(super) fn unbind(local: &SocketAddr, e: io::Error) -> io::Error {
+    if let Some(path) = local.as_pathname() {
+        let _ = fs::remove_file(path);
+    }
+    e
+}
+
+/// How far along a nonblocking connection attempt is.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum ConnectProgress {
+    Connected,
+    /// The socket becomes writable once the attempt has completed.
+    InProgress,
+    /// The backlog of the server is full, so the attempt never got underway. The socket is
+    /// writable right away in this case, leaving nothing to wait on before retrying.
+    BacklogFull,
+}
+
+/// Creates a nonblocking socket of the given type, binds it to `local` if that is given, and
+/// starts connecting it to `remote`.
+///
+/// As with [`create_client()`], a failure after binding removes the socket file, but a failure
+/// later on in the connection attempt is for the caller to [`unbind()`].
+pub(super) fn start_connect(
+    ty: c_int,
+    local: Option<&SocketAddr>,
+    remote: &SocketAddr,
+) -> io::Result<(OwnedFd, ConnectProgress)> {
+    let sock = create_socket(ty, true)?;
+    if !CAN_CREATE_NONBLOCKING {
+        set_nonblocking(sock.as_fd(), true)?;
+    }
+    if let Some(local) = local {
+        bind(sock.as_fd(), local)?;
+    }
+    let progress = continue_connect(sock.as_fd(), remote)
+        .map_err(|e| local.map_or(e, |local| unbind(local, e)))?;
+    Ok((sock, progress))
+}
+/// Checks the outcome of a connection attempt that was started with [`start_connect()`], retrying
+/// it if it never got underway, as happens when the backlog of the server is full.
+pub(super) fn continue_connect(
+    fd: BorrowedFd<'_>,
+    remote: &SocketAddr,
+) -> io::Result<ConnectProgress> {
+    let mut error: c_int = 0;
+    let mut len = libc::socklen_t::try_from(size_of::<c_int>()).unwrap_or(0);
+    unsafe {
+        libc::getsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_ERROR,
+            error.as_mut_ptr().cast(),
+            len.as_mut_ptr(),
+        ) != -1
+    }
+    .true_val_or_errno(())?;
+    if error != 0 {
+        return Err(io::Error::from_raw_os_error(error));
+    }
+    match connect(fd, remote) {
+        Ok(()) => Ok(ConnectProgress::Connected),
+        Err(e) => match e.raw_os_error() {
+            Some(libc::EISCONN) => Ok(ConnectProgress::Connected),
+            Some(libc::EINPROGRESS | libc::EALREADY | libc::EINTR) => {
+                Ok(ConnectProgress::InProgress)
+            }
+            // This is how Linux reports a full backlog.
+            Some(libc::EAGAIN) => Ok(ConnectProgress::BacklogFull),
+            _ => Err(e),
+        },
+    }
+}
+
 #[allow(dead_code)]
 pub(super) fn shutdown(fd: BorrowedFd<'_>, how: std::net::Shutdown) -> io::Result<()> {
     use std::net::Shutdown::*;
@@ -292,3 +498,410 @@ pub(super) fn shutdown(fd: BorrowedFd<'_>, how: std::net::Shutdown) -> io::Resul
     };
     unsafe { libc::shutdown(fd.as_raw_fd(), how) != -1 }.true_val_or_errno(())
 }
+
+fn sockbuf_opt(send: bool) -> c_int { if send { libc::SO_SNDBUF } else { libc::SO_RCVBUF } }
+
+pub(super) fn get_socket_buffer_size(fd: BorrowedFd<'_>, send: bool) -> io::Result<usize> {
+    let mut size: c_int = 0;
+    let mut len = libc::socklen_t::try_from(size_of::<c_int>()).unwrap_or(0);
+    unsafe {
+        libc::getsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            sockbuf_opt(send),
+            size.as_mut_ptr().cast(),
+            len.as_mut_ptr(),
+        ) != -1
+    }
+    .true_val_or_errno(())?;
+    Ok(usize::try_from(size).unwrap_or(0))
+}
+
+pub(super) fn set_socket_buffer_size(
+    fd: BorrowedFd<'_>,
+    send: bool,
+    size: usize,
+) -> io::Result<()> {
+    let size = c_int::try_from(size).unwrap_or(c_int::MAX);
+    let len = libc::socklen_t::try_from(size_of::<c_int>()).unwrap_or(0);
+    unsafe {
+        libc::setsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            sockbuf_opt(send),
+            size.as_ptr().cast(),
+            len,
+        ) != -1
+    }
+    .true_val_or_errno(())
+}
+
+/// Sets both `SO_RCVTIMEO` and `SO_SNDTIMEO`, with `None` disabling the timeouts. A timed out
+/// operation fails with [`WouldBlock`](io::ErrorKind::WouldBlock).
+#[cfg(feature = "handshake")]
+pub(super) fn set_io_timeout(
+    fd: BorrowedFd<'_>,
+    timeout: Option<std::time::Duration>,
+) -> io::Result<()> {
+    let mut tv = libc::timeval { tv_sec: 0, tv_usec: 0 };
+    if let Some(timeout) = timeout {
+        tv.tv_sec = libc::time_t::try_from(timeout.as_secs()).unwrap_or(libc::time_t::MAX);
+        tv.tv_usec = libc::suseconds_t::try_from(timeout.subsec_micros()).unwrap_or(0);
+        // A zero timeval disables the timeout instead of expiring right away.
+        if tv.tv_sec == 0 && tv.tv_usec == 0 {
+            tv.tv_usec = 1;
+        }
+    }
+    for opt in [libc::SO_RCVTIMEO, libc::SO_SNDTIMEO] {
+        // SAFETY: both options take a timeval
+        let len = size_of::<libc::timeval>();
+        unsafe { set_socket_option(fd, libc::SOL_SOCKET, opt, tv.as_ptr().cast(), len) }?;
+    }
+    Ok(())
+}
+
+/// Retrieves the value of an arbitrary socket option into `len` bytes at `val`, returning how
+/// many of them the system has written.
+///
+/// # Safety
+/// `val` must be valid for writes of `len` bytes, and those bytes must be laid out the way the
+/// option expects.
+pub(super) unsafe fn get_socket_option(
+    fd: BorrowedFd<'_>,
+    level: c_int,
+    name: c_int,
+    val: *mut libc::c_void,
+    len: usize,
+) -> io::Result<usize> {
+    let mut len = libc::socklen_t::try_from(len)
+        .map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+    unsafe { libc::getsockopt(fd.as_raw_fd(), level, name, val, len.as_mut_ptr()) != -1 }
+        .true_val_or_errno(())?;
+    Ok(usize::try_from(len).unwrap_or(0))
+}
+
+/// Sets an arbitrary socket option to the `len` bytes at `val`.
+///
+/// # Safety
+/// `val` must be valid for reads of `len` bytes, and those bytes must be laid out the way the
+/// option expects.
+pub(super) unsafe fn set_socket_option(
+    fd: BorrowedFd<'_>,
+    level: c_int,
+    name: c_int,
+    val: *const libc::c_void,
+    len: usize,
+) -> io::Result<()> {
+    let len = libc::socklen_t::try_from(len)
+        .map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+    unsafe { libc::setsockopt(fd.as_raw_fd(), level, name, val, len) != -1 }
+        .true_val_or_errno(())
+}
+
+/// Retrieves the credentials of the peer of a connected socket.
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "haiku"))]
+pub(super) fn peer_credentials(fd: BorrowedFd<'_>) -> io::Result<PeerCredentials> {
+    let mut cred = unsafe { zeroed::<libc::ucred>() };
+    let mut len = libc::socklen_t::try_from(size_of::<libc::ucred>()).unwrap_or(0);
+    unsafe {
+        libc::getsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            cred.as_mut_ptr().cast(),
+            len.as_mut_ptr(),
+        ) != -1
+    }
+    .true_val_or_errno(())?;
+    Ok(PeerCredentials {
+        pid: u32::try_from(cred.pid).ok(),
+        uid: Some(cred.uid),
+        gid: Some(cred.gid),
+    })
+}
+/// Retrieves the credentials of the peer of a connected socket.
+#[cfg(any(
+    target_vendor = "apple",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "openbsd",
+    target_os = "aix",
+))]
+pub(super) fn peer_credentials(fd: BorrowedFd<'_>) -> io::Result<PeerCredentials> {
+    let (mut uid, mut gid) = (0, 0);
+    unsafe { libc::getpeereid(fd.as_raw_fd(), uid.as_mut_ptr(), gid.as_mut_ptr()) != -1 }
+        .true_val_or_errno(())?;
+    Ok(PeerCredentials { pid: peer_pid(fd), uid: Some(uid), gid: Some(gid) })
+}
+/// Retrieves the credentials of the peer of a connected socket.
+#[cfg(target_os = "netbsd")]
+pub(super) fn peer_credentials(fd: BorrowedFd<'_>) -> io::Result<PeerCredentials> {
+    // Socket options of Unix domain sockets live at level 0 on NetBSD.
+    const SOL_LOCAL: c_int = 0;
+    let mut id = unsafe { zeroed::<libc::unpcbid>() };
+    let mut len = libc::socklen_t::try_from(size_of::<libc::unpcbid>()).unwrap_or(0);
+    unsafe {
+        libc::getsockopt(
+            fd.as_raw_fd(),
+            SOL_LOCAL,
+            libc::LOCAL_PEEREID,
+            id.as_mut_ptr().cast(),
+            len.as_mut_ptr(),
+        ) != -1
+    }
+    .true_val_or_errno(())?;
+    Ok(PeerCredentials {
+        pid: u32::try_from(id.unp_pid).ok(),
+        uid: Some(id.unp_euid),
+        gid: Some(id.unp_egid),
+    })
+}
+/// Retrieves the credentials of the peer of a connected socket.
+#[cfg(any(target_os = "illumos", target_os = "solaris"))]
+pub(super) fn peer_credentials(fd: BorrowedFd<'_>) -> io::Result<PeerCredentials> {
+    let mut ucred = std::ptr::null_mut::<libc::ucred_t>();
+    unsafe { libc::getpeerucred(fd.as_raw_fd(), ucred.as_mut_ptr()) != -1 }
+        .true_val_or_errno(())?;
+    // The accessors return -1 (as the unsigned type) for information that isn't available.
+    let creds = unsafe {
+        PeerCredentials {
+            pid: u32::try_from(libc::ucred_getpid(ucred)).ok(),
+            uid: Some(libc::ucred_geteuid(ucred)).filter(|&uid| uid != libc::uid_t::MAX),
+            gid: Some(libc::ucred_getegid(ucred)).filter(|&gid| gid != libc::gid_t::MAX),
+        }
+    };
+    unsafe { libc::ucred_free(ucred) };
+    Ok(creds)
+}
+/// Retrieves the credentials of the peer of a connected socket.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "haiku",
+    target_os = "netbsd",
+    target_os = "illumos",
+    target_os = "solaris",
+    target_vendor = "apple",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "openbsd",
+    target_os = "aix",
+)))]
+pub(super) fn peer_credentials(fd: BorrowedFd<'_>) -> io::Result<PeerCredentials> {
+    let _ = fd;
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "peer credentials are not supported on this platform",
+    ))
+}
+
+#[cfg(target_vendor = "apple")]
+fn peer_pid(fd: BorrowedFd<'_>) -> Option<u32> {
+    let mut pid: libc::pid_t = 0;
+    let mut len = libc::socklen_t::try_from(size_of::<libc::pid_t>()).unwrap_or(0);
+    unsafe {
+        libc::getsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_LOCAL,
+            libc::LOCAL_PEERPID,
+            pid.as_mut_ptr().cast(),
+            len.as_mut_ptr(),
+        ) != -1
+    }
+    .then(|| u32::try_from(pid).ok())
+    .flatten()
+}
+#[cfg(any(
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "openbsd",
+    target_os = "aix",
+))]
+#[inline(always)]
+fn peer_pid(fd: BorrowedFd<'_>) -> Option<u32> {
+    let _ = fd;
+    None
+}
+
+/// Flags for `send()` and `sendmsg()` which keep them from raising `SIGPIPE` if the peer has hung
+/// up. Apple platforms don't have `MSG_NOSIGNAL`, and get `SO_NOSIGPIPE` set on the socket itself
+/// by [`suppress_sigpipe()`] instead.
+#[cfg(not(any(target_vendor = "apple", target_vendor = "wasmer")))]
+const SEND_FLAGS: c_int = libc::MSG_NOSIGNAL;
+#[cfg(any(target_vendor = "apple", target_vendor = "wasmer"))]
+const SEND_FLAGS: c_int = 0;
+
+/// Makes sure that sending to the socket never raises `SIGPIPE`, on platforms where that is a
+/// property of the socket rather than of individual send calls.
+#[cfg(target_vendor = "apple")]
+pub(super) fn suppress_sigpipe(fd: BorrowedFd<'_>) -> io::Result<()> {
+    let one: c_int = 1;
+    let len = libc::socklen_t::try_from(size_of::<c_int>()).unwrap_or(0);
+    unsafe {
+        libc::setsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_NOSIGPIPE,
+            one.as_ptr().cast(),
+            len,
+        ) != -1
+    }
+    .true_val_or_errno(())
+}
+#[cfg(not(target_vendor = "apple"))]
+#[inline(always)]
+pub(super) fn suppress_sigpipe(fd: BorrowedFd<'_>) -> io::Result<()> {
+    let _ = fd;
+    Ok(())
+}
+
+/// Like `read()`, but doesn't require the buffer to be initialized.
+pub(super) fn recv(fd: BorrowedFd<'_>, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+    let rcvd = unsafe { libc::recv(fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len(), 0) };
+    (rcvd != -1).true_or_errno(|| usize::try_from(rcvd).unwrap_or(0))
+}
+
+/// Like `write()`, but never raises `SIGPIPE`.
+pub(super) fn send(fd: BorrowedFd<'_>, buf: &[u8]) -> io::Result<usize> {
+    let sent = unsafe { libc::send(fd.as_raw_fd(), buf.as_ptr().cast(), buf.len(), SEND_FLAGS) };
+    (sent != -1).true_or_errno(|| usize::try_from(sent).unwrap_or(0))
+}
+
+/// Like `writev()`, but never raises `SIGPIPE`.
+pub(super) fn send_vectored(fd: BorrowedFd<'_>, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+    let mut hdr = unsafe { zeroed::<libc::msghdr>() };
+    // IoSlice is guaranteed to be ABI-compatible with iovec.
+    hdr.msg_iov = bufs.as_ptr().cast_mut().cast();
+    hdr.msg_iovlen = bufs.len().try_into().unwrap_or(0);
+    let sent = unsafe { libc::sendmsg(fd.as_raw_fd(), hdr.as_ptr(), SEND_FLAGS) };
+    (sent != -1).true_or_errno(|| usize::try_from(sent).unwrap_or(0))
+}
+
+/// Ancillary data buffer large enough for a handful of file descriptors, aligned for `cmsghdr`.
+#[cfg(unix)]
+#[repr(C)]
+union CmsgBuf {
+    _align: libc::cmsghdr,
+    bytes: [u8; Self::LEN],
+}
+#[cfg(unix)]
+impl CmsgBuf {
+    const LEN: usize = 128;
+    const MAX_FDS: usize = MAX_FDS_PER_MSG;
+    #[inline]
+    fn new() -> Self { Self { bytes: [0; Self::LEN] } }
+}
+/// The most file descriptors that can be sent or received with one message.
+pub(super) const MAX_FDS_PER_MSG: usize = 8;
+
+/// Like [`send()`], but also passes `pass` to the peer with `SCM_RIGHTS`. The file descriptors
+/// arrive along with the first byte of `buf`, which must not be empty.
+///
+/// At most [`MAX_FDS_PER_MSG`] file descriptors can be sent at once.
+#[cfg(unix)]
+pub(super) fn send_with_fds(
+    fd: BorrowedFd<'_>,
+    buf: &[u8],
+    pass: &[BorrowedFd<'_>],
+) -> io::Result<usize> {
+    if pass.len() > CmsgBuf::MAX_FDS {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "too many file descriptors to send at once",
+        ));
+    }
+    let mut cmsg_buf = CmsgBuf::new();
+    let data_len = libc::c_uint::try_from(size_of::<c_int>().saturating_mul(pass.len()))
+        .unwrap_or(0);
+    let mut iov = libc::iovec { iov_base: buf.as_ptr().cast_mut().cast(), iov_len: buf.len() };
+    let mut hdr = unsafe { zeroed::<libc::msghdr>() };
+    hdr.msg_iov = iov.as_mut_ptr();
+    hdr.msg_iovlen = 1;
+    if !pass.is_empty() {
+        hdr.msg_control = cmsg_buf.as_mut_ptr().cast();
+        hdr.msg_controllen = unsafe { libc::CMSG_SPACE(data_len) }.try_into().unwrap_or(0);
+        unsafe {
+            // SAFETY: the control buffer is large enough for MAX_FDS file descriptors, which is
+            // what CMSG_FIRSTHDR checks against msg_controllen for
+            let cmsg = libc::CMSG_FIRSTHDR(hdr.as_ptr());
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(data_len).try_into().unwrap_or(0);
+            let data = libc::CMSG_DATA(cmsg).cast::<c_int>();
+            for (i, pass) in pass.iter().enumerate() {
+                data.add(i).write_unaligned(pass.as_raw_fd());
+            }
+        }
+    }
+    let sent = unsafe { libc::sendmsg(fd.as_raw_fd(), hdr.as_ptr(), SEND_FLAGS) };
+    (sent != -1).true_or_errno(|| usize::try_from(sent).unwrap_or(0))
+}
+
+/// Like [`recv()`], but also collects file descriptors passed with `SCM_RIGHTS` into `fds`.
+///
+/// The received file descriptors are never inherited by child processes. Only the first
+/// `max_fds` of them are appended to `fds`, and the excess ones are closed. Returns how much
+/// data was received, along with whether any file descriptors were dropped, either because of
+/// `max_fds` or because there were more than fit into the internal buffer. The data is returned
+/// either way, so that dropped file descriptors don't desynchronize the stream.
+#[cfg(unix)]
+pub(super) fn recv_with_fds(
+    fd: BorrowedFd<'_>,
+    buf: &mut [MaybeUninit<u8>],
+    fds: &mut Vec<OwnedFd>,
+    max_fds: usize,
+) -> io::Result<(usize, bool)> {
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+    const RECV_FLAGS: c_int = libc::MSG_CMSG_CLOEXEC;
+    #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd")))]
+    const RECV_FLAGS: c_int = 0;
+    let atomic_cloexec = RECV_FLAGS != 0;
+
+    let mut cmsg_buf = CmsgBuf::new();
+    let max_len = libc::c_uint::try_from(size_of::<c_int>().saturating_mul(CmsgBuf::MAX_FDS));
+    let mut iov = libc::iovec { iov_base: buf.as_mut_ptr().cast(), iov_len: buf.len() };
+    let mut hdr = unsafe { zeroed::<libc::msghdr>() };
+    hdr.msg_iov = iov.as_mut_ptr();
+    hdr.msg_iovlen = 1;
+    hdr.msg_control = cmsg_buf.as_mut_ptr().cast();
+    hdr.msg_controllen =
+        unsafe { libc::CMSG_SPACE(max_len.unwrap_or(0)) }.try_into().unwrap_or(0);
+    let rcvd = unsafe { libc::recvmsg(fd.as_raw_fd(), hdr.as_mut_ptr(), RECV_FLAGS) };
+    let rcvd = (rcvd != -1).true_or_errno(|| usize::try_from(rcvd).unwrap_or(0))?;
+
+    let header_len = usize::try_from(unsafe { libc::CMSG_LEN(0) }).unwrap_or(0);
+    let start = fds.len();
+    let mut excess = false;
+    let mut cmsg = unsafe { libc::CMSG_FIRSTHDR(hdr.as_ptr()) };
+    while !cmsg.is_null() {
+        // SAFETY: non-null pointers returned by CMSG_FIRSTHDR and CMSG_NXTHDR point to complete
+        // headers within the control buffer
+        let (level, ty, len) =
+            unsafe { ((*cmsg).cmsg_level, (*cmsg).cmsg_type, (*cmsg).cmsg_len) };
+        if level == libc::SOL_SOCKET && ty == libc::SCM_RIGHTS {
+            let data = unsafe { libc::CMSG_DATA(cmsg) }.cast::<c_int>();
+            let count = usize::try_from(len)
+                .unwrap_or(0)
+                .saturating_sub(header_len)
+                .checked_div(size_of::<c_int>())
+                .unwrap_or(0);
+            for i in 0..count {
+                // SAFETY: the kernel has just given us ownership of these descriptors. All of
+                // them are wrapped before anything can fail, so that none of them leak.
+                let new_fd = unsafe { OwnedFd::from_raw_fd(data.add(i).read_unaligned()) };
+                if fds.len().saturating_sub(start) < max_fds {
+                    fds.push(new_fd);
+                } else {
+                    excess = true;
+                }
+            }
+        }
+        cmsg = unsafe { libc::CMSG_NXTHDR(hdr.as_ptr(), cmsg) };
+    }
+    if !atomic_cloexec {
+        for new_fd in fds.get(start..).unwrap_or_default() {
+            set_inheritable(new_fd.as_fd(), false)?;
+        }
+    }
+    Ok((rcvd, excess || hdr.msg_flags & libc::MSG_CTRUNC != 0))
+}
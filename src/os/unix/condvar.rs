@@ -0,0 +1,84 @@
+use {
+    super::mutex::{check, deadline, Mutex},
+    crate::{
+        local_socket::Name,
+        shared_memory::{wait_until_ready, SharedMemory},
+    },
+    std::{
+        cell::UnsafeCell,
+        io,
+        mem::{size_of, MaybeUninit},
+        sync::atomic::{AtomicU32, Ordering},
+        time::Duration,
+    },
+};
+
+/// The clock used for timed waits. Apple platforms cannot select it with
+/// `pthread_condattr_setclock()` and always use `CLOCK_REALTIME`.
+#[cfg(not(target_vendor = "apple"))]
+const CLOCK: libc::clockid_t = libc::CLOCK_MONOTONIC;
+#[cfg(target_vendor = "apple")]
+const CLOCK: libc::clockid_t = libc::CLOCK_REALTIME;
+
+#[repr(C)]
+struct Shared {
+    ready: AtomicU32,
+    cond: UnsafeCell<libc::pthread_cond_t>,
+}
+
+/// A process-shared pthread condition variable, stored in a shared memory object.
+#[derive(Debug)]
+pub(crate) struct Condvar(SharedMemory);
+impl Condvar {
+    pub fn create(name: &Name<'_>) -> io::Result<Self> {
+        let opts = SharedMemory::options().name(name.borrow()).size(size_of::<Shared>());
+        let slf = Self(opts.create()?);
+        let mut attr = MaybeUninit::<libc::pthread_condattr_t>::uninit();
+        check(unsafe { libc::pthread_condattr_init(attr.as_mut_ptr()) })?;
+        let rslt = (|| {
+            let attr = attr.as_mut_ptr();
+            let pshared = libc::PTHREAD_PROCESS_SHARED;
+            check(unsafe { libc::pthread_condattr_setpshared(attr, pshared) })?;
+            #[cfg(not(target_vendor = "apple"))]
+            check(unsafe { libc::pthread_condattr_setclock(attr, CLOCK) })?;
+            check(unsafe { libc::pthread_cond_init(slf.raw(), attr) })
+        })();
+        unsafe { libc::pthread_condattr_destroy(attr.as_mut_ptr()) };
+        rslt?;
+        slf.shared().ready.store(1, Ordering::Release);
+        Ok(slf)
+    }
+    pub fn open(name: &Name<'_>) -> io::Result<Self> {
+        let opts = SharedMemory::options().name(name.borrow()).size(size_of::<Shared>());
+        let slf = Self(opts.open()?);
+        wait_until_ready(&slf.shared().ready)?;
+        Ok(slf)
+    }
+
+    fn shared(&self) -> &Shared {
+        // SAFETY: the mapping is page-aligned and at least as big as `Shared`
+        unsafe { &*self.0.as_ptr().cast::<Shared>() }
+    }
+    fn raw(&self) -> *mut libc::pthread_cond_t { self.shared().cond.get() }
+
+    /// Must be called with `mutex` locked by the current thread. Returns whether the previous
+    /// owner of the mutex died while it was being reacquired, and whether the timeout expired.
+    pub fn wait(&self, mutex: &Mutex, timeout: Option<Duration>) -> io::Result<(bool, bool)> {
+        let ret = match timeout {
+            None => unsafe { libc::pthread_cond_wait(self.raw(), mutex.raw()) },
+            Some(timeout) => {
+                let deadline = deadline(CLOCK, timeout)?;
+                unsafe { libc::pthread_cond_timedwait(self.raw(), mutex.raw(), &deadline) }
+            }
+        };
+        // ETIMEDOUT is reported as `None`, with the mutex reacquired all the same.
+        let owner_died = mutex.interpret_lock(ret)?;
+        Ok((owner_died.unwrap_or(false), owner_died.is_none()))
+    }
+    pub fn notify_one(&self) -> io::Result<()> {
+        check(unsafe { libc::pthread_cond_signal(self.raw()) })
+    }
+    pub fn notify_all(&self) -> io::Result<()> {
+        check(unsafe { libc::pthread_cond_broadcast(self.raw()) })
+    }
+}
@@ -0,0 +1,62 @@
+use {
+    super::unixprelude::*,
+    std::{fs::File, io},
+};
+
+/// Acquires an advisory lock on the whole file, returning `false` if `block` is not set and the
+/// lock is held by someone else.
+///
+/// Open file description locks are used on Linux and Android, and `flock()` elsewhere. Both kinds
+/// belong to the open file description, so they conflict between separately opened files even
+/// within the same process.
+pub(crate) fn lock(file: &File, exclusive: bool, block: bool) -> io::Result<bool> {
+    loop {
+        if lock_once(file.as_fd(), exclusive, block) != -1 {
+            return Ok(true);
+        }
+        let e = io::Error::last_os_error();
+        match e.raw_os_error() {
+            Some(libc::EINTR) => {}
+            Some(libc::EAGAIN | libc::EACCES) if !block => return Ok(false),
+            #[allow(unreachable_patterns)] // EWOULDBLOCK is EAGAIN on most platforms
+            Some(libc::EWOULDBLOCK) if !block => return Ok(false),
+            _ => return Err(e),
+        }
+    }
+}
+pub(crate) fn unlock(file: &File) -> io::Result<()> {
+    if unlock_once(file.as_fd()) == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn lock_once(fd: BorrowedFd<'_>, exclusive: bool, block: bool) -> c_int {
+    let ty = if exclusive { libc::F_WRLCK } else { libc::F_RDLCK };
+    let cmd = if block { libc::F_OFD_SETLKW } else { libc::F_OFD_SETLK };
+    ofd_setlk(fd, cmd, ty)
+}
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn unlock_once(fd: BorrowedFd<'_>) -> c_int { ofd_setlk(fd, libc::F_OFD_SETLK, libc::F_UNLCK) }
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn ofd_setlk(fd: BorrowedFd<'_>, cmd: c_int, ty: c_int) -> c_int {
+    // SAFETY: all-zero is a valid flock structure, and a zero length covers the whole file
+    let mut lock = unsafe { std::mem::zeroed::<libc::flock>() };
+    lock.l_type = c_short::try_from(ty).unwrap_or(0);
+    lock.l_whence = c_short::try_from(libc::SEEK_SET).unwrap_or(0);
+    unsafe { libc::fcntl(fd.as_raw_fd(), cmd, &mut lock) }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn lock_once(fd: BorrowedFd<'_>, exclusive: bool, block: bool) -> c_int {
+    let mut op = if exclusive { libc::LOCK_EX } else { libc::LOCK_SH };
+    if !block {
+        op |= libc::LOCK_NB;
+    }
+    unsafe { libc::flock(fd.as_raw_fd(), op) }
+}
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn unlock_once(fd: BorrowedFd<'_>) -> c_int {
+    unsafe { libc::flock(fd.as_raw_fd(), libc::LOCK_UN) }
+}
@@ -1,13 +1,52 @@
 //! Unix-specific local socket features.
 
 pub(crate) mod dispatch_sync;
-#[cfg(feature = "tokio")]
+#[cfg(feature = "local-socket-tokio")]
 pub(crate) mod dispatch_tokio;
 pub(crate) mod name_type;
+pub mod sockopt;
 
-use crate::{local_socket::ListenerOptions, Sealed};
+use {
+    crate::{
+        local_socket::{ConnectOptions, ListenerOptions, Name},
+        os::unix::uds_local_socket as uds_impl,
+        Sealed,
+    },
+    std::io,
+};
 pub use name_type::*;
 
+/// Checks that the socket file which the given name refers to is a socket, and, before a client
+/// trusts whoever is listening on it, that it's owned by `owner` (if specified) and has none of
+/// the permission bits in `forbidden_mode` set.
+///
+/// A client of a daemon run by root can use this to refuse a socket that someone else has put
+/// in its place, passing `Some(0)` as the owner and, say, `0o002` as the forbidden mode to also
+/// refuse one that everyone can connect to. The same check can be performed as part of
+/// connecting by means of [`ConnectOptionsExt::expect_socket_owner()`] and
+/// [`ConnectOptionsExt::forbid_socket_mode()`].
+///
+/// The socket file is looked up at the time of the call, and may be replaced afterwards by
+/// anyone who can write to the directory it resides in, which is why the check is best combined
+/// with a [directory audit](ConnectOptionsExt::audit_directory). Checking the credentials of
+/// the server after connecting, with
+/// [`ConnectOptions::require_server_uid()`](ConnectOptions::require_server_uid), is not subject
+/// to that race.
+///
+/// # Errors
+/// Fails with [`PermissionDenied`](io::ErrorKind::PermissionDenied) if the socket file does not
+/// meet the expectations, with [`InvalidInput`](io::ErrorKind::InvalidInput) if the name does
+/// not refer to the filesystem, and with whatever error `stat()` produces if the file cannot be
+/// looked up.
+pub fn check_socket_file(
+    name: Name<'_>,
+    owner: Option<u32>,
+    forbidden_mode: libc::mode_t,
+) -> io::Result<()> {
+    let addr = uds_impl::name_to_addr(name, false)?;
+    uds_impl::check_socket_file(&addr, owner, forbidden_mode)
+}
+
 /// Unix-specific [listener options](ListenerOptions).
 #[allow(private_bounds)]
 pub trait ListenerOptionsExt: Sized + Sealed {
@@ -19,6 +58,12 @@ pub trait ListenerOptionsExt: Sized + Sealed {
     /// only checks directories but not the socket file itself. If you expect your program to be
     /// used on a wide range of Unix systems, do not rely on this as a security mechanism.
     ///
+    /// For sockets bound to filesystem paths, the mode is exactly the requested one, regardless
+    /// of the umask, by the time the socket starts accepting connections: the socket file is
+    /// never created with more permissions than requested, and any that the umask has stripped
+    /// are restored before `listen()` is called, leaving no window in which clients could connect
+    /// while the permissions are other than intended.
+    ///
     /// # Implementation notes
     /// An opportunistic `fchmod()` is performed on the socket. If the system responds with a
     /// `EINVAL`, Interprocess concludes that `fchmod()` on sockets is not supported on the
@@ -34,6 +79,14 @@ pub trait ListenerOptionsExt: Sized + Sealed {
     /// the `umask` to be shared across threads.
     #[must_use = builder_must_use!()]
     fn mode(self, mode: libc::mode_t) -> Self;
+
+    /// Sets whether the directory that the socket file is to be created in is to be
+    /// [audited](ConnectOptionsExt::audit_directory) first, failing with
+    /// [`PermissionDenied`](std::io::ErrorKind::PermissionDenied) if the audit does.
+    ///
+    /// The default value is `false`.
+    #[must_use = builder_must_use!()]
+    fn audit_directory(self, audit: bool) -> Self;
 }
 
 impl ListenerOptionsExt for ListenerOptions<'_> {
@@ -42,4 +95,84 @@ impl ListenerOptionsExt for ListenerOptions<'_> {
         self.mode = Some(mode);
         self
     }
+    #[inline(always)]
+    fn audit_directory(mut self, audit: bool) -> Self {
+        self.audit_directory = audit;
+        self
+    }
+}
+
+/// Unix-specific [connection options](ConnectOptions).
+#[allow(private_bounds)]
+pub trait ConnectOptionsExt<'n>: Sized + Sealed {
+    /// Binds the client socket to the given name before connecting, making it visible to the
+    /// server as the [peer name](crate::local_socket::traits::StreamCommon::peer_name) of the
+    /// resulting stream.
+    ///
+    /// Names of all types are accepted, with pseudo-namespaced names mapped to paths the same way
+    /// they are for listeners. No name reclamation takes place: if a filesystem path is used, the
+    /// socket file remains on the filesystem after the stream is closed and must be deleted
+    /// manually, otherwise subsequent attempts to bind to that name will fail with
+    /// [`AddrInUse`](std::io::ErrorKind::AddrInUse).
+    #[must_use = builder_must_use!()]
+    fn bind_name(self, name: Name<'n>) -> Self;
+
+    /// Sets whether the directory containing the socket file is to be audited before connecting,
+    /// failing with [`PermissionDenied`](std::io::ErrorKind::PermissionDenied) if the audit does.
+    ///
+    /// The audit refuses directories which are owned by a user other than the current one (by
+    /// effective user ID) or root, as well as ones which are writable by everyone but lack the
+    /// sticky bit. In either case, someone else could replace the socket file with one of their
+    /// own, which is how sockets in `/tmp` and similar directories are classically hijacked. Only
+    /// the immediate parent directory is checked, and names which don't refer to the filesystem
+    /// always pass.
+    ///
+    /// The default value is `false`.
+    #[must_use = builder_must_use!()]
+    fn audit_directory(self, audit: bool) -> Self;
+
+    /// Sets the user ID that the socket file must be owned by, failing with
+    /// [`PermissionDenied`](std::io::ErrorKind::PermissionDenied) before connecting if it isn't,
+    /// or if the file is not a socket. See [`check_socket_file()`] for more.
+    ///
+    /// Names which don't refer to the filesystem fail with
+    /// [`InvalidInput`](std::io::ErrorKind::InvalidInput) if this is set.
+    ///
+    /// The default value is `None`.
+    #[must_use = builder_must_use!()]
+    fn expect_socket_owner(self, owner: Option<u32>) -> Self;
+
+    /// Sets the permission bits that the socket file must not have, failing with
+    /// [`PermissionDenied`](std::io::ErrorKind::PermissionDenied) before connecting if any of
+    /// them are set, or if the file is not a socket. See [`check_socket_file()`] for more.
+    ///
+    /// Names which don't refer to the filesystem fail with
+    /// [`InvalidInput`](std::io::ErrorKind::InvalidInput) if this is nonzero.
+    ///
+    /// The default value is `0`.
+    #[must_use = builder_must_use!()]
+    fn forbid_socket_mode(self, mode: libc::mode_t) -> Self;
+}
+
+impl<'n> ConnectOptionsExt<'n> for ConnectOptions<'n> {
+    #[inline(always)]
+    fn bind_name(mut self, name: Name<'n>) -> Self {
+        self.bind_name = Some(name);
+        self
+    }
+    #[inline(always)]
+    fn audit_directory(mut self, audit: bool) -> Self {
+        self.audit_directory = audit;
+        self
+    }
+    #[inline(always)]
+    fn expect_socket_owner(mut self, owner: Option<u32>) -> Self {
+        self.socket_owner = owner;
+        self
+    }
+    #[inline(always)]
+    fn forbid_socket_mode(mut self, mode: libc::mode_t) -> Self {
+        self.forbidden_socket_mode = mode;
+        self
+    }
 }
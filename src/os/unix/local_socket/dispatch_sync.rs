@@ -1,6 +1,6 @@
 use {
     super::super::uds_local_socket as uds_impl,
-    crate::local_socket::{prelude::*, Listener, ListenerOptions, Name, Stream},
+    crate::local_socket::{prelude::*, ConnectOptions, Listener, ListenerOptions, Stream},
     std::io,
 };
 
@@ -10,6 +10,6 @@ pub fn from_options(options: ListenerOptions<'_>) -> io::Result<Listener> {
 }
 
 #[inline]
-pub fn connect(name: Name<'_>) -> io::Result<Stream> {
-    uds_impl::Stream::connect(name).map(Stream::from)
+pub fn connect(options: &ConnectOptions<'_>) -> io::Result<Stream> {
+    options.connect_sync_as::<uds_impl::Stream>().map(Stream::from)
 }
@@ -2,7 +2,7 @@ use {
     super::super::uds_local_socket::tokio as uds_impl,
     crate::local_socket::{
         tokio::{prelude::*, Listener, Stream},
-        ListenerOptions, Name,
+        ConnectOptions, ListenerOptions,
     },
     std::io,
 };
@@ -13,6 +13,6 @@ pub fn from_options(options: ListenerOptions<'_>) -> io::Result<Listener> {
 }
 
 #[inline]
-pub async fn connect(name: Name<'_>) -> io::Result<Stream> {
-    uds_impl::Stream::connect(name).await.map(Stream::from)
+pub async fn connect(options: &ConnectOptions<'_>) -> io::Result<Stream> {
+    options.connect_tokio_as::<uds_impl::Stream>().await.map(Stream::from)
 }
@@ -15,10 +15,52 @@ fn c2os(ccow: Cow<'_, CStr>) -> Cow<'_, OsStr> {
     }
 }
 
+/// Size of `sun_path` of `sockaddr_un`, including room for the nul terminator of paths.
+pub(crate) const SUN_LEN: usize = {
+    let dummy = unsafe { std::mem::zeroed::<libc::sockaddr_un>() };
+    dummy.sun_path.len()
+};
+/// Maximum length of names mapped by [`SpecialDirUdSocket`], which leaves room for the longest
+/// possible special directory.
+pub(crate) const NMCAP: usize = SUN_LEN - "/run/user/18446744073709551614/".len();
+/// Maximum length of the file name of a path reached through `/proc/self/fd/<dirfd>/`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const PROC_NMCAP: usize = SUN_LEN - "/proc/self/fd/2147483647/".len() - 1;
+
+fn check_len(what: &str, len: usize, max: usize) -> io::Result<()> {
+    if len > max {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{what} is {len} bytes long, which exceeds the limit of {max} bytes"),
+        ));
+    }
+    Ok(())
+}
+/// Checks that a socket path fits into `sun_path`, either directly or, on Linux and Android, by
+/// way of `/proc/self/fd`.
+fn check_path_len(path: &OsStr) -> io::Result<()> {
+    if path.len() < SUN_LEN {
+        return Ok(());
+    }
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let base = std::path::Path::new(path).file_name().unwrap_or_default();
+        check_len("socket file name", base.len(), PROC_NMCAP)
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    {
+        check_len("socket path", path.len(), SUN_LEN - 1)
+    }
+}
+
 tag_enum!(
 /// [Mapping](NameType) that produces local socket names referring to Unix domain sockets bound to
 /// the filesystem.
 ///
+/// Paths longer than `sun_path` allows are rejected right away, except on Linux and Android,
+/// where they only need a short enough file name (see
+/// [`GenericFilePath`](crate::local_socket::GenericFilePath)).
+///
 /// For Unix domain sockets residing in the Linux abstract namespace, see `AbstractNsUdSocket`
 /// instead.
 FilesystemUdSocket);
@@ -36,6 +78,7 @@ impl PathNameType<OsStr> for FilesystemUdSocket {
                 ));
             }
         }
+        check_path_len(&path)?;
         Ok(Name(NameInner::UdSocketPath(path)))
     }
 }
@@ -65,6 +108,7 @@ impl NamespacedNameType<OsStr> for SpecialDirUdSocket {
                 ));
             }
         }
+        check_len("special directory-bound name", name.len(), NMCAP)?;
         Ok(Name(NameInner::UdSocketPseudoNs(name)))
     }
 }
@@ -93,6 +137,8 @@ impl NamespacedNameType<OsStr> for AbstractNsUdSocket {
             Cow::Borrowed(b) => Cow::Borrowed(b.as_bytes()),
             Cow::Owned(o) => Cow::Owned(o.into_vec()),
         };
+        // The name is preceded by a nul byte in sun_path.
+        check_len("abstract namespace name", name.len(), SUN_LEN - 1)?;
         Ok(Name(NameInner::UdSocketNs(name)))
     }
 }
@@ -102,6 +148,85 @@ impl NamespacedNameType<CStr> for AbstractNsUdSocket {
     fn map(name: Cow<'_, CStr>) -> io::Result<Name<'_>> { Self::map(c2os(name)) }
 }
 
+#[cfg(target_os = "macos")]
+tag_enum!(
+/// [Mapping](NameType) that produces local socket names referring to Unix domain sockets inside
+/// the shared container directory of a macOS app group.
+///
+/// Sandboxed apps cannot create sockets at arbitrary paths, but all apps in the same app group
+/// can access its container, located at `~/Library/Group Containers/<group ID>`. Names of this
+/// type are of the form `<group ID>/<socket name>`, where the socket name may itself contain
+/// slashes to place the socket in a subdirectory of the container, e.g.
+/// `ABCDE12345.com.example.app/ipc.sock`.
+///
+/// The home directory is looked up in the password database rather than taken from the `HOME`
+/// environment variable, since the latter points to the app's own container inside the sandbox.
+///
+/// Note that the full path must fit into `sun_path`, which has room for 103 bytes on macOS.
+#[cfg_attr(feature = "doc_cfg", doc(cfg(target_os = "macos")))]
+AppGroupUdSocket);
+#[cfg(target_os = "macos")]
+impl NameType for AppGroupUdSocket {
+    fn is_supported() -> bool { true }
+}
+#[cfg(target_os = "macos")]
+impl NamespacedNameType<OsStr> for AppGroupUdSocket {
+    fn map(name: Cow<'_, OsStr>) -> io::Result<Name<'_>> {
+        let bytes = name.as_bytes();
+        let Some(sep) = bytes.iter().position(|&b| b == b'/') else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "app group socket names must be of the form <group ID>/<socket name>",
+            ));
+        };
+        let (group, sockname) = bytes.split_at(sep);
+        let sockname = sockname.get(1..).unwrap_or_default();
+        if group.is_empty() || sockname.is_empty() || bytes.contains(&0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "app group socket names must have a nonempty group ID and socket name and \
+cannot contain interior nuls",
+            ));
+        }
+        let mut path = user_home_dir()?;
+        path.push("Library/Group Containers");
+        path.push(OsStr::from_bytes(group));
+        path.push(OsStr::from_bytes(sockname));
+        check_len("app group socket path", path.as_os_str().len(), SUN_LEN - 1)?;
+        Ok(Name(NameInner::UdSocketPath(Cow::Owned(path.into_os_string()))))
+    }
+}
+#[cfg(target_os = "macos")]
+impl NamespacedNameType<CStr> for AppGroupUdSocket {
+    #[inline]
+    fn map(name: Cow<'_, CStr>) -> io::Result<Name<'_>> { Self::map(c2os(name)) }
+}
+
+/// Finds the real home directory of the current user, which inside the sandbox is not `$HOME`.
+#[cfg(target_os = "macos")]
+fn user_home_dir() -> io::Result<std::path::PathBuf> {
+    use {crate::AsMutPtr, std::ptr};
+    let mut pwd = unsafe { std::mem::zeroed::<libc::passwd>() };
+    let mut buf: Vec<libc::c_char> = vec![0; 4096];
+    let mut result = ptr::null_mut();
+    let ec = unsafe {
+        libc::getpwuid_r(
+            libc::getuid(),
+            pwd.as_mut_ptr(),
+            buf.as_mut_ptr(),
+            buf.len(),
+            result.as_mut_ptr(),
+        )
+    };
+    if ec == 0 && !result.is_null() && !pwd.pw_dir.is_null() {
+        let dir = unsafe { CStr::from_ptr(pwd.pw_dir) };
+        return Ok(OsStr::from_bytes(dir.to_bytes()).into());
+    }
+    std::env::var_os("HOME").map(Into::into).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, "could not determine the home directory")
+    })
+}
+
 macro_rules! map_generic {
     (path $name:ident for $str:ident) => {
         pub(crate) fn $name(path: Cow<'_, $str>) -> io::Result<Name<'_>> {
@@ -0,0 +1,197 @@
+//! Socket options of Unix domain sockets beyond those that Interprocess sets up by itself.
+//!
+//! [`SocketOptionExt`] gets and sets options on local socket streams and listeners, either
+//! through one of the [`SocketOption`]s defined in this module or one defined by the user for an
+//! option that's not among them, or, as a last resort, with raw bytes.
+
+#[cfg(feature = "local-socket-tokio")]
+use crate::local_socket::tokio::{Listener as TokioListener, Stream as TokioStream};
+use {
+    crate::{
+        local_socket::{Listener, Stream},
+        os::unix::{c_wrappers, uds_local_socket as uds_impl, unixprelude::*},
+        AsPtr, Sealed,
+    },
+    std::{
+        io,
+        mem::{size_of, MaybeUninit},
+    },
+};
+
+/// A socket option whose value is of a known type.
+///
+/// # Safety
+/// `Value` must be the type that the system reads and writes for the option identified by
+/// [`LEVEL`](Self::LEVEL) and [`NAME`](Self::NAME), and every bit pattern of its size must be a
+/// valid `Value`.
+pub unsafe trait SocketOption {
+    /// The value of the option, as passed to `setsockopt()` and returned by `getsockopt()`.
+    type Value: Copy;
+    /// The protocol level at which the option is defined, typically `SOL_SOCKET`.
+    const LEVEL: c_int;
+    /// The option's identifier within its level.
+    const NAME: c_int;
+}
+
+macro_rules! sockopts {
+    ($($(#[$attr:meta])* $ty:ident = $level:ident::$name:ident: $val:ty;)+) => {$(
+        $(#[$attr])*
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub struct $ty;
+        unsafe impl SocketOption for $ty {
+            type Value = $val;
+            const LEVEL: c_int = libc::$level;
+            const NAME: c_int = libc::$name;
+        }
+    )+};
+}
+
+sockopts! {
+    /// `SO_RCVLOWAT`: the smallest number of bytes that receive operations wait for.
+    RecvLowWatermark = SOL_SOCKET::SO_RCVLOWAT: c_int;
+    /// `SO_SNDLOWAT`: the smallest amount of free space in the send buffer that send operations
+    /// wait for. Read-only on Linux.
+    SendLowWatermark = SOL_SOCKET::SO_SNDLOWAT: c_int;
+}
+#[cfg(any(target_os = "linux", target_os = "android"))]
+sockopts! {
+    /// `SO_PRIORITY`: the priority of the packets sent on the socket, from 0 to 6 without
+    /// `CAP_NET_ADMIN`.
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    Priority = SOL_SOCKET::SO_PRIORITY: c_int;
+    /// `SO_PASSCRED`: whether to receive `SCM_CREDENTIALS` control messages; nonzero for yes.
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    PassCred = SOL_SOCKET::SO_PASSCRED: c_int;
+    /// `SO_PASSSEC`: whether to receive `SCM_SECURITY` control messages with the security
+    /// context of the peer; nonzero for yes.
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    PassSec = SOL_SOCKET::SO_PASSSEC: c_int;
+}
+
+/// Access to the socket options of local socket streams and listeners.
+#[allow(private_bounds)]
+pub trait SocketOptionExt: AsSocketFd + Sealed {
+    /// Retrieves the value of the given option.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # #[cfg(target_os = "linux")] {
+    /// use interprocess::{
+    ///     local_socket::{prelude::*, GenericNamespaced, Stream},
+    ///     os::unix::local_socket::sockopt::{Priority, SocketOptionExt},
+    /// };
+    ///
+    /// let stream = Stream::connect("example.sock".to_ns_name::<GenericNamespaced>()?)?;
+    /// stream.set_socket_option::<Priority>(6)?;
+    /// assert_eq!(stream.get_socket_option::<Priority>()?, 6);
+    /// # }
+    /// # std::io::Result::Ok(())
+    /// ```
+    fn get_socket_option<O: SocketOption>(&self) -> io::Result<O::Value> {
+        let mut val = MaybeUninit::<O::Value>::zeroed();
+        let len = size_of::<O::Value>();
+        // SAFETY: `val` is `len` bytes long, and is of the layout that the option expects as
+        // per the contract of `SocketOption`
+        unsafe {
+            c_wrappers::get_socket_option(
+                self.socket_fd(),
+                O::LEVEL,
+                O::NAME,
+                val.as_mut_ptr().cast(),
+                len,
+            )?;
+        }
+        // SAFETY: zero-initialized and possibly partially overwritten, both of which are valid
+        // bit patterns as per the contract of `SocketOption`
+        Ok(unsafe { val.assume_init() })
+    }
+    /// Sets the given option to the given value.
+    fn set_socket_option<O: SocketOption>(&self, value: O::Value) -> io::Result<()> {
+        // SAFETY: as above
+        unsafe {
+            c_wrappers::set_socket_option(
+                self.socket_fd(),
+                O::LEVEL,
+                O::NAME,
+                value.as_ptr().cast(),
+                size_of::<O::Value>(),
+            )
+        }
+    }
+
+    /// Retrieves the value of an arbitrary option into `buf`, returning how many bytes of it the
+    /// system has filled in.
+    ///
+    /// # Safety
+    /// The option must not be one which interprets its buffer as containing pointers, as some
+    /// of those defined outside of `SOL_SOCKET` do.
+    unsafe fn get_socket_option_raw(
+        &self,
+        level: c_int,
+        name: c_int,
+        buf: &mut [u8],
+    ) -> io::Result<usize> {
+        unsafe {
+            c_wrappers::get_socket_option(
+                self.socket_fd(),
+                level,
+                name,
+                buf.as_mut_ptr().cast(),
+                buf.len(),
+            )
+        }
+    }
+    /// Sets an arbitrary option to the given bytes.
+    ///
+    /// # Safety
+    /// The bytes must be of the layout that the option expects. Options like `SO_ATTACH_FILTER`
+    /// contain pointers, which the system will follow.
+    unsafe fn set_socket_option_raw(
+        &self,
+        level: c_int,
+        name: c_int,
+        value: &[u8],
+    ) -> io::Result<()> {
+        unsafe {
+            c_wrappers::set_socket_option(
+                self.socket_fd(),
+                level,
+                name,
+                value.as_ptr().cast(),
+                value.len(),
+            )
+        }
+    }
+}
+impl<T: AsSocketFd + Sealed> SocketOptionExt for T {}
+
+pub(crate) trait AsSocketFd {
+    fn socket_fd(&self) -> BorrowedFd<'_>;
+}
+
+macro_rules! impl_as_socket_fd {
+    ($($ty:ty),+ $(,)?) => {$(
+        impl AsSocketFd for $ty {
+            #[inline]
+            fn socket_fd(&self) -> BorrowedFd<'_> { self.as_fd() }
+        }
+    )+};
+}
+impl_as_socket_fd!(uds_impl::Stream, uds_impl::Listener);
+#[cfg(feature = "local-socket-tokio")]
+impl_as_socket_fd!(uds_impl::tokio::Stream, uds_impl::tokio::Listener);
+
+macro_rules! impl_as_socket_fd_enum {
+    ($($ty:ty),+ $(,)?) => {$(
+        impl AsSocketFd for $ty {
+            #[inline]
+            fn socket_fd(&self) -> BorrowedFd<'_> {
+                let Self::UdSocket(x) = self;
+                x.socket_fd()
+            }
+        }
+    )+};
+}
+impl_as_socket_fd_enum!(Stream, Listener);
+#[cfg(feature = "local-socket-tokio")]
+impl_as_socket_fd_enum!(TokioStream, TokioListener);
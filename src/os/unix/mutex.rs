@@ -0,0 +1,154 @@
+use {
+    super::unixprelude::*,
+    crate::{
+        local_socket::Name,
+        shared_memory::{wait_until_ready, SharedMemory},
+    },
+    std::{
+        cell::UnsafeCell,
+        io,
+        mem::{size_of, MaybeUninit},
+        sync::atomic::{AtomicU32, Ordering},
+        time::Duration,
+    },
+};
+
+#[repr(C)]
+struct Shared {
+    ready: AtomicU32,
+    mutex: UnsafeCell<libc::pthread_mutex_t>,
+}
+
+/// A robust, process-shared pthread mutex, stored in a shared memory object.
+#[derive(Debug)]
+pub(crate) struct Mutex(SharedMemory);
+impl Mutex {
+    pub fn create(name: &Name<'_>) -> io::Result<Self> {
+        let opts = SharedMemory::options().name(name.borrow()).size(size_of::<Shared>());
+        let slf = Self(opts.create()?);
+        let mut attr = MaybeUninit::<libc::pthread_mutexattr_t>::uninit();
+        check(unsafe { libc::pthread_mutexattr_init(attr.as_mut_ptr()) })?;
+        let rslt = (|| {
+            let attr = attr.as_mut_ptr();
+            let pshared = libc::PTHREAD_PROCESS_SHARED;
+            check(unsafe { libc::pthread_mutexattr_setpshared(attr, pshared) })?;
+            set_robust(attr)?;
+            check(unsafe { libc::pthread_mutex_init(slf.raw(), attr) })
+        })();
+        unsafe { libc::pthread_mutexattr_destroy(attr.as_mut_ptr()) };
+        rslt?;
+        slf.shared().ready.store(1, Ordering::Release);
+        Ok(slf)
+    }
+    pub fn open(name: &Name<'_>) -> io::Result<Self> {
+        let opts = SharedMemory::options().name(name.borrow()).size(size_of::<Shared>());
+        let slf = Self(opts.open()?);
+        wait_until_ready(&slf.shared().ready)?;
+        Ok(slf)
+    }
+
+    fn shared(&self) -> &Shared {
+        // SAFETY: the mapping is page-aligned and at least as big as `Shared`
+        unsafe { &*self.0.as_ptr().cast::<Shared>() }
+    }
+    pub(super) fn raw(&self) -> *mut libc::pthread_mutex_t { self.shared().mutex.get() }
+
+    /// Returns `None` on timeout, or whether the previous owner died otherwise. The mutex is
+    /// made consistent again right away in the latter case.
+    pub fn lock(&self, timeout: Option<Duration>) -> io::Result<Option<bool>> {
+        let ret = match timeout {
+            None => unsafe { libc::pthread_mutex_lock(self.raw()) },
+            Some(Duration::ZERO) => unsafe { libc::pthread_mutex_trylock(self.raw()) },
+            Some(timeout) => timed_lock(self.raw(), timeout),
+        };
+        let rslt = self.interpret_lock(ret);
+        if ret == libc::EOWNERDEAD && rslt.is_err() {
+            // There is no guard yet to release the lock that we have just acquired. Unlocking a
+            // mutex that has not been made consistent renders it unusable, which other processes
+            // then get to see as ENOTRECOVERABLE instead of waiting for it forever.
+            let _ = self.unlock();
+        }
+        rslt
+    }
+    /// If the mutex cannot be made consistent, the error is returned with the lock still held,
+    /// for the caller to release.
+    pub(super) fn interpret_lock(&self, ret: c_int) -> io::Result<Option<bool>> {
+        match ret {
+            0 => Ok(Some(false)),
+            libc::EBUSY | libc::ETIMEDOUT => Ok(None),
+            libc::EOWNERDEAD => {
+                make_consistent(self.raw())?;
+                Ok(Some(true))
+            }
+            e => Err(io::Error::from_raw_os_error(e)),
+        }
+    }
+    /// Must only be called by the thread which holds the lock.
+    pub fn unlock(&self) -> io::Result<()> {
+        check(unsafe { libc::pthread_mutex_unlock(self.raw()) })
+    }
+}
+/// pthread functions return error numbers instead of setting `errno`.
+pub(super) fn check(ret: c_int) -> io::Result<()> {
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::from_raw_os_error(ret))
+    }
+}
+
+/// Converts a timeout to an absolute deadline on the given clock, as taken by the timed pthread
+/// functions.
+pub(super) fn deadline(clock: libc::clockid_t, timeout: Duration) -> io::Result<libc::timespec> {
+    let mut now = MaybeUninit::<libc::timespec>::uninit();
+    if unsafe { libc::clock_gettime(clock, now.as_mut_ptr()) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: initialized by clock_gettime()
+    let now = unsafe { now.assume_init() };
+    add_to_timespec(now, timeout)
+}
+fn add_to_timespec(mut ts: libc::timespec, d: Duration) -> io::Result<libc::timespec> {
+    const NANOS_PER_SEC: libc::c_long = 1_000_000_000;
+    let overflow = || io::Error::new(io::ErrorKind::InvalidInput, "timeout is too long");
+    let secs = libc::time_t::try_from(d.as_secs()).map_err(|_| overflow())?;
+    let nanos = libc::c_long::try_from(d.subsec_nanos()).map_err(|_| overflow())?;
+    let mut nanos = ts.tv_nsec.saturating_add(nanos);
+    let mut carry = 0;
+    if nanos >= NANOS_PER_SEC {
+        nanos = nanos.saturating_sub(NANOS_PER_SEC);
+        carry = 1;
+    }
+    let secs = ts.tv_sec.checked_add(secs).and_then(|s| s.checked_add(carry));
+    ts.tv_sec = secs.ok_or_else(overflow)?;
+    ts.tv_nsec = nanos;
+    Ok(ts)
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn timed_lock(mutex: *mut libc::pthread_mutex_t, timeout: Duration) -> c_int {
+    match deadline(libc::CLOCK_REALTIME, timeout) {
+        Ok(deadline) => unsafe { libc::pthread_mutex_timedlock(mutex, &deadline) },
+        Err(e) => e.raw_os_error().unwrap_or(libc::EINVAL),
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn set_robust(attr: *mut libc::pthread_mutexattr_t) -> io::Result<()> {
+    check(unsafe { libc::pthread_mutexattr_setrobust(attr, libc::PTHREAD_MUTEX_ROBUST) })
+}
+#[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+fn set_robust(_: *mut libc::pthread_mutexattr_t) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "robust mutexes are not supported on this platform",
+    ))
+}
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn make_consistent(mutex: *mut libc::pthread_mutex_t) -> io::Result<()> {
+    check(unsafe { libc::pthread_mutex_consistent(mutex) })
+}
+#[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+fn make_consistent(_: *mut libc::pthread_mutex_t) -> io::Result<()> { Ok(()) }
+#[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+fn timed_lock(_: *mut libc::pthread_mutex_t, _: Duration) -> c_int { libc::ENOTSUP }
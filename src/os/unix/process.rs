@@ -0,0 +1,67 @@
+use {
+    super::{c_wrappers, unixprelude::*, uds_local_socket},
+    crate::{local_socket::Stream, process::CHANNEL_ENV_VAR, OrErrno},
+    std::{
+        env, io, mem,
+        os::unix::{net::UnixStream, process::CommandExt},
+        process::{Child, Command},
+        sync::atomic::{AtomicBool, Ordering::SeqCst},
+    },
+};
+
+pub(crate) fn spawn_with_channel(mut cmd: Command) -> io::Result<(Child, Stream)> {
+    // The standard library creates both ends with FD_CLOEXEC set.
+    let (parent, child) = UnixStream::pair()?;
+    let child_fd = child.as_raw_fd();
+    cmd.env(CHANNEL_ENV_VAR, child_fd.to_string());
+    unsafe {
+        // SAFETY: fcntl() is async-signal-safe
+        cmd.pre_exec(move || {
+            c_wrappers::set_inheritable(BorrowedFd::borrow_raw(child_fd), true)
+        });
+    }
+    let proc = cmd.spawn()?;
+    drop(child);
+    Ok((proc, Stream::UdSocket(uds_local_socket::Stream::from(parent))))
+}
+
+/// Makes sure that the inherited descriptor can only be claimed once, since it would otherwise be
+/// closed twice.
+static CLAIMED: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn from_inherited() -> io::Result<Stream> {
+    if CLAIMED.load(SeqCst) {
+        return Err(already_claimed());
+    }
+    let fd = inherited_fd()?;
+    let mut st = unsafe { mem::zeroed::<libc::stat>() };
+    unsafe { libc::fstat(fd, &mut st) != -1 }.true_val_or_errno(())?;
+    if st.st_mode & libc::S_IFMT != libc::S_IFSOCK {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "inherited file descriptor is not a socket",
+        ));
+    }
+    if CLAIMED.swap(true, SeqCst) {
+        return Err(already_claimed());
+    }
+    // Processes spawned by this one must not mistake whatever descriptor ends up with the same
+    // number for a channel of their own.
+    env::remove_var(CHANNEL_ENV_VAR);
+    // SAFETY: the descriptor was inherited specifically for us to own, and CLAIMED ensures that
+    // this only happens once
+    let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+    c_wrappers::set_inheritable(fd.as_fd(), false)?;
+    Ok(Stream::UdSocket(uds_local_socket::Stream::from(UnixStream::from(fd))))
+}
+
+fn already_claimed() -> io::Error {
+    io::Error::new(io::ErrorKind::AlreadyExists, "the inherited channel has already been claimed")
+}
+
+fn inherited_fd() -> io::Result<c_int> {
+    let val = env::var(CHANNEL_ENV_VAR).map_err(|e| io::Error::new(io::ErrorKind::NotFound, e))?;
+    val.parse::<c_int>().ok().filter(|&fd| fd >= 0).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "malformed inherited channel descriptor")
+    })
+}
@@ -0,0 +1,263 @@
+//! POSIX named semaphores, created with `sem_open()`.
+//!
+//! A semaphore holds a count which [posting](NamedSemaphore::post) increments and
+//! [waiting](NamedSemaphore::wait) decrements, blocking while the count is zero. This is the
+//! classic way of coordinating producers and consumers in different processes, such as those
+//! of a queue in [shared memory](super::shared_memory).
+
+use {
+    super::{shared_memory::shm_name, unixprelude::*},
+    crate::local_socket::Name,
+    std::{
+        ffi::{CStr, CString, OsStr},
+        io,
+        sync::{Arc, Mutex, PoisonError},
+        time::Duration,
+    },
+};
+
+/// Options for the creation of a [`NamedSemaphore`].
+#[derive(Clone, Debug)]
+pub struct SemaphoreOptions {
+    mode: mode_t,
+    unlink_on_drop: bool,
+    initial_value: u32,
+}
+impl SemaphoreOptions {
+    /// Starts with the default parameters: a mode of `0o600`, unlinking on drop, and an initial
+    /// value of zero. Identical to `Default::default()`.
+    pub const fn new() -> Self { Self { mode: 0o600, unlink_on_drop: true, initial_value: 0 } }
+    /// Sets the permissions of the semaphore, which, as with files, are masked by the umask.
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn mode(mut self, mode: mode_t) -> Self {
+        self.mode = mode;
+        self
+    }
+    /// Sets whether the name of the semaphore is to be unlinked when the [`NamedSemaphore`] is
+    /// dropped. Processes which have the semaphore open can keep using it either way.
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn unlink_on_drop(mut self, unlink_on_drop: bool) -> Self {
+        self.unlink_on_drop = unlink_on_drop;
+        self
+    }
+    /// Sets the count that the semaphore starts with.
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn initial_value(mut self, initial_value: u32) -> Self {
+        self.initial_value = initial_value;
+        self
+    }
+
+    /// Creates a semaphore with the given name, failing with
+    /// [`AlreadyExists`](io::ErrorKind::AlreadyExists) if one already exists.
+    ///
+    /// For portability, the name should consist of a slash followed by up to 30 characters
+    /// which are not slashes – Apple platforms have a particularly low limit on its length.
+    #[inline]
+    pub fn create(self, name: impl AsRef<OsStr>) -> io::Result<NamedSemaphore> {
+        self.create_impl(to_cstring(name.as_ref())?)
+    }
+    fn create_impl(self, name: CString) -> io::Result<NamedSemaphore> {
+        let sem = unsafe {
+            libc::sem_open(
+                name.as_ptr(),
+                libc::O_CREAT | libc::O_EXCL,
+                libc::c_uint::from(self.mode),
+                libc::c_uint::from(self.initial_value),
+            )
+        };
+        let unlink = self.unlink_on_drop.then_some(name);
+        NamedSemaphore::from_raw(sem, unlink)
+    }
+}
+impl Default for SemaphoreOptions {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+
+/// A POSIX named semaphore.
+///
+/// Created with [`SemaphoreOptions`] or opened by name with [`NamedSemaphore::open()`].
+///
+/// # Example
+/// ```no_run
+/// use interprocess::os::unix::semaphore::{NamedSemaphore, SemaphoreOptions};
+///
+/// let items = SemaphoreOptions::new().create("/example-items")?;
+///
+/// // In another process:
+/// NamedSemaphore::open("/example-items")?.post()?;
+///
+/// items.wait()?;
+/// # std::io::Result::Ok(())
+/// ```
+#[derive(Debug)]
+pub struct NamedSemaphore(Arc<Inner>);
+impl NamedSemaphore {
+    /// Opens an existing semaphore. Its name is never unlinked on drop.
+    #[inline]
+    pub fn open(name: impl AsRef<OsStr>) -> io::Result<Self> {
+        Self::open_impl(&to_cstring(name.as_ref())?)
+    }
+    fn open_impl(name: &CStr) -> io::Result<Self> {
+        let sem = unsafe { libc::sem_open(name.as_ptr(), 0) };
+        Self::from_raw(sem, None)
+    }
+    fn from_raw(sem: *mut libc::sem_t, unlink: Option<CString>) -> io::Result<Self> {
+        if sem == libc::SEM_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self(Arc::new(Inner { sem, unlink: Mutex::new(unlink) })))
+    }
+    /// Removes the semaphore with the given name. Processes which have it open can keep using
+    /// it.
+    pub fn unlink(name: impl AsRef<OsStr>) -> io::Result<()> {
+        let name = to_cstring(name.as_ref())?;
+        if unsafe { libc::sem_unlink(name.as_ptr()) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+    /// Keeps the name of the semaphore from being unlinked when it is dropped.
+    pub fn do_not_unlink_on_drop(&self) {
+        self.0.unlink.lock().unwrap_or_else(PoisonError::into_inner).take();
+    }
+
+    /// Increments the count, waking up a waiter if there is one.
+    #[inline]
+    pub fn post(&self) -> io::Result<()> { self.0.post() }
+    /// Decrements the count, blocking while it is zero.
+    pub fn wait(&self) -> io::Result<()> {
+        while unsafe { libc::sem_wait(self.0.sem) } == -1 {
+            let e = io::Error::last_os_error();
+            if e.kind() != io::ErrorKind::Interrupted {
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+    /// Decrements the count if it is not zero, returning whether it was.
+    pub fn try_wait(&self) -> io::Result<bool> {
+        if unsafe { libc::sem_trywait(self.0.sem) } == -1 {
+            let e = io::Error::last_os_error();
+            return match e.raw_os_error() {
+                Some(libc::EAGAIN) => Ok(false),
+                _ => Err(e),
+            };
+        }
+        Ok(true)
+    }
+    /// Decrements the count, blocking while it is zero, for no longer than the given timeout.
+    /// Returns `false` if the timeout has expired.
+    ///
+    /// # Platform-specific behavior
+    /// ## Apple platforms
+    /// `sem_timedwait()` is not available, so the semaphore is polled every millisecond instead.
+    #[inline]
+    pub fn wait_timeout(&self, timeout: Duration) -> io::Result<bool> {
+        self.0.wait_timeout(timeout)
+    }
+
+    /// Decrements the count, waiting asynchronously while it is zero.
+    ///
+    /// POSIX semaphores cannot be waited on with `poll()`, so the waiting is done by a helper
+    /// task on Tokio's blocking thread pool, which checks every few milliseconds whether the
+    /// future has been dropped. If the future is dropped after the helper has decremented the
+    /// count, the count is incremented back.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+    pub async fn wait_async(&self) -> io::Result<()> {
+        const POLL_INTERVAL: Duration = Duration::from_millis(10);
+        if self.try_wait()? {
+            return Ok(());
+        }
+        let inner = Arc::clone(&self.0);
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::task::spawn_blocking(move || {
+            let rslt = loop {
+                match inner.wait_timeout(POLL_INTERVAL) {
+                    Ok(false) if tx.is_closed() => return,
+                    Ok(false) => continue,
+                    rslt => break rslt.map(drop),
+                }
+            };
+            if let Err(Ok(())) = tx.send(rslt) {
+                // Nobody is waiting anymore, so give the count back.
+                let _ = inner.post();
+            }
+        });
+        rx.await.unwrap_or_else(|_| Err(io::Error::other("semaphore helper task panicked")))
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    sem: *mut libc::sem_t,
+    unlink: Mutex<Option<CString>>,
+}
+// SAFETY: semaphore operations are thread-safe
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+impl Inner {
+    fn post(&self) -> io::Result<()> {
+        if unsafe { libc::sem_post(self.sem) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+    #[cfg(not(target_vendor = "apple"))]
+    fn wait_timeout(&self, timeout: Duration) -> io::Result<bool> {
+        let deadline = super::mutex::deadline(libc::CLOCK_REALTIME, timeout)?;
+        while unsafe { libc::sem_timedwait(self.sem, &deadline) } == -1 {
+            let e = io::Error::last_os_error();
+            match e.raw_os_error() {
+                Some(libc::EINTR) => {}
+                Some(libc::ETIMEDOUT) => return Ok(false),
+                _ => return Err(e),
+            }
+        }
+        Ok(true)
+    }
+    #[cfg(target_vendor = "apple")]
+    fn wait_timeout(&self, timeout: Duration) -> io::Result<bool> {
+        let start = std::time::Instant::now();
+        loop {
+            if unsafe { libc::sem_trywait(self.sem) } == 0 {
+                return Ok(true);
+            }
+            let e = io::Error::last_os_error();
+            if e.raw_os_error() != Some(libc::EAGAIN) {
+                return Err(e);
+            }
+            if start.elapsed() >= timeout {
+                return Ok(false);
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe { libc::sem_close(self.sem) };
+        if let Some(name) = self.unlink.get_mut().unwrap_or_else(PoisonError::into_inner) {
+            unsafe { libc::sem_unlink(name.as_ptr()) };
+        }
+    }
+}
+
+/// Creates a semaphore named after a namespaced local socket name, like
+/// [shared memory](crate::shared_memory) is, with the given suffix.
+#[inline]
+pub(crate) fn create_named(name: &Name<'_>, suffix: &str) -> io::Result<NamedSemaphore> {
+    SemaphoreOptions::new().create_impl(shm_name(name, suffix)?)
+}
+#[inline]
+pub(crate) fn open_named(name: &Name<'_>, suffix: &str) -> io::Result<NamedSemaphore> {
+    NamedSemaphore::open_impl(&shm_name(name, suffix)?)
+}
+
+fn to_cstring(name: &OsStr) -> io::Result<CString> {
+    CString::new(name.as_bytes()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
@@ -0,0 +1,444 @@
+//! POSIX shared memory objects, created with `shm_open()` and mapped with `mmap()`.
+//!
+//! This is the Unix half of [`shared_memory`](crate::shared_memory), for cases where control
+//! over Unix-specific parameters is needed.
+
+use {
+    super::{c_wrappers, unixprelude::*},
+    crate::{
+        local_socket::{Name, NameInner, Stream},
+        AsMutPtr, FdOrErrno, OrErrno,
+    },
+    std::{
+        collections::VecDeque,
+        ffi::{CStr, CString, OsStr},
+        io,
+        mem::{zeroed, MaybeUninit},
+        ptr::{self, NonNull},
+        slice,
+    },
+};
+
+/// Options for the creation of a [`ShmObject`].
+#[derive(Clone, Debug)]
+pub struct ShmOptions {
+    mode: mode_t,
+    unlink_on_drop: bool,
+    inheritable: bool,
+}
+impl ShmOptions {
+    /// Starts with the default parameters: a mode of `0o600`, unlinking on drop, and no
+    /// inheritance by child processes. Identical to `Default::default()`.
+    pub const fn new() -> Self { Self { mode: 0o600, unlink_on_drop: true, inheritable: false } }
+    /// Sets the permissions of named objects.
+    ///
+    /// Unlike the mode passed to `shm_open()`, this is not masked by the umask: it is applied
+    /// with `fchmod()` right after creation, before the object is resized. Until then, the
+    /// object is only accessible by its owner.
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn mode(mut self, mode: mode_t) -> Self {
+        self.mode = mode;
+        self
+    }
+    /// Sets whether the name of the object is to be unlinked when the [`ShmObject`] is dropped.
+    /// Objects without names are destroyed once the last file descriptor and mapping of them
+    /// go away.
+    ///
+    /// Has no effect on anonymous objects, which are unlinked right away.
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn unlink_on_drop(mut self, unlink_on_drop: bool) -> Self {
+        self.unlink_on_drop = unlink_on_drop;
+        self
+    }
+    /// Sets whether the file descriptor is to be inherited by child processes.
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn inheritable(mut self, inheritable: bool) -> Self {
+        self.inheritable = inheritable;
+        self
+    }
+
+    /// Creates an object of the given size with the given name, failing with
+    /// [`AlreadyExists`](io::ErrorKind::AlreadyExists) if one already exists.
+    ///
+    /// For portability, the name should consist of a slash followed by up to 30 characters
+    /// which are not slashes – Apple platforms have a particularly low limit on its length.
+    #[inline]
+    pub fn create(self, name: impl AsRef<OsStr>, size: usize) -> io::Result<ShmObject> {
+        self.create_impl(to_cstring(name.as_ref())?, size)
+    }
+    fn create_impl(self, name: CString, size: usize) -> io::Result<ShmObject> {
+        let fd = shm_open(&name, libc::O_RDWR | libc::O_CREAT | libc::O_EXCL, 0o600)?;
+        // From this point on, the guard unlinks the name if anything goes wrong.
+        let guard = UnlinkGuard(name);
+        unsafe { libc::fchmod(fd.as_raw_fd(), self.mode) != -1 }.true_val_or_errno(())?;
+        self.setup(&fd, size)?;
+        let unlink = if self.unlink_on_drop {
+            Some(guard)
+        } else {
+            guard.forget();
+            None
+        };
+        Ok(ShmObject { fd, size, unlink })
+    }
+    /// Creates an object of the given size which has no name and is thus only reachable through
+    /// the file descriptor, which can be inherited by child processes or sent over a Unix domain
+    /// socket.
+    pub fn create_anonymous(self, size: usize) -> io::Result<ShmObject> {
+        let fd = anonymous_fd()?;
+        self.setup(&fd, size)?;
+        Ok(ShmObject { fd, size, unlink: None })
+    }
+    fn setup(&self, fd: &OwnedFd, size: usize) -> io::Result<()> {
+        let len = libc::off_t::try_from(size).map_err(|_| too_big())?;
+        unsafe { libc::ftruncate(fd.as_raw_fd(), len) != -1 }.true_val_or_errno(())?;
+        if self.inheritable {
+            c_wrappers::set_inheritable(fd.as_fd(), true)?;
+        }
+        Ok(())
+    }
+}
+impl Default for ShmOptions {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+
+/// A shared memory object, referred to by a file descriptor.
+///
+/// The object itself is only destroyed once its name is unlinked and all file descriptors and
+/// mappings of it are gone.
+#[derive(Debug)]
+pub struct ShmObject {
+    fd: OwnedFd,
+    size: usize,
+    unlink: Option<UnlinkGuard>,
+}
+impl ShmObject {
+    /// Creates an object which has no name, with the default options. See
+    /// [`ShmOptions::create_anonymous()`].
+    #[inline]
+    pub fn create_anonymous(size: usize) -> io::Result<Self> {
+        ShmOptions::new().create_anonymous(size)
+    }
+    /// Opens an existing named object for reading and writing. Its name is not unlinked on drop.
+    #[inline]
+    pub fn open(name: impl AsRef<OsStr>) -> io::Result<Self> {
+        Self::open_impl(&to_cstring(name.as_ref())?)
+    }
+    fn open_impl(name: &CStr) -> io::Result<Self> {
+        let fd = shm_open(name, libc::O_RDWR, 0)?;
+        let size = usize::try_from(fstat_size(fd.as_fd())?).map_err(|_| too_big())?;
+        Ok(Self { fd, size, unlink: None })
+    }
+    /// Adopts a file descriptor of a shared memory object which is at least `size` bytes long,
+    /// such as one inherited from a parent process or received over a Unix domain socket.
+    ///
+    /// Ownership of the descriptor passes to the returned `ShmObject`, which closes it on drop
+    /// but never unlinks the object's name. The size of the object is checked with `fstat()`,
+    /// failing with [`InvalidData`](io::ErrorKind::InvalidData) if it's smaller than `size`.
+    ///
+    /// # Safety
+    /// The object must not be truncated below `size` bytes for as long as the `ShmObject` or any
+    /// of its [mappings](Self::map) exist, since accessing a mapping past the end of the object
+    /// raises `SIGBUS`. The check above only covers the size at the time of the call.
+    pub unsafe fn from_fd(fd: OwnedFd, size: usize) -> io::Result<Self> {
+        if fstat_size(fd.as_fd())? < u64::try_from(size).unwrap_or(u64::MAX) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "shared memory object is smaller than its stated size",
+            ));
+        }
+        Ok(Self { fd, size, unlink: None })
+    }
+    /// Returns the size of the object in bytes.
+    #[inline]
+    pub fn size(&self) -> usize { self.size }
+    /// Maps the whole object into memory for reading and writing.
+    pub fn map(&self) -> io::Result<Mapping> { Mapping::new(self.fd.as_fd(), self.size) }
+    /// Disables unlinking of the name on drop, leaving the object around for other processes to
+    /// open after this one is done with it.
+    #[inline]
+    pub fn do_not_unlink_on_drop(&mut self) {
+        if let Some(guard) = self.unlink.take() {
+            guard.forget();
+        }
+    }
+}
+impl AsFd for ShmObject {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> { self.fd.as_fd() }
+}
+/// Unlinks the name of the object if it would have been unlinked on drop.
+impl From<ShmObject> for OwnedFd {
+    #[inline]
+    fn from(obj: ShmObject) -> Self { obj.fd }
+}
+
+#[derive(Debug)]
+struct UnlinkGuard(CString);
+impl UnlinkGuard {
+    #[inline]
+    fn forget(self) { std::mem::forget(self) }
+}
+impl Drop for UnlinkGuard {
+    fn drop(&mut self) { unsafe { libc::shm_unlink(self.0.as_ptr()) }; }
+}
+
+/// Maps a namespaced local socket name to the name of a shared memory object by prepending a
+/// slash and appending the given suffix.
+///
+/// The same mapping is used for named semaphores, whose names are distinguished from those of
+/// shared memory objects by their suffixes.
+pub(super) fn shm_name(name: &Name<'_>, suffix: &str) -> io::Result<CString> {
+    let name: &[u8] = match &name.0 {
+        NameInner::UdSocketPseudoNs(name) => name.as_bytes(),
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        NameInner::UdSocketNs(name) => name,
+        NameInner::UdSocketPath(..) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "shared memory objects cannot be named with filesystem paths",
+            ))
+        }
+    };
+    let mut buf = Vec::with_capacity(name.len().saturating_add(suffix.len()).saturating_add(2));
+    buf.push(b'/');
+    buf.extend_from_slice(name);
+    buf.extend_from_slice(suffix.as_bytes());
+    CString::new(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+#[inline]
+pub(crate) fn create_named(name: &Name<'_>, size: usize) -> io::Result<ShmObject> {
+    ShmOptions::new().create_impl(shm_name(name, "")?, size)
+}
+pub(crate) fn open_named(name: &Name<'_>, size: Option<usize>) -> io::Result<ShmObject> {
+    let obj = ShmObject::open_impl(&shm_name(name, "")?)?;
+    match size {
+        // SAFETY: named objects are shared with cooperating processes only, which are trusted
+        // not to truncate them, just like with ShmObject::open()
+        Some(size) => unsafe { ShmObject::from_fd(obj.into(), size) },
+        None => Ok(obj),
+    }
+}
+
+fn to_cstring(name: &OsStr) -> io::Result<CString> {
+    CString::new(name.as_bytes()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+fn fstat_size(fd: BorrowedFd<'_>) -> io::Result<u64> {
+    let mut st = unsafe { zeroed::<libc::stat>() };
+    unsafe { libc::fstat(fd.as_raw_fd(), st.as_mut_ptr()) != -1 }.true_val_or_errno(())?;
+    Ok(u64::try_from(st.st_size).unwrap_or(0))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn anonymous_fd() -> io::Result<OwnedFd> {
+    let name = b"interprocess\0";
+    let flags = libc::MFD_CLOEXEC | libc::MFD_ALLOW_SEALING;
+    let fd = unsafe { libc::memfd_create(name.as_ptr().cast(), flags) };
+    // SAFETY: we just created this descriptor
+    fd.fd_or_errno().map(|fd| unsafe { OwnedFd::from_raw_fd(fd) })
+}
+/// Emulates anonymous objects by creating a named one with a unique name and unlinking it right
+/// away.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn anonymous_fd() -> io::Result<OwnedFd> {
+    use std::{
+        process,
+        sync::atomic::{AtomicU32, Ordering::Relaxed},
+    };
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    loop {
+        // Apple platforms limit names to 31 bytes.
+        let name = format!("/ipc-{:x}-{:x}", process::id(), COUNTER.fetch_add(1, Relaxed));
+        let name = CString::new(name).map_err(io::Error::other)?;
+        match shm_open(&name, libc::O_RDWR | libc::O_CREAT | libc::O_EXCL, 0o600) {
+            Ok(fd) => {
+                drop(UnlinkGuard(name));
+                return Ok(fd);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn shm_open(name: &CStr, oflag: c_int, mode: mode_t) -> io::Result<OwnedFd> {
+    // shm_open() is variadic on Apple platforms, where the mode thus undergoes integer promotion.
+    #[cfg(target_vendor = "apple")]
+    let mode = libc::c_uint::from(mode);
+    let fd = unsafe { libc::shm_open(name.as_ptr(), oflag | libc::O_CLOEXEC, mode) };
+    let fd = fd.fd_or_errno()?;
+    // SAFETY: we just created this descriptor
+    let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+    // POSIX does not require O_CLOEXEC to be honored by shm_open().
+    c_wrappers::set_inheritable(fd.as_fd(), false)?;
+    Ok(fd)
+}
+
+/// A read-write mapping of a [`ShmObject`], unmapped on drop.
+///
+/// The mapping keeps the object alive even if the `ShmObject` is dropped.
+#[derive(Debug)]
+pub struct Mapping {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+// The mapping is just memory – the synchronization of access to it is up to the user.
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+impl Mapping {
+    fn new(fd: BorrowedFd<'_>, len: usize) -> io::Result<Self> {
+        if len == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot map an empty shared memory object",
+            ));
+        }
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd.as_raw_fd(),
+                0,
+            )
+        };
+        let ptr = (ptr != libc::MAP_FAILED).true_val_or_errno(ptr)?;
+        let ptr = NonNull::new(ptr.cast()).ok_or_else(io::Error::last_os_error)?;
+        Ok(Self { ptr, len })
+    }
+    /// Returns a pointer to the start of the mapping, which is valid for reads and writes of
+    /// [`.len()`](Self::len) bytes for as long as the mapping exists.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut u8 { self.ptr.as_ptr() }
+    /// Returns the length of the mapping in bytes.
+    #[inline]
+    pub fn len(&self) -> usize { self.len }
+    /// Returns `false`, since mappings cannot be empty. Provided for consistency with slices.
+    #[inline]
+    pub fn is_empty(&self) -> bool { false }
+    /// Borrows the contents of the mapping.
+    ///
+    /// # Safety
+    /// No other thread or process may write to the object while the slice exists.
+    #[inline]
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+    /// Mutably borrows the contents of the mapping.
+    ///
+    /// # Safety
+    /// No other thread or process may access the object while the slice exists, including
+    /// through other mappings of it in this process.
+    #[inline]
+    pub unsafe fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.as_ptr(), self.len) }
+    }
+}
+impl Drop for Mapping {
+    fn drop(&mut self) { unsafe { libc::munmap(self.ptr.as_ptr().cast(), self.len) }; }
+}
+
+pub(crate) type Segment = ShmObject;
+
+/// File descriptors received out of band, waiting for their headers to be parsed.
+pub(crate) type SegmentQueue = VecDeque<OwnedFd>;
+
+/// Receives from the stream, collecting the shared memory objects sent along with the data into
+/// `segments`. Their sizes are left to be filled in by [`claim_segment()`].
+pub(crate) fn recv_with_segments(
+    stream: &Stream,
+    buf: &mut [MaybeUninit<u8>],
+    segments: &mut SegmentQueue,
+) -> io::Result<usize> {
+    let Stream::UdSocket(stream) = stream;
+    let mut fds = Vec::new();
+    let rslt = stream.recv_with_fds(buf, &mut fds);
+    segments.extend(fds);
+    rslt
+}
+
+/// Sends `header`, which must not be empty, along with the shared memory object. The object
+/// arrives in the `segments` queue of [`recv_with_segments()`] on the other end, to be picked up
+/// with [`claim_segment()`].
+pub(crate) fn send_with_segment(stream: &Stream, header: &[u8], seg: &Segment) -> io::Result<()> {
+    seal_size(seg.as_fd())?;
+    let Stream::UdSocket(stream) = stream;
+    let fd = stream.as_fd();
+    let mut sent = loop {
+        match c_wrappers::send_with_fds(fd, header, &[seg.as_fd()]) {
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            rslt => break rslt?,
+        }
+    };
+    while let Some(rem) = header.get(sent..).filter(|rem| !rem.is_empty()) {
+        match c_wrappers::send(fd, rem) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(incr) => sent = sent.saturating_add(incr),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Sender-side token for a shared memory object. File descriptors travel out of band, so there
+/// is nothing to put in it.
+#[inline]
+pub(crate) fn segment_token(_: &Stream, _: &Segment) -> io::Result<u64> { Ok(0) }
+
+/// Takes the next shared memory object out of the queue filled by [`recv_with_segments()`].
+pub(crate) fn claim_segment(
+    _: &Stream,
+    _token: u64,
+    size: usize,
+    segments: &mut SegmentQueue,
+) -> io::Result<Segment> {
+    let fd = segments.pop_front().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "shared memory descriptor did not arrive along with its header",
+        )
+    })?;
+    ensure_size_sealed(fd.as_fd())?;
+    // SAFETY: on Linux and Android, the size has just been checked to be sealed. Elsewhere, the
+    // peer is trusted not to truncate the object, as documented on HybridStream
+    unsafe { Segment::from_fd(fd, size) }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const SIZE_SEALS: c_int = libc::F_SEAL_SHRINK | libc::F_SEAL_GROW;
+
+/// Fixes the size of the object for good, so that the receiver can rely on its mapping not
+/// extending past the end of the object later on.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn seal_size(fd: BorrowedFd<'_>) -> io::Result<()> {
+    unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_ADD_SEALS, SIZE_SEALS) != -1 }
+        .true_val_or_errno(())
+}
+/// Checks that the sender has sealed the size of the object, since a peer which truncates it
+/// while we're reading from it would otherwise get us killed with `SIGBUS`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn ensure_size_sealed(fd: BorrowedFd<'_>) -> io::Result<()> {
+    let seals = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GET_SEALS) };
+    let seals = (seals != -1).true_val_or_errno(seals)?;
+    if seals & SIZE_SEALS != SIZE_SEALS {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "shared memory object was received without its size being sealed",
+        ));
+    }
+    Ok(())
+}
+/// Objects created with `shm_open()` cannot be sealed.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn seal_size(_: BorrowedFd<'_>) -> io::Result<()> { Ok(()) }
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn ensure_size_sealed(_: BorrowedFd<'_>) -> io::Result<()> { Ok(()) }
+
+fn too_big() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "shared memory object size is too big")
+}
@@ -0,0 +1,112 @@
+use {
+    super::{c_wrappers, unixprelude::*},
+    crate::{signal::Signal, OrErrno},
+    std::{
+        fs::File,
+        io::{self, prelude::*},
+        sync::atomic::{AtomicI32, Ordering},
+        thread,
+    },
+};
+
+/// Write end of the self-pipe, which the signal handler writes signal numbers into.
+static PIPE_WRITE: AtomicI32 = AtomicI32::new(-1);
+
+const SIGNALS: [(c_int, Signal); 3] = [
+    (libc::SIGINT, Signal::Interrupt),
+    (libc::SIGTERM, Signal::Terminate),
+    (libc::SIGHUP, Signal::Hangup),
+];
+
+/// Installs handlers for the termination signals, which forward them to `dispatch` on a helper
+/// thread. Must only be called once.
+///
+/// The handlers use the self-pipe trick: they write the number of the signal into a
+/// nonblocking pipe, which is the only thing that they can safely do, and the helper thread
+/// reads it from the other end.
+pub(crate) fn install(dispatch: fn(Signal)) -> io::Result<()> {
+    let mut fds = [0; 2];
+    unsafe { libc::pipe(fds.as_mut_ptr()) != -1 }.true_val_or_errno(())?;
+    let [r, w] = fds;
+    // SAFETY: we just created both of those file descriptors
+    let (r, w) = unsafe { (OwnedFd::from_raw_fd(r), OwnedFd::from_raw_fd(w)) };
+    for fd in [r.as_fd(), w.as_fd()] {
+        c_wrappers::set_inheritable(fd, false)?;
+    }
+    // A full pipe must not block the handler. Signals are dropped in that case, which is fine,
+    // since the helper thread has plenty of them to process already.
+    c_wrappers::set_nonblocking(w.as_fd(), true)?;
+
+    let mut r = File::from(r);
+    thread::Builder::new().name("interprocess signal dispatcher".to_owned()).spawn(move || {
+        let mut buf = [0; 1];
+        loop {
+            match r.read(&mut buf) {
+                Ok(0) => return,
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => return,
+            }
+            let signum = c_int::from(buf.first().copied().unwrap_or(0));
+            if let Some((_, signal)) = SIGNALS.iter().find(|(num, _)| *num == signum) {
+                dispatch(*signal);
+            }
+        }
+    })?;
+    // The write end stays open for as long as the process runs.
+    PIPE_WRITE.store(w.into_raw_fd(), Ordering::Release);
+
+    for (signum, _) in SIGNALS {
+        // SAFETY: all-zero is a valid sigaction structure
+        let mut action = unsafe { std::mem::zeroed::<libc::sigaction>() };
+        #[allow(clippy::as_conversions)]
+        {
+            action.sa_sigaction = handler as extern "C" fn(c_int) as libc::sighandler_t;
+        }
+        action.sa_flags = libc::SA_RESTART;
+        unsafe {
+            libc::sigemptyset(&mut action.sa_mask);
+            libc::sigaction(signum, &action, std::ptr::null_mut()) != -1
+        }
+        .true_val_or_errno(())?;
+    }
+    Ok(())
+}
+
+extern "C" fn handler(signum: c_int) {
+    // The handler runs on whichever thread the signal interrupted, which might be about to read
+    // errno after a failed call, so write() must not clobber it.
+    let errno = errno_location();
+    // SAFETY: errno_location() returns a valid pointer to the errno of the current thread
+    let saved = errno.map(|errno| unsafe { *errno });
+    let fd = PIPE_WRITE.load(Ordering::Acquire);
+    let byte = u8::try_from(signum).unwrap_or(0);
+    // SAFETY: write() is async-signal-safe
+    unsafe { libc::write(fd, std::ptr::addr_of!(byte).cast(), 1) };
+    if let (Some(errno), Some(saved)) = (errno, saved) {
+        // SAFETY: as above
+        unsafe { *errno = saved };
+    }
+}
+
+/// Returns the location of the current thread's errno, on platforms where it is known.
+#[cfg(target_os = "linux")]
+fn errno_location() -> Option<*mut c_int> { Some(unsafe { libc::__errno_location() }) }
+#[cfg(any(target_os = "android", target_os = "netbsd", target_os = "openbsd"))]
+fn errno_location() -> Option<*mut c_int> { Some(unsafe { libc::__errno() }) }
+#[cfg(any(target_vendor = "apple", target_os = "freebsd", target_os = "dragonfly"))]
+fn errno_location() -> Option<*mut c_int> { Some(unsafe { libc::__error() }) }
+#[cfg(any(target_os = "solaris", target_os = "illumos"))]
+fn errno_location() -> Option<*mut c_int> { Some(unsafe { libc::___errno() }) }
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_vendor = "apple",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "solaris",
+    target_os = "illumos",
+)))]
+fn errno_location() -> Option<*mut c_int> { None }
@@ -5,7 +5,7 @@ mod stream;
 
 pub use {listener::*, stream::*};
 
-#[cfg(feature = "tokio")]
+#[cfg(feature = "local-socket-tokio")]
 pub(crate) mod tokio {
     mod listener;
     mod stream;
@@ -18,49 +18,267 @@ use std::os::android::net::SocketAddrExt;
 use std::os::linux::net::SocketAddrExt;
 use {
     crate::{
-        local_socket::{Name, NameInner},
-        os::unix::unixprelude::*,
+        local_socket::{ConnectOptions, Name, NameInner, NamePolicy},
+        os::unix::{
+            c_wrappers,
+            local_socket::name_type::{NMCAP, SUN_LEN},
+            unixprelude::*,
+        },
     },
     std::{
         borrow::Cow,
         ffi::{OsStr, OsString},
-        fs, io, mem,
+        fs, io,
+        mem,
+        ops::Deref,
         os::unix::net::SocketAddr,
         path::Path,
+        sync::atomic::{AtomicU32, Ordering::Relaxed},
     },
 };
 
+/// Carries out the [name policy](NamePolicy) of a listener on drop.
 #[derive(Clone, Debug, Default)]
-struct ReclaimGuard(Option<Name<'static>>);
+struct ReclaimGuard {
+    name: Option<Name<'static>>,
+    policy: NamePolicy,
+    /// Device and inode numbers of the socket file, recorded for `UnlinkIfOwned`.
+    identity: Option<(u64, u64)>,
+}
 impl ReclaimGuard {
-    fn new(name: Name<'static>) -> Self { Self(if name.is_path() { Some(name) } else { None }) }
-    #[cfg_attr(not(feature = "tokio"), allow(dead_code))]
-    fn take(&mut self) -> Self { Self(self.0.take()) }
-    fn forget(&mut self) { self.0 = None; }
+    fn new(name: Name<'static>, policy: NamePolicy) -> Self {
+        let NameInner::UdSocketPath(path) = &name.0 else { return Self::default() };
+        let identity = match policy {
+            NamePolicy::Keep => return Self::default(),
+            NamePolicy::UnlinkIfOwned => file_identity(Path::new(path)),
+            _ => None,
+        };
+        Self { name: Some(name), policy, identity }
+    }
+    #[cfg_attr(not(feature = "local-socket-tokio"), allow(dead_code))]
+    fn take(&mut self) -> Self { mem::take(self) }
+    fn forget(&mut self) { self.name = None; }
 }
 impl Drop for ReclaimGuard {
     fn drop(&mut self) {
-        if let Self(Some(Name(NameInner::UdSocketPath(path)))) = self {
-            let _ = std::fs::remove_file(path);
+        let Some(Name(NameInner::UdSocketPath(path))) = &self.name else { return };
+        let path = Path::new(path);
+        match self.policy {
+            NamePolicy::Keep => {}
+            NamePolicy::UnlinkIfOwned => {
+                if self.identity.is_some() && file_identity(path) == self.identity {
+                    let _ = fs::remove_file(path);
+                }
+            }
+            NamePolicy::RenameAside => {
+                let mut aside = path.as_os_str().to_owned();
+                aside.push(".old");
+                let _ = fs::rename(path, aside);
+            }
+            NamePolicy::Unlink => {
+                let _ = fs::remove_file(path);
+            }
         }
     }
 }
+fn file_identity(path: &Path) -> Option<(u64, u64)> {
+    let meta = fs::symlink_metadata(path).ok()?;
+    Some((meta.dev(), meta.ino()))
+}
+
+/// The accept backlog last requested for a listener.
+#[derive(Debug)]
+struct Backlog(AtomicU32);
+impl Backlog {
+    /// Stands for the platform default, which is never requested explicitly, since `listen()`
+    /// takes a `c_int`.
+    const DEFAULT: u32 = u32::MAX;
+    fn new(backlog: Option<u32>) -> Self {
+        Self(AtomicU32::new(backlog.map_or(Self::DEFAULT, Self::clamp)))
+    }
+    #[inline]
+    fn clamp(backlog: u32) -> u32 { backlog.min(c_int::MAX.unsigned_abs()) }
+    fn get(&self) -> Option<u32> {
+        Some(self.0.load(Relaxed)).filter(|&backlog| backlog != Self::DEFAULT)
+    }
+    fn set(&self, fd: BorrowedFd<'_>, backlog: u32) -> io::Result<()> {
+        let backlog = Self::clamp(backlog);
+        c_wrappers::listen(fd, Some(backlog))?;
+        self.0.store(backlog, Relaxed);
+        Ok(())
+    }
+}
+impl Default for Backlog {
+    #[inline]
+    fn default() -> Self { Self::new(None) }
+}
+
+/// Recovers the name a socket is bound to from its address, if it has one.
+fn addr_to_name(addr: &SocketAddr) -> Option<Name<'static>> {
+    if let Some(path) = addr.as_pathname() {
+        return Some(Name(NameInner::UdSocketPath(Cow::Owned(path.as_os_str().to_owned()))));
+    }
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if let Some(name) = addr.as_abstract_name() {
+        return Some(Name(NameInner::UdSocketNs(Cow::Owned(name.to_owned()))));
+    }
+    None
+}
+
+/// On Android, SELinux policy denies apps the use of filesystem-bound sockets almost everywhere,
+/// which surfaces as a bare `EACCES` that doesn't hint at the solution. This adds the missing
+/// guidance to such errors.
+#[cfg(target_os = "android")]
+fn explain_denial(name: &Name<'_>, e: io::Error) -> io::Error {
+    if e.kind() != io::ErrorKind::PermissionDenied || name.is_namespaced() {
+        return e;
+    }
+    io::Error::new(
+        e.kind(),
+        format!(
+            "{e} (on Android, SELinux policy generally prevents apps from using Unix domain \
+sockets bound to the filesystem outside of their own data directory – use a GenericNamespaced \
+name, which maps to the abstract namespace, instead)"
+        ),
+    )
+}
+#[cfg(not(target_os = "android"))]
+#[inline(always)]
+fn explain_denial(name: &Name<'_>, e: io::Error) -> io::Error {
+    let _ = name;
+    e
+}
+
+/// A socket address, along with the directory it refers through if it was shortened by
+/// `shorten_path()`. The directory must stay open until the socket is bound or connected.
+#[derive(Debug)]
+pub(crate) struct UdAddr {
+    addr: SocketAddr,
+    _dir: Option<OwnedFd>,
+}
+impl From<SocketAddr> for UdAddr {
+    #[inline]
+    fn from(addr: SocketAddr) -> Self { Self { addr, _dir: None } }
+}
+impl Deref for UdAddr {
+    type Target = SocketAddr;
+    #[inline]
+    fn deref(&self) -> &SocketAddr { &self.addr }
+}
 
 #[allow(clippy::indexing_slicing)]
-fn name_to_addr(name: Name<'_>, create_dirs: bool) -> io::Result<SocketAddr> {
+pub(crate) fn name_to_addr(name: Name<'_>, create_dirs: bool) -> io::Result<UdAddr> {
     match name.0 {
-        NameInner::UdSocketPath(path) => SocketAddr::from_pathname(path),
-        NameInner::UdSocketPseudoNs(name) => construct_and_prepare_pseudo_ns(name, create_dirs),
+        NameInner::UdSocketPath(path) => path_to_addr(Path::new(&path)),
+        NameInner::UdSocketPseudoNs(name) => {
+            construct_and_prepare_pseudo_ns(name, create_dirs).map(UdAddr::from)
+        }
         #[cfg(any(target_os = "linux", target_os = "android"))]
-        NameInner::UdSocketNs(name) => SocketAddr::from_abstract_name(name),
+        NameInner::UdSocketNs(name) => SocketAddr::from_abstract_name(name).map(UdAddr::from),
     }
 }
 
-const SUN_LEN: usize = {
-    let dummy = unsafe { mem::zeroed::<libc::sockaddr_un>() };
-    dummy.sun_path.len()
-};
-const NMCAP: usize = SUN_LEN - "/run/user/18446744073709551614/".len();
+/// Performs the checks that the client asked for before connecting to the given address.
+fn check_before_connect(options: &ConnectOptions<'_>, addr: &SocketAddr) -> io::Result<()> {
+    if options.audit_directory {
+        audit_directory(addr)?;
+    }
+    if options.socket_owner.is_some() || options.forbidden_socket_mode != 0 {
+        check_socket_file(addr, options.socket_owner, options.forbidden_socket_mode)?;
+    }
+    Ok(())
+}
+
+/// Checks that the socket file at the given address is a socket, is owned by `owner` if that is
+/// specified, and has none of the permission bits in `forbidden_mode` set.
+pub(crate) fn check_socket_file(
+    addr: &SocketAddr,
+    owner: Option<u32>,
+    forbidden_mode: libc::mode_t,
+) -> io::Result<()> {
+    let Some(path) = addr.as_pathname() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "local socket name does not refer to a socket file",
+        ));
+    };
+    let meta = fs::metadata(path)?;
+    let refuse = |why| {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("socket file {} {why}", path.display()),
+        ))
+    };
+    if !meta.file_type().is_socket() {
+        return refuse("is not a socket".to_owned());
+    }
+    if let Some(owner) = owner.filter(|&o| o != meta.uid()) {
+        return refuse(format!("is owned by user {}, not {owner}", meta.uid()));
+    }
+    let mode = meta.mode() & 0o7777;
+    let forbidden = mode & u32::from(forbidden_mode);
+    if forbidden != 0 {
+        return refuse(format!("has mode {mode:o}, which includes forbidden bits {forbidden:o}"));
+    }
+    Ok(())
+}
+
+/// Checks that nobody but the current user and root can replace files in the directory that
+/// the socket file at the given address resides in.
+fn audit_directory(addr: &SocketAddr) -> io::Result<()> {
+    let Some(dir) = addr.as_pathname().and_then(Path::parent) else { return Ok(()) };
+    let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+    let meta = fs::metadata(dir)?;
+    let refuse = |why| {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("socket directory {} {why}", dir.display()),
+        ))
+    };
+    let owner = meta.uid();
+    if owner != 0 && owner != unsafe { libc::geteuid() } {
+        return refuse("is owned by another user");
+    }
+    let mode = meta.mode();
+    if mode & 0o002 != 0 && mode & 0o1000 == 0 {
+        return refuse("is writable by everyone and lacks the sticky bit");
+    }
+    Ok(())
+}
+
+/// Overlong paths are only shortened on Linux and Android. Elsewhere, doing so would take
+/// `chdir()`, whose effect is process-wide rather than confined to the calling thread, and
+/// would thus break relative paths used concurrently by other threads.
+fn path_to_addr(path: &Path) -> io::Result<UdAddr> {
+    match SocketAddr::from_pathname(path) {
+        Ok(addr) => Ok(addr.into()),
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        Err(..) if path.as_os_str().len() >= SUN_LEN => shorten_path(path),
+        Err(e) => Err(e),
+    }
+}
+
+/// Makes a path which is too long for `sun_path` fit by opening its parent directory and
+/// referring to the socket file through `/proc/self/fd`. This only helps if the file name
+/// itself is short enough, which is the common case of a deeply nested directory.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn shorten_path(path: &Path) -> io::Result<UdAddr> {
+    let toolong = || io::Error::new(io::ErrorKind::InvalidInput, TOOLONG);
+    let base = path.file_name().ok_or_else(toolong)?;
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let dir = fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_PATH | libc::O_DIRECTORY)
+        .open(dir)
+        .map(OwnedFd::from)?;
+    let mut short = OsString::from(format!("/proc/self/fd/{}/", dir.as_raw_fd()));
+    short.push(base);
+    let addr = SocketAddr::from_pathname(short)?;
+    Ok(UdAddr { addr, _dir: Some(dir) })
+}
 
 static TOOLONG: &str = "local socket name length exceeds capacity of sun_path of sockaddr_un";
 
@@ -1,11 +1,16 @@
 use {
-    super::{name_to_addr, ReclaimGuard, Stream},
+    super::{
+        addr_to_name, audit_directory, explain_denial, name_to_addr, Backlog, ReclaimGuard,
+        Stream,
+    },
     crate::{
         local_socket::{
+            audit::{self, Direction},
             traits::{self, Stream as _},
-            ListenerNonblockingMode, ListenerOptions,
+            ListenerNonblockingMode, ListenerOptions, ListenerStats, Name, StatsCounters,
         },
         os::unix::c_wrappers,
+        NonblockingCache,
     },
     std::{
         io,
@@ -16,6 +21,14 @@ use {
         sync::atomic::{AtomicBool, Ordering::SeqCst},
     },
 };
+#[cfg(feature = "handshake")]
+use {
+    crate::local_socket::handshake::{self, PendingHandshake, SharedSecret},
+    std::{
+        io::prelude::*,
+        time::{Duration, Instant},
+    },
+};
 #[cfg(unix)]
 use std::os::unix::net::UnixListener;
 #[cfg(target_vendor = "wasmer")]
@@ -26,8 +39,17 @@ use std::os::wasi::net::UnixListener;
 #[derive(Debug)]
 pub struct Listener {
     pub(super) listener: UnixListener,
+    pub(super) name: Option<Name<'static>>,
     pub(super) reclaim: ReclaimGuard,
     pub(super) nonblocking_streams: AtomicBool,
+    /// Whether the listener itself is in nonblocking mode.
+    pub(super) nonblocking_accept: NonblockingCache,
+    pub(super) backlog: Backlog,
+    #[cfg(feature = "handshake")]
+    pub(super) shared_secret: Option<SharedSecret>,
+    #[cfg(feature = "handshake")]
+    pub(super) handshake_timeout: Duration,
+    pub(super) stats: StatsCounters,
 }
 impl Listener {
     fn decode_listen_error(error: io::Error) -> io::Error {
@@ -36,6 +58,48 @@ impl Listener {
             _ => return error,
         })
     }
+    fn accept_uncounted(&self) -> io::Result<Stream> {
+        let stream = self.accept_unauthenticated()?;
+        #[cfg(feature = "handshake")]
+        if let Some(secret) = &self.shared_secret {
+            authenticate_within(&stream, secret, self.handshake_timeout)?;
+            if self.nonblocking_streams.load(SeqCst) {
+                stream.set_nonblocking(true)?;
+            }
+        }
+        Ok(stream)
+    }
+    /// Accepts a connection, leaving the stream in blocking mode if the handshake has yet to be
+    /// performed over it.
+    fn accept_unauthenticated(&self) -> io::Result<Stream> {
+        // TODO(2.3.0) make use of the second return value in some shape or form
+        let stream = self.listener.accept().map(|(s, _)| Stream::from(s))?;
+        #[cfg(feature = "handshake")]
+        if self.shared_secret.is_some() {
+            stream.set_nonblocking(false)?;
+            return Ok(stream);
+        }
+        if self.nonblocking_streams.load(SeqCst) {
+            stream.set_nonblocking(true)?;
+        }
+        Ok(stream)
+    }
+    #[cfg(feature = "handshake")]
+    pub(crate) fn accept_pending(&self) -> io::Result<PendingHandshake> {
+        let rslt = self.accept_unauthenticated();
+        self.stats.record(&rslt);
+        audit::record(Direction::Accept, self.name.as_ref().map(Name::borrow), &rslt);
+        let stream = rslt?;
+        Ok(match &self.shared_secret {
+            Some(secret) => PendingHandshake::new(
+                stream,
+                secret.clone(),
+                self.handshake_timeout,
+                self.nonblocking_streams.load(SeqCst),
+            ),
+            None => PendingHandshake::ready(stream),
+        })
+    }
 }
 impl crate::Sealed for Listener {}
 impl traits::Listener for Listener {
@@ -44,45 +108,79 @@ impl traits::Listener for Listener {
     fn from_options(options: ListenerOptions<'_>) -> io::Result<Self> {
         let nonblocking = options.nonblocking.accept_nonblocking();
 
+        let addr = name_to_addr(options.name.borrow(), true)?;
+        if options.audit_directory {
+            audit_directory(&addr)?;
+        }
         let listener = c_wrappers::create_server(
             libc::SOCK_STREAM,
-            &name_to_addr(options.name.borrow(), true)?,
+            &addr,
             nonblocking,
             options.mode,
+            options.backlog,
         )
         .map(UnixListener::from)
-        .map_err(Self::decode_listen_error)?;
+        .map_err(|e| explain_denial(&options.name, Self::decode_listen_error(e)))?;
 
         if !c_wrappers::CAN_CREATE_NONBLOCKING && nonblocking {
             listener.set_nonblocking(true)?;
         }
 
+        // Abstract names are read back from the socket to pick up whatever the kernel actually
+        // bound it to; everything else is remembered verbatim so that pseudo-namespaced names
+        // are reported the way they were specified rather than as the path they're mapped to.
+        let name = if options.name.is_namespaced() {
+            listener.local_addr().ok().as_ref().and_then(addr_to_name)
+        } else {
+            Some(options.name.into_owned())
+        };
+
         Ok(Self {
             listener,
-            reclaim: options
-                .reclaim_name
-                .then(|| options.name.into_owned())
-                .map(ReclaimGuard::new)
+            reclaim: name
+                .clone()
+                .map(|name| ReclaimGuard::new(name, options.name_policy))
                 .unwrap_or_default(),
+            name,
             nonblocking_streams: AtomicBool::new(options.nonblocking.stream_nonblocking()),
+            nonblocking_accept: NonblockingCache::known(nonblocking),
+            backlog: Backlog::new(options.backlog),
+            #[cfg(feature = "handshake")]
+            shared_secret: options.shared_secret,
+            #[cfg(feature = "handshake")]
+            handshake_timeout: options.handshake_timeout,
+            stats: StatsCounters::default(),
         })
     }
     #[inline]
     fn accept(&self) -> io::Result<Stream> {
-        // TODO(2.3.0) make use of the second return value in some shape or form
-        let stream = self.listener.accept().map(|(s, _)| Stream::from(s))?;
-        if self.nonblocking_streams.load(SeqCst) {
-            stream.set_nonblocking(true)?;
-        }
-        Ok(stream)
+        let rslt = self.accept_uncounted();
+        self.stats.record(&rslt);
+        audit::record(Direction::Accept, self.name.as_ref().map(Name::borrow), &rslt);
+        rslt
     }
     #[inline]
     fn set_nonblocking(&self, nonblocking: ListenerNonblockingMode) -> io::Result<()> {
         use ListenerNonblockingMode::*;
-        self.listener.set_nonblocking(matches!(nonblocking, Accept | Both))?;
+        let accept = matches!(nonblocking, Accept | Both);
+        self.nonblocking_accept.set(accept, || self.listener.set_nonblocking(accept))?;
         self.nonblocking_streams.store(matches!(nonblocking, Stream | Both), SeqCst);
         Ok(())
     }
+    #[inline]
+    fn local_name(&self) -> Option<Name<'_>> { self.name.as_ref().map(Name::borrow) }
+    #[inline]
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+        c_wrappers::set_inheritable(self.listener.as_fd(), inheritable)
+    }
+    #[inline]
+    fn backlog(&self) -> Option<u32> { self.backlog.get() }
+    #[inline]
+    fn set_backlog(&self, backlog: u32) -> io::Result<()> {
+        self.backlog.set(self.listener.as_fd(), backlog)
+    }
+    #[inline]
+    fn stats(&self) -> ListenerStats { self.stats.snapshot() }
     fn do_not_reclaim_name_on_drop(&mut self) { self.reclaim.forget(); }
 }
 impl Iterator for Listener {
@@ -109,10 +207,86 @@ impl From<Listener> for OwnedFd {
 }
 impl From<OwnedFd> for Listener {
     fn from(fd: OwnedFd) -> Self {
+        let listener = UnixListener::from(fd);
         Listener {
-            listener: fd.into(),
+            name: listener.local_addr().ok().as_ref().and_then(addr_to_name),
+            listener,
             reclaim: ReclaimGuard::default(),
             nonblocking_streams: AtomicBool::new(false),
+            nonblocking_accept: NonblockingCache::unknown(),
+            backlog: Backlog::default(),
+            #[cfg(feature = "handshake")]
+            shared_secret: None,
+            #[cfg(feature = "handshake")]
+            handshake_timeout: handshake::DEFAULT_TIMEOUT,
+            stats: StatsCounters::default(),
         }
     }
 }
+
+/// Performs the server side of the handshake, giving up once the timeout has passed.
+#[cfg(feature = "handshake")]
+pub(crate) fn authenticate_within(
+    stream: &Stream,
+    secret: &SharedSecret,
+    timeout: Duration,
+) -> io::Result<()> {
+    let mut deadline = Deadline { stream, at: Instant::now().checked_add(timeout) };
+    let rslt = handshake::authenticate_server(&mut deadline, secret);
+    c_wrappers::set_io_timeout(stream.as_fd(), None)?;
+    rslt
+}
+
+/// Sets the socket timeouts to the time that remains until the deadline before every read and
+/// write, which bounds the handshake as a whole rather than each of them. A deadline too far in
+/// the future to be represented is no deadline at all.
+#[cfg(feature = "handshake")]
+struct Deadline<'s> {
+    stream: &'s Stream,
+    at: Option<Instant>,
+}
+#[cfg(feature = "handshake")]
+impl Deadline<'_> {
+    fn arm(&self) -> io::Result<()> {
+        let Some(at) = self.at else { return Ok(()) };
+        let remaining = at.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(handshake_timed_out());
+        }
+        c_wrappers::set_io_timeout(self.stream.as_fd(), Some(remaining))
+    }
+}
+#[cfg(feature = "handshake")]
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.arm()?;
+        let mut stream = self.stream;
+        stream.read(buf).map_err(map_timeout)
+    }
+}
+#[cfg(feature = "handshake")]
+impl Write for Deadline<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.arm()?;
+        let mut stream = self.stream;
+        stream.write(buf).map_err(map_timeout)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        let mut stream = self.stream;
+        stream.flush()
+    }
+}
+
+/// Socket timeouts expire with `EAGAIN`.
+#[cfg(feature = "handshake")]
+fn map_timeout(e: io::Error) -> io::Error {
+    if e.kind() == io::ErrorKind::WouldBlock {
+        handshake_timed_out()
+    } else {
+        e
+    }
+}
+#[cfg(feature = "handshake")]
+fn handshake_timed_out() -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, "client did not complete the handshake in time")
+}
@@ -1,35 +1,72 @@
 use {
-    super::name_to_addr,
+    super::{addr_to_name, check_before_connect, explain_denial, name_to_addr, UdAddr},
     crate::{
         error::ReuniteError,
         local_socket::{
             traits::{self, ReuniteResult},
-            ConcurrencyDetector, LocalSocketSite, Name,
+            ConcurrencyDetector, ConnectOptions, LocalSocketSite, Name, PeerCredentials,
         },
-        Sealed, TryClone,
+        os::unix::c_wrappers::{self, ConnectProgress},
+        NonblockingCache, Sealed, TryClone,
     },
     std::{
         io::{self, prelude::*, IoSlice, IoSliceMut},
-        os::{fd::OwnedFd, unix::net::UnixStream},
-        sync::Arc,
+        mem::MaybeUninit,
+        os::{
+            fd::{AsFd, BorrowedFd, OwnedFd},
+            unix::net::UnixStream,
+        },
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering::Relaxed},
+            Arc,
+        },
     },
 };
 
 /// Wrapper around [`UnixStream`] that implements
 /// [`Stream`](crate::local_socket::traits::Stream).
+///
+/// # `SIGPIPE`
+/// Sending to a socket whose peer has hung up fails with
+/// [`BrokenPipe`](io::ErrorKind::BrokenPipe) and never raises `SIGPIPE`, regardless of the
+/// process's signal disposition. This matters for programs that don't ignore the signal the way
+/// Rust executables do by default, such as libraries loaded into non-Rust processes. Sends use
+/// `MSG_NOSIGNAL` where it is available, and `SO_NOSIGPIPE` is set on the socket on Apple
+/// platforms instead.
 #[derive(Debug)]
-pub struct Stream(pub(super) UnixStream, ConcurrencyDetector<LocalSocketSite>);
+pub struct Stream(
+    pub(super) UnixStream,
+    ConcurrencyDetector<LocalSocketSite>,
+    /// Limit on file descriptors accepted by [`.recv_fds()`](Self::recv_fds) per message.
+    AtomicUsize,
+    NonblockingCache,
+    /// Whether file descriptors have been dropped since the last
+    /// [`.take_fds_truncated()`](Self::take_fds_truncated).
+    AtomicBool,
+);
 impl Sealed for Stream {}
 impl traits::Stream for Stream {
     type RecvHalf = RecvHalf;
     type SendHalf = SendHalf;
 
-    fn connect(name: Name<'_>) -> io::Result<Self> {
-        UnixStream::connect_addr(&name_to_addr(name, false)?).map(Self::from)
+    fn from_options(options: &ConnectOptions<'_>) -> io::Result<Self> {
+        let addr = name_to_addr(options.name.borrow(), false)?;
+        check_before_connect(options, &addr)?;
+        match &options.bind_name {
+            Some(bind_name) => c_wrappers::create_client(
+                libc::SOCK_STREAM,
+                &name_to_addr(bind_name.borrow(), true)?,
+                &addr,
+            )
+            .map(UnixStream::from),
+            None => UnixStream::connect_addr(&addr),
+        }
+        .map(Self::from)
+        .map_err(|e| explain_denial(&options.name, e))
     }
     #[inline]
     fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
-        self.0.set_nonblocking(nonblocking)
+        self.3.set(nonblocking, || self.0.set_nonblocking(nonblocking))
     }
     #[inline]
     fn split(self) -> (RecvHalf, SendHalf) {
@@ -48,6 +85,141 @@ impl traits::Stream for Stream {
     }
 }
 
+impl traits::StreamCommon for Stream {
+    #[inline]
+    fn peer_name(&self) -> Option<Name<'static>> {
+        self.0.peer_addr().ok().as_ref().and_then(addr_to_name)
+    }
+    #[inline]
+    fn peer_credentials(&self) -> io::Result<PeerCredentials> {
+        c_wrappers::peer_credentials(self.0.as_fd())
+    }
+    #[inline]
+    fn send_buffer_size(&self) -> io::Result<usize> {
+        c_wrappers::get_socket_buffer_size(self.0.as_fd(), true)
+    }
+    #[inline]
+    fn recv_buffer_size(&self) -> io::Result<usize> {
+        c_wrappers::get_socket_buffer_size(self.0.as_fd(), false)
+    }
+    #[inline]
+    fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        c_wrappers::set_socket_buffer_size(self.0.as_fd(), true, size)
+    }
+    #[inline]
+    fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        c_wrappers::set_socket_buffer_size(self.0.as_fd(), false, size)
+    }
+    #[inline]
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+        c_wrappers::set_inheritable(self.0.as_fd(), inheritable)
+    }
+}
+
+impl Stream {
+    /// Same as `.read()` from the [`Read`] trait, but accepts an uninitialized buffer.
+    #[inline]
+    pub fn read_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+        let _guard = self.1.lock();
+        c_wrappers::recv(self.0.as_fd(), buf)
+    }
+    /// Sends `buf` along with the given file descriptors, which the peer receives with
+    /// [`.recv_fds()`](Self::recv_fds). Returns how much of `buf` was sent.
+    ///
+    /// The file descriptors arrive with the first byte of `buf`, which must not be empty unless
+    /// `fds` is. Up to 8 file descriptors can be sent at once.
+    #[cfg(unix)]
+    pub fn send_fds(&self, buf: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
+        if buf.is_empty() && !fds.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "file descriptors must be sent along with at least one byte",
+            ));
+        }
+        let _guard = self.1.lock();
+        c_wrappers::send_with_fds(self.0.as_fd(), buf, fds)
+    }
+    /// Receives data into `buf`, appending the file descriptors that arrive with it to `fds`.
+    ///
+    /// The received file descriptors are not inherited by child processes. If the peer sends
+    /// more of them at once than [`.max_recv_fds()`](Self::max_recv_fds), the excess ones are
+    /// closed, which is reported by [`.take_fds_truncated()`](Self::take_fds_truncated)
+    /// rather than by failing, since the data has been received regardless.
+    #[cfg(unix)]
+    pub fn recv_fds(&self, buf: &mut [u8], fds: &mut Vec<OwnedFd>) -> io::Result<usize> {
+        let _guard = self.1.lock();
+        self.recv_with_fds(crate::weaken_buf_init_mut(buf), fds)
+    }
+    #[cfg(unix)]
+    pub(crate) fn recv_with_fds(
+        &self,
+        buf: &mut [MaybeUninit<u8>],
+        fds: &mut Vec<OwnedFd>,
+    ) -> io::Result<usize> {
+        let (rcvd, truncated) =
+            c_wrappers::recv_with_fds(self.0.as_fd(), buf, fds, self.max_recv_fds())?;
+        if truncated {
+            self.4.store(true, Relaxed);
+        }
+        Ok(rcvd)
+    }
+    /// Returns whether any file descriptors sent by the peer have been closed instead of being
+    /// received since the last call, and resets the flag.
+    ///
+    /// This happens when the peer sends more of them at once than
+    /// [`.max_recv_fds()`](Self::max_recv_fds) allows.
+    #[cfg(unix)]
+    #[inline]
+    pub fn take_fds_truncated(&self) -> bool { self.4.swap(false, Relaxed) }
+    /// Sets the maximum number of file descriptors that [`.recv_fds()`](Self::recv_fds) accepts
+    /// per message, protecting against peers that try to exhaust this process's file descriptor
+    /// table.
+    ///
+    /// The default, and the largest limit that can be set, is 8. Larger values are clamped.
+    #[cfg(unix)]
+    pub fn set_max_recv_fds(&self, max: usize) {
+        self.2.store(max.min(c_wrappers::MAX_FDS_PER_MSG), Relaxed);
+    }
+    /// Returns the limit set by [`.set_max_recv_fds()`](Self::set_max_recv_fds).
+    #[cfg(unix)]
+    #[inline]
+    pub fn max_recv_fds(&self) -> usize { self.2.load(Relaxed) }
+}
+
+/// A nonblocking connection attempt that has not completed yet.
+#[derive(Debug)]
+pub(crate) struct Connecting {
+    sock: OwnedFd,
+    addr: UdAddr,
+    backlog_full: bool,
+}
+impl Connecting {
+    pub(crate) fn start(name: Name<'_>) -> io::Result<Result<Stream, Self>> {
+        let addr = name_to_addr(name, false)?;
+        let (sock, progress) = c_wrappers::start_connect(libc::SOCK_STREAM, None, &addr)?;
+        Ok(Self { sock, addr, backlog_full: false }.into_result(progress))
+    }
+    pub(crate) fn finish(self) -> io::Result<Result<Stream, Self>> {
+        let progress = c_wrappers::continue_connect(self.sock.as_fd(), &self.addr)?;
+        Ok(self.into_result(progress))
+    }
+    /// Whether the socket cannot be waited on, since the attempt never got underway.
+    #[inline]
+    pub(crate) fn must_retry(&self) -> bool { self.backlog_full }
+    fn into_result(mut self, progress: ConnectProgress) -> Result<Stream, Self> {
+        self.backlog_full = progress == ConnectProgress::BacklogFull;
+        if progress == ConnectProgress::Connected {
+            Ok(Stream::from(self.sock))
+        } else {
+            Err(self)
+        }
+    }
+}
+impl AsFd for Connecting {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> { self.sock.as_fd() }
+}
+
 impl Read for &Stream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         let _guard = self.1.lock();
@@ -62,19 +234,30 @@ impl Read for &Stream {
 impl Write for &Stream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         let _guard = self.1.lock();
-        (&mut &self.0).write(buf)
+        c_wrappers::send(self.0.as_fd(), buf)
     }
     fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
         let _guard = self.1.lock();
-        (&mut &self.0).write_vectored(bufs)
+        c_wrappers::send_vectored(self.0.as_fd(), bufs)
     }
     #[inline]
     fn flush(&mut self) -> io::Result<()> { Ok(()) }
     // FUTURE is_write_vectored
 }
 
+/// On Apple platforms, also sets `SO_NOSIGPIPE` on the socket, making a best effort to uphold
+/// the [`SIGPIPE` guarantee](Stream#sigpipe) for sockets not created by Interprocess.
 impl From<UnixStream> for Stream {
-    fn from(s: UnixStream) -> Self { Self(s, ConcurrencyDetector::new()) }
+    fn from(s: UnixStream) -> Self {
+        let _ = c_wrappers::suppress_sigpipe(s.as_fd());
+        Self(
+            s,
+            ConcurrencyDetector::new(),
+            AtomicUsize::new(c_wrappers::MAX_FDS_PER_MSG),
+            NonblockingCache::unknown(),
+            AtomicBool::new(false),
+        )
+    }
 }
 
 impl From<OwnedFd> for Stream {
@@ -83,7 +266,13 @@ impl From<OwnedFd> for Stream {
 
 impl TryClone for Stream {
     #[inline]
-    fn try_clone(&self) -> std::io::Result<Self> { self.0.try_clone().map(Self::from) }
+    fn try_clone(&self) -> std::io::Result<Self> {
+        let mut new = Self::from(self.0.try_clone()?);
+        new.2.store(self.2.load(Relaxed), Relaxed);
+        // The duplicate shares the nonblocking flag of the open file description.
+        new.3 = self.3.share();
+        Ok(new)
+    }
 }
 
 multimacro! {
@@ -99,6 +288,13 @@ impl Sealed for RecvHalf {}
 impl traits::RecvHalf for RecvHalf {
     type Stream = Stream;
 }
+impl RecvHalf {
+    /// Same as `.read()` from the [`Read`] trait, but accepts an uninitialized buffer.
+    #[inline]
+    pub fn read_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+        self.0.read_to_uninit(buf)
+    }
+}
 multimacro! {
     RecvHalf,
     forward_rbv(Stream, *),
@@ -2,21 +2,43 @@ use {
     super::Stream,
     crate::{
         local_socket::{
+            audit::{self, Direction},
             prelude::*, traits::tokio as traits, ListenerNonblockingMode, ListenerOptions,
+            ListenerStats, Name, StatsCounters,
         },
-        os::unix::uds_local_socket::{listener::Listener as SyncListener, ReclaimGuard},
-        Sealed,
+        os::unix::{
+            c_wrappers,
+            uds_local_socket::{listener::Listener as SyncListener, Backlog, ReclaimGuard},
+        },
+        NonblockingCache, Sealed,
     },
     std::{
         fmt::{self, Debug, Formatter},
         io,
         os::unix::prelude::*,
+        sync::atomic::AtomicBool,
     },
     tokio::net::UnixListener,
 };
 pub struct Listener {
     listener: UnixListener,
+    name: Option<Name<'static>>,
     reclaim: ReclaimGuard,
+    backlog: Backlog,
+    stats: StatsCounters,
+}
+impl Listener {
+    fn from_nonblocking_sync(mut sync: SyncListener) -> io::Result<Self> {
+        let (name, reclaim) = (sync.name.take(), sync.reclaim.take());
+        let backlog = Backlog::new(sync.backlog.get());
+        Ok(Self {
+            listener: UnixListener::from_std(sync.into())?,
+            name,
+            reclaim,
+            backlog,
+            stats: StatsCounters::default(),
+        })
+    }
 }
 impl Sealed for Listener {}
 impl traits::Listener for Listener {
@@ -26,19 +48,32 @@ impl traits::Listener for Listener {
         options
             .nonblocking(ListenerNonblockingMode::Both)
             .create_sync_as::<SyncListener>()
-            .and_then(|mut sync| {
-                let reclaim = sync.reclaim.take();
-                Ok(Self {
-                    listener: UnixListener::from_std(sync.into())?,
-                    reclaim
-                })
-            })
+            .and_then(Self::from_nonblocking_sync)
     }
     async fn accept(&self) -> io::Result<Stream> {
-        let inner = self.listener.accept().await?.0;
-        Ok(Stream::from(inner))
+        let rslt = match self.listener.accept().await {
+            Ok((inner, _)) => Stream::suppress_sigpipe(inner),
+            Err(e) => Err(e),
+        };
+        self.stats.record(&rslt);
+        audit::record(Direction::Accept, self.name.as_ref().map(Name::borrow), &rslt);
+        rslt
     }
 
+    #[inline]
+    fn local_name(&self) -> Option<Name<'_>> { self.name.as_ref().map(Name::borrow) }
+    #[inline]
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+        c_wrappers::set_inheritable(self.listener.as_fd(), inheritable)
+    }
+    #[inline]
+    fn backlog(&self) -> Option<u32> { self.backlog.get() }
+    #[inline]
+    fn set_backlog(&self, backlog: u32) -> io::Result<()> {
+        self.backlog.set(self.listener.as_fd(), backlog)
+    }
+    #[inline]
+    fn stats(&self) -> ListenerStats { self.stats.snapshot() }
     fn do_not_reclaim_name_on_drop(&mut self) { self.reclaim.forget(); }
 }
 
@@ -47,12 +82,39 @@ impl traits::Listener for Listener {
 // TODO(3.0.0) remove handholding and assume nonblocking
 impl TryFrom<SyncListener> for Listener {
     type Error = io::Error;
-    fn try_from(mut sync: SyncListener) -> io::Result<Self> {
+    fn try_from(sync: SyncListener) -> io::Result<Self> {
+        #[cfg(feature = "handshake")]
+        if sync.shared_secret.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the shared secret handshake is not supported by Tokio listeners",
+            ));
+        }
         sync.set_nonblocking(ListenerNonblockingMode::Both)?;
-        let reclaim = sync.reclaim.take();
+        Self::from_nonblocking_sync(sync)
+    }
+}
+
+/// Puts the listener back in blocking mode, which is the default for sync listeners, and makes
+/// it produce blocking streams.
+impl TryFrom<Listener> for SyncListener {
+    type Error = io::Error;
+    fn try_from(slf: Listener) -> io::Result<Self> {
+        let Listener { listener, name, reclaim, backlog, .. } = slf;
+        let listener = listener.into_std()?;
+        listener.set_nonblocking(false)?;
         Ok(Self {
-            listener: UnixListener::from_std(sync.into())?,
-            reclaim
+            listener: OwnedFd::from(listener).into(),
+            name,
+            reclaim,
+            nonblocking_streams: AtomicBool::new(false),
+            nonblocking_accept: NonblockingCache::known(false),
+            backlog,
+            #[cfg(feature = "handshake")]
+            shared_secret: None,
+            #[cfg(feature = "handshake")]
+            handshake_timeout: crate::local_socket::handshake::DEFAULT_TIMEOUT,
+            stats: StatsCounters::default(),
         })
     }
 }
@@ -61,7 +123,10 @@ impl Debug for Listener {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("Listener")
             .field("fd", &self.listener.as_raw_fd())
+            .field("name", &self.name)
             .field("reclaim", &self.reclaim)
+            .field("backlog", &self.backlog)
+            .field("stats", &self.stats)
             .finish()
     }
 }
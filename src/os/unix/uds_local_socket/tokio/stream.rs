@@ -1,36 +1,73 @@
 use {
-    super::super::name_to_addr,
+    super::super::{
+        addr_to_name, check_before_connect, explain_denial, name_to_addr, Stream as SyncStream,
+        UdAddr,
+    },
     crate::{
         error::ReuniteError,
-        local_socket::{traits::tokio as traits, Name},
+        local_socket::{
+            tokio::Deadlines,
+            traits::{tokio as traits, StreamCommon},
+            ConnectOptions, Name, PeerCredentials,
+        },
+        os::unix::c_wrappers::{self, ConnectProgress},
         Sealed,
     },
     std::{
-        io::{self, ErrorKind::WouldBlock},
+        io::{self, ErrorKind::WouldBlock, IoSliceMut},
+        net::Shutdown,
         os::{
             fd::{AsFd, OwnedFd},
             unix::{
-                net::{SocketAddr, UnixStream as SyncUnixStream},
+                net::UnixStream as SyncUnixStream,
                 prelude::BorrowedFd,
             },
         },
         pin::Pin,
         task::{ready, Context, Poll},
+        time::Duration,
     },
     tokio::{
-        io::{AsyncRead, AsyncWrite, ReadBuf},
+        io::{AsyncRead, AsyncReadExt, AsyncWrite, Interest, ReadBuf, Ready},
     },
     tokio::net::unix::{OwnedReadHalf as RecvHalfImpl, OwnedWriteHalf as SendHalfImpl},
     tokio::net::UnixStream,
+    tokio::time,
 };
 
+/// How often a connection attempt is repeated while the backlog of the server is full.
+const BACKLOG_RETRY_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Sending has the same [`SIGPIPE` guarantee](super::super::Stream#sigpipe) as with sync streams.
 #[derive(Debug)]
-pub struct Stream(pub(super) UnixStream);
+pub struct Stream(pub(super) UnixStream, Deadlines);
 impl Sealed for Stream {}
 
 impl Stream {
+    #[inline]
+    pub(crate) fn deadlines(&self) -> &Deadlines { &self.1 }
+    #[inline]
+    pub(crate) async fn ready(&self, interest: Interest) -> io::Result<Ready> {
+        self.0.ready(interest).await
+    }
+    #[inline]
+    pub(crate) fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> { self.0.try_read(buf) }
+    #[inline]
+    pub(crate) fn try_read_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.0.try_read_vectored(bufs)
+    }
+    #[inline]
+    pub(crate) fn try_write(&self, buf: &[u8]) -> io::Result<usize> { self.0.try_write(buf) }
+    /// Shuts down the sending direction, which the peer sees as end of file, and waits for it
+    /// to close its end in response, discarding anything that it sends in the meantime.
+    pub(crate) async fn close(self) -> io::Result<()> {
+        c_wrappers::shutdown(self.0.as_fd(), Shutdown::Write)?;
+        let mut buf = [0; 512];
+        while (&self).read(&mut buf).await? != 0 {}
+        Ok(())
+    }
     #[allow(clippy::unwrap_used)]
-    async fn _connect(addr: SocketAddr) -> io::Result<UnixStream> {
+    async fn _connect(addr: UdAddr) -> io::Result<UnixStream> {
         #[cfg(any(target_os = "linux", target_os = "android"))]
         {
             #[cfg(target_os = "android")]
@@ -49,14 +86,43 @@ impl Stream {
         }
         UnixStream::connect(addr.as_pathname().unwrap().as_os_str().to_str().unwrap()).await
     }
+    pub(super) fn suppress_sigpipe(stream: UnixStream) -> io::Result<Self> {
+        c_wrappers::suppress_sigpipe(stream.as_fd())?;
+        Ok(Self::from(stream))
+    }
+    async fn _connect_bound(local: UdAddr, remote: UdAddr) -> io::Result<UnixStream> {
+        let (sock, mut progress) =
+            c_wrappers::start_connect(libc::SOCK_STREAM, Some(&local), &remote)?;
+        let connect = async {
+            let stream = UnixStream::from_std(SyncUnixStream::from(sock))?;
+            loop {
+                match progress {
+                    ConnectProgress::Connected => return Ok(stream),
+                    ConnectProgress::InProgress => stream.writable().await?,
+                    // The socket is writable right away, so there is nothing to wait on.
+                    ConnectProgress::BacklogFull => time::sleep(BACKLOG_RETRY_INTERVAL).await,
+                }
+                progress = c_wrappers::continue_connect(stream.as_fd(), &remote)?;
+            }
+        };
+        connect.await.map_err(|e| c_wrappers::unbind(&local, e))
+    }
 }
 
 impl traits::Stream for Stream {
     type RecvHalf = RecvHalf;
     type SendHalf = SendHalf;
 
-    async fn connect(name: Name<'_>) -> io::Result<Self> {
-        Self::_connect(name_to_addr(name, false)?).await.map(Self::from)
+    async fn from_options(options: &ConnectOptions<'_>) -> io::Result<Self> {
+        let addr = name_to_addr(options.name.borrow(), false)?;
+        check_before_connect(options, &addr)?;
+        let stream = match &options.bind_name {
+            Some(bind_name) => {
+                Self::_connect_bound(name_to_addr(bind_name.borrow(), true)?, addr).await
+            }
+            None => Self::_connect(addr).await,
+        };
+        stream.map_err(|e| explain_denial(&options.name, e)).and_then(Self::suppress_sigpipe)
     }
     fn split(self) -> (RecvHalf, SendHalf) {
         let (r, w) = self.0.into_split();
@@ -72,6 +138,38 @@ impl traits::Stream for Stream {
     }
 }
 
+impl StreamCommon for Stream {
+    #[inline]
+    fn peer_name(&self) -> Option<Name<'static>> {
+        let addr = std::os::unix::net::SocketAddr::from(self.0.peer_addr().ok()?);
+        addr_to_name(&addr)
+    }
+    #[inline]
+    fn peer_credentials(&self) -> io::Result<PeerCredentials> {
+        c_wrappers::peer_credentials(self.0.as_fd())
+    }
+    #[inline]
+    fn send_buffer_size(&self) -> io::Result<usize> {
+        c_wrappers::get_socket_buffer_size(self.0.as_fd(), true)
+    }
+    #[inline]
+    fn recv_buffer_size(&self) -> io::Result<usize> {
+        c_wrappers::get_socket_buffer_size(self.0.as_fd(), false)
+    }
+    #[inline]
+    fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        c_wrappers::set_socket_buffer_size(self.0.as_fd(), true, size)
+    }
+    #[inline]
+    fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        c_wrappers::set_socket_buffer_size(self.0.as_fd(), false, size)
+    }
+    #[inline]
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+        c_wrappers::set_inheritable(self.0.as_fd(), inheritable)
+    }
+}
+
 fn ioloop(
     mut try_io: impl FnMut() -> io::Result<usize>,
     mut poll_read_ready: impl FnMut() -> Poll<io::Result<()>>,
@@ -88,9 +186,16 @@ multimacro! {
     Stream,
     pinproj_for_unpin(UnixStream),
     forward_rbv(UnixStream, &),
-    forward_tokio_rw,
+    derive_tokio_mut_rw,
     forward_as_handle,
-    derive_trivial_conv(UnixStream),
+}
+impl From<UnixStream> for Stream {
+    #[inline]
+    fn from(stream: UnixStream) -> Self { Self(stream, Deadlines::default()) }
+}
+impl From<Stream> for UnixStream {
+    #[inline]
+    fn from(stream: Stream) -> Self { stream.0 }
 }
 impl AsyncRead for &Stream {
     #[inline]
@@ -99,6 +204,7 @@ impl AsyncRead for &Stream {
         cx: &mut Context<'_>,
         buf: &mut ReadBuf<'_>,
     ) -> Poll<io::Result<()>> {
+        self.1.read.check(cx)?;
         ioloop(|| self.0.try_read_buf(buf), || self.0.poll_read_ready(cx)).map(|e| e.map(|_| ()))
     }
 }
@@ -109,7 +215,11 @@ impl AsyncWrite for &Stream {
         cx: &mut Context<'_>,
         buf: &[u8],
     ) -> Poll<io::Result<usize>> {
-        ioloop(|| self.0.try_write(buf), || self.0.poll_write_ready(cx))
+        self.1.write.check(cx)?;
+        ioloop(
+            || self.0.try_io(Interest::WRITABLE, || c_wrappers::send(self.0.as_fd(), buf)),
+            || self.0.poll_write_ready(cx),
+        )
     }
     #[inline]
     fn poll_write_vectored(
@@ -117,7 +227,9 @@ impl AsyncWrite for &Stream {
         cx: &mut Context<'_>,
         bufs: &[io::IoSlice<'_>],
     ) -> Poll<io::Result<usize>> {
-        ioloop(|| self.0.try_write_vectored(bufs), || self.0.poll_write_ready(cx))
+        self.1.write.check(cx)?;
+        let send = || c_wrappers::send_vectored(self.0.as_fd(), bufs);
+        ioloop(|| self.0.try_io(Interest::WRITABLE, send), || self.0.poll_write_ready(cx))
     }
     #[inline]
     fn is_write_vectored(&self) -> bool { self.0.is_write_vectored() }
@@ -130,6 +242,23 @@ impl AsyncWrite for &Stream {
         Poll::Ready(Ok(()))
     }
 }
+/// Sets the sync stream to nonblocking mode, as required by Tokio.
+impl TryFrom<SyncStream> for Stream {
+    type Error = io::Error;
+    fn try_from(sync: SyncStream) -> io::Result<Self> {
+        sync.0.set_nonblocking(true)?;
+        Ok(UnixStream::from_std(sync.0)?.into())
+    }
+}
+/// Puts the stream back in blocking mode, which is the default for sync streams.
+impl TryFrom<Stream> for SyncStream {
+    type Error = io::Error;
+    fn try_from(slf: Stream) -> io::Result<Self> {
+        let stream = slf.0.into_std()?;
+        stream.set_nonblocking(false)?;
+        Ok(stream.into())
+    }
+}
 impl TryFrom<Stream> for OwnedFd {
     type Error = io::Error;
     #[inline]
@@ -189,7 +318,11 @@ impl AsyncWrite for &SendHalf {
         cx: &mut Context<'_>,
         buf: &[u8],
     ) -> Poll<io::Result<usize>> {
-        ioloop(|| self.0.try_write(buf), || self.0.as_ref().poll_write_ready(cx))
+        let sock = self.0.as_ref();
+        ioloop(
+            || sock.try_io(Interest::WRITABLE, || c_wrappers::send(sock.as_fd(), buf)),
+            || sock.poll_write_ready(cx),
+        )
     }
     #[inline]
     fn poll_write_vectored(
@@ -197,7 +330,9 @@ impl AsyncWrite for &SendHalf {
         cx: &mut Context<'_>,
         bufs: &[io::IoSlice<'_>],
     ) -> Poll<io::Result<usize>> {
-        ioloop(|| self.0.try_write_vectored(bufs), || self.0.as_ref().poll_write_ready(cx))
+        let sock = self.0.as_ref();
+        let send = || c_wrappers::send_vectored(sock.as_fd(), bufs);
+        ioloop(|| sock.try_io(Interest::WRITABLE, send), || sock.poll_write_ready(cx))
     }
     #[inline]
     fn is_write_vectored(&self) -> bool { self.0.is_write_vectored() }
@@ -1,9 +1,11 @@
 //! Windows-specific functionality for various interprocess communication primitives, as well as
 //! Windows-specific ones.
 
+pub mod event;
 pub mod local_socket;
 pub mod named_pipe;
 pub mod security_descriptor;
+pub mod shared_memory;
 pub mod unnamed_pipe;
 //pub mod mailslot;
 
@@ -18,12 +20,14 @@ mod limbo_pool;
 pub(crate) mod misc;
 mod needs_flush;
 
-#[cfg(feature = "tokio")]
+#[cfg(feature = "local-socket-tokio")]
+mod tokio_blocking;
+#[cfg(feature = "local-socket-tokio")]
 mod tokio_flusher;
 
 mod limbo {
     pub(super) mod sync;
-    #[cfg(feature = "tokio")]
+    #[cfg(feature = "local-socket-tokio")]
     pub(super) mod tokio;
 
     pub(crate) static LIMBO_ERR: &str =
@@ -33,4 +37,10 @@ mod limbo {
 
 pub(crate) use {file_handle::*, misc::*, needs_flush::*};
 
-mod c_wrappers;
+pub(crate) mod c_wrappers;
+pub(crate) mod condvar;
+pub(crate) mod fs_lock;
+pub(crate) mod mutex;
+pub(crate) mod process;
+pub(crate) mod semaphore;
+pub(crate) mod signal;
@@ -1,10 +1,30 @@
 use {
-    super::winprelude::*,
-    crate::OrErrno,
-    std::io,
+    super::{security_descriptor::LocalBox, winprelude::*},
+    crate::{OrErrno, RawOsErrorExt as _},
+    std::{
+        ffi::{c_void, OsString},
+        io,
+        mem::size_of,
+        os::windows::ffi::OsStringExt,
+        path::PathBuf,
+        ptr,
+        time::Duration,
+    },
+    widestring::U16CStr,
     windows_sys::Win32::{
-        Foundation::{DuplicateHandle, DUPLICATE_SAME_ACCESS},
-        System::Threading::GetCurrentProcess,
+        Foundation::{
+            DuplicateHandle, GetLastError, SetHandleInformation, DUPLICATE_CLOSE_SOURCE,
+            DUPLICATE_SAME_ACCESS, ERROR_ALREADY_EXISTS, ERROR_INSUFFICIENT_BUFFER,
+            HANDLE_FLAG_INHERIT, WAIT_ABANDONED, WAIT_OBJECT_0, WAIT_TIMEOUT,
+        },
+        Security::{
+            Authorization::ConvertStringSidToSidW, EqualSid, GetTokenInformation, TokenUser,
+            TOKEN_QUERY, TOKEN_USER,
+        },
+        System::Threading::{
+            GetCurrentProcess, OpenProcess, OpenProcessToken, QueryFullProcessImageNameW,
+            WaitForSingleObject, INFINITE, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+        },
     },
 };
 
@@ -19,6 +39,28 @@ pub fn duplicate_handle_to_foreign(
     duplicate_handle_inner(handle, Some(other_process))
 }
 
+/// Moves a handle out of another process into this one, closing it in the other process.
+pub fn take_handle_from_foreign(
+    other_process: BorrowedHandle<'_>,
+    handle: HANDLE,
+) -> io::Result<OwnedHandle> {
+    let mut new_handle = INVALID_HANDLE_VALUE;
+    let new_handle = unsafe {
+        DuplicateHandle(
+            other_process.as_int_handle(),
+            handle,
+            GetCurrentProcess(),
+            &mut new_handle,
+            0,
+            0,
+            DUPLICATE_SAME_ACCESS | DUPLICATE_CLOSE_SOURCE,
+        )
+    }
+    .true_val_or_errno(new_handle)?;
+    // SAFETY: DuplicateHandle() has just created this handle in our process
+    Ok(unsafe { OwnedHandle::from_raw_handle(new_handle.to_std()) })
+}
+
 fn duplicate_handle_inner(
     handle: BorrowedHandle<'_>,
     other_process: Option<BorrowedHandle<'_>>,
@@ -38,3 +80,123 @@ fn duplicate_handle_inner(
     }
     .true_val_or_errno(new_handle)
 }
+
+pub fn set_inheritable(handle: BorrowedHandle<'_>, inheritable: bool) -> io::Result<()> {
+    let flags = if inheritable { HANDLE_FLAG_INHERIT } else { 0 };
+    unsafe { SetHandleInformation(handle.as_int_handle(), HANDLE_FLAG_INHERIT, flags) }
+        .true_val_or_errno(())
+}
+
+/// Takes ownership of the handle returned by a `Create*W()` function for a named kernel object,
+/// failing with [`AlreadyExists`](io::ErrorKind::AlreadyExists) if the function opened an
+/// existing object instead. Must be called right after the function returns.
+pub fn created_object(handle: HANDLE) -> io::Result<OwnedHandle> {
+    let existed = unsafe { GetLastError() } == ERROR_ALREADY_EXISTS;
+    let handle = opened_object(handle)?;
+    if existed {
+        return Err(io::ErrorKind::AlreadyExists.into());
+    }
+    Ok(handle)
+}
+/// Takes ownership of the handle returned by a function which returns null on failure.
+pub fn opened_object(handle: HANDLE) -> io::Result<OwnedHandle> {
+    let handle = (handle != 0).true_val_or_errno(handle)?;
+    // SAFETY: the caller has just obtained this handle
+    Ok(unsafe { OwnedHandle::from_raw_handle(handle.to_std()) })
+}
+
+/// How a wait on a kernel object ended.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WaitOutcome {
+    Signaled,
+    /// The object is a mutex whose owner exited without releasing it. Ownership passes to the
+    /// waiting thread.
+    Abandoned,
+    TimedOut,
+}
+/// Rounds up to whole milliseconds, so that short timeouts do not turn into polling.
+pub fn timeout_to_ms(timeout: Option<Duration>) -> u32 {
+    let Some(timeout) = timeout else { return INFINITE };
+    let ms = timeout.as_nanos().div_ceil(1_000_000);
+    u32::try_from(ms).unwrap_or(INFINITE).min(INFINITE.saturating_sub(1))
+}
+pub fn wait(handle: BorrowedHandle<'_>, timeout: Option<Duration>) -> io::Result<WaitOutcome> {
+    interpret_wait(unsafe { WaitForSingleObject(handle.as_int_handle(), timeout_to_ms(timeout)) })
+}
+pub fn interpret_wait(ret: u32) -> io::Result<WaitOutcome> {
+    match ret {
+        WAIT_OBJECT_0 => Ok(WaitOutcome::Signaled),
+        WAIT_ABANDONED => Ok(WaitOutcome::Abandoned),
+        WAIT_TIMEOUT => Ok(WaitOutcome::TimedOut),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+/// Returns whether the process with the given ID runs as the user identified by the string SID
+/// `sid`, such as `S-1-5-18` for the LocalSystem account.
+pub fn process_runs_as(pid: u32, sid: &U16CStr) -> io::Result<bool> {
+    let mut expected = ptr::null_mut();
+    unsafe { ConvertStringSidToSidW(sid.as_ptr(), &mut expected) }.true_val_or_errno(())?;
+    // SAFETY: allocated by ConvertStringSidToSidW() with LocalAlloc()
+    let mut expected = unsafe { LocalBox::<c_void>::from_raw(expected) };
+
+    let process = open_process_for_query(pid)?;
+    let mut token = 0;
+    unsafe { OpenProcessToken(process.as_int_handle(), TOKEN_QUERY, &mut token) }
+        .true_val_or_errno(())?;
+    let token = opened_object(token)?;
+
+    // The first call fails, reporting the required size. The buffer is made of words so that
+    // the pointer inside of TOKEN_USER is aligned.
+    let mut len = 0;
+    let token_raw = token.as_int_handle();
+    unsafe { GetTokenInformation(token_raw, TokenUser, ptr::null_mut(), 0, &mut len) };
+    let len_bytes = usize::try_from(len).unwrap_or(0).max(size_of::<TOKEN_USER>());
+    let mut buf = vec![0_usize; len_bytes.div_ceil(size_of::<usize>())];
+    let cap = u32::try_from(buf.len().saturating_mul(size_of::<usize>())).unwrap_or(u32::MAX);
+    unsafe {
+        GetTokenInformation(
+            token_raw,
+            TokenUser,
+            buf.as_mut_ptr().cast(),
+            cap,
+            &mut len,
+        )
+    }
+    .true_val_or_errno(())?;
+    // SAFETY: filled in by GetTokenInformation() and suitably aligned
+    let user = unsafe { &*buf.as_ptr().cast::<TOKEN_USER>() };
+    Ok(unsafe { EqualSid(user.User.Sid, expected.as_mut_ptr()) } != 0)
+}
+
+/// Returns the full path of the executable of the process with the given ID.
+pub fn process_image_path(pid: u32) -> io::Result<PathBuf> {
+    // The longest path that Windows supports, in UTF-16 code units.
+    const MAX: usize = 32768;
+    let process = open_process_for_query(pid)?;
+    let mut buf = vec![0_u16; 260];
+    loop {
+        let mut len = u32::try_from(buf.len()).unwrap_or(u32::MAX);
+        let success = unsafe {
+            QueryFullProcessImageNameW(
+                process.as_int_handle(),
+                PROCESS_NAME_WIN32,
+                buf.as_mut_ptr(),
+                &mut len,
+            )
+        };
+        if success != 0 {
+            buf.truncate(usize::try_from(len).unwrap_or(0));
+            return Ok(OsString::from_wide(&buf).into());
+        }
+        let e = io::Error::last_os_error();
+        if !e.raw_os_error().eeq(ERROR_INSUFFICIENT_BUFFER) || buf.len() >= MAX {
+            return Err(e);
+        }
+        buf.resize(buf.len().saturating_mul(2).min(MAX), 0);
+    }
+}
+
+fn open_process_for_query(pid: u32) -> io::Result<OwnedHandle> {
+    opened_object(unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) })
+}
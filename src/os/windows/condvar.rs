@@ -0,0 +1,101 @@
+use {
+    super::{
+        c_wrappers::{self, WaitOutcome},
+        mutex::Mutex,
+        path_conversion::local_object_name,
+        winprelude::*,
+    },
+    crate::{local_socket::Name, shared_memory::SharedMemory, OrErrno},
+    std::{
+        io,
+        mem::size_of,
+        ptr,
+        sync::atomic::{AtomicU32, Ordering},
+        time::Duration,
+    },
+    windows_sys::Win32::System::Threading::{
+        CreateSemaphoreW, OpenSemaphoreW, ReleaseSemaphore, SignalObjectAndWait,
+        SEMAPHORE_MODIFY_STATE, SYNCHRONIZATION_SYNCHRONIZE,
+    },
+};
+
+/// Suffix of the name of the semaphore, which is told apart from the shared memory object that
+/// holds the waiter count by it.
+const SEMAPHORE_SUFFIX: &str = ".condvar-sem";
+
+/// A condition variable made of a semaphore that waiters sleep on and a count of waiters in
+/// shared memory, which tells notifiers how many times to release the semaphore.
+///
+/// Releasing the mutex and starting to wait on the semaphore happen atomically thanks to
+/// `SignalObjectAndWait()`. A waiter which times out while being notified leaves an extra count
+/// in the semaphore behind, which results in a spurious wakeup later.
+#[derive(Debug)]
+pub(crate) struct Condvar {
+    shm: SharedMemory,
+    semaphore: OwnedHandle,
+}
+impl Condvar {
+    pub fn create(name: &Name<'_>) -> io::Result<Self> {
+        let opts = SharedMemory::options().name(name.borrow()).size(size_of::<AtomicU32>());
+        let shm = opts.create()?;
+        let sem_name = local_object_name(name, SEMAPHORE_SUFFIX)?;
+        let handle = unsafe { CreateSemaphoreW(ptr::null(), 0, i32::MAX, sem_name.as_ptr()) };
+        Ok(Self { shm, semaphore: c_wrappers::created_object(handle)? })
+    }
+    pub fn open(name: &Name<'_>) -> io::Result<Self> {
+        let opts = SharedMemory::options().name(name.borrow()).size(size_of::<AtomicU32>());
+        let shm = opts.open()?;
+        let sem_name = local_object_name(name, SEMAPHORE_SUFFIX)?;
+        let access = SYNCHRONIZATION_SYNCHRONIZE | SEMAPHORE_MODIFY_STATE;
+        let handle = unsafe { OpenSemaphoreW(access, 0, sem_name.as_ptr()) };
+        Ok(Self { shm, semaphore: c_wrappers::opened_object(handle)? })
+    }
+
+    fn waiters(&self) -> &AtomicU32 {
+        // SAFETY: the mapping is page-aligned and at least as big as the counter
+        unsafe { &*self.shm.as_ptr().cast::<AtomicU32>() }
+    }
+
+    /// Must be called with `mutex` locked by the current thread. Returns whether the previous
+    /// owner of the mutex died while it was being reacquired, and whether the timeout expired.
+    pub fn wait(&self, mutex: &Mutex, timeout: Option<Duration>) -> io::Result<(bool, bool)> {
+        self.waiters().fetch_add(1, Ordering::SeqCst);
+        let ret = unsafe {
+            SignalObjectAndWait(
+                mutex.as_handle().as_int_handle(),
+                self.semaphore.as_int_handle(),
+                c_wrappers::timeout_to_ms(timeout),
+                0,
+            )
+        };
+        let outcome = c_wrappers::interpret_wait(ret);
+        if !matches!(outcome, Ok(WaitOutcome::Signaled)) {
+            // Nobody consumed our place in the count, so we take it back ourselves, unless a
+            // notifier got to it first.
+            let _ = self.waiters().fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                n.checked_sub(1)
+            });
+        }
+        // The mutex has to be reacquired even if the wait failed, since the caller's guard is
+        // going to release it.
+        let owner_died = mutex.lock(None)?.unwrap_or(false);
+        Ok((owner_died, outcome? == WaitOutcome::TimedOut))
+    }
+    pub fn notify_one(&self) -> io::Result<()> {
+        let dec = |n: u32| n.checked_sub(1);
+        match self.waiters().fetch_update(Ordering::SeqCst, Ordering::SeqCst, dec) {
+            Ok(..) => self.release(1),
+            Err(..) => Ok(()),
+        }
+    }
+    pub fn notify_all(&self) -> io::Result<()> {
+        match self.waiters().swap(0, Ordering::SeqCst) {
+            0 => Ok(()),
+            n => self.release(i32::try_from(n).unwrap_or(i32::MAX)),
+        }
+    }
+    fn release(&self, count: i32) -> io::Result<()> {
+        unsafe { ReleaseSemaphore(self.semaphore.as_int_handle(), count, ptr::null_mut()) }
+            .true_val_or_errno(())
+    }
+}
@@ -0,0 +1,264 @@
+//! Event objects, the simplest of Windows' synchronization primitives.
+//!
+//! An event is either set or reset. Waiting on a set event completes immediately, and waiting on
+//! a reset one blocks until it is set. Named events can be opened by other processes, which makes
+//! them a natural way for one process to tell another that something has happened, such as data
+//! having been written into [shared memory](super::shared_memory).
+
+use {
+    super::{
+        c_wrappers::{self, WaitOutcome},
+        path_conversion::{to_io_error, ToWtf16},
+        security_descriptor::{create_security_attributes, BorrowedSecurityDescriptor},
+        winprelude::*,
+    },
+    crate::{OrErrno, Sealed},
+    std::{
+        ffi::c_void,
+        future::Future,
+        io,
+        pin::Pin,
+        ptr,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex, PoisonError,
+        },
+        task::{Context, Poll, Waker},
+        time::Duration,
+    },
+    widestring::U16CStr,
+    windows_sys::Win32::{
+        Foundation::BOOLEAN,
+        System::Threading::{
+            CreateEventW, OpenEventW, RegisterWaitForSingleObject, ResetEvent, SetEvent,
+            UnregisterWaitEx, EVENT_MODIFY_STATE, INFINITE, SYNCHRONIZATION_SYNCHRONIZE,
+            WT_EXECUTEONLYONCE,
+        },
+    },
+};
+
+/// Options for the creation of an [`Event`].
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct EventOptions<'sd> {
+    /// Security descriptor for the event object.
+    pub security_descriptor: Option<BorrowedSecurityDescriptor<'sd>>,
+    /// Specifies whether the resulting handle can be inherited by child processes.
+    ///
+    /// The default value is `false`.
+    pub inheritable: bool,
+    /// Specifies whether the event stays set until it is [reset](Event::reset). Otherwise, it is
+    /// reset automatically when a single waiting thread is released.
+    ///
+    /// The default value is `false`.
+    pub manual_reset: bool,
+    /// Specifies whether the event is set right after it is created.
+    ///
+    /// The default value is `false`.
+    pub initially_set: bool,
+}
+impl Sealed for EventOptions<'_> {}
+impl<'sd> EventOptions<'sd> {
+    /// Starts with the default parameters. Identical to `Default::default()`.
+    pub const fn new() -> Self {
+        Self {
+            security_descriptor: None,
+            inheritable: false,
+            manual_reset: false,
+            initially_set: false,
+        }
+    }
+
+    builder_setters! {
+        /// Specifies the pointer to the security descriptor for the event object.
+        ///
+        /// See the [associated field](#structfield.security_descriptor) for more.
+        security_descriptor: Option<BorrowedSecurityDescriptor<'sd>>,
+        /// Specifies whether the resulting handle can be inherited by child processes.
+        ///
+        /// See the [associated field](#structfield.inheritable) for more.
+        inheritable: bool,
+        /// Specifies whether the event has to be reset manually.
+        ///
+        /// See the [associated field](#structfield.manual_reset) for more.
+        manual_reset: bool,
+        /// Specifies whether the event is initially set.
+        ///
+        /// See the [associated field](#structfield.initially_set) for more.
+        initially_set: bool,
+    }
+
+    /// Creates an event object which has no name and is thus only reachable through the handle,
+    /// either by inheriting it or by having it duplicated into another process.
+    #[inline]
+    pub fn create_anonymous(self) -> io::Result<Event> { self.create_impl(None) }
+    /// Creates an event object with the given name, such as `Local\my-app-event`.
+    ///
+    /// Fails with [`AlreadyExists`](io::ErrorKind::AlreadyExists) if an object with that name
+    /// exists, instead of opening it the way `CreateEventW()` does.
+    pub fn create_named<'n>(self, name: impl ToWtf16<'n>) -> io::Result<Event> {
+        let name = name.to_wtf_16().map_err(to_io_error)?;
+        self.create_impl(Some(&name))
+    }
+
+    fn create_impl(self, name: Option<&U16CStr>) -> io::Result<Event> {
+        let sa = create_security_attributes(self.security_descriptor, self.inheritable);
+        let handle = unsafe {
+            CreateEventW(
+                sa.as_ptr(),
+                self.manual_reset.into(),
+                self.initially_set.into(),
+                name.map_or(ptr::null(), U16CStr::as_ptr),
+            )
+        };
+        c_wrappers::created_object(handle).map(Event)
+    }
+}
+impl Default for EventOptions<'_> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+
+/// An event object.
+///
+/// Created with [`EventOptions`] or opened by name with [`Event::open()`].
+///
+/// # Example
+/// ```no_run
+/// use interprocess::os::windows::event::{Event, EventOptions};
+///
+/// let event = EventOptions::new().create_named(r"Local\example-event")?;
+///
+/// // In another process:
+/// Event::open(r"Local\example-event")?.set()?;
+///
+/// event.wait()?;
+/// # std::io::Result::Ok(())
+/// ```
+#[derive(Debug)]
+pub struct Event(OwnedHandle);
+impl Event {
+    /// Creates a non-inheritable, auto-reset event object which has no name and is initially
+    /// reset. See [`EventOptions::create_anonymous()`].
+    #[inline]
+    pub fn create_anonymous() -> io::Result<Self> { EventOptions::new().create_anonymous() }
+    /// Opens an existing named event object for waiting, setting and resetting.
+    pub fn open<'n>(name: impl ToWtf16<'n>) -> io::Result<Self> {
+        let name = name.to_wtf_16().map_err(to_io_error)?;
+        let access = SYNCHRONIZATION_SYNCHRONIZE | EVENT_MODIFY_STATE;
+        let handle = unsafe { OpenEventW(access, 0, name.as_ptr()) };
+        c_wrappers::opened_object(handle).map(Self)
+    }
+
+    /// Sets the event, releasing either all waiting threads, if it is manual-reset, or one of
+    /// them otherwise.
+    pub fn set(&self) -> io::Result<()> {
+        unsafe { SetEvent(self.0.as_int_handle()) }.true_val_or_errno(())
+    }
+    /// Resets the event.
+    pub fn reset(&self) -> io::Result<()> {
+        unsafe { ResetEvent(self.0.as_int_handle()) }.true_val_or_errno(())
+    }
+    /// Blocks until the event is set. An auto-reset event is reset as the wait completes.
+    pub fn wait(&self) -> io::Result<()> { self.wait_impl(None).map(drop) }
+    /// Blocks until the event is set or the timeout expires, returning `false` in the latter
+    /// case. The timeout is rounded up to whole milliseconds.
+    pub fn wait_timeout(&self, timeout: Duration) -> io::Result<bool> {
+        self.wait_impl(Some(timeout))
+    }
+    fn wait_impl(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        Ok(c_wrappers::wait(self.0.as_handle(), timeout)? != WaitOutcome::TimedOut)
+    }
+    /// Returns a future which completes when the event is set.
+    ///
+    /// The future registers a wait with the system thread pool when first polled, so it works
+    /// with any async runtime and does not occupy a thread of its own. As with
+    /// [`.wait()`](Self::wait), an auto-reset event is reset as the wait completes – even if the
+    /// future is dropped before it gets to report that.
+    #[inline]
+    pub fn wait_async(&self) -> EventWait<'_> { EventWait { event: self, registration: None } }
+}
+impl AsHandle for Event {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> { self.0.as_handle() }
+}
+impl From<Event> for OwnedHandle {
+    #[inline]
+    fn from(event: Event) -> Self { event.0 }
+}
+/// Assumes that the handle refers to an event object.
+impl From<OwnedHandle> for Event {
+    #[inline]
+    fn from(handle: OwnedHandle) -> Self { Self(handle) }
+}
+
+/// Future returned by [`Event::wait_async()`].
+#[derive(Debug)]
+pub struct EventWait<'e> {
+    event: &'e Event,
+    registration: Option<Registration>,
+}
+impl Future for EventWait<'_> {
+    type Output = io::Result<()>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let slf = &mut *self;
+        let registration = match &slf.registration {
+            Some(registration) => registration,
+            None => slf.registration.insert(Registration::new(slf.event)?),
+        };
+        let state = &registration.state;
+        *state.waker.lock().unwrap_or_else(PoisonError::into_inner) = Some(cx.waker().clone());
+        // Checked after the waker is stored, so that a wakeup in between is not missed.
+        if state.fired.load(Ordering::Acquire) {
+            return Poll::Ready(Ok(()));
+        }
+        Poll::Pending
+    }
+}
+
+#[derive(Debug, Default)]
+struct WaitState {
+    fired: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+/// A wait registered with `RegisterWaitForSingleObject()`, unregistered on drop.
+#[derive(Debug)]
+struct Registration {
+    wait_handle: HANDLE,
+    state: Arc<WaitState>,
+}
+impl Registration {
+    fn new(event: &Event) -> io::Result<Self> {
+        let state = Arc::new(WaitState::default());
+        let mut wait_handle = 0;
+        unsafe {
+            RegisterWaitForSingleObject(
+                &mut wait_handle,
+                event.0.as_int_handle(),
+                Some(wait_callback),
+                Arc::as_ptr(&state).cast(),
+                INFINITE,
+                WT_EXECUTEONLYONCE,
+            )
+        }
+        .true_val_or_errno(())?;
+        Ok(Self { wait_handle, state })
+    }
+}
+impl Drop for Registration {
+    fn drop(&mut self) {
+        // Blocks until the callback, if it has started running, returns, which keeps it from
+        // accessing the state after it's freed.
+        unsafe { UnregisterWaitEx(self.wait_handle, INVALID_HANDLE_VALUE) };
+    }
+}
+unsafe extern "system" fn wait_callback(ctx: *mut c_void, _timed_out: BOOLEAN) {
+    // SAFETY: the context is the state of a registration, which outlives the callback
+    let state = unsafe { &*ctx.cast_const().cast::<WaitState>() };
+    state.fired.store(true, Ordering::Release);
+    let waker = state.waker.lock().unwrap_or_else(PoisonError::into_inner).take();
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
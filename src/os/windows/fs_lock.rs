@@ -0,0 +1,48 @@
+use {
+    super::winprelude::*,
+    std::{fs::File, io, mem::zeroed},
+    windows_sys::Win32::{
+        Foundation::ERROR_LOCK_VIOLATION,
+        Storage::FileSystem::{
+            LockFileEx, UnlockFileEx, LOCKFILE_EXCLUSIVE_LOCK, LOCKFILE_FAIL_IMMEDIATELY,
+        },
+        System::IO::OVERLAPPED,
+    },
+};
+
+/// Acquires a lock on the whole file with `LockFileEx()`, returning `false` if `block` is not set
+/// and the lock is held by someone else.
+///
+/// The file must have been opened for synchronous I/O, which is what `std` does.
+pub(crate) fn lock(file: &File, exclusive: bool, block: bool) -> io::Result<bool> {
+    let mut flags = 0;
+    if exclusive {
+        flags |= LOCKFILE_EXCLUSIVE_LOCK;
+    }
+    if !block {
+        flags |= LOCKFILE_FAIL_IMMEDIATELY;
+    }
+    // SAFETY: all-zero is a valid OVERLAPPED structure, with an offset of zero
+    let mut overlapped = unsafe { zeroed::<OVERLAPPED>() };
+    let ok = unsafe {
+        LockFileEx(file.as_int_handle(), flags, 0, u32::MAX, u32::MAX, &mut overlapped)
+    };
+    if ok != 0 {
+        return Ok(true);
+    }
+    let e = io::Error::last_os_error();
+    match e.raw_os_error().and_then(|code| u32::try_from(code).ok()) {
+        Some(ERROR_LOCK_VIOLATION) if !block => Ok(false),
+        _ => Err(e),
+    }
+}
+pub(crate) fn unlock(file: &File) -> io::Result<()> {
+    // SAFETY: as above
+    let mut overlapped = unsafe { zeroed::<OVERLAPPED>() };
+    let ok =
+        unsafe { UnlockFileEx(file.as_int_handle(), 0, u32::MAX, u32::MAX, &mut overlapped) };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
@@ -2,12 +2,11 @@
 
 use {
     crate::{
-        os::windows::{winprelude::*, FileHandle},
+        os::windows::{tokio_blocking, winprelude::*, FileHandle},
         DebugExpectExt, LOCK_POISON,
     },
     std::sync::{Mutex, OnceLock},
     tokio::{
-        fs::File,
         net::windows::named_pipe::{NamedPipeClient, NamedPipeServer},
         runtime::{self, Handle as RuntimeHandle, Runtime},
         sync::mpsc::{unbounded_channel, UnboundedSender},
@@ -18,7 +17,8 @@ use {
 pub(crate) enum Corpse {
     NpServer(NamedPipeServer),
     NpClient(NamedPipeClient),
-    Unnamed(File),
+    #[cfg(feature = "tokio")]
+    Unnamed(tokio::fs::File),
 }
 impl Drop for Corpse {
     fn drop(&mut self) {
@@ -32,6 +32,7 @@ impl AsRawHandle for Corpse {
         match self {
             Corpse::NpServer(o) => o.as_raw_handle(),
             Corpse::NpClient(o) => o.as_raw_handle(),
+            #[cfg(feature = "tokio")]
             Corpse::Unnamed(o) => o.as_raw_handle(),
         }
     }
@@ -60,7 +61,7 @@ out of scope outside of another Tokio runtime)",
 }
 
 fn bury(c: Corpse) {
-    task::spawn_blocking(move || {
+    tokio_blocking::spawn_detached(move || {
         let handle = c.as_int_handle();
         FileHandle::flush_hndl(handle).debug_expect("limbo flush failed");
     });
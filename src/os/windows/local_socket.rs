@@ -1,22 +1,95 @@
 //! Windows-specific local socket functionality.
 
+pub mod app_container;
 pub(crate) mod dispatch_sync;
-#[cfg(feature = "tokio")]
+#[cfg(feature = "local-socket-tokio")]
 pub(crate) mod dispatch_tokio;
 pub(crate) mod name_type;
 
 pub use name_type::*;
+#[cfg(feature = "polling")]
+use polling::{PollMode, Poller};
 use {
-    super::security_descriptor::SecurityDescriptor,
-    crate::{local_socket::ListenerOptions, Sealed},
+    super::{
+        named_pipe::WaitTimeout,
+        security_descriptor::{RawSecurityAttributes, SecurityDescriptor},
+    },
+    crate::{
+        local_socket::{Listener, ListenerOptions, Stream},
+        Sealed,
+    },
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+        path::PathBuf,
+        sync::Arc,
+    },
+    windows_sys::Win32::Security::SECURITY_ATTRIBUTES,
 };
 
+/// The process on the server side of a named pipe, as presented to the
+/// [`verify_server()`](crate::local_socket::ConnectOptions::verify_server) callback.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct ServerProcess {
+    /// Process ID of the server, from `GetNamedPipeServerProcessId`.
+    pub pid: u32,
+    /// Full path of the executable that the server runs, from `QueryFullProcessImageNameW`.
+    pub image_path: PathBuf,
+}
+
+/// Callback of [`verify_server()`](crate::local_socket::ConnectOptions::verify_server).
+#[derive(Clone)]
+pub(crate) struct ServerVerifier(pub(crate) Arc<dyn Fn(&ServerProcess) -> bool + Send + Sync>);
+impl Debug for ServerVerifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServerVerifier").finish_non_exhaustive()
+    }
+}
+
 /// Windows-specific [listener options](ListenerOptions).
 #[allow(private_bounds)]
 pub trait ListenerOptionsExt: Sized + Sealed {
     /// Sets the security descriptor that will control access to the underlying named pipe.
     #[must_use = builder_must_use!()]
     fn security_descriptor(self, sd: SecurityDescriptor) -> Self;
+
+    /// Sets additional flags to be ORed into the `dwOpenMode` parameter of `CreateNamedPipeW`.
+    ///
+    /// See the [field of the same name][f] on `PipeListenerOptions` for caveats.
+    ///
+    /// [f]: super::named_pipe::PipeListenerOptions::extra_open_mode
+    #[must_use = builder_must_use!()]
+    fn extra_open_mode(self, flags: u32) -> Self;
+
+    /// Sets additional flags to be ORed into the `dwPipeMode` parameter of `CreateNamedPipeW`.
+    ///
+    /// See the [field of the same name][f] on `PipeListenerOptions` for caveats.
+    ///
+    /// [f]: super::named_pipe::PipeListenerOptions::extra_pipe_mode
+    #[must_use = builder_must_use!()]
+    fn extra_pipe_mode(self, flags: u32) -> Self;
+
+    /// Sets the `nDefaultTimeOut` parameter of `CreateNamedPipeW`, which is how long clients wait
+    /// for an instance of the named pipe to free up when all of them are busy before giving up
+    /// with a [`TimedOut`](io::ErrorKind::TimedOut) error.
+    ///
+    /// The default of [`WaitTimeout::DEFAULT`] makes Windows use 50 milliseconds, which may be
+    /// too short for clients that arrive in bursts to a server that takes a while to accept each
+    /// one.
+    #[must_use = builder_must_use!()]
+    fn wait_timeout(self, timeout: WaitTimeout) -> Self;
+
+    /// Sets a raw `SECURITY_ATTRIBUTES` structure to create the named pipe with, overriding the
+    /// [security descriptor](ListenerOptionsExt::security_descriptor).
+    ///
+    /// # Safety
+    /// `sa` must point to a valid `SECURITY_ATTRIBUTES` structure, along with the security
+    /// descriptor it refers to, for as long as the options table, any of its copies and any
+    /// listeners created from them exist, since new instances of the named pipe are created with
+    /// it every time a client is accepted.
+    #[must_use = builder_must_use!()]
+    unsafe fn raw_security_attributes(self, sa: *const SECURITY_ATTRIBUTES) -> Self;
 }
 
 impl ListenerOptionsExt for ListenerOptions<'_> {
@@ -25,4 +98,86 @@ impl ListenerOptionsExt for ListenerOptions<'_> {
         self.security_descriptor = Some(sd);
         self
     }
+    #[inline(always)]
+    fn extra_open_mode(mut self, flags: u32) -> Self {
+        self.extra_open_mode = flags;
+        self
+    }
+    #[inline(always)]
+    fn extra_pipe_mode(mut self, flags: u32) -> Self {
+        self.extra_pipe_mode = flags;
+        self
+    }
+    #[inline(always)]
+    fn wait_timeout(mut self, timeout: WaitTimeout) -> Self {
+        self.wait_timeout = timeout;
+        self
+    }
+    #[inline(always)]
+    unsafe fn raw_security_attributes(mut self, sa: *const SECURITY_ATTRIBUTES) -> Self {
+        self.raw_security_attributes = Some(RawSecurityAttributes(sa));
+        self
+    }
+}
+
+/// Windows-specific [local socket listener](Listener) functionality.
+///
+/// With the `polling` feature, this is how the listener is registered with a [`polling`]
+/// `Poller`. Local socket streams cannot be registered on Windows; see the
+/// [`PipeListener` methods][m] this delegates to.
+///
+/// [m]: super::named_pipe::PipeListener::add_to_poller
+#[allow(private_bounds)]
+pub trait ListenerExt: Sealed {
+    /// Disconnects the client of a stream that was accepted from this listener, and keeps the
+    /// pipe instance for `.accept()` to hand out to a later client instead of creating a new one.
+    /// See [`PipeListener::recycle()`](super::named_pipe::PipeListener::recycle) for the details.
+    fn recycle(&self, stream: Stream) -> io::Result<()>;
+    /// Registers the listener with the given poller, using `key` to identify it in events.
+    ///
+    /// # Safety
+    /// The listener must be [removed](Self::remove_from_poller) from the poller before it is
+    /// dropped.
+    #[cfg(feature = "polling")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "polling")))]
+    unsafe fn add_to_poller(&self, poller: &Poller, key: usize, mode: PollMode) -> io::Result<()>;
+    /// Changes the key or mode the listener is registered with, re-arming the registration.
+    #[cfg(feature = "polling")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "polling")))]
+    fn modify_in_poller(&self, poller: &Poller, key: usize, mode: PollMode) -> io::Result<()>;
+    /// Removes the listener from the given poller.
+    #[cfg(feature = "polling")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "polling")))]
+    fn remove_from_poller(&self, poller: &Poller) -> io::Result<()>;
+}
+
+impl ListenerExt for Listener {
+    #[inline]
+    fn recycle(&self, stream: Stream) -> io::Result<()> {
+        let (Listener::NamedPipe(l), Stream::NamedPipe(s)) = (self, stream);
+        l.recycle(s)
+    }
+    #[inline]
+    #[cfg(feature = "polling")]
+    unsafe fn add_to_poller(
+        &self,
+        poller: &Poller,
+        key: usize,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        let Listener::NamedPipe(l) = self;
+        unsafe { l.pipe_listener().add_to_poller(poller, key, mode) }
+    }
+    #[inline]
+    #[cfg(feature = "polling")]
+    fn modify_in_poller(&self, poller: &Poller, key: usize, mode: PollMode) -> io::Result<()> {
+        let Listener::NamedPipe(l) = self;
+        l.pipe_listener().modify_in_poller(poller, key, mode)
+    }
+    #[inline]
+    #[cfg(feature = "polling")]
+    fn remove_from_poller(&self, poller: &Poller) -> io::Result<()> {
+        let Listener::NamedPipe(l) = self;
+        l.pipe_listener().remove_from_poller(poller)
+    }
 }
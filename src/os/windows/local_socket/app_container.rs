@@ -0,0 +1,73 @@
+//! Helpers for accepting local socket connections from apps running inside an AppContainer, such
+//! as packaged and UWP apps.
+//!
+//! Code inside an AppContainer cannot open named pipes in the global namespace. Instead, a client
+//! in the container connects to `\\.\pipe\LOCAL\<name>`, which the OS redirects to a pipe inside
+//! the container's own object namespace. A server outside of the container must therefore create
+//! the pipe at the path that redirection points to, and grant the container access to it.
+
+use {
+    super::NamedPipe,
+    crate::{
+        local_socket::{Name, PathNameType},
+        os::windows::security_descriptor::SecurityDescriptor,
+    },
+    std::{
+        borrow::Cow,
+        ffi::{OsStr, OsString},
+        io,
+    },
+    widestring::U16CString,
+};
+
+/// Returns the name a server outside of an AppContainer must listen on to be reachable by
+/// clients inside of it that connect to `\\.\pipe\LOCAL\<name>`.
+///
+/// `session_id` is the ID of the logon session the client runs in (usually that of the
+/// interactive user), and `container_sid` is the string form of the AppContainer's package SID
+/// (`S-1-15-2-…`), which `DeriveAppContainerSidFromAppContainerName` produces from the package
+/// family name.
+pub fn pipe_name(
+    session_id: u32,
+    container_sid: &OsStr,
+    name: &OsStr,
+) -> io::Result<Name<'static>> {
+    validate_sid(container_sid)?;
+    let mut path =
+        OsString::from(format!(r"\\.\pipe\Sessions\{session_id}\AppContainerNamedObjects\"));
+    path.push(container_sid);
+    path.push(r"\");
+    path.push(name);
+    NamedPipe::map(Cow::Owned(path)).map(Name::into_owned)
+}
+
+/// Builds a security descriptor for a pipe created at [`pipe_name()`] that grants read and write
+/// access to the AppContainer with the given package SID, in addition to full access for the
+/// creator of the pipe, administrators and LocalSystem.
+///
+/// A low mandatory integrity label is also included, since AppContainer processes run at low
+/// integrity and would otherwise be denied write access regardless of the DACL.
+pub fn security_descriptor(container_sid: &OsStr) -> io::Result<SecurityDescriptor> {
+    validate_sid(container_sid)?;
+    let mut sddl = OsString::from("D:(A;;GA;;;OW)(A;;GA;;;BA)(A;;GA;;;SY)(A;;GRGW;;;");
+    sddl.push(container_sid);
+    sddl.push(")S:(ML;;NW;;;LW)");
+    let sddl = U16CString::from_os_str(sddl)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    SecurityDescriptor::deserialize(&sddl)
+}
+
+/// Makes sure that the SID string is of the AppContainer kind, without attempting to interpret
+/// it further, so that it cannot inject anything into a path or an SDDL string.
+fn validate_sid(sid: &OsStr) -> io::Result<()> {
+    let ok = sid.to_str().is_some_and(|sid| {
+        sid.strip_prefix("S-1-15-2-").is_some_and(|rest| {
+            !rest.is_empty() && rest.bytes().all(|b| b.is_ascii_digit() || b == b'-')
+        })
+    });
+    if ok {
+        Ok(())
+    } else {
+        Err(io::Error::new(io::ErrorKind::InvalidInput, "not an AppContainer package SID"))
+    }
+}
@@ -1,6 +1,6 @@
 use {
     super::super::named_pipe::local_socket as np_impl,
-    crate::local_socket::{prelude::*, Listener, ListenerOptions, Name, Stream},
+    crate::local_socket::{prelude::*, ConnectOptions, Listener, ListenerOptions, Stream},
     std::io,
 };
 
@@ -9,6 +9,7 @@ pub fn from_options(options: ListenerOptions<'_>) -> io::Result<Listener> {
     options.create_sync_as::<np_impl::Listener>().map(Listener::from)
 }
 
-pub fn connect(name: Name<'_>) -> io::Result<Stream> {
-    np_impl::Stream::connect(name).map(Stream::from)
+#[inline]
+pub fn connect(options: &ConnectOptions<'_>) -> io::Result<Stream> {
+    options.connect_sync_as::<np_impl::Stream>().map(Stream::from)
 }
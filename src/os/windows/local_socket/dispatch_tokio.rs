@@ -2,7 +2,7 @@ use {
     super::super::named_pipe::local_socket::tokio as np_impl,
     crate::local_socket::{
         tokio::{prelude::*, Listener, Stream},
-        ListenerOptions, Name,
+        ConnectOptions, ListenerOptions,
     },
     std::io,
 };
@@ -12,6 +12,7 @@ pub fn from_options(options: ListenerOptions<'_>) -> io::Result<Listener> {
     options.create_tokio_as::<np_impl::Listener>().map(Listener::from)
 }
 
-pub async fn connect(name: Name<'_>) -> io::Result<Stream> {
-    np_impl::Stream::connect(name).await.map(Stream::from)
+#[inline]
+pub async fn connect(options: &ConnectOptions<'_>) -> io::Result<Stream> {
+    options.connect_tokio_as::<np_impl::Stream>().await.map(Stream::from)
 }
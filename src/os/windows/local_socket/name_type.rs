@@ -1,9 +1,10 @@
 use {
     crate::{
-        local_socket::{Name, NameInner, NameType, PathNameType},
+        local_socket::{Name, NameInner, NameType, NamespacedNameType, PathNameType},
         os::windows::{convert_and_encode_path, convert_osstr},
     },
     std::{borrow::Cow, ffi::OsStr, io},
+    widestring::U16CStr,
 };
 
 tag_enum!(
@@ -15,7 +16,13 @@ tag_enum!(
 ///
 /// Namespaced strings have `\\.\pipe\` prepended to them – using
 /// [`ToNsName`](crate::local_socket::ToNsName) conversions implies the hostname `.`, which is the
-/// local system.
+/// local system. Strings which already are named pipe paths are passed through verbatim instead.
+/// Otherwise, they are validated right away, so that an invalid name fails the conversion rather
+/// than a later `CreateNamedPipeW()` or `CreateFileW()` call with an opaque error: they must not
+/// be empty or contain backslashes, which are only allowed in the prefix.
+///
+/// Either way, the full path is limited to 256 UTF-16 code units, which is checked by the
+/// conversion.
 NamedPipe);
 impl NameType for NamedPipe {
     fn is_supported() -> bool { true }
@@ -25,8 +32,55 @@ impl PathNameType<OsStr> for NamedPipe {
         if !is_pipefs(&path) {
             return Err(io::Error::new(io::ErrorKind::Unsupported, "not a named pipe path"));
         }
-        Ok(Name(NameInner::NamedPipe(Cow::Owned(convert_osstr(&path)?))))
+        let path = convert_osstr(&path)?;
+        check_len(&path)?;
+        Ok(Name(NameInner::NamedPipe(Cow::Owned(path))))
+    }
+}
+
+impl NamespacedNameType<OsStr> for NamedPipe {
+    fn map(name: Cow<'_, OsStr>) -> io::Result<Name<'_>> {
+        if is_pipefs(&name) {
+            return <Self as PathNameType<OsStr>>::map(name);
+        }
+        validate_pipe_name(&name)?;
+        // The prefix is prepended here, at the time of conversion to UTF-16.
+        let path = convert_and_encode_path(&name, None)?;
+        check_len(&path)?;
+        Ok(Name(NameInner::NamedPipe(Cow::Owned(path))))
+    }
+}
+
+/// Checks a full named pipe path against the limit of 256 characters, which Windows measures
+/// in UTF-16 code units.
+fn check_len(path: &U16CStr) -> io::Result<()> {
+    const MAX: usize = 256;
+    let len = path.len();
+    if len > MAX {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "named pipe path is {len} UTF-16 units long, which exceeds the limit of {MAX}"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks the part of a named pipe path after `\\.\pipe\`.
+fn validate_pipe_name(name: &OsStr) -> io::Result<()> {
+    let invalid = |msg| Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+    let b = name.as_encoded_bytes();
+    if b.is_empty() {
+        return invalid("named pipe name is empty");
+    }
+    if findslash(b).is_some() {
+        return invalid("named pipe name contains a backslash");
+    }
+    if b.contains(&0) {
+        return invalid("named pipe name contains a nul character");
     }
+    Ok(())
 }
 
 pub(crate) fn map_generic_path_osstr(path: Cow<'_, OsStr>) -> io::Result<Name<'_>> {
@@ -36,8 +90,7 @@ pub(crate) fn map_generic_path_osstr(path: Cow<'_, OsStr>) -> io::Result<Name<'_
 }
 
 pub(crate) fn map_generic_namespaced_osstr(name: Cow<'_, OsStr>) -> io::Result<Name<'_>> {
-    // The prepending currently happens at a later point.
-    Ok(Name(NameInner::NamedPipe(Cow::Owned(convert_and_encode_path(&name, None)?))))
+    <NamedPipe as NamespacedNameType<OsStr>>::map(name)
 }
 
 #[allow(clippy::indexing_slicing, clippy::arithmetic_side_effects)] // minlen check
@@ -92,4 +145,30 @@ mod tests {
     fn bad() { assert_not_pipefs("iwiwiwiwiwiwiwiwiwiwiwiwi"); }
     #[test]
     fn can_not_do_unix_things() { assert_not_pipefs(r"C:\Users\GetSilly\neovide.sock"); }
+
+    #[track_caller]
+    fn ns(s: &str) -> io::Result<Name<'_>> {
+        <NamedPipe as NamespacedNameType<OsStr>>::map(Cow::Borrowed(OsStr::new(s)))
+    }
+    #[test]
+    fn ns_prepends() {
+        let Name(NameInner::NamedPipe(path)) = ns("yeah").unwrap();
+        assert_eq!(path.to_string_lossy(), r"\\.\pipe\yeah");
+    }
+    #[test]
+    fn ns_passes_qualified() {
+        let Name(NameInner::NamedPipe(path)) = ns(r"\\.\pipe\yeah").unwrap();
+        assert_eq!(path.to_string_lossy(), r"\\.\pipe\yeah");
+    }
+    #[test]
+    fn ns_rejects_long() {
+        assert!(ns(&"a".repeat(256 - 9)).is_ok());
+        assert_eq!(ns(&"a".repeat(256 - 8)).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+    #[test]
+    fn ns_rejects_invalid() {
+        for bad in ["", r"nested\yeah", "nul\0yeah"] {
+            assert_eq!(ns(bad).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        }
+    }
 }
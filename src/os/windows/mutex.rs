@@ -0,0 +1,45 @@
+use {
+    super::{
+        c_wrappers::{self, WaitOutcome},
+        path_conversion::local_object_name,
+        winprelude::*,
+    },
+    crate::{local_socket::Name, OrErrno},
+    std::{io, ptr, time::Duration},
+    windows_sys::Win32::System::Threading::{
+        CreateMutexW, OpenMutexW, ReleaseMutex, MUTEX_MODIFY_STATE, SYNCHRONIZATION_SYNCHRONIZE,
+    },
+};
+
+/// A named kernel mutex, which Windows marks as abandoned if its owner exits while holding it.
+#[derive(Debug)]
+pub(crate) struct Mutex(OwnedHandle);
+impl Mutex {
+    pub fn create(name: &Name<'_>) -> io::Result<Self> {
+        let name = local_object_name(name, "")?;
+        let handle = unsafe { CreateMutexW(ptr::null(), 0, name.as_ptr()) };
+        c_wrappers::created_object(handle).map(Self)
+    }
+    pub fn open(name: &Name<'_>) -> io::Result<Self> {
+        let name = local_object_name(name, "")?;
+        let access = SYNCHRONIZATION_SYNCHRONIZE | MUTEX_MODIFY_STATE;
+        let handle = unsafe { OpenMutexW(access, 0, name.as_ptr()) };
+        c_wrappers::opened_object(handle).map(Self)
+    }
+    /// Returns `None` on timeout, or whether the previous owner died otherwise.
+    pub fn lock(&self, timeout: Option<Duration>) -> io::Result<Option<bool>> {
+        Ok(match c_wrappers::wait(self.0.as_handle(), timeout)? {
+            WaitOutcome::Signaled => Some(false),
+            WaitOutcome::Abandoned => Some(true),
+            WaitOutcome::TimedOut => None,
+        })
+    }
+    /// Must only be called by the thread which holds the lock.
+    pub fn unlock(&self) -> io::Result<()> {
+        unsafe { ReleaseMutex(self.0.as_int_handle()) }.true_val_or_errno(())
+    }
+}
+impl AsHandle for Mutex {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> { self.0.as_handle() }
+}
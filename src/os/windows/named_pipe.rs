@@ -39,12 +39,15 @@
 // TODO(2.3.0) raw instance functionality
 // TODO(2.3.0) transactions
 
+pub mod overlapped;
+
 mod enums;
+mod info;
 mod listener;
 mod stream;
 mod wait_timeout;
 
-pub use {enums::*, listener::*, stream::*, wait_timeout::*};
+pub use {enums::*, info::*, listener::*, stream::*, wait_timeout::*};
 
 /// Local sockets implemented using Windows named pipes.
 pub mod local_socket {
@@ -53,7 +56,7 @@ pub mod local_socket {
     pub use {listener::*, stream::*};
 
     /// Async local sockets for Tokio implemented using named pipes.
-    #[cfg(feature = "tokio")]
+    #[cfg(feature = "local-socket-tokio")]
     pub(crate) mod tokio {
         mod listener;
         mod stream;
@@ -75,10 +78,16 @@ use maybe_arc::*;
 /// Types from this module will *not* work with other async runtimes, such as `async-std` or `smol`,
 /// since the Tokio types' methods will panic whenever they're called outside of a Tokio runtime
 /// context. Open an issue if you'd like to see other runtimes supported as well.
-#[cfg(feature = "tokio")]
-#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+#[cfg(feature = "local-socket-tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "local-socket-tokio")))]
 pub mod tokio {
     mod listener;
     mod stream;
-    pub use {listener::*, stream::*};
+    pub use {
+        crate::os::windows::tokio_blocking::{
+            blocking_strategy, set_blocking_strategy, BlockingExecutor, BlockingStrategy,
+        },
+        listener::*,
+        stream::*,
+    };
 }
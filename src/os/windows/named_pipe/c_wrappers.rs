@@ -8,13 +8,18 @@ use {
         },
         AsMutPtr, HandleOrErrno, OrErrno, RawOsErrorExt, SubUsizeExt,
     },
-    std::{io, mem::MaybeUninit, os::windows::prelude::*, ptr},
-    widestring::U16CStr,
+    std::{
+        io,
+        mem::{size_of, MaybeUninit},
+        os::windows::prelude::*,
+        ptr,
+    },
+    widestring::{U16CStr, U16CString},
     windows_sys::Win32::{
         Foundation::{ERROR_PIPE_BUSY, GENERIC_READ, GENERIC_WRITE},
         Storage::FileSystem::{
-            CreateFileW, FILE_FLAG_OVERLAPPED, FILE_SHARE_READ, FILE_SHARE_WRITE,
-            FILE_WRITE_ATTRIBUTES, OPEN_EXISTING,
+            CreateFileW, FileNameInfo, GetFileInformationByHandleEx, FILE_FLAG_OVERLAPPED,
+            FILE_SHARE_READ, FILE_SHARE_WRITE, FILE_WRITE_ATTRIBUTES, OPEN_EXISTING,
         },
         System::Pipes::{
             GetNamedPipeHandleStateW, GetNamedPipeInfo, PeekNamedPipe, SetNamedPipeHandleState,
@@ -104,6 +109,35 @@ pub(crate) fn set_np_handle_state(
     .true_val_or_errno(())
 }
 
+/// Retrieves the path of the named pipe the handle belongs to, in the `\\.\pipe\` form.
+///
+/// The hostname of remote pipes is not preserved, since the system only reports the name of the
+/// pipe within the pipe filesystem.
+pub(crate) fn get_path(handle: BorrowedHandle<'_>) -> io::Result<U16CString> {
+    // Same layout as FILE_NAME_INFO, but with enough room for the longest possible pipe name.
+    #[repr(C)]
+    struct NameInfo {
+        len: u32,
+        name: [u16; 256],
+    }
+    let mut info = NameInfo { len: 0, name: [0; 256] };
+    unsafe {
+        GetFileInformationByHandleEx(
+            handle.as_int_handle(),
+            FileNameInfo,
+            info.as_mut_ptr().cast(),
+            u32::try_from(size_of::<NameInfo>()).unwrap_or(u32::MAX),
+        )
+    }
+    .true_val_or_errno(())?;
+
+    // The length is in bytes, and the name already starts with a backslash.
+    let name = info.name.get(..(info.len / 2).to_usize()).unwrap_or(&info.name);
+    let mut path = r"\\.\pipe".encode_utf16().collect::<Vec<_>>();
+    path.extend_from_slice(name);
+    U16CString::from_vec(path).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
 #[inline]
 pub(crate) fn get_flags(handle: BorrowedHandle<'_>) -> io::Result<u32> {
     let mut flags: u32 = 0;
@@ -0,0 +1,59 @@
+use {
+    super::{c_wrappers, PipeMode},
+    std::{io, num::NonZeroU8, os::windows::io::BorrowedHandle},
+    windows_sys::Win32::System::Pipes::{
+        PIPE_SERVER_END, PIPE_TYPE_MESSAGE, PIPE_UNLIMITED_INSTANCES,
+    },
+};
+
+/// Information about a named pipe, as reported by [`GetNamedPipeInfo`][gnpi].
+///
+/// Since these properties are set by the server when it creates the pipe, the buffer sizes are
+/// given from the server's perspective regardless of which end they were queried from: the input
+/// buffer holds data that goes into the server, and the output buffer holds data that goes out of
+/// it.
+///
+/// [gnpi]: https://learn.microsoft.com/en-us/windows/win32/api/namedpipeapi/nf-namedpipeapi-getnamedpipeinfo
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PipeInfo {
+    /// Whether the information was queried from the server end of the pipe.
+    pub is_server: bool,
+    /// The mode the pipe was created in.
+    pub mode: PipeMode,
+    /// The size of the buffer for data coming into the server, in bytes. Zero means that the
+    /// buffer is allocated as needed.
+    pub input_buffer_size: u32,
+    /// The size of the buffer for data coming out of the server, in bytes. Zero means that the
+    /// buffer is allocated as needed.
+    pub output_buffer_size: u32,
+    /// The maximum number of instances of the pipe that can exist at the same time, or `None` if
+    /// there is no limit.
+    pub instance_limit: Option<NonZeroU8>,
+}
+impl PipeInfo {
+    /// Queries the information for the given named pipe handle.
+    pub fn query(handle: BorrowedHandle<'_>) -> io::Result<Self> {
+        let (mut flags, mut input_buffer_size, mut output_buffer_size, mut max_instances) =
+            (0, 0, 0, 0);
+        c_wrappers::get_np_info(
+            handle,
+            Some(&mut flags),
+            Some(&mut input_buffer_size),
+            Some(&mut output_buffer_size),
+            Some(&mut max_instances),
+        )?;
+        let mode =
+            if flags & PIPE_TYPE_MESSAGE != 0 { PipeMode::Messages } else { PipeMode::Bytes };
+        let instance_limit = match max_instances {
+            PIPE_UNLIMITED_INSTANCES => None,
+            n => u8::try_from(n).ok().and_then(NonZeroU8::new),
+        };
+        Ok(Self {
+            is_server: flags & PIPE_SERVER_END != 0,
+            mode,
+            input_buffer_size,
+            output_buffer_size,
+            instance_limit,
+        })
+    }
+}
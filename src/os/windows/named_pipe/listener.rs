@@ -1,11 +1,15 @@
+mod connect_event;
 mod create_instance;
 mod incoming;
 mod options;
+#[cfg(feature = "polling")]
+mod polling;
 
 use {
-    super::{c_wrappers, PipeModeTag, PipeStream, PipeStreamRole, RawPipeStream},
+    self::connect_event::ConnectEvent,
+    super::{c_wrappers, PipeInfo, PipeModeTag, PipeStream, PipeStreamRole, RawPipeStream},
     crate::{
-        os::windows::{winprelude::*, FileHandle},
+        os::windows::{c_wrappers as os_c_wrappers, winprelude::*, FileHandle},
         poison_error, OrErrno, RawOsErrorExt, LOCK_POISON,
     },
     std::{
@@ -15,8 +19,8 @@ use {
         mem::replace,
         ptr,
         sync::{
-            atomic::{AtomicBool, Ordering::Relaxed},
-            Mutex,
+            atomic::{AtomicBool, AtomicU32, Ordering::Relaxed},
+            Mutex, OnceLock,
         },
     },
     windows_sys::Win32::{
@@ -39,7 +43,12 @@ pub use {incoming::*, options::*};
 pub struct PipeListener<Rm: PipeModeTag, Sm: PipeModeTag> {
     config: PipeListenerOptions<'static>, // We need the options to create new instances
     nonblocking: AtomicBool,
+    inheritable: AtomicBool,
+    input_buffer_size_hint: AtomicU32,
+    output_buffer_size_hint: AtomicU32,
     stored_instance: Mutex<FileHandle>,
+    spare_instances: Mutex<Vec<FileHandle>>,
+    connect_event: OnceLock<ConnectEvent>,
     _phantom: PhantomData<(Rm, Sm)>,
 }
 impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
@@ -55,9 +64,23 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
             // Doesn't actually even need to be atomic to begin with, but it's simpler and more
             // convenient to do this instead. The mutex takes care of ordering.
             let nonblocking = self.nonblocking.load(Relaxed);
-            block_on_connect(stored_instance.as_handle())?;
-            let new_instance = self.create_instance(nonblocking)?;
-            replace(&mut *stored_instance, new_instance)
+            let event = self.connect_event.get();
+            if let Some(event) = event {
+                // A failure to re-arm the event after the previous client was accepted is
+                // reported here rather than by dropping that client.
+                if let Some(e) = event.take_failure() {
+                    event.rearm(stored_instance.as_handle());
+                    return Err(e);
+                }
+                event.disarm()?;
+            }
+            let rslt = block_on_connect(stored_instance.as_handle())
+                .and_then(|()| self.next_instance(nonblocking))
+                .map(|new_instance| replace(&mut *stored_instance, new_instance));
+            if let Some(event) = event {
+                event.rearm(stored_instance.as_handle());
+            }
+            rslt?
         };
 
         let raw = RawPipeStream::new_server(instance_to_hand_out);
@@ -65,11 +88,125 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
         Ok(PipeStream::new(raw))
     }
 
+    /// Like [`.accept()`](Self::accept), but waits for the client on a new pipe instance that is
+    /// created with the given input and output buffer size hints, so that the returned stream
+    /// gets bigger (or smaller) buffers than the ones the listener otherwise creates instances
+    /// with.
+    ///
+    /// The buffers of a pipe instance are sized once and for all when it's created, which is why
+    /// this is done on a dedicated instance rather than by resizing an existing one. No other
+    /// stream accepted from the listener gets the given sizes.
+    ///
+    /// The instance that the listener keeps waiting for a client remains available during the
+    /// call, and Windows does not allow clients to pick the instance that they connect to. If
+    /// other clients may connect at the same time, the one that gets the dedicated instance is
+    /// thus not predictable, and a client that connects to the regular instance instead is left
+    /// for the next `.accept()`.
+    ///
+    /// # Errors
+    /// Since nonblocking instances never wait for clients, this method fails with
+    /// [`Unsupported`](io::ErrorKind::Unsupported) if the listener is in nonblocking mode. If the
+    /// [instance limit](PipeListenerOptions::instance_limit) has been reached, creating the
+    /// dedicated instance fails.
+    pub fn accept_with_buffer_sizes(
+        &self,
+        input: u32,
+        output: u32,
+    ) -> io::Result<PipeStream<Rm, Sm>> {
+        if self.nonblocking.load(Relaxed) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "dedicated instances cannot be waited on by nonblocking listeners",
+            ));
+        }
+        let instance = self.create_instance(false, (input, output))?;
+        block_on_connect(instance.as_handle())?;
+        Ok(PipeStream::new(RawPipeStream::new_server(instance)))
+    }
+
+    /// Disconnects the client of a stream that was accepted from this listener, and keeps the
+    /// pipe instance around for `.accept()` to hand out to a later client instead of creating a
+    /// new one.
+    ///
+    /// Creating a pipe instance is a relatively costly system call, so servers that go through
+    /// many short-lived connections can save on it by recycling streams that they are done with
+    /// rather than dropping them. This waits for the client to receive everything that has been
+    /// sent, since disconnecting discards whatever is still in the pipe. The number of spare
+    /// instances kept by the listener never exceeds the number of connections that were open at
+    /// once.
+    ///
+    /// # Errors
+    /// Fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the stream is client-side or
+    /// split. Recycling a server-side stream that came from another listener (or from a handle)
+    /// is not an error, but makes that instance accept clients of the other pipe. The stream is
+    /// closed on failure.
+    pub fn recycle(&self, stream: PipeStream<Rm, Sm>) -> io::Result<()> {
+        let instance = stream.disconnect()?;
+        self.spare_instances.lock().map_err(poison_error)?.push(instance);
+        Ok(())
+    }
+
     /// Creates an iterator which accepts connections from clients, blocking each time `next()` is
     /// called until one connects.
     #[inline]
     pub fn incoming(&self) -> Incoming<'_, Rm, Sm> { Incoming(self) }
 
+    /// Queries [information](PipeInfo) about the named pipe, using the instance that is currently
+    /// waiting for a client.
+    ///
+    /// This momentarily locks an internal mutex, and thus waits for a concurrent `.accept()` call
+    /// to finish.
+    pub fn info(&self) -> io::Result<PipeInfo> {
+        let instance = self.stored_instance.lock().map_err(poison_error)?;
+        PipeInfo::query(instance.as_handle())
+    }
+
+    /// Returns a handle to a manual-reset event object which becomes signaled once a client is
+    /// waiting to be accepted, allowing the listener to take part in `WaitForMultipleObjects`
+    /// loops.
+    ///
+    /// Once the event is signaled, `.accept()` returns without blocking (barring races with other
+    /// threads accepting on the same listener) and resets the event for the next client. The
+    /// event and the helper thread that signals it are created on the first call to this method;
+    /// subsequent calls return the same handle.
+    ///
+    /// # Errors
+    /// Since nonblocking instances never wait for clients, this method fails with
+    /// [`Unsupported`](io::ErrorKind::Unsupported) if the listener is in nonblocking mode.
+    /// Enabling nonblocking mode after the event has been created results in it getting signaled
+    /// spuriously.
+    ///
+    /// If the event could not be re-armed after `.accept()` handed out a client, it is left
+    /// signaled, and the error is returned by the next call to either `.accept()` or this
+    /// method, which both try to re-arm it again.
+    pub fn connect_event(&self) -> io::Result<BorrowedHandle<'_>> {
+        if let Some(event) = self.connect_event.get() {
+            if let Some(e) = event.take_failure() {
+                let stored_instance = self.stored_instance.lock().map_err(poison_error)?;
+                event.rearm(stored_instance.as_handle());
+                return Err(e);
+            }
+            return Ok(event.as_handle());
+        }
+        let stored_instance = self.stored_instance.lock().map_err(poison_error)?;
+        if self.nonblocking.load(Relaxed) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "connect events cannot be used with nonblocking listeners",
+            ));
+        }
+        // The mutex ensures that no other thread is initializing the event concurrently.
+        let event = match self.connect_event.get() {
+            Some(event) => event,
+            None => {
+                let event = ConnectEvent::new()?;
+                event.arm(stored_instance.as_handle())?;
+                self.connect_event.get_or_init(|| event)
+            }
+        };
+        Ok(event.as_handle())
+    }
+
     /// Enables or disables the nonblocking mode for all existing instances of the listener and
     /// future ones. By default, it is disabled.
     ///
@@ -90,6 +227,49 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
         Ok(())
     }
 
+    /// Sets whether the instance that is currently waiting for a client and all future ones are
+    /// to be inherited by child processes. By default, this is determined by the
+    /// [`inheritable` field](PipeListenerOptions::inheritable) of the creation options.
+    ///
+    /// Since every instance is eventually handed out by `.accept()`, the resulting streams have
+    /// the same setting.
+    pub fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+        let instance = self.stored_instance.lock().map_err(poison_error)?;
+        self.inheritable.store(inheritable, Relaxed);
+        os_c_wrappers::set_inheritable(instance.as_handle(), inheritable)?;
+        drop(instance);
+        Ok(())
+    }
+
+    /// Changes the input and output buffer size hints that the listener creates pipe instances
+    /// with, in place of the
+    /// [`input_buffer_size_hint`](PipeListenerOptions::input_buffer_size_hint) and
+    /// [`output_buffer_size_hint`](PipeListenerOptions::output_buffer_size_hint) fields of the
+    /// creation options.
+    ///
+    /// The buffers of a pipe instance are sized once and for all when it's created, and there is
+    /// no way to resize them afterwards – `SetNamedPipeHandleState` only covers the read and
+    /// blocking modes. The new sizes thus apply to instances created from now on, the first of
+    /// which is handed out to the client after the one that `.accept()` is to return next: the
+    /// instance which is already waiting for a client is kept, since a client may already have
+    /// connected to it. Spare instances kept by [`.recycle()`](Self::recycle) have the old sizes
+    /// and are closed.
+    ///
+    /// To give bigger buffers to a single client only, use
+    /// [`.accept_with_buffer_sizes()`](Self::accept_with_buffer_sizes) instead.
+    pub fn set_buffer_size_hints(&self, input: u32, output: u32) -> io::Result<()> {
+        let mut spare_instances = self.spare_instances.lock().map_err(poison_error)?;
+        self.input_buffer_size_hint.store(input, Relaxed);
+        self.output_buffer_size_hint.store(output, Relaxed);
+        spare_instances.clear();
+        Ok(())
+    }
+    /// Returns the input and output buffer size hints that new pipe instances are created with.
+    #[inline]
+    pub fn buffer_size_hints(&self) -> (u32, u32) {
+        (self.input_buffer_size_hint.load(Relaxed), self.output_buffer_size_hint.load(Relaxed))
+    }
+
     /// Creates a listener from a handle and a [`PipeListenerOptions`] table with the assumption
     /// that the handle was created with those options.
     ///
@@ -102,16 +282,46 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
     ) -> Self {
         Self {
             nonblocking: AtomicBool::new(options.nonblocking),
+            inheritable: AtomicBool::new(options.inheritable),
+            input_buffer_size_hint: AtomicU32::new(options.input_buffer_size_hint),
+            output_buffer_size_hint: AtomicU32::new(options.output_buffer_size_hint),
             config: options,
             stored_instance: Mutex::new(FileHandle::from(handle)),
+            spare_instances: Mutex::default(),
+            connect_event: OnceLock::new(),
             _phantom: PhantomData,
         }
     }
 
-    fn create_instance(&self, nonblocking: bool) -> io::Result<FileHandle> {
-        self.config
-            .create_instance(false, nonblocking, false, Self::STREAM_ROLE, Rm::MODE)
-            .map(FileHandle::from)
+    /// Takes out a recycled instance, bringing it up to date with the listener's settings, or
+    /// creates a new one if there are none.
+    fn next_instance(&self, nonblocking: bool) -> io::Result<FileHandle> {
+        let spare = self.spare_instances.lock().map_err(poison_error)?.pop();
+        let Some(instance) = spare else {
+            return self.create_instance(nonblocking, self.buffer_size_hints());
+        };
+        c_wrappers::set_nonblocking_given_readmode(instance.as_handle(), nonblocking, Rm::MODE)?;
+        os_c_wrappers::set_inheritable(instance.as_handle(), self.inheritable.load(Relaxed))?;
+        Ok(instance)
+    }
+    fn create_instance(
+        &self,
+        nonblocking: bool,
+        buffer_sizes: (u32, u32),
+    ) -> io::Result<FileHandle> {
+        let instance = self.config.create_instance_with_buffer_sizes(
+            false,
+            nonblocking,
+            false,
+            Self::STREAM_ROLE,
+            Rm::MODE,
+            buffer_sizes,
+        )?;
+        let inheritable = self.inheritable.load(Relaxed);
+        if inheritable != self.config.inheritable {
+            os_c_wrappers::set_inheritable(instance.as_handle(), inheritable)?;
+        }
+        Ok(FileHandle::from(instance))
     }
 }
 impl<Rm: PipeModeTag, Sm: PipeModeTag> Debug for PipeListener<Rm, Sm> {
@@ -119,7 +329,10 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> Debug for PipeListener<Rm, Sm> {
         f.debug_struct("PipeListener")
             .field("config", &self.config)
             .field("instance", &self.stored_instance)
+            .field("spare_instances", &self.spare_instances)
             .field("nonblocking", &self.nonblocking.load(Relaxed))
+            .field("inheritable", &self.inheritable.load(Relaxed))
+            .field("buffer_size_hints", &self.buffer_size_hints())
             .finish()
     }
 }
@@ -137,6 +350,7 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> AsRawHandle for PipeListener<Rm, Sm> {
 
 impl<Rm: PipeModeTag, Sm: PipeModeTag> From<PipeListener<Rm, Sm>> for OwnedHandle {
     fn from(p: PipeListener<Rm, Sm>) -> Self {
+        drop(p.connect_event);
         p.stored_instance.into_inner().expect(LOCK_POISON).into()
     }
 }
@@ -0,0 +1,154 @@
+use {
+    super::block_on_connect,
+    crate::{os::windows::winprelude::*, poison_error, OrErrno},
+    std::{
+        io, ptr,
+        sync::{
+            atomic::{AtomicBool, Ordering::SeqCst},
+            Arc, Condvar, Mutex, PoisonError,
+        },
+        thread::{self, JoinHandle},
+        time::Duration,
+    },
+    windows_sys::Win32::System::{
+        Threading::{CreateEventW, ResetEvent, SetEvent},
+        IO::CancelSynchronousIo,
+    },
+};
+
+/// A manual-reset event which gets signaled once a client connects to the instance of the server
+/// it's armed for.
+///
+/// Since the instances created by the sync listener are not overlapped, `ConnectNamedPipe` cannot
+/// signal an event by itself. Instead, a helper thread performs the blocking wait on a duplicate
+/// of the instance handle and sets the event when it's done.
+#[derive(Debug)]
+pub(super) struct ConnectEvent {
+    event: OwnedHandle,
+    waiter: Mutex<Option<Waiter>>,
+    /// Error from a failed [`.rearm()`](Self::rearm), waiting to be reported.
+    failure: Mutex<Option<io::Error>>,
+}
+
+#[derive(Debug)]
+struct Waiter {
+    thread: JoinHandle<()>,
+    state: Arc<WaiterState>,
+}
+
+#[derive(Debug, Default)]
+struct WaiterState {
+    cancelled: AtomicBool,
+    finished: Mutex<bool>,
+    finished_cv: Condvar,
+}
+impl WaiterState {
+    fn finish(&self) {
+        *self.finished.lock().unwrap_or_else(PoisonError::into_inner) = true;
+        self.finished_cv.notify_all();
+    }
+}
+
+/// How long to wait for the helper thread to exit before repeating a cancellation request that
+/// found no I/O to cancel.
+const CANCEL_RETRY_INTERVAL: Duration = Duration::from_millis(1);
+
+impl ConnectEvent {
+    pub fn new() -> io::Result<Self> {
+        let event = unsafe { CreateEventW(ptr::null(), 1, 0, ptr::null()) };
+        (event != 0).true_or_errno(|| Self {
+            // SAFETY: we just created this handle
+            event: unsafe { OwnedHandle::from_raw_handle(event.to_std()) },
+            waiter: Mutex::new(None),
+            failure: Mutex::new(None),
+        })
+    }
+    #[inline]
+    pub fn as_handle(&self) -> BorrowedHandle<'_> { self.event.as_handle() }
+
+    /// Resets the event and starts waiting for a client to connect to the given instance. A
+    /// previous wait, if any, is disarmed first.
+    pub fn arm(&self, instance: BorrowedHandle<'_>) -> io::Result<()> {
+        let mut waiter = self.waiter.lock().map_err(poison_error)?;
+        if let Some(old) = waiter.take() {
+            old.disarm();
+        }
+        unsafe { ResetEvent(self.event.as_int_handle()) }.true_val_or_errno(())?;
+
+        let instance = instance.try_clone_to_owned()?;
+        let event = self.event.try_clone()?;
+        let state = Arc::new(WaiterState::default());
+        let thread = thread::Builder::new()
+            .name("named pipe connect waiter".to_owned())
+            .spawn({
+                let state = Arc::clone(&state);
+                move || {
+                    if !state.cancelled.load(SeqCst) {
+                        // Errors other than cancellation will be reported by .accept(), which
+                        // sees the same instance, so waking it up is all that needs to be done
+                        // here.
+                        let rslt = block_on_connect(instance.as_handle());
+                        if !state.cancelled.load(SeqCst) || rslt.is_ok() {
+                            unsafe { SetEvent(event.as_int_handle()) };
+                        }
+                    }
+                    state.finish();
+                }
+            })?;
+        *waiter = Some(Waiter { thread, state });
+        Ok(())
+    }
+
+    /// Like [`.arm()`](Self::arm), but for when the failure cannot be reported right away,
+    /// such as after a client has been accepted. On failure, the error is kept for
+    /// [`.take_failure()`](Self::take_failure) and the event is signaled, so that whoever waits
+    /// on it wakes up and gets to see the error.
+    pub fn rearm(&self, instance: BorrowedHandle<'_>) {
+        if let Err(e) = self.arm(instance) {
+            *self.failure.lock().unwrap_or_else(PoisonError::into_inner) = Some(e);
+            unsafe { SetEvent(self.event.as_int_handle()) };
+        }
+    }
+    /// Returns the error from the last failed [`.rearm()`](Self::rearm), if it has not been
+    /// reported yet.
+    pub fn take_failure(&self) -> Option<io::Error> {
+        self.failure.lock().unwrap_or_else(PoisonError::into_inner).take()
+    }
+
+    /// Stops waiting for a client to connect, without resetting the event.
+    pub fn disarm(&self) -> io::Result<()> {
+        if let Some(waiter) = self.waiter.lock().map_err(poison_error)?.take() {
+            waiter.disarm();
+        }
+        Ok(())
+    }
+}
+impl Drop for ConnectEvent {
+    fn drop(&mut self) {
+        if let Some(waiter) = self.waiter.get_mut().ok().and_then(Option::take) {
+            waiter.disarm();
+        }
+    }
+}
+
+impl Waiter {
+    fn disarm(self) {
+        self.state.cancelled.store(true, SeqCst);
+        let mut finished = self.state.finished.lock().unwrap_or_else(PoisonError::into_inner);
+        while !*finished {
+            // The thread might not have entered ConnectNamedPipe yet by the time a cancellation
+            // request is made, which makes that request a no-op. Only then does it have to be
+            // repeated; otherwise, the thread is on its way out and merely has to be waited for.
+            let cancelled = unsafe { CancelSynchronousIo(self.thread.as_int_handle()) } != 0;
+            let cv = &self.state.finished_cv;
+            finished = if cancelled {
+                cv.wait(finished).unwrap_or_else(PoisonError::into_inner)
+            } else {
+                let rslt = cv.wait_timeout(finished, CANCEL_RETRY_INTERVAL);
+                rslt.unwrap_or_else(PoisonError::into_inner).0
+            };
+        }
+        drop(finished);
+        let _ = self.thread.join();
+    }
+}
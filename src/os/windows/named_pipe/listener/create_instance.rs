@@ -38,6 +38,27 @@ impl PipeListenerOptions<'_> {
         overlapped: bool,
         role: PipeStreamRole,
         recv_mode: Option<PipeMode>,
+    ) -> io::Result<OwnedHandle> {
+        let buffer_sizes = (self.input_buffer_size_hint, self.output_buffer_size_hint);
+        self.create_instance_with_buffer_sizes(
+            first,
+            nonblocking,
+            overlapped,
+            role,
+            recv_mode,
+            buffer_sizes,
+        )
+    }
+    /// Like `create_instance()`, but with the given input and output buffer size hints instead of
+    /// the ones in the options.
+    pub(crate) fn create_instance_with_buffer_sizes(
+        &self,
+        first: bool,
+        nonblocking: bool,
+        overlapped: bool,
+        role: PipeStreamRole,
+        recv_mode: Option<PipeMode>,
+        (input_buffer_size, output_buffer_size): (u32, u32),
     ) -> io::Result<OwnedHandle> {
         if recv_mode == Some(PipeMode::Messages) && self.mode == PipeMode::Bytes {
             return Err(io::Error::new(
@@ -55,6 +76,7 @@ cannot create pipe server that has byte type but receives messages – have you
             self.security_descriptor.as_ref().map(|sd| sd.borrow()),
             self.inheritable,
         );
+        let sa_ptr = self.raw_security_attributes.map_or(sa.as_ptr(), |raw| raw.0);
 
         let max_instances = match self.instance_limit.map(NonZeroU8::get) {
             Some(255) => return Err(io::Error::new(
@@ -71,10 +93,10 @@ cannot create pipe server that has byte type but receives messages – have you
                 open_mode,
                 pipe_mode,
                 max_instances,
-                self.output_buffer_size_hint,
-                self.input_buffer_size_hint,
+                output_buffer_size,
+                input_buffer_size,
                 self.wait_timeout.to_raw(),
-                sa.as_ptr().cast_mut().cast(),
+                sa_ptr.cast_mut().cast(),
             )
             .handle_or_errno()
             .map(|h|
@@ -95,7 +117,7 @@ cannot create pipe server that has byte type but receives messages – have you
         if overlapped {
             open_mode |= FILE_FLAG_OVERLAPPED;
         }
-        open_mode
+        open_mode | self.extra_open_mode
     }
     fn pipe_mode(&self, recv_mode: Option<PipeMode>, nonblocking: bool) -> u32 {
         let mut pipe_mode = 0_u32;
@@ -107,6 +129,6 @@ cannot create pipe server that has byte type but receives messages – have you
         if !self.accept_remote {
             pipe_mode |= PIPE_REJECT_REMOTE_CLIENTS;
         }
-        pipe_mode
+        pipe_mode | self.extra_pipe_mode
     }
 }
@@ -4,12 +4,13 @@ use {
         os::windows::{
             named_pipe::{pipe_mode, PipeMode, WaitTimeout},
             path_conversion::*,
-            security_descriptor::SecurityDescriptor,
+            security_descriptor::{RawSecurityAttributes, SecurityDescriptor},
         },
         TryClone,
     },
     std::{borrow::Cow, num::NonZeroU8},
     widestring::{u16cstr, U16CStr},
+    windows_sys::Win32::Security::SECURITY_ATTRIBUTES,
 };
 
 /// Allows for thorough customization of [`PipeListener`]s during creation.
@@ -64,7 +65,8 @@ pub struct PipeListenerOptions<'path> {
     /// size to align it as required or clip it by the minimum or maximum buffer size.
     pub output_buffer_size_hint: u32,
     /// The default timeout clients use when connecting. Used unless another timeout is specified
-    /// when waiting by a client.
+    /// when waiting by a client, which clients created by Interprocess never do; they fail with
+    /// [`TimedOut`](io::ErrorKind::TimedOut) once it elapses without a free instance.
     pub wait_timeout: WaitTimeout,
     /// The security descriptor to create the named pipe server with.
     pub security_descriptor: Option<SecurityDescriptor>,
@@ -72,6 +74,31 @@ pub struct PipeListenerOptions<'path> {
     ///
     /// There is little to no reason for this to ever be `true`.
     pub inheritable: bool,
+    /// Additional flags to be ORed into the `dwOpenMode` parameter of
+    /// `CreateNamedPipeW`, on top of those derived from the other options.
+    ///
+    /// This is an escape hatch for uncommon configurations, such as `WRITE_DAC` or
+    /// `ACCESS_SYSTEM_SECURITY`. Flags that conflict with the ones Interprocess sets – most
+    /// notably `FILE_FLAG_OVERLAPPED`, as well as the pipe direction – will result in unexpected
+    /// behavior.
+    pub extra_open_mode: u32,
+    /// Additional flags to be ORed into the `dwPipeMode` parameter of
+    /// `CreateNamedPipeW`, on top of those derived from the other options.
+    ///
+    /// The same caveats as with [`extra_open_mode`](#structfield.extra_open_mode) apply.
+    pub extra_pipe_mode: u32,
+    /// Specifies how many instances of the pipe the Tokio listener keeps waiting for clients at
+    /// once. The default is 1.
+    ///
+    /// With only one instance, clients that connect while the previous one is being handed out
+    /// find no instance to connect to and have to wait for the next one to be created. Raising
+    /// this lets bursts of clients connect in parallel, each to an instance of its own, at the
+    /// cost of keeping that many idle instances around. If there is an
+    /// [instance limit](#structfield.instance_limit), it must leave room for them.
+    ///
+    /// Has no effect on the synchronous listener.
+    pub accept_parallelism: NonZeroU8,
+    pub(crate) raw_security_attributes: Option<RawSecurityAttributes>,
 }
 
 impl<'path> PipeListenerOptions<'path> {
@@ -90,6 +117,10 @@ impl<'path> PipeListenerOptions<'path> {
             wait_timeout: WaitTimeout::DEFAULT,
             security_descriptor: None,
             inheritable: false,
+            extra_open_mode: 0,
+            extra_pipe_mode: 0,
+            accept_parallelism: NonZeroU8::MIN,
+            raw_security_attributes: None,
         }
     }
     /// Clones configuration options which are not owned by value and returns a copy of the original
@@ -117,6 +148,10 @@ impl<'path> PipeListenerOptions<'path> {
                 .map(|sd| sd.try_clone())
                 .transpose()?,
             inheritable: self.inheritable,
+            extra_open_mode: self.extra_open_mode,
+            extra_pipe_mode: self.extra_pipe_mode,
+            accept_parallelism: self.accept_parallelism,
+            raw_security_attributes: self.raw_security_attributes,
         })
     }
 
@@ -137,6 +172,24 @@ impl<'path> PipeListenerOptions<'path> {
         wait_timeout: WaitTimeout,
         security_descriptor: Option<SecurityDescriptor>,
         inheritable: bool,
+        extra_open_mode: u32,
+        extra_pipe_mode: u32,
+        accept_parallelism: NonZeroU8,
+    }
+
+    /// Sets a raw `SECURITY_ATTRIBUTES` structure to create the named pipe server with,
+    /// overriding the [`security_descriptor`](#structfield.security_descriptor) and
+    /// [`inheritable`](#structfield.inheritable) options.
+    ///
+    /// # Safety
+    /// `sa` must point to a valid `SECURITY_ATTRIBUTES` structure, along with the security
+    /// descriptor it refers to, for as long as the options table, any of its copies and any
+    /// listeners created from them exist, since new instances of the named pipe are created with
+    /// it every time a client is accepted.
+    #[inline]
+    pub unsafe fn raw_security_attributes(mut self, sa: *const SECURITY_ATTRIBUTES) -> Self {
+        self.raw_security_attributes = Some(RawSecurityAttributes(sa));
+        self
     }
 
     /// Creates the pipe listener from the builder. The `Rm` and `Sm` generic arguments specify the
@@ -196,6 +249,10 @@ impl TryClone for PipeListenerOptions<'_> {
                 .map(|sd| sd.try_clone())
                 .transpose()?,
             inheritable: self.inheritable,
+            extra_open_mode: self.extra_open_mode,
+            extra_pipe_mode: self.extra_pipe_mode,
+            accept_parallelism: self.accept_parallelism,
+            raw_security_attributes: self.raw_security_attributes,
         })
     }
 }
@@ -0,0 +1,49 @@
+use {
+    super::PipeListener,
+    crate::os::windows::named_pipe::PipeModeTag,
+    ::polling::{os::iocp::PollerIocpExt, Event, PollMode, Poller},
+    std::io,
+};
+
+/// Integration with the [`polling`] crate.
+///
+/// Named pipes are not sockets, and thus cannot be polled for readiness the way `polling` polls
+/// sockets. What can be polled instead is the listener's [connect event](Self::connect_event),
+/// which `polling` supports as a waitable handle. Once it is signaled, the poller reports the
+/// listener as readable, and `.accept()` can be called without blocking. Streams have no such
+/// event and cannot be registered with a poller.
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "polling")))]
+impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
+    /// Registers the listener with the given poller, creating the connect event if necessary.
+    ///
+    /// # Errors
+    /// Fails if the connect event cannot be created, as described in the documentation of
+    /// [`.connect_event()`](Self::connect_event), or if registration itself fails.
+    ///
+    /// # Safety
+    /// The listener must be [removed](Self::remove_from_poller) from the poller before it is
+    /// dropped.
+    pub unsafe fn add_to_poller(
+        &self,
+        poller: &Poller,
+        key: usize,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        let event = self.connect_event()?;
+        unsafe { poller.add_waitable(event, Event::readable(key), mode) }
+    }
+    /// Changes the key or mode the listener is registered with. In oneshot mode, this is also how
+    /// the registration is re-armed after the listener has been reported as readable.
+    pub fn modify_in_poller(
+        &self,
+        poller: &Poller,
+        key: usize,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        poller.modify_waitable(self.connect_event()?, Event::readable(key), mode)
+    }
+    /// Removes the listener from the given poller.
+    pub fn remove_from_poller(&self, poller: &Poller) -> io::Result<()> {
+        poller.remove_waitable(self.connect_event()?)
+    }
+}
@@ -2,8 +2,9 @@ use {
     super::stream::Stream,
     crate::{
         local_socket::{
+            audit::{self, Direction},
             traits::{self, ListenerNonblockingMode, Stream as _},
-            ListenerOptions, NameInner,
+            ListenerOptions, ListenerStats, Name, NameInner, StatsCounters,
         },
         os::windows::named_pipe::{pipe_mode::Bytes, PipeListener, PipeListenerOptions},
         AtomicEnum, Sealed,
@@ -11,6 +12,31 @@ use {
     std::{io, iter::FusedIterator, os::windows::prelude::*, sync::atomic::Ordering::SeqCst},
 };
 
+#[cfg(feature = "handshake")]
+use {
+    crate::{
+        local_socket::handshake::{self, PendingHandshake, SharedSecret},
+        os::windows::winprelude::*,
+        OrErrno,
+    },
+    std::{
+        ffi::c_void,
+        io::prelude::*,
+        sync::atomic::AtomicBool,
+        time::Duration,
+    },
+    windows_sys::Win32::{
+        Foundation::BOOLEAN,
+        System::{
+            Threading::{
+                CreateTimerQueueTimer, DeleteTimerQueueTimer, GetCurrentThreadId, OpenThread,
+                THREAD_TERMINATE, WT_EXECUTEDEFAULT,
+            },
+            IO::CancelSynchronousIo,
+        },
+    },
+};
+
 type ListenerImpl = PipeListener<Bytes, Bytes>;
 
 /// Wrapper around [`PipeListener`] that implements
@@ -18,42 +44,117 @@ type ListenerImpl = PipeListener<Bytes, Bytes>;
 #[derive(Debug)]
 pub struct Listener {
     listener: ListenerImpl,
+    name: Name<'static>,
     nonblocking: AtomicEnum<ListenerNonblockingMode>,
+    #[cfg(feature = "handshake")]
+    shared_secret: Option<SharedSecret>,
+    #[cfg(feature = "handshake")]
+    handshake_timeout: Duration,
+    stats: StatsCounters,
 }
 impl Sealed for Listener {}
+impl Listener {
+    /// Borrows the underlying named pipe listener.
+    #[inline]
+    pub fn pipe_listener(&self) -> &PipeListener<Bytes, Bytes> { &self.listener }
+    /// Disconnects the client of a stream that was accepted from this listener, and keeps the
+    /// pipe instance for a later client. See [`PipeListener::recycle()`].
+    #[inline]
+    pub fn recycle(&self, stream: Stream) -> io::Result<()> { self.listener.recycle(stream.0) }
+
+    fn accept_uncounted(&self) -> io::Result<Stream> {
+        let stream = self.accept_unauthenticated()?;
+        #[cfg(feature = "handshake")]
+        if let Some(secret) = &self.shared_secret {
+            authenticate_within(&stream, secret, self.handshake_timeout)?;
+            if self.nonblocking.load(SeqCst).stream_nonblocking() {
+                stream.set_nonblocking(true)?;
+            }
+        }
+        Ok(stream)
+    }
+    /// Accepts a connection, leaving the stream in blocking mode if the handshake has yet to be
+    /// performed over it.
+    fn accept_unauthenticated(&self) -> io::Result<Stream> {
+        use ListenerNonblockingMode as LNM;
+        let stream = self.listener.accept().map(Stream::from)?;
+        #[cfg(feature = "handshake")]
+        if self.shared_secret.is_some() {
+            stream.set_nonblocking(false)?;
+            return Ok(stream);
+        }
+        // TODO(2.3.0) verify necessity of orderings
+        let nonblocking = self.nonblocking.load(SeqCst);
+        if matches!(nonblocking, LNM::Accept) {
+            stream.set_nonblocking(false)?;
+        } else if matches!(nonblocking, LNM::Stream) {
+            stream.set_nonblocking(true)?;
+        }
+        Ok(stream)
+    }
+    #[cfg(feature = "handshake")]
+    pub(crate) fn accept_pending(&self) -> io::Result<PendingHandshake> {
+        let rslt = self.accept_unauthenticated();
+        self.stats.record(&rslt);
+        audit::record(Direction::Accept, Some(self.name.borrow()), &rslt);
+        let stream = rslt?;
+        Ok(match &self.shared_secret {
+            Some(secret) => PendingHandshake::new(
+                stream,
+                secret.clone(),
+                self.handshake_timeout,
+                self.nonblocking.load(SeqCst).stream_nonblocking(),
+            ),
+            None => PendingHandshake::ready(stream),
+        })
+    }
+}
 
 impl traits::Listener for Listener {
     type Stream = Stream;
 
     fn from_options(options: ListenerOptions<'_>) -> io::Result<Self> {
         let mut impl_options = PipeListenerOptions::new();
+        let name = options.name.borrow().into_owned();
         let NameInner::NamedPipe(path) = options.name.0;
         impl_options.path = path;
         impl_options.nonblocking = options.nonblocking.accept_nonblocking();
         impl_options.security_descriptor = options.security_descriptor;
+        impl_options.extra_open_mode = options.extra_open_mode;
+        impl_options.extra_pipe_mode = options.extra_pipe_mode;
+        impl_options.wait_timeout = options.wait_timeout;
+        impl_options.raw_security_attributes = options.raw_security_attributes;
 
         Ok(Self {
             listener: impl_options.create()?,
+            name,
             nonblocking: AtomicEnum::new(options.nonblocking),
+            #[cfg(feature = "handshake")]
+            shared_secret: options.shared_secret,
+            #[cfg(feature = "handshake")]
+            handshake_timeout: options.handshake_timeout,
+            stats: StatsCounters::default(),
         })
     }
     fn accept(&self) -> io::Result<Stream> {
-        use ListenerNonblockingMode as LNM;
-        let stream = self.listener.accept().map(Stream)?;
-        // TODO(2.3.0) verify necessity of orderings
-        let nonblocking = self.nonblocking.load(SeqCst);
-        if matches!(nonblocking, LNM::Accept) {
-            stream.set_nonblocking(false)?;
-        } else if matches!(nonblocking, LNM::Stream) {
-            stream.set_nonblocking(true)?;
-        }
-        Ok(stream)
+        let rslt = self.accept_uncounted();
+        self.stats.record(&rslt);
+        audit::record(Direction::Accept, Some(self.name.borrow()), &rslt);
+        rslt
     }
     fn set_nonblocking(&self, nonblocking: ListenerNonblockingMode) -> io::Result<()> {
         self.listener.set_nonblocking(nonblocking.accept_nonblocking())?;
         self.nonblocking.store(nonblocking, SeqCst);
         Ok(())
     }
+    #[inline]
+    fn local_name(&self) -> Option<Name<'_>> { Some(self.name.borrow()) }
+    #[inline]
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+        self.listener.set_inheritable(inheritable)
+    }
+    #[inline]
+    fn stats(&self) -> ListenerStats { self.stats.snapshot() }
     fn do_not_reclaim_name_on_drop(&mut self) {}
 }
 impl Iterator for Listener {
@@ -67,3 +168,114 @@ impl From<Listener> for OwnedHandle {
     #[inline]
     fn from(l: Listener) -> Self { l.listener.into() }
 }
+
+/// Performs the server side of the handshake, giving up once the timeout has passed.
+#[cfg(feature = "handshake")]
+pub(crate) fn authenticate_within(
+    stream: &Stream,
+    secret: &SharedSecret,
+    timeout: Duration,
+) -> io::Result<()> {
+    let watchdog = Watchdog::start(timeout)?;
+    handshake::authenticate_server(&mut Guarded { stream, watchdog: &watchdog }, secret)
+}
+
+/// How often the watchdog repeats its cancellation request once the timeout has expired.
+#[cfg(feature = "handshake")]
+const WATCHDOG_PERIOD: Duration = Duration::from_millis(10);
+
+/// A timer which cancels the synchronous I/O of the thread that started it once the timeout
+/// expires, since reads from and writes to pipe instances that aren't overlapped cannot time
+/// out by themselves.
+///
+/// A cancellation request that arrives between two operations finds nothing to cancel, so the
+/// timer keeps repeating it until it's dropped, and operations check whether it has expired
+/// before they start.
+#[cfg(feature = "handshake")]
+struct Watchdog {
+    timer: HANDLE,
+    state: Box<WatchdogState>,
+}
+#[cfg(feature = "handshake")]
+struct WatchdogState {
+    thread: OwnedHandle,
+    expired: AtomicBool,
+}
+#[cfg(feature = "handshake")]
+impl Watchdog {
+    fn start(timeout: Duration) -> io::Result<Self> {
+        let thread = unsafe { OpenThread(THREAD_TERMINATE, 0, GetCurrentThreadId()) };
+        let thread = (thread != 0).true_val_or_errno(thread)?;
+        // SAFETY: we just opened this handle
+        let thread = unsafe { OwnedHandle::from_raw_handle(thread.to_std()) };
+        let state = Box::new(WatchdogState { thread, expired: AtomicBool::new(false) });
+        let due = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX).max(1);
+        let period = u32::try_from(WATCHDOG_PERIOD.as_millis()).unwrap_or(1);
+        let param: *const WatchdogState = &*state;
+        let mut timer = INVALID_HANDLE_VALUE;
+        unsafe {
+            CreateTimerQueueTimer(
+                &mut timer,
+                0,
+                Some(expire),
+                param.cast(),
+                due,
+                period,
+                WT_EXECUTEDEFAULT,
+            )
+        }
+        .true_val_or_errno(())?;
+        Ok(Self { timer, state })
+    }
+    fn expired(&self) -> bool { self.state.expired.load(SeqCst) }
+}
+#[cfg(feature = "handshake")]
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        // Waits for callbacks that are already running to return, which is what makes it safe
+        // to free the state afterwards.
+        unsafe { DeleteTimerQueueTimer(0, self.timer, INVALID_HANDLE_VALUE) };
+    }
+}
+#[cfg(feature = "handshake")]
+unsafe extern "system" fn expire(param: *mut c_void, _: BOOLEAN) {
+    // SAFETY: the state outlives the timer, as per the drop order of Watchdog
+    let state = unsafe { &*param.cast_const().cast::<WatchdogState>() };
+    state.expired.store(true, SeqCst);
+    unsafe { CancelSynchronousIo(state.thread.as_int_handle()) };
+}
+
+/// Reads from and writes to the stream under the watch of a [`Watchdog`].
+#[cfg(feature = "handshake")]
+struct Guarded<'a> {
+    stream: &'a Stream,
+    watchdog: &'a Watchdog,
+}
+#[cfg(feature = "handshake")]
+impl Guarded<'_> {
+    fn check<T>(&self, op: impl FnOnce(&Stream) -> io::Result<T>) -> io::Result<T> {
+        if self.watchdog.expired() {
+            return Err(handshake_timed_out());
+        }
+        let expired = || self.watchdog.expired();
+        op(self.stream).map_err(|e| if expired() { handshake_timed_out() } else { e })
+    }
+}
+#[cfg(feature = "handshake")]
+impl Read for Guarded<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.check(|mut stream| stream.read(buf))
+    }
+}
+#[cfg(feature = "handshake")]
+impl Write for Guarded<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.check(|mut stream| stream.write(buf))
+    }
+    fn flush(&mut self) -> io::Result<()> { self.check(|mut stream| stream.flush()) }
+}
+
+#[cfg(feature = "handshake")]
+fn handshake_timed_out() -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, "client did not complete the handshake in time")
+}
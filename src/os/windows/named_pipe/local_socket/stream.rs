@@ -3,17 +3,23 @@ use {
         error::{FromHandleError, ReuniteError},
         local_socket::{
             traits::{self, ReuniteResult},
-            Name, NameInner,
+            ConnectOptions, FlushMode, Name, NameInner, PeerCredentials,
         },
         os::windows::named_pipe::{
-            pipe_mode::Bytes, DuplexPipeStream, RecvPipeStream, SendPipeStream,
+            c_wrappers, pipe_mode::Bytes, DuplexPipeStream, PipeInfo, RecvPipeStream,
+            SendPipeStream,
         },
-        Sealed,
+        AtomicEnum, Sealed, SubUsizeExt, TryClone,
     },
     std::{
+        borrow::Cow,
         io::{self, Write},
-        os::windows::io::OwnedHandle,
+        mem::MaybeUninit,
+        os::windows::io::{AsHandle, BorrowedHandle, OwnedHandle},
+        sync::atomic::Ordering::SeqCst,
     },
+    widestring::U16CString,
+    windows_sys::Win32::System::Pipes,
 };
 
 type StreamImpl = DuplexPipeStream<Bytes>;
@@ -23,16 +29,16 @@ type SendHalfImpl = SendPipeStream<Bytes>;
 /// Wrapper around [`DuplexPipeStream`] that implements
 /// [`Stream`](crate::local_socket::traits::Stream).
 #[derive(Debug)]
-pub struct Stream(pub(super) StreamImpl);
+pub struct Stream(pub(super) StreamImpl, AtomicEnum<FlushMode>);
 
 impl Sealed for Stream {}
 impl traits::Stream for Stream {
     type RecvHalf = RecvHalf;
     type SendHalf = SendHalf;
 
-    fn connect(name: Name<'_>) -> io::Result<Self> {
-        let NameInner::NamedPipe(path) = name.0;
-        StreamImpl::connect_by_path(path).map(Self)
+    fn from_options(options: &ConnectOptions<'_>) -> io::Result<Self> {
+        let NameInner::NamedPipe(path) = &options.name.0;
+        StreamImpl::connect_by_path(&**path).map(Self::from)
     }
 
     #[inline]
@@ -45,25 +51,130 @@ impl traits::Stream for Stream {
         (RecvHalf(rh), SendHalf(sh))
     }
     fn reunite(rh: RecvHalf, sh: SendHalf) -> ReuniteResult<Self> {
-        StreamImpl::reunite(rh.0, sh.0).map(Self).map_err(|ReuniteError { rh, sh }| {
+        StreamImpl::reunite(rh.0, sh.0).map(Self::from).map_err(|ReuniteError { rh, sh }| {
             ReuniteError { rh: RecvHalf(rh), sh: SendHalf(sh) }
         })
     }
 }
 
-/// Flushing fails with [`Unsupported`](io::ErrorKind::Unsupported).
+/// A connection attempt that found all instances of the pipe busy.
+#[derive(Debug)]
+pub(crate) struct Connecting(U16CString);
+impl Connecting {
+    pub(crate) fn start(name: Name<'_>) -> io::Result<Result<Stream, Self>> {
+        let NameInner::NamedPipe(path) = name.0;
+        Self(path.into_owned()).finish()
+    }
+    pub(crate) fn finish(self) -> io::Result<Result<Stream, Self>> {
+        match StreamImpl::try_connect_by_path(&self.0) {
+            Ok(stream) => {
+                stream.set_nonblocking(true)?;
+                Ok(Ok(Stream::from(stream)))
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(Err(self)),
+            Err(e) => Err(e),
+        }
+    }
+    /// There is never anything to wait on, since all instances of the pipe were busy.
+    #[inline]
+    pub(crate) fn must_retry(&self) -> bool { true }
+}
+
+impl traits::StreamCommon for Stream {
+    fn peer_name(&self) -> Option<Name<'static>> {
+        let path = c_wrappers::get_path(self.0.as_handle()).ok()?;
+        Some(Name(NameInner::NamedPipe(Cow::Owned(path))))
+    }
+    #[inline]
+    fn peer_credentials(&self) -> io::Result<PeerCredentials> {
+        peer_credentials(self.0.as_handle())
+    }
+    #[inline]
+    fn send_buffer_size(&self) -> io::Result<usize> { buffer_size(self.0.as_handle(), true) }
+    #[inline]
+    fn recv_buffer_size(&self) -> io::Result<usize> { buffer_size(self.0.as_handle(), false) }
+    #[inline]
+    fn set_send_buffer_size(&self, _: usize) -> io::Result<()> { Err(fixed_buffer_size()) }
+    #[inline]
+    fn set_recv_buffer_size(&self, _: usize) -> io::Result<()> { Err(fixed_buffer_size()) }
+    #[inline]
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+        crate::os::windows::c_wrappers::set_inheritable(self.0.as_handle(), inheritable)
+    }
+}
+
+impl Stream {
+    /// Same as `.read()` from the [`Read`](io::Read) trait, but accepts an uninitialized buffer.
+    #[inline]
+    pub fn read_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+        self.0.read_to_uninit(buf)
+    }
+    /// Sets what flushing does, as described in [`FlushMode`].
+    #[inline]
+    pub fn set_flush_mode(&self, mode: FlushMode) { self.1.store(mode, SeqCst) }
+    /// Returns what flushing does, as set by [`.set_flush_mode()`](Self::set_flush_mode).
+    #[inline]
+    pub fn flush_mode(&self) -> FlushMode { self.1.load(SeqCst) }
+}
+
+/// Looks up the size of the buffer for the given direction, keeping in mind that
+/// `GetNamedPipeInfo` reports it from the server's perspective.
+pub(super) fn buffer_size(handle: BorrowedHandle<'_>, send: bool) -> io::Result<usize> {
+    let info = PipeInfo::query(handle)?;
+    let size =
+        if info.is_server == send { info.output_buffer_size } else { info.input_buffer_size };
+    Ok(size.to_usize())
+}
+/// Looks up the process ID of the other end of the connection.
+pub(super) fn peer_credentials(handle: BorrowedHandle<'_>) -> io::Result<PeerCredentials> {
+    let f = if PipeInfo::query(handle)?.is_server {
+        Pipes::GetNamedPipeClientProcessId
+    } else {
+        Pipes::GetNamedPipeServerProcessId
+    };
+    let pid = unsafe { c_wrappers::hget(handle, f) }?;
+    Ok(PeerCredentials { pid: Some(pid), ..Default::default() })
+}
+pub(super) fn fixed_buffer_size() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "named pipe buffer sizes cannot be changed after creation",
+    )
+}
+
+/// Flushing behaves according to the stream's [`FlushMode`].
 impl Write for &Stream {
     #[inline]
-    fn write(&mut self, buf: &[u8]) -> io::Result<usize> { (&self.0).write(buf) }
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = (&self.0).write(buf)?;
+        self.sent();
+        Ok(n)
+    }
     #[inline]
     fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
-        (&self.0).write_vectored(bufs)
+        let n = (&self.0).write_vectored(bufs)?;
+        self.sent();
+        Ok(n)
     }
 
     #[inline]
-    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    fn flush(&mut self) -> io::Result<()> {
+        match self.flush_mode() {
+            FlushMode::Os => self.0.flush(),
+            FlushMode::NoOp | FlushMode::OnClose => Ok(()),
+        }
+    }
     // FUTURE is_write_vectored
 }
+impl Stream {
+    /// Keeps the pipe from being flushed on drop if the flush mode says so.
+    #[inline]
+    fn sent(&self) {
+        if self.flush_mode() == FlushMode::NoOp {
+            self.0.assume_flushed();
+        }
+    }
+}
 
 impl From<Stream> for OwnedHandle {
     fn from(s: Stream) -> Self {
@@ -78,7 +189,7 @@ impl TryFrom<OwnedHandle> for Stream {
 
     fn try_from(handle: OwnedHandle) -> Result<Self, Self::Error> {
         match StreamImpl::try_from(handle) {
-            Ok(s) => Ok(Self(s)),
+            Ok(s) => Ok(Self::from(s)),
             Err(e) => Err(FromHandleError {
                 details: Default::default(),
                 cause: Some(e.details.into()),
@@ -94,14 +205,34 @@ multimacro! {
     forward_sync_read,
     forward_sync_ref_read,
     forward_as_handle,
-    forward_try_clone,
     derive_sync_mut_write,
-    derive_trivial_conv(StreamImpl),
+}
+/// The clone starts out with the same flush mode.
+impl TryClone for Stream {
+    #[inline]
+    fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self(self.0.try_clone()?, AtomicEnum::new(self.flush_mode())))
+    }
+}
+impl From<StreamImpl> for Stream {
+    #[inline]
+    fn from(stream: StreamImpl) -> Self { Self(stream, AtomicEnum::new(FlushMode::default())) }
+}
+impl From<Stream> for StreamImpl {
+    #[inline]
+    fn from(stream: Stream) -> Self { stream.0 }
 }
 
 /// Wrapper around [`RecvPipeStream`] that implements
 /// [`RecvHalf`](crate::local_socket::traits::RecvHalf).
 pub struct RecvHalf(pub(super) RecvHalfImpl);
+impl RecvHalf {
+    /// Same as `.read()` from the [`Read`](io::Read) trait, but accepts an uninitialized buffer.
+    #[inline]
+    pub fn read_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+        self.0.read_to_uninit(buf)
+    }
+}
 multimacro! {
     RecvHalf,
     forward_rbv(RecvHalfImpl, &),
@@ -1,7 +1,11 @@
 use {
     super::Stream,
     crate::{
-        local_socket::{traits::tokio as traits, ListenerOptions, NameInner},
+        local_socket::{
+            audit::{self, Direction},
+            traits::tokio as traits, ListenerOptions, ListenerStats, Name, NameInner,
+            StatsCounters,
+        },
         os::windows::named_pipe::{
             pipe_mode,
             tokio::{PipeListener as GenericPipeListener, PipeListenerOptionsExt as _},
@@ -15,21 +19,40 @@ use {
 type PipeListener = GenericPipeListener<pipe_mode::Bytes, pipe_mode::Bytes>;
 
 #[derive(Debug)]
-pub struct Listener(PipeListener);
+pub struct Listener {
+    listener: PipeListener,
+    name: Name<'static>,
+    stats: StatsCounters,
+}
 impl Sealed for Listener {}
 impl traits::Listener for Listener {
     type Stream = Stream;
 
     fn from_options(options: ListenerOptions<'_>) -> io::Result<Self> {
         let mut impl_options = PipeListenerOptions::new();
+        let name = options.name.borrow().into_owned();
         let NameInner::NamedPipe(path) = options.name.0;
         impl_options.path = path;
         impl_options.security_descriptor = options.security_descriptor;
-        impl_options.create_tokio().map(Self)
+        impl_options.extra_open_mode = options.extra_open_mode;
+        impl_options.extra_pipe_mode = options.extra_pipe_mode;
+        impl_options.wait_timeout = options.wait_timeout;
+        impl_options.raw_security_attributes = options.raw_security_attributes;
+        Ok(Self { listener: impl_options.create_tokio()?, name, stats: StatsCounters::default() })
     }
     async fn accept(&self) -> io::Result<Stream> {
-        let inner = self.0.accept().await?;
-        Ok(Stream(inner))
+        let rslt = self.listener.accept().await.map(Stream::from);
+        self.stats.record(&rslt);
+        audit::record(Direction::Accept, Some(self.name.borrow()), &rslt);
+        rslt
+    }
+    #[inline]
+    fn local_name(&self) -> Option<Name<'_>> { Some(self.name.borrow()) }
+    #[inline]
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+        self.listener.set_inheritable(inheritable)
     }
+    #[inline]
+    fn stats(&self) -> ListenerStats { self.stats.snapshot() }
     fn do_not_reclaim_name_on_drop(&mut self) {}
 }
@@ -1,23 +1,33 @@
 use {
+    super::super::stream::{buffer_size, fixed_buffer_size, peer_credentials},
     crate::{
-        error::{FromHandleError, ReuniteError},
+        error::{ConversionError, FromHandleError, ReuniteError},
         local_socket::{
-            traits::tokio::{self as traits, ReuniteResult},
-            Name, NameInner,
+            traits::{
+                tokio::{self as traits, ReuniteResult},
+                StreamCommon,
+            },
+            tokio::Deadlines,
+            ConnectOptions, Name, NameInner, PeerCredentials,
         },
         os::windows::named_pipe::{
+            c_wrappers,
             pipe_mode::Bytes,
             tokio::{DuplexPipeStream, RecvPipeStream, SendPipeStream},
         },
         Sealed,
     },
     std::{
-        io,
+        borrow::Cow,
+        io::{self, IoSliceMut},
         os::windows::prelude::*,
         pin::Pin,
         task::{Context, Poll},
     },
-    tokio::io::AsyncWrite,
+    tokio::{
+        io::{AsyncRead, AsyncWrite, Interest, ReadBuf, Ready},
+        net::windows::named_pipe::{NamedPipeClient, NamedPipeServer},
+    },
 };
 
 type StreamImpl = DuplexPipeStream<Bytes>;
@@ -25,15 +35,39 @@ type RecvHalfImpl = RecvPipeStream<Bytes>;
 type SendHalfImpl = SendPipeStream<Bytes>;
 
 #[derive(Debug)]
-pub struct Stream(pub(super) StreamImpl);
+pub struct Stream(pub(super) StreamImpl, Deadlines);
 impl Sealed for Stream {}
+impl Stream {
+    #[inline]
+    pub(crate) fn deadlines(&self) -> &Deadlines { &self.1 }
+    #[inline]
+    pub(crate) async fn ready(&self, interest: Interest) -> io::Result<Ready> {
+        self.0.ready(interest).await
+    }
+    #[inline]
+    pub(crate) fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> { self.0.try_read(buf) }
+    #[inline]
+    pub(crate) fn try_read_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.0.try_read_vectored(bufs)
+    }
+    #[inline]
+    pub(crate) fn try_write(&self, buf: &[u8]) -> io::Result<usize> { self.0.try_write(buf) }
+    /// Waits for the client to receive everything that has been sent, after which the pipe can
+    /// be closed without anything being lost. Named pipes have no notion of half-closing, so
+    /// this is as close as it gets to an acknowledgement of end of file.
+    pub(crate) async fn close(self) -> io::Result<()> {
+        self.0.flush().await?;
+        self.0.evade_limbo();
+        Ok(())
+    }
+}
 impl traits::Stream for Stream {
     type RecvHalf = RecvHalf;
     type SendHalf = SendHalf;
 
-    async fn connect(name: Name<'_>) -> io::Result<Self> {
-        let NameInner::NamedPipe(path) = name.0;
-        StreamImpl::connect_by_path(path).await.map(Self)
+    async fn from_options(options: &ConnectOptions<'_>) -> io::Result<Self> {
+        let NameInner::NamedPipe(path) = &options.name.0;
+        StreamImpl::connect_by_path(&**path).await.map(Self::from)
     }
     #[inline]
     fn split(self) -> (RecvHalf, SendHalf) {
@@ -42,12 +76,45 @@ impl traits::Stream for Stream {
     }
     #[inline]
     fn reunite(rh: RecvHalf, sh: SendHalf) -> ReuniteResult<Self> {
-        StreamImpl::reunite(rh.0, sh.0).map(Self).map_err(|ReuniteError { rh, sh }| {
+        StreamImpl::reunite(rh.0, sh.0).map(Self::from).map_err(|ReuniteError { rh, sh }| {
             ReuniteError { rh: RecvHalf(rh), sh: SendHalf(sh) }
         })
     }
 }
+impl StreamCommon for Stream {
+    fn peer_name(&self) -> Option<Name<'static>> {
+        let path = c_wrappers::get_path(self.0.as_handle()).ok()?;
+        Some(Name(NameInner::NamedPipe(Cow::Owned(path))))
+    }
+    #[inline]
+    fn peer_credentials(&self) -> io::Result<PeerCredentials> {
+        peer_credentials(self.0.as_handle())
+    }
+    #[inline]
+    fn send_buffer_size(&self) -> io::Result<usize> { buffer_size(self.0.as_handle(), true) }
+    #[inline]
+    fn recv_buffer_size(&self) -> io::Result<usize> { buffer_size(self.0.as_handle(), false) }
+    #[inline]
+    fn set_send_buffer_size(&self, _: usize) -> io::Result<()> { Err(fixed_buffer_size()) }
+    #[inline]
+    fn set_recv_buffer_size(&self, _: usize) -> io::Result<()> { Err(fixed_buffer_size()) }
+    #[inline]
+    fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+        crate::os::windows::c_wrappers::set_inheritable(self.0.as_handle(), inheritable)
+    }
+}
 
+impl AsyncRead for &Stream {
+    #[inline]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.1.read.check(cx)?;
+        Pin::new(&mut &self.get_mut().0).poll_read(cx, buf)
+    }
+}
 impl AsyncWrite for &Stream {
     #[inline]
     fn poll_write(
@@ -55,6 +122,7 @@ impl AsyncWrite for &Stream {
         cx: &mut Context<'_>,
         buf: &[u8],
     ) -> Poll<io::Result<usize>> {
+        self.1.write.check(cx)?;
         Pin::new(&mut &self.get_mut().0).poll_write(cx, buf)
     }
     #[inline]
@@ -72,7 +140,7 @@ impl TryFrom<OwnedHandle> for Stream {
 
     fn try_from(handle: OwnedHandle) -> Result<Self, Self::Error> {
         match StreamImpl::try_from(handle) {
-            Ok(s) => Ok(Self(s)),
+            Ok(s) => Ok(Self::from(s)),
             Err(e) => Err(FromHandleError {
                 details: Default::default(),
                 cause: Some(e.details.into()),
@@ -82,15 +150,44 @@ impl TryFrom<OwnedHandle> for Stream {
     }
 }
 
+impl From<NamedPipeServer> for Stream {
+    #[inline]
+    fn from(server: NamedPipeServer) -> Self { Self::from(StreamImpl::from(server)) }
+}
+impl From<NamedPipeClient> for Stream {
+    #[inline]
+    fn from(client: NamedPipeClient) -> Self { Self::from(StreamImpl::from(client)) }
+}
+impl TryFrom<Stream> for NamedPipeServer {
+    type Error = ConversionError<Stream>;
+    #[inline]
+    fn try_from(slf: Stream) -> Result<Self, Self::Error> {
+        Self::try_from(slf.0).map_err(|e| e.map_source(Stream::from))
+    }
+}
+impl TryFrom<Stream> for NamedPipeClient {
+    type Error = ConversionError<Stream>;
+    #[inline]
+    fn try_from(slf: Stream) -> Result<Self, Self::Error> {
+        Self::try_from(slf.0).map_err(|e| e.map_source(Stream::from))
+    }
+}
+
 multimacro! {
     Stream,
     pinproj_for_unpin(StreamImpl),
     forward_rbv(StreamImpl, &),
-    forward_tokio_read,
-    forward_tokio_ref_read,
+    derive_tokio_mut_read,
     forward_as_handle,
     derive_tokio_mut_write,
-    derive_trivial_conv(StreamImpl),
+}
+impl From<StreamImpl> for Stream {
+    #[inline]
+    fn from(stream: StreamImpl) -> Self { Self(stream, Deadlines::default()) }
+}
+impl From<Stream> for StreamImpl {
+    #[inline]
+    fn from(stream: Stream) -> Self { stream.0 }
 }
 
 pub struct RecvHalf(pub(super) RecvHalfImpl);
@@ -0,0 +1,349 @@
+//! Runtime-agnostic overlapped I/O on named pipes, driven by a user-provided I/O completion port.
+//!
+//! This is a lower-level alternative to the Tokio integration for applications that run their own
+//! event loop, such as custom executors and game engines. The workflow is as follows:
+//! 1. Create named pipe handles for overlapped I/O with [`create_server()`] and [`connect()`].
+//! 2. [Associate](CompletionPort::associate) them with a [`CompletionPort`], picking a
+//!    completion key that identifies the handle.
+//! 3. Start [`Operation`]s on them, keeping track of their [IDs](Operation::id).
+//! 4. [Wait](CompletionPort::wait) for [`Completion`]s and
+//!    [finish](Operation::try_finish) the operations they refer to.
+//!
+//! Operations own their buffers and `OVERLAPPED` structures for as long as the OS might access
+//! them. Dropping an operation that is still in progress cancels it and blocks until the
+//! cancellation goes through; a completion packet for it is still posted to the port, and should
+//! be ignored. Generally, completions with unknown operation IDs are to be ignored.
+
+use {
+    super::{c_wrappers, PipeListenerOptions, PipeMode, PipeStreamRole},
+    crate::{
+        os::windows::{path_conversion::*, winprelude::*},
+        OrErrno, RawOsErrorExt, SubUsizeExt,
+    },
+    std::{
+        borrow::Cow,
+        fmt::{self, Debug, Formatter},
+        io, mem, ptr,
+        time::Duration,
+    },
+    windows_sys::Win32::{
+        Foundation::{ERROR_IO_INCOMPLETE, ERROR_IO_PENDING, ERROR_PIPE_CONNECTED},
+        Storage::FileSystem::{ReadFile, WriteFile},
+        System::{
+            Pipes::ConnectNamedPipe,
+            Threading::{CreateEventW, INFINITE},
+            IO::{
+                CancelIoEx, CreateIoCompletionPort, GetOverlappedResult,
+                GetQueuedCompletionStatus, PostQueuedCompletionStatus, OVERLAPPED,
+            },
+        },
+    },
+};
+
+/// Creates an instance of a named pipe server for overlapped I/O, using the given options.
+///
+/// `first` sets the `FILE_FLAG_FIRST_PIPE_INSTANCE` flag, which makes creation fail if the pipe
+/// already exists. The [`nonblocking`](PipeListenerOptions::nonblocking) option is ignored, since
+/// overlapped I/O doesn't block to begin with. Use [`Operation::connect()`] to wait for a client.
+pub fn create_server(
+    options: &PipeListenerOptions<'_>,
+    first: bool,
+) -> io::Result<OwnedHandle> {
+    let role = PipeStreamRole::RecverAndSender;
+    options.create_instance(first, false, true, role, Some(options.mode))
+}
+
+/// Opens the client end of a named pipe for overlapped I/O in byte read mode. The
+/// `\\<hostname>\pipe\` prefix is not added automatically.
+///
+/// If all instances of the server are busy, [`WouldBlock`](io::ErrorKind::WouldBlock) is returned
+/// instead of waiting.
+pub fn connect<'s>(path: impl ToWtf16<'s>) -> io::Result<OwnedHandle> {
+    let path = path.to_wtf_16().map(Cow::into_owned).map_err(to_io_error)?;
+    let mode = Some(PipeMode::Bytes);
+    c_wrappers::connect_without_waiting(&path, mode, mode, true).map(OwnedHandle::from)
+}
+
+/// An I/O completion port, which receives notifications about finished overlapped operations on
+/// the handles associated with it.
+#[derive(Debug)]
+pub struct CompletionPort(OwnedHandle);
+impl CompletionPort {
+    /// Creates a new completion port.
+    ///
+    /// `concurrency` is the maximum number of threads that the OS allows to process completions
+    /// concurrently, with 0 meaning as many as there are processors.
+    pub fn new(concurrency: u32) -> io::Result<Self> {
+        let port = unsafe { CreateIoCompletionPort(INVALID_HANDLE_VALUE, 0, 0, concurrency) };
+        (port != 0).true_or_errno(|| {
+            // SAFETY: we just created this handle
+            Self(unsafe { OwnedHandle::from_raw_handle(port.to_std()) })
+        })
+    }
+
+    /// Associates a handle with the completion port, making completions of overlapped operations
+    /// on it get posted to the port with the given completion key.
+    ///
+    /// A handle can only be associated with one completion port, and the association cannot be
+    /// undone.
+    pub fn associate(&self, handle: BorrowedHandle<'_>, key: usize) -> io::Result<()> {
+        let port = self.0.as_int_handle();
+        let rslt = unsafe { CreateIoCompletionPort(handle.as_int_handle(), port, key, 0) };
+        (rslt != 0).true_val_or_errno(())
+    }
+
+    /// Waits for a completion to be posted to the port, with an optional timeout.
+    ///
+    /// If the timeout elapses, an error of kind [`TimedOut`](io::ErrorKind::TimedOut) is
+    /// returned.
+    pub fn wait(&self, timeout: Option<Duration>) -> io::Result<Completion> {
+        let timeout_ms = timeout.map_or(INFINITE, |t| {
+            u32::try_from(t.as_millis()).unwrap_or(INFINITE.saturating_sub(1))
+        });
+        let (mut bytes, mut key, mut ov) = (0_u32, 0_usize, ptr::null_mut::<OVERLAPPED>());
+        let port = self.0.as_int_handle();
+        let success =
+            unsafe { GetQueuedCompletionStatus(port, &mut bytes, &mut key, &mut ov, timeout_ms) }
+                != 0;
+        let error = match (ov.is_null(), (!success).then(io::Error::last_os_error)) {
+            // Either the wait itself failed or it timed out; no operation is involved.
+            (true, Some(e)) => return Err(e),
+            (_, error) => error,
+        };
+        let op = OperationId(ov.cast_const().cast());
+        Ok(Completion { key, bytes: bytes.to_usize(), op, error })
+    }
+
+    /// Posts a completion packet with the given key and no associated operation to the port,
+    /// which can be used to wake up a thread waiting on it.
+    ///
+    /// Such packets cause [`.wait()`](Self::wait) to return a [`Completion`] with a null
+    /// [operation ID](Completion::operation), which is never returned by [`Operation::id()`].
+    pub fn post(&self, key: usize) -> io::Result<()> {
+        let rslt =
+            unsafe { PostQueuedCompletionStatus(self.0.as_int_handle(), 0, key, ptr::null()) };
+        (rslt != 0).true_val_or_errno(())
+    }
+}
+impl AsHandle for CompletionPort {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> { self.0.as_handle() }
+}
+impl From<CompletionPort> for OwnedHandle {
+    #[inline]
+    fn from(port: CompletionPort) -> Self { port.0 }
+}
+/// Assumes that the handle is an I/O completion port.
+impl From<OwnedHandle> for CompletionPort {
+    #[inline]
+    fn from(handle: OwnedHandle) -> Self { Self(handle) }
+}
+
+/// A notification of a finished overlapped operation, retrieved from a [`CompletionPort`].
+#[derive(Debug)]
+pub struct Completion {
+    key: usize,
+    bytes: usize,
+    op: OperationId,
+    error: Option<io::Error>,
+}
+impl Completion {
+    /// Returns the completion key of the handle the operation was performed on.
+    #[inline]
+    pub fn key(&self) -> usize { self.key }
+    /// Returns the ID of the operation that finished, to be matched against [`Operation::id()`].
+    #[inline]
+    pub fn operation(&self) -> OperationId { self.op }
+    /// Returns the number of bytes transferred by the operation.
+    #[inline]
+    pub fn bytes_transferred(&self) -> usize { self.bytes }
+    /// Returns the error the operation failed with, if any.
+    #[inline]
+    pub fn error(&self) -> Option<&io::Error> { self.error.as_ref() }
+}
+
+/// Identifies an [`Operation`] for as long as it exists. IDs may be reused afterwards.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OperationId(*const ());
+// SAFETY: only used for comparisons, never dereferenced
+unsafe impl Send for OperationId {}
+unsafe impl Sync for OperationId {}
+
+struct OpInner {
+    // Must be the first field so that the pointer to the OVERLAPPED is also the pointer to the
+    // OpInner.
+    overlapped: OVERLAPPED,
+    buf: Vec<u8>,
+    // Manual-reset event signaled when the operation finishes, so that waiting for it with
+    // GetOverlappedResult doesn't wait on the file handle, which is also signaled by other
+    // operations on it.
+    event: OwnedHandle,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum OpKind {
+    Read,
+    Write,
+    Connect,
+}
+
+/// An overlapped operation on a named pipe handle, owning its buffer and `OVERLAPPED` structure.
+pub struct Operation<'h> {
+    // Boxed so that it doesn't move while the OS has a pointer to it.
+    inner: Box<OpInner>,
+    handle: BorrowedHandle<'h>,
+    kind: OpKind,
+    pending: bool,
+}
+impl<'h> Operation<'h> {
+    fn new(handle: BorrowedHandle<'h>, buf: Vec<u8>, kind: OpKind) -> io::Result<Self> {
+        let event = unsafe { CreateEventW(ptr::null(), 1, 0, ptr::null()) };
+        (event != 0).true_val_or_errno(())?;
+        // SAFETY: we just created this handle
+        let event = unsafe { OwnedHandle::from_raw_handle(event.to_std()) };
+        let mut inner = Box::new(OpInner { overlapped: unsafe { mem::zeroed() }, buf, event });
+        inner.overlapped.hEvent = inner.event.as_int_handle();
+        Ok(Self { inner, handle, kind, pending: true })
+    }
+    /// Handles the return value of the function that started the operation.
+    fn started(mut self, success: bool) -> io::Result<Self> {
+        if success {
+            return Ok(self);
+        }
+        let e = io::Error::last_os_error();
+        if e.raw_os_error().eeq(ERROR_IO_PENDING) {
+            Ok(self)
+        } else {
+            // The operation never started, so the OS holds no pointers into it.
+            self.pending = false;
+            Err(e)
+        }
+    }
+
+    /// Starts receiving data into the spare capacity of `buf`, which must be nonzero.
+    ///
+    /// Once finished, the received data is appended to the buffer.
+    pub fn read(handle: BorrowedHandle<'h>, buf: Vec<u8>) -> io::Result<Self> {
+        let mut op = Self::new(handle, buf, OpKind::Read)?;
+        let inner = &mut *op.inner;
+        let spare = inner.buf.spare_capacity_mut();
+        let len = u32::try_from(spare.len()).unwrap_or(u32::MAX);
+        let success = unsafe {
+            ReadFile(
+                handle.as_int_handle(),
+                spare.as_mut_ptr().cast(),
+                len,
+                ptr::null_mut(),
+                &mut inner.overlapped,
+            )
+        } != 0;
+        op.started(success)
+    }
+    /// Starts sending the contents of `buf`.
+    ///
+    /// Once finished, the buffer is returned unchanged, along with the number of bytes sent.
+    pub fn write(handle: BorrowedHandle<'h>, buf: Vec<u8>) -> io::Result<Self> {
+        let mut op = Self::new(handle, buf, OpKind::Write)?;
+        let inner = &mut *op.inner;
+        let len = u32::try_from(inner.buf.len()).unwrap_or(u32::MAX);
+        let success = unsafe {
+            WriteFile(
+                handle.as_int_handle(),
+                inner.buf.as_ptr().cast(),
+                len,
+                ptr::null_mut(),
+                &mut inner.overlapped,
+            )
+        } != 0;
+        op.started(success)
+    }
+    /// Starts waiting for a client to connect to a server created with [`create_server()`].
+    ///
+    /// If a client has already connected by the time this is called, `None` is returned, and no
+    /// completion is posted to the completion port.
+    pub fn connect(handle: BorrowedHandle<'h>) -> io::Result<Option<Self>> {
+        let mut op = Self::new(handle, Vec::new(), OpKind::Connect)?;
+        let success =
+            unsafe { ConnectNamedPipe(handle.as_int_handle(), &mut op.inner.overlapped) } != 0;
+        match op.started(success) {
+            Err(e) if e.raw_os_error().eeq(ERROR_PIPE_CONNECTED) => Ok(None),
+            els => els.map(Some),
+        }
+    }
+
+    /// Returns the ID of the operation, which is reported by the [`Completion`] for it.
+    #[inline]
+    pub fn id(&self) -> OperationId { OperationId(ptr::addr_of!(self.inner.overlapped).cast()) }
+
+    fn get_result(&mut self, wait: bool) -> io::Result<usize> {
+        let mut bytes = 0_u32;
+        let success = unsafe {
+            GetOverlappedResult(
+                self.handle.as_int_handle(),
+                &self.inner.overlapped,
+                &mut bytes,
+                i32::from(wait),
+            )
+        } != 0;
+        let rslt = success.true_or_errno(|| bytes.to_usize());
+        if !matches!(&rslt, Err(e) if e.raw_os_error().eeq(ERROR_IO_INCOMPLETE)) {
+            self.pending = false;
+        }
+        rslt
+    }
+    fn into_output(mut self, bytes: usize) -> (Vec<u8>, usize) {
+        let mut buf = mem::take(&mut self.inner.buf);
+        if self.kind == OpKind::Read {
+            let new_len = buf.len().saturating_add(bytes).min(buf.capacity());
+            // SAFETY: the OS has initialized this many bytes past the previous length
+            unsafe { buf.set_len(new_len) };
+        }
+        (buf, bytes)
+    }
+
+    /// Retrieves the outcome of the operation if it has finished, returning it back otherwise.
+    ///
+    /// On success, the buffer and the number of bytes transferred are returned.
+    pub fn try_finish(mut self) -> Result<io::Result<(Vec<u8>, usize)>, Self> {
+        match self.get_result(false) {
+            Ok(bytes) => Ok(Ok(self.into_output(bytes))),
+            Err(..) if self.pending => Err(self),
+            Err(e) => Ok(Err(e)),
+        }
+    }
+    /// Blocks until the operation finishes, and then retrieves its outcome.
+    ///
+    /// On success, the buffer and the number of bytes transferred are returned.
+    pub fn wait(mut self) -> io::Result<(Vec<u8>, usize)> {
+        let bytes = self.get_result(true)?;
+        Ok(self.into_output(bytes))
+    }
+}
+impl Drop for Operation<'_> {
+    fn drop(&mut self) {
+        if !self.pending {
+            return;
+        }
+        unsafe { CancelIoEx(self.handle.as_int_handle(), &self.inner.overlapped) };
+        // The OS may still be accessing the buffer and the OVERLAPPED until the cancellation goes
+        // through, so we have to wait for it no matter what.
+        while self.pending {
+            let _ = self.get_result(true);
+        }
+    }
+}
+impl Debug for Operation<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            OpKind::Read => "read",
+            OpKind::Write => "write",
+            OpKind::Connect => "connect",
+        };
+        f.debug_struct("Operation")
+            .field("id", &self.id())
+            .field("handle", &self.handle)
+            .field("kind", &kind)
+            .field("pending", &self.pending)
+            .finish()
+    }
+}
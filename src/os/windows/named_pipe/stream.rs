@@ -9,6 +9,7 @@ use {
     crate::{
         local_socket::{ConcurrencyDetectionSite, ConcurrencyDetector},
         os::windows::{FileHandle, NeedsFlush},
+        NonblockingCache,
     },
     std::{marker::PhantomData, os::windows::prelude::*},
 };
@@ -86,6 +87,7 @@ pub(crate) struct RawPipeStream {
     handle: Option<FileHandle>,
     is_server: bool,
     needs_flush: NeedsFlush,
+    nonblocking: NonblockingCache,
     concurrency_detector: ConcurrencyDetector<NamedPipeSite>,
 }
 
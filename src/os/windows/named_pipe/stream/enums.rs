@@ -19,15 +19,15 @@ pub mod pipe_mode {
         use super::*;
         pub trait PipeModeTag: Copy + std::fmt::Debug + Eq + Send + Sync + Unpin {
             const MODE: Option<PipeMode>;
-            #[cfg(feature = "tokio")]
+            #[cfg(feature = "local-socket-tokio")]
             type TokioFlusher: std::fmt::Debug + Default;
         }
-        #[cfg(feature = "tokio")]
+        #[cfg(feature = "local-socket-tokio")]
         pub trait NotNone:
             PipeModeTag<TokioFlusher = crate::os::windows::tokio_flusher::TokioFlusher>
         {
         }
-        #[cfg(not(feature = "tokio"))]
+        #[cfg(not(feature = "local-socket-tokio"))]
         pub trait NotNone: PipeModeTag {}
     }
     pub(crate) use seal::*;
@@ -37,7 +37,7 @@ pub mod pipe_mode {
                 tag_enum!($( #[$attr] )* $tag);
                 impl PipeModeTag for $tag {
                     const MODE: Option<PipeMode> = $mode;
-                    #[cfg(feature = "tokio")]
+                    #[cfg(feature = "local-socket-tokio")]
                     type TokioFlusher = ();
                 }
         };
@@ -45,7 +45,7 @@ pub mod pipe_mode {
                 tag_enum!($( #[$attr] )* $tag);
                 impl PipeModeTag for $tag {
                     const MODE: Option<PipeMode> = $mode;
-                    #[cfg(feature = "tokio")]
+                    #[cfg(feature = "local-socket-tokio")]
                     type TokioFlusher = crate::os::windows::tokio_flusher::TokioFlusher;
                 }
         };
@@ -15,7 +15,7 @@ use {
             decode_eof,
             named_pipe::{
                 c_wrappers::{self as c_wrappers, hget},
-                PipeMode,
+                PipeInfo, PipeMode,
             },
             AsRawHandleExt, FileHandle, ImpersonationGuard, NeedsFlushVal,
         },
@@ -74,6 +74,10 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
         unsafe { hget(self.as_handle(), Pipes::GetNamedPipeServerSessionId) }
     }
 
+    /// Queries [information](PipeInfo) about the named pipe.
+    #[inline]
+    pub fn info(&self) -> io::Result<PipeInfo> { PipeInfo::query(self.as_handle()) }
+
     /// Returns `true` if the stream was created by a listener (server-side), `false` if it was
     /// created by connecting to a server (server-side).
     #[inline]
@@ -97,11 +101,15 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
     /// [`.set_nonblocking()`], can be used to set the mode in bulk for all current instances and
     /// future ones.
     ///
+    /// Calls that would leave the mode unchanged are skipped without making a system call.
+    ///
     /// [`nonblocking`]: super::super::PipeListenerOptions::nonblocking
     /// [`.set_nonblocking()`]: super::super::PipeListener::set_nonblocking
     #[inline]
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
-        c_wrappers::set_nonblocking_given_readmode(self.as_handle(), nonblocking, Rm::MODE)
+        self.raw.nonblocking.set(nonblocking, || {
+            c_wrappers::set_nonblocking_given_readmode(self.as_handle(), nonblocking, Rm::MODE)
+        })
     }
 
     /// [Impersonates the client][imp] of the named pipe.
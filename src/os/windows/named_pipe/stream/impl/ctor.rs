@@ -1,6 +1,9 @@
 use {
     super::*,
-    crate::os::windows::{named_pipe::WaitTimeout, path_conversion::*},
+    crate::{
+        os::windows::{named_pipe::WaitTimeout, path_conversion::*},
+        NonblockingCache,
+    },
     widestring::U16CStr,
     windows_sys::Win32::System::Pipes::PIPE_READMODE_MESSAGE,
 };
@@ -11,6 +14,7 @@ impl RawPipeStream {
             handle: Some(handle),
             is_server,
             needs_flush: NeedsFlush::from(nfv),
+            nonblocking: NonblockingCache::unknown(),
             concurrency_detector: ConcurrencyDetector::new(),
         }
     }
@@ -23,16 +27,23 @@ impl RawPipeStream {
         recv: Option<PipeMode>,
         send: Option<PipeMode>,
     ) -> io::Result<Self> {
-        let handle = loop {
-            match c_wrappers::connect_without_waiting(path, recv, send, false) {
+        loop {
+            match Self::try_connect(path, recv, send) {
                 Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
                     c_wrappers::block_for_server(path, WaitTimeout::DEFAULT)?;
                     continue;
                 }
                 els => break els,
             }
-        }?;
-
+        }
+    }
+    /// Fails with [`WouldBlock`](io::ErrorKind::WouldBlock) if all instances of the pipe are busy.
+    fn try_connect(
+        path: &U16CStr,
+        recv: Option<PipeMode>,
+        send: Option<PipeMode>,
+    ) -> io::Result<Self> {
+        let handle = c_wrappers::connect_without_waiting(path, recv, send, false)?;
         if recv == Some(PipeMode::Messages) {
             c_wrappers::set_np_handle_state(
                 handle.as_handle(),
@@ -54,6 +65,14 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
             .map(Self::new)
     }
 
+    /// Like [`.connect_by_path()`](Self::connect_by_path), but fails with
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) instead of waiting if all instances of the pipe
+    /// are busy.
+    #[inline]
+    pub(crate) fn try_connect_by_path(path: &U16CStr) -> io::Result<Self> {
+        RawPipeStream::try_connect(path, Rm::MODE, Sm::MODE).map(Self::new)
+    }
+
     /// Internal constructor used by the listener. It's a logic error, but not UB, to create the
     /// thing from the wrong kind of thing, but that never ever happens, to the best of my ability.
     pub(crate) fn new(raw: RawPipeStream) -> Self {
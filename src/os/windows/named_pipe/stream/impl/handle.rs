@@ -61,6 +61,33 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> TryFrom<PipeStream<Rm, Sm>> for OwnedHand
     }
 }
 
+impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
+    /// Waits for the client to receive everything that has been sent, then disconnects it,
+    /// returning the pipe instance so that it can wait for another client.
+    pub(crate) fn disconnect(self) -> io::Result<FileHandle> {
+        if !self.is_server() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "client-side pipe streams cannot be disconnected",
+            ));
+        }
+        let raw = match self.raw {
+            MaybeArc::Inline(x) => x,
+            MaybeArc::Shared(..) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "split pipe streams cannot be disconnected",
+                ))
+            }
+        };
+        // DisconnectNamedPipe() discards whatever the client has yet to receive.
+        raw.flush()?;
+        let handle = OwnedHandle::from(raw);
+        unsafe { Pipes::DisconnectNamedPipe(handle.as_int_handle()) != 0 }.true_val_or_errno(())?;
+        Ok(FileHandle::from(handle))
+    }
+}
+
 /// Attempts to wrap the given handle into the high-level pipe stream type. If the underlying pipe
 /// type is wrong or trying to figure out whether it's wrong or not caused a system call error, the
 /// corresponding error condition is returned.
@@ -92,7 +119,9 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> TryClone for PipeStream<Rm, Sm> {
     fn try_clone(&self) -> io::Result<Self> {
         let handle = duplicate_handle(self.as_handle())?;
         self.raw.needs_flush.on_clone();
-        let new = RawPipeStream::new(handle.into(), self.is_server(), NeedsFlushVal::Always);
+        let mut new = RawPipeStream::new(handle.into(), self.is_server(), NeedsFlushVal::Always);
+        // The duplicate refers to the same pipe end, whose blocking mode it thus shares.
+        new.nonblocking = self.raw.nonblocking.share();
         Ok(Self::new(new))
     }
 }
@@ -14,7 +14,7 @@ impl RawPipeStream {
     }
 
     #[track_caller]
-    fn flush(&self) -> io::Result<()> {
+    pub(super) fn flush(&self) -> io::Result<()> {
         if self.needs_flush.take() {
             let r = self.file_handle().flush();
             if r.is_err() {
@@ -7,15 +7,18 @@ use {
                 tokio::{PipeStream, RawPipeStream},
                 PipeListenerOptions, PipeModeTag,
             },
-            winprelude::*,
+            c_wrappers, winprelude::*,
         },
         Sealed,
     },
     std::{
         fmt::{self, Debug, Formatter},
+        future::{poll_fn, Future},
         io,
         marker::PhantomData,
         mem::replace,
+        sync::atomic::{AtomicBool, Ordering::Relaxed},
+        task::Poll,
     },
     tokio::{net::windows::named_pipe::NamedPipeServer as TokioNPServer, sync::Mutex},
 };
@@ -37,7 +40,9 @@ use {
 /// ```
 pub struct PipeListener<Rm: PipeModeTag, Sm: PipeModeTag> {
     config: PipeListenerOptions<'static>, // We need the options to create new instances
-    stored_instance: Mutex<TokioNPServer>,
+    inheritable: AtomicBool,
+    /// Instances waiting for clients, of which there are up to `config.accept_parallelism`.
+    stored_instances: Mutex<Vec<TokioNPServer>>,
     _phantom: PhantomData<(Rm, Sm)>,
 }
 impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
@@ -45,12 +50,39 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
 
     /// Asynchronously waits until a client connects to the named pipe, creating a `Stream` to
     /// communicate with the pipe.
+    ///
+    /// If [`accept_parallelism`](PipeListenerOptions::accept_parallelism) is above 1, the
+    /// instances that are missing are created first, and the client of whichever instance gets
+    /// connected to first is the one that is accepted. Connections to the other instances stay
+    /// in flight and are picked up by subsequent calls.
     pub async fn accept(&self) -> io::Result<PipeStream<Rm, Sm>> {
         let instance_to_hand_out = {
-            let mut stored_instance = self.stored_instance.lock().await;
-            stored_instance.connect().await?;
+            let mut stored_instances = self.stored_instances.lock().await;
+            let parallelism = usize::from(self.config.accept_parallelism.get());
+            while stored_instances.len() < parallelism {
+                stored_instances.push(self.create_instance()?);
+            }
+            let ready = if let [only] = stored_instances.as_slice() {
+                // The default of a single instance needs no pinning of the future on the heap.
+                only.connect().await.map(|()| 0)?
+            } else {
+                // Tokio's connect is cancel-safe, so the operations on the instances that lose
+                // the race carry on in the background and complete on the next call.
+                let mut connects =
+                    stored_instances.iter().map(|i| Box::pin(i.connect())).collect::<Vec<_>>();
+                poll_fn(|cx| {
+                    for (idx, connect) in connects.iter_mut().enumerate() {
+                        if let Poll::Ready(rslt) = connect.as_mut().poll(cx) {
+                            return Poll::Ready(rslt.map(|()| idx));
+                        }
+                    }
+                    Poll::Pending
+                })
+                .await?
+            };
             let new_instance = self.create_instance()?;
-            replace(&mut *stored_instance, new_instance)
+            let slot = stored_instances.get_mut(ready).ok_or(io::ErrorKind::NotFound)?;
+            replace(slot, new_instance)
         };
 
         let raw = RawPipeStream::new_server(instance_to_hand_out);
@@ -68,7 +100,12 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
         tokio_object: TokioNPServer,
         options: PipeListenerOptions<'static>,
     ) -> Self {
-        Self { config: options, stored_instance: Mutex::new(tokio_object), _phantom: PhantomData }
+        Self {
+            inheritable: AtomicBool::new(options.inheritable),
+            config: options,
+            stored_instances: Mutex::new(vec![tokio_object]),
+            _phantom: PhantomData,
+        }
     }
 
     /// Creates a listener from a handle and a [`PipeListenerOptions`] table with the assumption
@@ -87,17 +124,38 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
         Ok(Self::from_tokio_and_options(npserver_from_handle(handle)?, options))
     }
 
+    /// Sets whether the instances that are currently waiting for clients and all future ones are
+    /// to be inherited by child processes. See the
+    /// [sync counterpart](crate::os::windows::named_pipe::PipeListener::set_inheritable) for
+    /// more.
+    ///
+    /// If an `.accept()` call is in progress, the instances it is waiting on are left unchanged.
+    pub fn set_inheritable(&self, inheritable: bool) -> io::Result<()> {
+        self.inheritable.store(inheritable, Relaxed);
+        if let Ok(instances) = self.stored_instances.try_lock() {
+            for instance in instances.iter() {
+                c_wrappers::set_inheritable(instance.as_handle(), inheritable)?;
+            }
+        }
+        Ok(())
+    }
+
     fn create_instance(&self) -> io::Result<TokioNPServer> {
-        self.config
-            .create_instance(false, false, true, Self::STREAM_ROLE, Rm::MODE)
-            .and_then(npserver_from_handle)
+        let instance =
+            self.config.create_instance(false, false, true, Self::STREAM_ROLE, Rm::MODE)?;
+        let inheritable = self.inheritable.load(Relaxed);
+        if inheritable != self.config.inheritable {
+            c_wrappers::set_inheritable(instance.as_handle(), inheritable)?;
+        }
+        npserver_from_handle(instance)
     }
 }
 impl<Rm: PipeModeTag, Sm: PipeModeTag> Debug for PipeListener<Rm, Sm> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("PipeListener")
             .field("config", &self.config)
-            .field("instance", &self.stored_instance)
+            .field("instances", &self.stored_instances)
+            .field("inheritable", &self.inheritable.load(Relaxed))
             .finish()
     }
 }
@@ -21,7 +21,7 @@ use {
     crate::os::windows::{
         named_pipe::{
             c_wrappers::{self, hget},
-            PipeMode,
+            PipeInfo, PipeMode,
         },
         winprelude::*,
     },
@@ -30,8 +30,11 @@ use {
         pin::Pin,
         task::{ready, Context, Poll},
     },
-    tokio::net::windows::named_pipe::{
-        NamedPipeClient as TokioNPClient, NamedPipeServer as TokioNPServer,
+    tokio::{
+        io::{Interest, Ready},
+        net::windows::named_pipe::{
+            NamedPipeClient as TokioNPClient, NamedPipeServer as TokioNPServer,
+        },
     },
     windows_sys::Win32::System::Pipes,
 };
@@ -80,6 +83,10 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
     pub fn server_session_id(&self) -> io::Result<u32> {
         unsafe { hget(self.as_handle(), Pipes::GetNamedPipeServerSessionId) }
     }
+
+    /// Queries [information](PipeInfo) about the named pipe.
+    #[inline]
+    pub fn info(&self) -> io::Result<PipeInfo> { PipeInfo::query(self.as_handle()) }
     /// Returns `true` if the stream was created by a listener (server-side), `false` if it was
     /// created by connecting to a server (server-side).
     #[inline]
@@ -88,4 +95,24 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
     /// it was created by a listener (server-side).
     #[inline]
     pub fn is_client(&self) -> bool { !self.is_server() }
+
+    /// Waits for the pipe to become ready for any of the given kinds of I/O, returning the kinds
+    /// that it is ready for.
+    ///
+    /// Readiness can be spurious, in which case the subsequent operation fails with
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock).
+    #[inline]
+    pub async fn ready(&self, interest: Interest) -> io::Result<Ready> {
+        same_clsrv!(x in self.raw.inner() => x.ready(interest).await)
+    }
+    /// Waits for the pipe to become readable. Shorthand for `.ready(Interest::READABLE)`.
+    #[inline]
+    pub async fn readable(&self) -> io::Result<()> {
+        same_clsrv!(x in self.raw.inner() => x.readable().await)
+    }
+    /// Waits for the pipe to become writable. Shorthand for `.ready(Interest::WRITABLE)`.
+    #[inline]
+    pub async fn writable(&self) -> io::Result<()> {
+        same_clsrv!(x in self.raw.inner() => x.writable().await)
+    }
 }
@@ -1,6 +1,8 @@
 use {
     super::*,
-    crate::os::windows::{named_pipe::WaitTimeout, path_conversion::*, NeedsFlushVal},
+    crate::os::windows::{
+        named_pipe::WaitTimeout, path_conversion::*, tokio_blocking::BlockingTask, NeedsFlushVal,
+    },
     std::{borrow::Cow, mem::take},
     widestring::U16CString,
 };
@@ -16,17 +18,16 @@ impl RawPipeStream {
     pub(crate) fn new_server(server: TokioNPServer) -> Self {
         Self::new(InnerTokio::Server(server), NeedsFlushVal::No)
     }
-    fn new_client(client: TokioNPClient) -> Self {
+    pub(crate) fn new_client(client: TokioNPClient) -> Self {
         Self::new(InnerTokio::Client(client), NeedsFlushVal::No)
     }
 
     async fn wait_for_server(path: U16CString) -> io::Result<U16CString> {
-        tokio::task::spawn_blocking(move || {
+        BlockingTask::spawn(move || {
             c_wrappers::block_for_server(&path, WaitTimeout::DEFAULT)?;
             Ok(path)
-        })
+        })?
         .await
-        .expect("waiting for server panicked")
     }
 
     async fn connect(
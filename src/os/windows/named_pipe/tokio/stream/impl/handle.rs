@@ -1,6 +1,6 @@
 use {
     super::*,
-    crate::os::windows::NeedsFlushVal,
+    crate::{error::ConversionError, os::windows::NeedsFlushVal},
     std::mem::ManuallyDrop,
     windows_sys::Win32::System::Pipes::{PIPE_SERVER_END, PIPE_TYPE_MESSAGE},
 };
@@ -94,4 +94,66 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> TryFrom<OwnedHandle> for PipeStream<Rm, S
     }
 }
 
+impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
+    /// Takes the Tokio object out, failing if the stream is split.
+    fn into_inner(mut self) -> Result<InnerTokio, Self> {
+        if !self.raw.try_make_owned() {
+            return Err(self);
+        }
+        let PipeStream { raw, flusher, _phantom } = self;
+        match raw {
+            MaybeArc::Inline(raw) => Ok(raw.into_inner()),
+            raw @ MaybeArc::Shared(..) => Err(PipeStream { raw, flusher, _phantom }),
+        }
+    }
+}
+
+/// Wraps a Tokio named pipe server, assuming that it was created with the pipe mode matching
+/// `Rm` and `Sm`.
+impl<Rm: PipeModeTag, Sm: PipeModeTag> From<TokioNPServer> for PipeStream<Rm, Sm> {
+    #[inline]
+    fn from(server: TokioNPServer) -> Self { Self::new(RawPipeStream::new_server(server)) }
+}
+/// Wraps a Tokio named pipe client, assuming that it was opened with the access rights and pipe
+/// mode matching `Rm` and `Sm`.
+impl<Rm: PipeModeTag, Sm: PipeModeTag> From<TokioNPClient> for PipeStream<Rm, Sm> {
+    #[inline]
+    fn from(client: TokioNPClient) -> Self { Self::new(RawPipeStream::new_client(client)) }
+}
+
+/// Fails if the stream is client-side or split, returning it back. Data that has been sent but
+/// not yet flushed is not waited for; flush the stream beforehand if that matters.
+impl<Rm: PipeModeTag, Sm: PipeModeTag> TryFrom<PipeStream<Rm, Sm>> for TokioNPServer {
+    type Error = ConversionError<PipeStream<Rm, Sm>>;
+    fn try_from(stream: PipeStream<Rm, Sm>) -> Result<Self, Self::Error> {
+        if stream.is_client() {
+            return Err(ConversionError::from_source(stream));
+        }
+        match stream.into_inner() {
+            Ok(InnerTokio::Server(server)) => Ok(server),
+            Ok(InnerTokio::Client(client)) => {
+                Err(ConversionError::from_source(PipeStream::from(client)))
+            }
+            Err(stream) => Err(ConversionError::from_source(stream)),
+        }
+    }
+}
+/// Fails if the stream is server-side or split, returning it back. Data that has been sent but
+/// not yet flushed is not waited for; flush the stream beforehand if that matters.
+impl<Rm: PipeModeTag, Sm: PipeModeTag> TryFrom<PipeStream<Rm, Sm>> for TokioNPClient {
+    type Error = ConversionError<PipeStream<Rm, Sm>>;
+    fn try_from(stream: PipeStream<Rm, Sm>) -> Result<Self, Self::Error> {
+        if stream.is_server() {
+            return Err(ConversionError::from_source(stream));
+        }
+        match stream.into_inner() {
+            Ok(InnerTokio::Client(client)) => Ok(client),
+            Ok(InnerTokio::Server(server)) => {
+                Err(ConversionError::from_source(PipeStream::from(server)))
+            }
+            Err(stream) => Err(ConversionError::from_source(stream)),
+        }
+    }
+}
+
 derive_asraw!({Rm: PipeModeTag, Sm: PipeModeTag} PipeStream<Rm, Sm>, windows);
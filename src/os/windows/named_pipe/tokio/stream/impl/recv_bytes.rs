@@ -1,6 +1,7 @@
 use {
     super::*,
     crate::os::windows::downgrade_eof,
+    std::io::IoSliceMut,
     tokio::io::{AsyncRead, ReadBuf},
 };
 
@@ -21,6 +22,24 @@ impl RawPipeStream {
     }
 }
 
+impl<Sm: PipeModeTag> PipeStream<pipe_mode::Bytes, Sm> {
+    /// Receives data into `buf` if any is available right away, failing with
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) otherwise, without waiting. End of file is
+    /// reported as `Ok(0)`.
+    ///
+    /// This checks whether a read started on the pipe completes immediately, and is meant to be
+    /// used together with [`.readable()`](Self::readable).
+    #[inline]
+    pub fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        downgrade_eof(same_clsrv!(x in self.raw.inner() => x.try_read(buf)))
+    }
+    /// Like [`.try_read()`](Self::try_read), but receives into multiple buffers.
+    #[inline]
+    pub fn try_read_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        downgrade_eof(same_clsrv!(x in self.raw.inner() => x.try_read_vectored(bufs)))
+    }
+}
+
 impl<Sm: PipeModeTag> AsyncRead for &PipeStream<pipe_mode::Bytes, Sm> {
     #[inline(always)]
     fn poll_read(
@@ -75,6 +75,23 @@ impl<Rm: PipeModeTag> PipeStream<Rm, pipe_mode::Messages> {
     }
 }
 
+impl<Rm: PipeModeTag> PipeStream<Rm, pipe_mode::Bytes> {
+    /// Sends as much of `buf` as fits into the send buffer right away, failing with
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) if none of it does, without waiting.
+    ///
+    /// This is meant to be used together with [`.writable()`](Self::writable). As with other
+    /// sends, the stream needs to be flushed before it's dropped for the data to be guaranteed
+    /// to arrive.
+    #[inline]
+    pub fn try_write(&self, buf: &[u8]) -> io::Result<usize> {
+        let rslt = same_clsrv!(x in self.raw.inner() => x.try_write(buf));
+        if rslt.is_ok() {
+            self.raw.needs_flush.mark_dirty();
+        }
+        rslt
+    }
+}
+
 impl<Rm: PipeModeTag> AsyncWrite for &PipeStream<Rm, pipe_mode::Bytes> {
     #[inline(always)]
     fn poll_write(
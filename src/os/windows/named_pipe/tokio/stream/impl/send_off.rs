@@ -8,6 +8,11 @@ use {
 
 impl RawPipeStream {
     pub(super) fn inner(&self) -> &InnerTokio { self.inner.as_ref().expect(LIMBO_ERR) }
+    /// Takes the Tokio object out without sending it off to limbo.
+    pub(super) fn into_inner(self) -> InnerTokio {
+        let mut slf = std::mem::ManuallyDrop::new(self);
+        slf.inner.take().expect(LIMBO_ERR)
+    }
 }
 
 impl Drop for RawPipeStream {
@@ -9,6 +9,13 @@ impl WaitTimeout {
     ///
     /// If specified on the client, uses the default wait timeout specified by the server. If
     /// the server also specifies this value, Windows defaults to **50 milliseconds**.
+    ///
+    /// Clients created by Interprocess always wait with this value, leaving the choice of timeout
+    /// to the server, which sets it with
+    /// [`PipeListenerOptions::wait_timeout`](super::PipeListenerOptions::wait_timeout) or, for
+    /// local sockets, [`ListenerOptionsExt::wait_timeout()`][lo].
+    ///
+    /// [lo]: crate::os::windows::local_socket::ListenerOptionsExt::wait_timeout
     pub const DEFAULT: Self = Self(0x00000000);
     /// Wait indefinitely.
     pub const FOREVER: Self = Self(0xffffffff);
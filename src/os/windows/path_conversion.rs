@@ -1,5 +1,8 @@
 use {
-    crate::NumExt,
+    crate::{
+        local_socket::{Name, NameInner},
+        NumExt,
+    },
     std::{
         borrow::Cow,
         ffi::{OsStr, OsString},
@@ -149,6 +152,27 @@ pub(crate) fn convert_and_encode_path(
     U16CString::from_vec(path).map_err(contains_nul_error_to_io)
 }
 
+/// Maps a namespaced local socket name, which has the form `\\.\pipe\<name>`, to the name of a
+/// kernel object in the session namespace, `Local\<name><suffix>`.
+///
+/// Kernel objects of all types share one namespace, so objects with the same local socket name
+/// that are created together are told apart by their suffixes.
+pub(crate) fn local_object_name(name: &Name<'_>, suffix: &str) -> io::Result<U16CString> {
+    const PREFIX: &str = r"\\.\pipe\";
+    let NameInner::NamedPipe(path) = &name.0;
+    let mut path = path.as_slice().iter().copied();
+    if !PREFIX.encode_utf16().eq(path.by_ref().take(PREFIX.len())) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "kernel objects cannot be named with paths to remote named pipes",
+        ));
+    }
+    let mut buf = r"Local\".encode_utf16().collect::<Vec<_>>();
+    buf.extend(path);
+    buf.extend(suffix.encode_utf16());
+    U16CString::from_vec(buf).map_err(to_io_error)
+}
+
 pub(crate) fn convert_osstr(str: &OsStr) -> io::Result<U16CString> {
     U16CString::from_os_str(str).map_err(contains_nul_error_to_io)
 }
@@ -0,0 +1,78 @@
+use {
+    super::{c_wrappers, winprelude::*},
+    crate::{
+        local_socket::{prelude::*, GenericNamespaced, ListenerOptions, Stream},
+        os::windows::named_pipe::local_socket as np_impl,
+        process::CHANNEL_ENV_VAR,
+    },
+    std::{
+        env, io,
+        process::{self, Child, Command},
+        sync::atomic::{AtomicBool, AtomicU32, Ordering::SeqCst},
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+pub(crate) fn spawn_with_channel(mut cmd: Command) -> io::Result<(Child, Stream)> {
+    let (parent, child) = connected_pair()?;
+    let child_handle = child.as_handle();
+    c_wrappers::set_inheritable(child_handle, true)?;
+    cmd.env(CHANNEL_ENV_VAR, child_handle.as_int_handle().to_string());
+    let proc = cmd.spawn();
+    // The child's end is closed right away in any case, which also stops it from being inherited
+    // by processes spawned after this point.
+    drop(child);
+    Ok((proc?, parent))
+}
+
+fn connected_pair() -> io::Result<(Stream, Stream)> {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.subsec_nanos());
+    let name = format!(
+        "interprocess-channel-{}-{}-{nanos:08x}",
+        process::id(),
+        COUNTER.fetch_add(1, SeqCst),
+    );
+    let listener = ListenerOptions::new()
+        .name(name.as_str().to_ns_name::<GenericNamespaced>()?)
+        .create_sync()?;
+    let child = Stream::connect(name.to_ns_name::<GenericNamespaced>()?)?;
+    let parent = listener.accept()?;
+    // Both ends are in this process, so anything else means someone else got in between.
+    if parent.peer_credentials()?.pid != Some(process::id()) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "channel was connected to by another process",
+        ));
+    }
+    Ok((parent, child))
+}
+
+static CLAIMED: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn from_inherited() -> io::Result<Stream> {
+    if CLAIMED.load(SeqCst) {
+        return Err(already_claimed());
+    }
+    let val = env::var(CHANNEL_ENV_VAR).map_err(|e| io::Error::new(io::ErrorKind::NotFound, e))?;
+    let handle = val.parse::<HANDLE>().ok().filter(|&h| h != 0 && h != INVALID_HANDLE_VALUE);
+    let handle = handle.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "malformed inherited channel handle")
+    })?;
+    if CLAIMED.swap(true, SeqCst) {
+        return Err(already_claimed());
+    }
+    // Processes spawned by this one must not mistake whatever handle ends up with the same value
+    // for a channel of their own.
+    env::remove_var(CHANNEL_ENV_VAR);
+    // SAFETY: the handle was inherited specifically for us to own, and CLAIMED ensures that this
+    // only happens once
+    let handle = unsafe { OwnedHandle::from_raw_handle(handle.to_std()) };
+    c_wrappers::set_inheritable(handle.as_handle(), false)?;
+    let stream = np_impl::Stream::try_from(handle).map_err(io::Error::from)?;
+    Ok(Stream::NamedPipe(stream))
+}
+
+fn already_claimed() -> io::Error {
+    io::Error::new(io::ErrorKind::AlreadyExists, "the inherited channel has already been claimed")
+}
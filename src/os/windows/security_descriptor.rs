@@ -34,6 +34,15 @@ unsafe fn validate(ptr: *mut c_void) {
     }
 }
 
+/// A user-supplied `SECURITY_ATTRIBUTES` pointer, the validity of which is guaranteed by the
+/// caller of the `unsafe` function it was passed to.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct RawSecurityAttributes(pub(crate) *const SECURITY_ATTRIBUTES);
+// SAFETY: the structure is only ever read from, and the creator of the value vouches for it being
+// valid for as long as it is in use, regardless of the thread.
+unsafe impl Send for RawSecurityAttributes {}
+unsafe impl Sync for RawSecurityAttributes {}
+
 pub(super) fn create_security_attributes(
     sd: Option<BorrowedSecurityDescriptor<'_>>,
     inheritable: bool,
@@ -0,0 +1,43 @@
+use {
+    super::{
+        c_wrappers::{self, WaitOutcome},
+        path_conversion::local_object_name,
+        winprelude::*,
+    },
+    crate::{local_socket::Name, OrErrno},
+    std::{io, ptr},
+    windows_sys::Win32::System::Threading::{
+        CreateSemaphoreW, OpenSemaphoreW, ReleaseSemaphore, SEMAPHORE_MODIFY_STATE,
+        SYNCHRONIZATION_SYNCHRONIZE,
+    },
+};
+
+/// A named kernel semaphore, the Windows counterpart of POSIX named semaphores.
+#[derive(Debug)]
+pub(crate) struct NamedSemaphore(OwnedHandle);
+impl NamedSemaphore {
+    pub fn post(&self) -> io::Result<()> {
+        unsafe { ReleaseSemaphore(self.0.as_int_handle(), 1, ptr::null_mut()) }
+            .true_val_or_errno(())
+    }
+    pub fn wait(&self) -> io::Result<()> {
+        match c_wrappers::wait(self.0.as_handle(), None)? {
+            WaitOutcome::Signaled => Ok(()),
+            _ => Err(io::Error::other("unexpected outcome of a wait on a semaphore")),
+        }
+    }
+}
+
+/// Creates a semaphore with a count of zero, named after a local socket name with the given
+/// suffix.
+pub(crate) fn create_named(name: &Name<'_>, suffix: &str) -> io::Result<NamedSemaphore> {
+    let name = local_object_name(name, suffix)?;
+    let handle = unsafe { CreateSemaphoreW(ptr::null(), 0, i32::MAX, name.as_ptr()) };
+    c_wrappers::created_object(handle).map(NamedSemaphore)
+}
+pub(crate) fn open_named(name: &Name<'_>, suffix: &str) -> io::Result<NamedSemaphore> {
+    let name = local_object_name(name, suffix)?;
+    let access = SYNCHRONIZATION_SYNCHRONIZE | SEMAPHORE_MODIFY_STATE;
+    let handle = unsafe { OpenSemaphoreW(access, 0, name.as_ptr()) };
+    c_wrappers::opened_object(handle).map(NamedSemaphore)
+}
@@ -0,0 +1,324 @@
+//! Shared memory in the form of file mapping objects backed by the paging file.
+//!
+//! This is the Windows half of [`shared_memory`](crate::shared_memory), for cases where control
+//! over Windows-specific parameters is needed.
+
+use {
+    super::{
+        c_wrappers,
+        path_conversion::{local_object_name, to_io_error, ToWtf16},
+        security_descriptor::{create_security_attributes, BorrowedSecurityDescriptor},
+        winprelude::*,
+    },
+    crate::{
+        local_socket::{Name, Stream},
+        AsMutPtr, AsPtr, OrErrno, Sealed,
+    },
+    std::{io, mem::MaybeUninit, ptr},
+    widestring::U16CStr,
+    windows_sys::Win32::{
+        Foundation::{GetLastError, ERROR_ALREADY_EXISTS},
+        System::{
+            Memory::{
+                CreateFileMappingW, MapViewOfFile, OpenFileMappingW, UnmapViewOfFile,
+                VirtualQuery, FILE_MAP_READ, FILE_MAP_WRITE, MEMORY_BASIC_INFORMATION,
+                MEMORY_MAPPED_VIEW_ADDRESS, PAGE_READWRITE,
+            },
+            Threading::{OpenProcess, PROCESS_DUP_HANDLE},
+        },
+    },
+};
+
+/// Options for the creation of a [`FileMapping`].
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct FileMappingOptions<'sd> {
+    /// Security descriptor for the file mapping object.
+    pub security_descriptor: Option<BorrowedSecurityDescriptor<'sd>>,
+    /// Specifies whether the resulting handle can be inherited by child processes.
+    ///
+    /// The default value is `false`.
+    pub inheritable: bool,
+}
+impl Sealed for FileMappingOptions<'_> {}
+impl<'sd> FileMappingOptions<'sd> {
+    /// Starts with the default parameters. Identical to `Default::default()`.
+    pub const fn new() -> Self { Self { security_descriptor: None, inheritable: false } }
+
+    builder_setters! {
+        /// Specifies the pointer to the security descriptor for the file mapping object.
+        ///
+        /// See the [associated field](#structfield.security_descriptor) for more.
+        security_descriptor: Option<BorrowedSecurityDescriptor<'sd>>,
+        /// Specifies whether the resulting handle can be inherited by child processes.
+        ///
+        /// See the [associated field](#structfield.inheritable) for more.
+        inheritable: bool,
+    }
+
+    /// Creates a file mapping object of the given size which has no name and is thus only
+    /// reachable through the handle, either by inheriting it or by having it duplicated into
+    /// another process.
+    #[inline]
+    pub fn create_anonymous(self, size: usize) -> io::Result<FileMapping> {
+        self.create_impl(None, size)
+    }
+    /// Creates a file mapping object of the given size with the given name, such as
+    /// `Local\my-app-shm`.
+    ///
+    /// Fails with [`AlreadyExists`](io::ErrorKind::AlreadyExists) if an object with that name
+    /// exists, instead of opening it the way `CreateFileMappingW()` does.
+    pub fn create_named<'n>(
+        self,
+        name: impl ToWtf16<'n>,
+        size: usize,
+    ) -> io::Result<FileMapping> {
+        let name = name.to_wtf_16().map_err(to_io_error)?;
+        self.create_impl(Some(&name), size)
+    }
+
+    fn create_impl(self, name: Option<&U16CStr>, size: usize) -> io::Result<FileMapping> {
+        let size64 = u64::try_from(size).map_err(|_| too_big())?;
+        let (hi, lo) = split_size(size64);
+        let sa = create_security_attributes(self.security_descriptor, self.inheritable);
+        let handle = unsafe {
+            CreateFileMappingW(
+                INVALID_HANDLE_VALUE,
+                sa.as_ptr(),
+                PAGE_READWRITE,
+                hi,
+                lo,
+                name.map_or(ptr::null(), U16CStr::as_ptr),
+            )
+        };
+        // Captured before the handle is closed, which may overwrite it.
+        let existed = unsafe { GetLastError() } == ERROR_ALREADY_EXISTS;
+        // CreateFileMappingW returns null rather than INVALID_HANDLE_VALUE on failure.
+        let handle = (handle != 0).true_val_or_errno(handle)?;
+        // SAFETY: we just created this handle
+        let handle = unsafe { OwnedHandle::from_raw_handle(handle.to_std()) };
+        if existed {
+            return Err(io::ErrorKind::AlreadyExists.into());
+        }
+        Ok(FileMapping { handle, size })
+    }
+}
+impl Default for FileMappingOptions<'_> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+
+/// A file mapping object backed by the paging file, referred to by a section handle.
+///
+/// The object is destroyed once all handles to it are closed and all of its views are unmapped.
+#[derive(Debug)]
+pub struct FileMapping {
+    handle: OwnedHandle,
+    size: usize,
+}
+impl FileMapping {
+    /// Creates a non-inheritable file mapping object which has no name. See
+    /// [`FileMappingOptions::create_anonymous()`].
+    #[inline]
+    pub fn create_anonymous(size: usize) -> io::Result<Self> {
+        FileMappingOptions::new().create_anonymous(size)
+    }
+    /// Opens an existing named file mapping object for reading and writing.
+    ///
+    /// Windows does not report the size of file mapping objects, so the size of the one that is
+    /// opened is measured by mapping it, which yields the size rounded up to the page size.
+    pub fn open<'n>(name: impl ToWtf16<'n>) -> io::Result<Self> {
+        let name = name.to_wtf_16().map_err(to_io_error)?;
+        let access = FILE_MAP_READ | FILE_MAP_WRITE;
+        let handle = unsafe { OpenFileMappingW(access, 0, name.as_ptr()) };
+        let handle = (handle != 0).true_val_or_errno(handle)?;
+        // SAFETY: we just opened this handle
+        let handle = unsafe { OwnedHandle::from_raw_handle(handle.to_std()) };
+        let size = {
+            let view = MappedView::new(handle.as_handle(), 0)?;
+            let mut info = unsafe { std::mem::zeroed::<MEMORY_BASIC_INFORMATION>() };
+            let infosz = std::mem::size_of::<MEMORY_BASIC_INFORMATION>();
+            let ret = unsafe { VirtualQuery(view.as_ptr().cast(), info.as_mut_ptr(), infosz) };
+            (ret != 0).true_val_or_errno(info.RegionSize)?
+        };
+        Ok(Self { handle, size })
+    }
+    /// Adopts a handle to a file mapping object which is at least `size` bytes long, such as one
+    /// inherited from a parent process or duplicated into this one by another process.
+    ///
+    /// Ownership of the handle passes to the returned `FileMapping`, which closes it on drop.
+    /// Neither the type of the object nor its size is checked right away: [mapping](Self::map)
+    /// fails if the object is not a file mapping object or is shorter than `size`.
+    ///
+    /// # Safety
+    /// The handle must refer to a file mapping object. Its contents must be allowed to be read
+    /// and written through the views returned by [`.map()`](Self::map) – in particular, the
+    /// object must not be backing memory which code elsewhere in this process relies on not
+    /// changing from under it.
+    #[inline]
+    pub unsafe fn from_handle(handle: OwnedHandle, size: usize) -> io::Result<Self> {
+        Ok(Self { handle, size })
+    }
+    /// Returns the size of the object in bytes.
+    #[inline]
+    pub fn size(&self) -> usize { self.size }
+    /// Maps the whole object into memory for reading and writing.
+    pub fn map(&self) -> io::Result<MappedView> {
+        if self.size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot map an empty file mapping object",
+            ));
+        }
+        MappedView::new(self.handle.as_handle(), self.size)
+    }
+}
+impl AsHandle for FileMapping {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> { self.handle.as_handle() }
+}
+impl From<FileMapping> for OwnedHandle {
+    #[inline]
+    fn from(seg: FileMapping) -> Self { seg.handle }
+}
+
+#[inline]
+pub(crate) fn create_named(name: &Name<'_>, size: usize) -> io::Result<FileMapping> {
+    FileMappingOptions::new().create_named(&*local_object_name(name, "")?, size)
+}
+pub(crate) fn open_named(name: &Name<'_>, size: Option<usize>) -> io::Result<FileMapping> {
+    let obj = FileMapping::open(&*local_object_name(name, "")?)?;
+    match size {
+        // SAFETY: the handle comes from a file mapping object that we just opened
+        Some(size) => unsafe { FileMapping::from_handle(obj.into(), size) },
+        None => Ok(obj),
+    }
+}
+
+fn split_size(size: u64) -> (u32, u32) {
+    let hi = u32::try_from(size >> 32).unwrap_or(u32::MAX);
+    let lo = u32::try_from(size & u64::from(u32::MAX)).unwrap_or(u32::MAX);
+    (hi, lo)
+}
+
+/// A read-write view of a [`FileMapping`], unmapped on drop.
+///
+/// The view keeps the object alive even if the `FileMapping` is dropped.
+#[derive(Debug)]
+pub struct MappedView {
+    ptr: ptr::NonNull<u8>,
+    len: usize,
+}
+// The mapping is just memory – the synchronization of access to it is up to the user.
+unsafe impl Send for MappedView {}
+unsafe impl Sync for MappedView {}
+impl MappedView {
+    /// A `len` of zero maps the whole object, which is only used when its size is unknown.
+    fn new(handle: BorrowedHandle<'_>, len: usize) -> io::Result<Self> {
+        let access = FILE_MAP_READ | FILE_MAP_WRITE;
+        let addr = unsafe { MapViewOfFile(handle.as_int_handle(), access, 0, 0, len) };
+        let ptr = ptr::NonNull::new(addr.Value.cast()).ok_or_else(io::Error::last_os_error)?;
+        Ok(Self { ptr, len })
+    }
+    /// Returns a pointer to the start of the view, which is valid for reads and writes of
+    /// [`.len()`](Self::len) bytes for as long as the view exists.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut u8 { self.ptr.as_ptr() }
+    /// Returns the length of the view in bytes.
+    #[inline]
+    pub fn len(&self) -> usize { self.len }
+    /// Returns `false`, since views cannot be empty. Provided for consistency with slices.
+    #[inline]
+    pub fn is_empty(&self) -> bool { false }
+    /// Borrows the contents of the view.
+    ///
+    /// # Safety
+    /// No other thread or process may write to the object while the slice exists.
+    #[inline]
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+    /// Mutably borrows the contents of the view.
+    ///
+    /// # Safety
+    /// No other thread or process may access the object while the slice exists, including
+    /// through other views of it in this process.
+    #[inline]
+    pub unsafe fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.as_ptr(), self.len) }
+    }
+}
+impl Drop for MappedView {
+    fn drop(&mut self) {
+        let addr = MEMORY_MAPPED_VIEW_ADDRESS { Value: self.ptr.as_ptr().cast() };
+        unsafe { UnmapViewOfFile(addr) };
+    }
+}
+
+pub(crate) type Segment = FileMapping;
+pub(crate) type Mapping = MappedView;
+
+/// Handles are passed in band as tokens, so there is nothing to queue up.
+pub(crate) type SegmentQueue = ();
+
+#[inline]
+pub(crate) fn recv_with_segments(
+    stream: &Stream,
+    buf: &mut [MaybeUninit<u8>],
+    _: &mut SegmentQueue,
+) -> io::Result<usize> {
+    stream.read_to_uninit(buf)
+}
+
+/// Sends `header`, which carries the token returned by [`segment_token()`].
+pub(crate) fn send_with_segment(stream: &Stream, header: &[u8], _: &Segment) -> io::Result<()> {
+    use std::io::Write;
+    let mut stream = stream;
+    stream.write_all(header)
+}
+
+/// Duplicates the handle within our own process, returning the value of the duplicate, which
+/// the peer moves into its process with [`claim_segment()`]. If the peer never claims it, the
+/// duplicate remains open in our process until it exits.
+pub(crate) fn segment_token(_: &Stream, seg: &Segment) -> io::Result<u64> {
+    let handle = c_wrappers::duplicate_handle(seg.as_handle())?;
+    let token = u64::try_from(handle.as_int_handle())
+        .map_err(|_| io::Error::other("duplicated handle has a negative value"))?;
+    // Ownership passes to the peer, which closes the handle in our process when it claims it.
+    std::mem::forget(handle);
+    Ok(token)
+}
+
+/// Moves the handle that the peer has left in its process for us into our process.
+///
+/// The handle is duplicated by us rather than pushed into our process by the peer, so that the
+/// handle we end up owning is one that the system has just created for us, whatever the value
+/// that the peer has sent. A value which is not a valid handle in the peer's process is refused
+/// by the system, and a handle to something other than a file mapping object fails to map.
+pub(crate) fn claim_segment(
+    stream: &Stream,
+    token: u64,
+    size: usize,
+    _: &mut SegmentQueue,
+) -> io::Result<Segment> {
+    use crate::local_socket::traits::StreamCommon;
+    let handle = HANDLE::try_from(token)
+        .ok()
+        .filter(|&h| h != 0 && h != INVALID_HANDLE_VALUE)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid section handle"))?;
+    let pid = stream.peer_credentials()?.pid.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::Unsupported, "could not determine process ID of peer")
+    })?;
+    let proc = unsafe { OpenProcess(PROCESS_DUP_HANDLE, 0, pid) };
+    let proc = (proc != 0).true_val_or_errno(proc)?;
+    // SAFETY: we just opened this handle
+    let proc = unsafe { OwnedHandle::from_raw_handle(proc.to_std()) };
+    let handle = c_wrappers::take_handle_from_foreign(proc.as_handle(), handle)?;
+    // SAFETY: the handle is a fresh one of ours, which is only ever used to map views that we
+    // copy messages out of
+    unsafe { Segment::from_handle(handle, size) }
+}
+
+fn too_big() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "shared memory object size is too big")
+}
@@ -0,0 +1,35 @@
+use {
+    crate::{signal::Signal, OrErrno},
+    std::{io, sync::OnceLock},
+    windows_sys::Win32::{
+        Foundation::BOOL,
+        System::Console::{
+            SetConsoleCtrlHandler, CTRL_BREAK_EVENT, CTRL_CLOSE_EVENT, CTRL_C_EVENT,
+            CTRL_LOGOFF_EVENT, CTRL_SHUTDOWN_EVENT,
+        },
+    },
+};
+
+static DISPATCH: OnceLock<fn(Signal)> = OnceLock::new();
+
+/// Installs a console control handler which forwards console events to `dispatch`. Must only be
+/// called once.
+///
+/// The system runs the handler on a thread of its own, which it creates for each event, so
+/// `dispatch` is called directly.
+pub(crate) fn install(dispatch: fn(Signal)) -> io::Result<()> {
+    let _ = DISPATCH.set(dispatch);
+    unsafe { SetConsoleCtrlHandler(Some(handler), 1) }.true_val_or_errno(())
+}
+
+unsafe extern "system" fn handler(event: u32) -> BOOL {
+    let signal = match event {
+        CTRL_C_EVENT | CTRL_BREAK_EVENT => Signal::Interrupt,
+        CTRL_CLOSE_EVENT => Signal::Close,
+        CTRL_LOGOFF_EVENT | CTRL_SHUTDOWN_EVENT => Signal::Terminate,
+        _ => return 0,
+    };
+    let Some(dispatch) = DISPATCH.get() else { return 0 };
+    dispatch(signal);
+    1
+}
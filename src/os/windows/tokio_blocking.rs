@@ -0,0 +1,149 @@
+use {
+    std::{
+        fmt::{self, Debug, Formatter},
+        future::Future,
+        io,
+        num::NonZeroUsize,
+        panic,
+        pin::Pin,
+        sync::{mpsc, Arc, Mutex, PoisonError, RwLock},
+        task::{ready, Context, Poll},
+        thread,
+    },
+    tokio::{
+        sync::oneshot,
+        task::{self, JoinHandle},
+    },
+};
+
+/// A function that runs the given task to completion on a thread of its choosing, such as one
+/// from a dedicated thread pool.
+pub type BlockingExecutor = Arc<dyn Fn(Box<dyn FnOnce() + Send>) + Send + Sync>;
+
+/// How Tokio-based pipes perform the operations that Windows only offers in blocking form.
+///
+/// Waiting for a busy named pipe server to free up an instance (`WaitNamedPipeW`) and flushing
+/// (`FlushFileBuffers`) cannot be done asynchronously, and are thus run on a separate thread.
+/// By default, that thread comes from Tokio's blocking pool, which is shared with everything
+/// else that calls `spawn_blocking()` and may grow to hundreds of threads, or be exhausted by
+/// long-running tasks.
+///
+/// The strategy is process-wide and can be [set](set_blocking_strategy) at any time, affecting
+/// the operations that start afterwards. It applies to Tokio named pipe streams, including named
+/// pipe-based local sockets, and to Tokio unnamed pipes.
+#[derive(Clone, Default)]
+#[non_exhaustive]
+pub enum BlockingStrategy {
+    /// Use Tokio's blocking thread pool via `spawn_blocking()`.
+    #[default]
+    TokioBlockingPool,
+    /// Hand the operations to the given executor, which must eventually run every task it is
+    /// given. If it drops a task instead, the operation fails with an error of kind
+    /// [`Other`](io::ErrorKind::Other).
+    Executor(BlockingExecutor),
+    /// Do not run blocking operations at all, and fail with
+    /// [`Unsupported`](io::ErrorKind::Unsupported) where one would be necessary. Connecting to a
+    /// server whose instances are all busy fails right away, and flushing a pipe that has
+    /// unflushed data fails.
+    ///
+    /// Pipes that are dropped with unflushed data are still flushed in the background, on
+    /// Tokio's blocking thread pool, since dropping cannot fail.
+    Disabled,
+}
+impl BlockingStrategy {
+    /// Spawns a pool of `threads` threads dedicated to blocking operations, and returns an
+    /// [`Executor`](Self::Executor) strategy that uses it.
+    ///
+    /// Operations that are started while all threads are busy wait in a queue. The threads exit
+    /// once the strategy and all of its clones are dropped and the queue is empty. A task that
+    /// panics fails its operation, but does not take its thread down with it.
+    pub fn dedicated_pool(threads: NonZeroUsize) -> io::Result<Self> {
+        let (tx, rx) = mpsc::channel::<Box<dyn FnOnce() + Send>>();
+        let rx = Arc::new(Mutex::new(rx));
+        for _ in 0..threads.get() {
+            let rx = Arc::clone(&rx);
+            thread::Builder::new().name("interprocess blocking pool".to_owned()).spawn(move || {
+                loop {
+                    let task = rx.lock().unwrap_or_else(PoisonError::into_inner).recv();
+                    let Ok(task) = task else { break };
+                    // The panic hook has already reported the panic, and the operation fails
+                    // once its result sender is dropped, so the thread can keep serving the
+                    // queue.
+                    let _ = panic::catch_unwind(panic::AssertUnwindSafe(task));
+                }
+            })?;
+        }
+        Ok(Self::Executor(Arc::new(move |task| {
+            let _ = tx.send(task);
+        })))
+    }
+}
+impl Debug for BlockingStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::TokioBlockingPool => "TokioBlockingPool",
+            Self::Executor(..) => "Executor(..)",
+            Self::Disabled => "Disabled",
+        })
+    }
+}
+
+static STRATEGY: RwLock<BlockingStrategy> = RwLock::new(BlockingStrategy::TokioBlockingPool);
+
+/// Sets the [blocking strategy](BlockingStrategy) of Tokio-based pipes for the whole process.
+pub fn set_blocking_strategy(strategy: BlockingStrategy) {
+    *STRATEGY.write().unwrap_or_else(PoisonError::into_inner) = strategy;
+}
+/// Returns the current [blocking strategy](BlockingStrategy) of Tokio-based pipes.
+pub fn blocking_strategy() -> BlockingStrategy {
+    STRATEGY.read().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+/// A blocking operation running in accordance with the [`BlockingStrategy`].
+#[derive(Debug)]
+pub(crate) enum BlockingTask<T> {
+    Tokio(JoinHandle<io::Result<T>>),
+    Executor(oneshot::Receiver<io::Result<T>>),
+}
+impl<T: Send + 'static> BlockingTask<T> {
+    /// Starts the operation, failing if the strategy is [`Disabled`](BlockingStrategy::Disabled).
+    pub(crate) fn spawn(f: impl FnOnce() -> io::Result<T> + Send + 'static) -> io::Result<Self> {
+        match blocking_strategy() {
+            BlockingStrategy::TokioBlockingPool => Ok(Self::Tokio(task::spawn_blocking(f))),
+            BlockingStrategy::Executor(executor) => {
+                let (tx, rx) = oneshot::channel();
+                executor(Box::new(move || {
+                    let _ = tx.send(f());
+                }));
+                Ok(Self::Executor(rx))
+            }
+            BlockingStrategy::Disabled => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "operation requires blocking, which the blocking strategy forbids",
+            )),
+        }
+    }
+}
+impl<T> Future for BlockingTask<T> {
+    type Output = io::Result<T>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Poll::Ready(match self.get_mut() {
+            Self::Tokio(jh) => match ready!(Pin::new(jh).poll(cx)) {
+                Ok(rslt) => rslt,
+                Err(e) => panic::resume_unwind(e.into_panic()),
+            },
+            Self::Executor(rx) => ready!(Pin::new(rx).poll(cx)).unwrap_or_else(|_| {
+                Err(io::Error::other("blocking executor dropped the task without running it"))
+            }),
+        })
+    }
+}
+
+/// Runs the given task in the background, on Tokio's blocking pool unless a custom executor is
+/// set.
+pub(crate) fn spawn_detached(f: impl FnOnce() + Send + 'static) {
+    match blocking_strategy() {
+        BlockingStrategy::Executor(executor) => executor(Box::new(f)),
+        _ => drop(task::spawn_blocking(f)),
+    }
+}
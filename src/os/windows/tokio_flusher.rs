@@ -1,6 +1,6 @@
 use {
     crate::{
-        os::windows::{winprelude::*, FileHandle, NeedsFlush},
+        os::windows::{tokio_blocking::BlockingTask, winprelude::*, FileHandle, NeedsFlush},
         UnpinExt, LOCK_POISON,
     },
     std::{
@@ -9,12 +9,11 @@ use {
         sync::{atomic::Ordering::*, Mutex},
         task::{ready, Context, Poll},
     },
-    tokio::task::JoinHandle,
 };
 
-type FlushJH = JoinHandle<io::Result<()>>;
+type FlushJH = BlockingTask<()>;
 
-/// Wraps `FlushFileBuffers()` ran in a `spawn_blocking()` task into a poll interface.
+/// Wraps `FlushFileBuffers()` ran as a [blocking task](BlockingTask) into a poll interface.
 #[derive(Debug)]
 pub struct TokioFlusher {
     join_handle: Mutex<Option<FlushJH>>,
@@ -50,8 +49,8 @@ impl TokioFlusher {
             return Poll::Ready(Ok(()));
         }
 
-        let jh = Self::ensure_flush_start(&mut flush, file_handle);
-        let rslt = ready!(jh.pin().poll(cx)).unwrap();
+        let jh = Self::ensure_flush_start(&mut flush, file_handle)?;
+        let rslt = ready!(jh.pin().poll(cx));
         if rslt.is_ok() {
             needs_flush.clear();
         }
@@ -72,8 +71,8 @@ impl TokioFlusher {
 
         let mut flush = self.join_handle.lock().expect(LOCK_POISON);
 
-        let jh = Self::ensure_flush_start(&mut flush, file_handle);
-        let rslt = ready!(jh.pin().poll(cx)).unwrap();
+        let jh = Self::ensure_flush_start(&mut flush, file_handle)?;
+        let rslt = ready!(jh.pin().poll(cx));
         if rslt.is_ok() {
             *needs_flush = false;
         }
@@ -84,13 +83,13 @@ impl TokioFlusher {
     fn ensure_flush_start<'opt>(
         join_handle: &'opt mut Option<FlushJH>,
         file_handle: BorrowedHandle<'_>,
-    ) -> &'opt mut FlushJH {
+    ) -> io::Result<&'opt mut FlushJH> {
         if let Some(jh) = join_handle {
-            return jh;
+            return Ok(jh);
         }
         let handle = file_handle.as_int_handle();
-        let task = tokio::task::spawn_blocking(move || FileHandle::flush_hndl(handle));
-        join_handle.insert(task)
+        let task = BlockingTask::spawn(move || FileHandle::flush_hndl(handle))?;
+        Ok(join_handle.insert(task))
     }
 }
 impl Default for TokioFlusher {
@@ -0,0 +1,64 @@
+//! Spawning of child processes with a preconnected local socket stream.
+//!
+//! This is the most straightforward way for a program to talk to a helper process it starts: no
+//! name needs to be picked and protected from other programs, since the connection is
+//! established before the child is even spawned and only the child inherits its end of it.
+//!
+//! # Examples
+//! In the parent process:
+//! ```no_run
+//! use {interprocess::process::spawn_with_channel, std::{io::prelude::*, process::Command}};
+//!
+//! let (mut child, mut conn) = spawn_with_channel(Command::new("helper"))?;
+//! conn.write_all(b"Hello from the parent!\n")?;
+//! child.wait()?;
+//! # std::io::Result::Ok(())
+//! ```
+//! In the child process:
+//! ```no_run
+//! use {interprocess::local_socket::Stream, std::io::{prelude::*, BufReader}};
+//!
+//! let mut conn = BufReader::new(Stream::from_inherited()?);
+//! let mut line = String::new();
+//! conn.read_line(&mut line)?;
+//! # std::io::Result::Ok(())
+//! ```
+
+impmod! {process,
+    spawn_with_channel as spawn_with_channel_impl,
+}
+use {
+    crate::local_socket::Stream,
+    std::{
+        io,
+        process::{Child, Command},
+    },
+};
+
+/// The environment variable through which [`spawn_with_channel()`] tells the child process where
+/// to find its end of the channel.
+///
+/// Its value is the file descriptor number on Unix and the handle value on Windows.
+pub const CHANNEL_ENV_VAR: &str = "INTERPROCESS_CHANNEL";
+
+/// Spawns a child process with a connected local socket stream, returning the stream for the
+/// parent's end of the channel. The child can retrieve its own end with
+/// [`Stream::from_inherited()`].
+///
+/// Exactly one end of the channel is made inheritable, and only for the duration of the spawn.
+/// The environment variable [`CHANNEL_ENV_VAR`] is set on the command.
+///
+/// # Platform-specific behavior
+/// ## Unix
+/// The channel is a `socketpair()`. The child's end has `FD_CLOEXEC` cleared after `fork()`, so
+/// other processes being spawned concurrently do not inherit it.
+///
+/// ## Windows
+/// The channel is a named pipe whose name is made unique by the process ID, a counter and the
+/// current time, and whose connection is verified to come from the current process before it is
+/// used. Windows has no way of restricting inheritance to
+/// one specific `CreateProcess` call through the standard library, so processes spawned by other
+/// threads while this function is running also inherit the child's end of the channel.
+pub fn spawn_with_channel(cmd: Command) -> io::Result<(Child, Stream)> {
+    spawn_with_channel_impl(cmd)
+}
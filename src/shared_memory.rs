@@ -0,0 +1,290 @@
+//! Shared memory, either named or handed over to other processes.
+//!
+//! [`SharedMemory`] is a block of memory which other processes can open by its name, which is a
+//! [local socket name](crate::local_socket::Name) – the same namespaced names that are used for
+//! local sockets can be used for shared memory. The platform-specific modules,
+//! [`os::unix::shared_memory`](crate::os::unix::shared_memory) and
+//! [`os::windows::shared_memory`](crate::os::windows::shared_memory), offer more control.
+//!
+//! A [`ShmRegion`] is a block of memory without a name. Sending one over a local socket with
+//! [`Stream::send_region()`] transfers the object itself rather than its contents, so large
+//! buffers can change hands without being copied.
+//!
+//! [`RingSender`] and [`RingReceiver`] are the two ends of a broadcast ring in named shared
+//! memory, through which one process publishes messages to any number of readers without ever
+//! waiting for them.
+//!
+//! # Examples
+//! ## Named shared memory
+//! ```no_run
+//! use interprocess::{
+//!     local_socket::{GenericNamespaced, ToNsName},
+//!     shared_memory::SharedMemory,
+//! };
+//!
+//! let name = "example-shm".to_ns_name::<GenericNamespaced>()?;
+//! let mut shm = SharedMemory::options().name(name.borrow()).size(4096).create()?;
+//! // SAFETY: nobody else has opened the object yet
+//! unsafe { shm.as_mut_slice() }[..5].copy_from_slice(b"Hello");
+//!
+//! // In another process:
+//! let shm = SharedMemory::options().name(name).open()?;
+//! # std::io::Result::Ok(())
+//! ```
+//!
+//! ## Handing over a region
+//! ```no_run
+//! use interprocess::{local_socket::Stream, shared_memory::ShmRegion};
+//! # fn with(conn: Stream) -> std::io::Result<()> {
+//! let mut region = ShmRegion::new(16 * 1024 * 1024)?;
+//! // SAFETY: the region has not been shared with anyone yet
+//! unsafe { region.as_mut_slice() }.fill(42);
+//! conn.send_region(region)?;
+//!
+//! // On the other end:
+//! let region = conn.recv_region()?;
+//! // SAFETY: the sender has given up its access to the region
+//! assert!(unsafe { region.as_slice() }.iter().all(|&b| b == 42));
+//! # Ok(()) }
+//! ```
+
+mod ring;
+pub use ring::*;
+
+impmod! {shared_memory,
+    Mapping,
+    Segment,
+    SegmentQueue,
+    claim_segment,
+    create_named,
+    open_named,
+    recv_with_segments,
+    segment_token,
+    send_with_segment,
+}
+use {
+    crate::local_socket::{Name, Stream},
+    std::{
+        io,
+        mem::MaybeUninit,
+        ops::{Deref, DerefMut},
+        sync::atomic::{AtomicU32, Ordering},
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// A builder for [`SharedMemory`].
+#[derive(Debug)]
+pub struct SharedMemoryOptions<'n> {
+    name: Name<'n>,
+    size: Option<usize>,
+}
+impl SharedMemoryOptions<'_> {
+    /// Creates an options table with default values.
+    #[inline]
+    pub fn new() -> Self { Self { name: Name::invalid(), size: None } }
+}
+impl<'n> SharedMemoryOptions<'n> {
+    /// Sets the name of the shared memory object.
+    ///
+    /// Only namespaced names are supported: on Unix, filesystem path names are rejected, and on
+    /// Windows, names of named pipes on other machines are.
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn name(mut self, name: Name<'n>) -> Self {
+        self.name = name;
+        self
+    }
+    /// Sets the size of the shared memory object in bytes.
+    ///
+    /// This is required for [`.create()`](Self::create). For [`.open()`](Self::open), it sets
+    /// the amount of memory that is mapped, which must not exceed the size of the object; if not
+    /// set, the whole object is mapped.
+    #[must_use = builder_must_use!()]
+    #[inline(always)]
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Creates a zero-filled shared memory object, failing with
+    /// [`AlreadyExists`](io::ErrorKind::AlreadyExists) if one with the same name exists.
+    ///
+    /// # Platform-specific behavior
+    /// ## Unix
+    /// The name is unlinked when the returned `SharedMemory` is dropped, even if other processes
+    /// still have the object open.
+    ///
+    /// ## Windows
+    /// The name remains valid for as long as any process has the object open.
+    pub fn create(self) -> io::Result<SharedMemory> {
+        let size = self.size.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "shared memory size not specified")
+        })?;
+        create_named(&self.name, size).and_then(SharedMemory::from_segment)
+    }
+    /// Opens an existing shared memory object.
+    ///
+    /// # Platform-specific behavior
+    /// ## Windows
+    /// If the size is not specified, it is rounded up to the page size, as Windows does not keep
+    /// track of the exact size of shared memory objects.
+    pub fn open(self) -> io::Result<SharedMemory> {
+        open_named(&self.name, self.size).and_then(SharedMemory::from_segment)
+    }
+}
+impl Default for SharedMemoryOptions<'_> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
+
+/// Named shared memory, mapped into the address space of the current process.
+///
+/// Created with [`SharedMemory::options()`]. Dropping a `SharedMemory` unmaps the memory. See the
+/// [module-level documentation](self) for an example.
+///
+/// The memory is accessed through the platform-specific mapping that the `SharedMemory`
+/// dereferences to, which provides `.len()`, `.as_ptr()`, `.as_slice()` and `.as_mut_slice()`.
+///
+/// # Platform-specific behavior
+/// ## Unix
+/// The object is created with `shm_open()`, with a name formed by prepending a slash to the
+/// local socket name, and a mode of `0o600`. Apple platforms limit its length to 31 bytes.
+///
+/// ## Windows
+/// The object is a file mapping backed by the paging file, residing in the session namespace
+/// under `Local\`, followed by the local socket name.
+#[derive(Debug)]
+pub struct SharedMemory {
+    // Declared first so that the view is unmapped before the object is closed.
+    mapping: Mapping,
+    // Only held on to for the sake of dropping it, which unlinks the name on Unix.
+    _segment: Segment,
+}
+impl SharedMemory {
+    /// Creates an options table with default values, from which a `SharedMemory` can be created
+    /// or opened.
+    #[inline]
+    pub fn options<'n>() -> SharedMemoryOptions<'n> { SharedMemoryOptions::new() }
+    fn from_segment(segment: Segment) -> io::Result<Self> {
+        Ok(Self { mapping: segment.map()?, _segment: segment })
+    }
+}
+impl Deref for SharedMemory {
+    type Target = Mapping;
+    #[inline]
+    fn deref(&self) -> &Mapping { &self.mapping }
+}
+impl DerefMut for SharedMemory {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Mapping { &mut self.mapping }
+}
+
+/// Length of the message that accompanies a region: its size, then a platform-specific token.
+const HEADER_LEN: usize = 16;
+
+/// A shared memory object mapped into the address space of the current process.
+///
+/// The object is destroyed once it is no longer mapped into, or referred to by, any process.
+///
+/// Like [`SharedMemory`], a region is accessed through the mapping that it dereferences to. A
+/// region that was created by this process and not yet sent can only be accessed by this
+/// process; a received one can still be accessed by processes which kept the object open, which
+/// the safety requirements of `.as_slice()` and `.as_mut_slice()` must take into account.
+///
+/// # Platform-specific behavior
+/// ## Unix
+/// The object is a `memfd` on Linux and Android and an immediately unlinked `shm_open()`
+/// object elsewhere. It is sent with `SCM_RIGHTS`. On Linux and Android, its size is sealed
+/// when it is sent, and regions whose size is not sealed are rejected when received.
+///
+/// ## Windows
+/// The object is a file mapping backed by the paging file. The sender leaves a handle to it in
+/// its own process, which the receiver moves into its own process. This requires the receiver
+/// to be able to open the sender with `PROCESS_DUP_HANDLE` access – as is the case for
+/// processes of the same user at the same integrity level.
+#[derive(Debug)]
+pub struct ShmRegion {
+    // Declared first so that the view is unmapped before the object is closed.
+    mapping: Mapping,
+    segment: Segment,
+}
+impl ShmRegion {
+    /// Creates a zero-filled region of the given size, which must not be zero.
+    pub fn new(size: usize) -> io::Result<Self> {
+        Segment::create_anonymous(size).and_then(Self::from_segment)
+    }
+    fn from_segment(segment: Segment) -> io::Result<Self> {
+        Ok(Self { mapping: segment.map()?, segment })
+    }
+}
+impl Deref for ShmRegion {
+    type Target = Mapping;
+    #[inline]
+    fn deref(&self) -> &Mapping { &self.mapping }
+}
+impl DerefMut for ShmRegion {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Mapping { &mut self.mapping }
+}
+
+/// How long opening a primitive waits for its creator to finish initializing it.
+const INIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Waits for the creator of a primitive in shared memory to set the flag, which it does once the
+/// primitive is initialized.
+pub(crate) fn wait_until_ready(ready: &AtomicU32) -> io::Result<()> {
+    let start = Instant::now();
+    while ready.load(Ordering::Acquire) == 0 {
+        if start.elapsed() > INIT_TIMEOUT {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the creator of the primitive did not initialize it",
+            ));
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+    Ok(())
+}
+
+pub(crate) fn send_region(stream: &Stream, region: ShmRegion) -> io::Result<()> {
+    let ShmRegion { mapping, segment } = region;
+    drop(mapping);
+    let size = u64::try_from(segment.size()).map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidInput, "region is too big to be sent")
+    })?;
+    let token = segment_token(stream, &segment)?;
+    let mut header = [0; HEADER_LEN];
+    header
+        .iter_mut()
+        .zip(size.to_le_bytes().into_iter().chain(token.to_le_bytes()))
+        .for_each(|(dst, src)| *dst = src);
+    send_with_segment(stream, &header, &segment)
+}
+
+pub(crate) fn recv_region(stream: &Stream) -> io::Result<ShmRegion> {
+    let mut header = [MaybeUninit::<u8>::uninit(); HEADER_LEN];
+    let mut segments = SegmentQueue::default();
+    let mut filled = 0;
+    while let Some(rem) = header.get_mut(filled..).filter(|rem| !rem.is_empty()) {
+        match recv_with_segments(stream, rem, &mut segments) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(incr) => filled = filled.saturating_add(incr),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    // SAFETY: the loop above has initialized the whole header
+    let header = header.map(|b| unsafe { b.assume_init() });
+    let read_u64 = |at: usize| {
+        let mut bytes = [0; 8];
+        bytes.iter_mut().zip(header.iter().skip(at)).for_each(|(dst, src)| *dst = *src);
+        u64::from_le_bytes(bytes)
+    };
+    let size = usize::try_from(read_u64(0)).map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidData, "received region is too big for this platform")
+    })?;
+    let segment = claim_segment(stream, read_u64(8), size, &mut segments)?;
+    ShmRegion::from_segment(segment)
+}
@@ -0,0 +1,331 @@
+use {
+    super::SharedMemory,
+    crate::local_socket::Name,
+    std::{
+        error::Error,
+        fmt::{self, Display, Formatter},
+        io,
+        mem::size_of,
+        sync::atomic::{fence, AtomicU64, Ordering},
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// How long opening a ring waits for its creator to finish initializing it.
+const INIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[repr(C)]
+struct Header {
+    ready: AtomicU64,
+    slot_size: AtomicU64,
+    slot_count: AtomicU64,
+    /// The number of messages published so far.
+    head: AtomicU64,
+}
+#[repr(C)]
+struct SlotHeader {
+    /// Seqlock word: odd while message number `(seq - 1) / 2` is being written into the slot,
+    /// and `2 * (number + 1)` once it's been written.
+    seq: AtomicU64,
+    len: AtomicU64,
+}
+
+/// Layout of a ring, as stored in its header.
+#[derive(Copy, Clone, Debug)]
+struct Layout {
+    slot_size: usize,
+    slot_count: u64,
+    stride: usize,
+}
+impl Layout {
+    fn new(slot_size: usize, slot_count: u64) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidInput, "invalid ring dimensions");
+        if slot_count == 0 {
+            return Err(invalid());
+        }
+        // Slot headers have to stay 8-byte aligned.
+        let stride = slot_size
+            .checked_next_multiple_of(8)
+            .and_then(|sz| sz.checked_add(size_of::<SlotHeader>()))
+            .ok_or_else(invalid)?;
+        Ok(Self { slot_size, slot_count, stride })
+    }
+    fn total_size(&self) -> Option<usize> {
+        usize::try_from(self.slot_count)
+            .ok()?
+            .checked_mul(self.stride)?
+            .checked_add(size_of::<Header>())
+    }
+}
+
+/// State shared by both ends.
+#[derive(Debug)]
+struct Ring {
+    shm: SharedMemory,
+    layout: Layout,
+}
+impl Ring {
+    fn header(&self) -> &Header {
+        // SAFETY: the mapping is page-aligned and at least as big as the header
+        unsafe { &*self.shm.as_ptr().cast::<Header>() }
+    }
+    /// Returns the slot header and data pointer of the slot that message number `n` goes into.
+    fn slot(&self, n: u64) -> (&SlotHeader, *mut u8) {
+        let idx = n.checked_rem(self.layout.slot_count).unwrap_or(0);
+        let idx = usize::try_from(idx).unwrap_or(0);
+        let offset = size_of::<Header>().saturating_add(idx.saturating_mul(self.layout.stride));
+        // SAFETY: the layout was checked against the size of the mapping, and slot headers are
+        // 8-byte aligned
+        unsafe {
+            let slot = self.shm.as_ptr().add(offset);
+            (&*slot.cast::<SlotHeader>(), slot.add(size_of::<SlotHeader>()))
+        }
+    }
+}
+
+/// The sending end of a single-producer, multi-consumer broadcast ring in shared memory.
+///
+/// The ring consists of a fixed number of fixed-size slots that messages are written into in
+/// turn. The sender never waits for receivers: once the ring wraps around, the oldest message is
+/// overwritten, and receivers which have not read it yet find out that they have
+/// [lagged behind](TryRecvError::Lagged). This makes rings fit for distributing frequently
+/// updated data, such as telemetry, where only recent values matter.
+///
+/// Each slot is guarded by a seqlock, so receivers never see a message that is only partially
+/// written, and neither side ever blocks the other. Receivers poll for new messages.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::{
+///     local_socket::{GenericNamespaced, ToNsName},
+///     shared_memory::{RingReceiver, RingSender, TryRecvError},
+/// };
+///
+/// let name = "example-telemetry".to_ns_name::<GenericNamespaced>()?;
+/// let mut sender = RingSender::create(name.borrow(), 64, 1024)?;
+/// let mut receiver = RingReceiver::open(name)?;
+///
+/// sender.send(b"temperature=21.5")?;
+/// match receiver.try_recv() {
+///     Ok(msg) => println!("{}", String::from_utf8_lossy(&msg)),
+///     Err(TryRecvError::Empty) => println!("nothing new"),
+///     Err(TryRecvError::Lagged(n)) => println!("missed {n} messages"),
+/// }
+/// # std::io::Result::Ok(())
+/// ```
+#[derive(Debug)]
+pub struct RingSender {
+    ring: Ring,
+    next: u64,
+}
+impl RingSender {
+    /// Creates a ring of `slot_count` slots, each of which holds a message of up to `slot_size`
+    /// bytes, failing with [`AlreadyExists`](io::ErrorKind::AlreadyExists) if shared memory with
+    /// the same name exists.
+    pub fn create(name: Name<'_>, slot_size: usize, slot_count: usize) -> io::Result<Self> {
+        let slot_count = u64::try_from(slot_count).unwrap_or(u64::MAX);
+        let layout = Layout::new(slot_size, slot_count)?;
+        let size = layout.total_size().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "ring is too big for this platform")
+        })?;
+        let shm = SharedMemory::options().name(name).size(size).create()?;
+        let ring = Ring { shm, layout };
+        let header = ring.header();
+        header.slot_size.store(u64::try_from(slot_size).unwrap_or(u64::MAX), Ordering::Relaxed);
+        header.slot_count.store(slot_count, Ordering::Relaxed);
+        header.ready.store(1, Ordering::Release);
+        Ok(Self { ring, next: 0 })
+    }
+    /// Returns the largest message that fits into a slot.
+    #[inline]
+    pub fn slot_size(&self) -> usize { self.ring.layout.slot_size }
+
+    /// Publishes a message, overwriting the oldest one if the ring is full. Never blocks.
+    ///
+    /// Messages longer than the [slot size](Self::slot_size) are rejected with
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput).
+    pub fn send(&mut self, msg: &[u8]) -> io::Result<()> {
+        if msg.len() > self.ring.layout.slot_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "message does not fit into a slot",
+            ));
+        }
+        let n = self.next;
+        let (slot, data) = self.ring.slot(n);
+        let seq = n.saturating_mul(2);
+        slot.seq.store(seq.saturating_add(1), Ordering::Relaxed);
+        // Keeps the writes below from becoming visible before the slot is marked as being
+        // written.
+        fence(Ordering::Release);
+        slot.len.store(u64::try_from(msg.len()).unwrap_or(u64::MAX), Ordering::Relaxed);
+        // SAFETY: the slot has room for the message, rounded up to whole words
+        unsafe { store_words(data, msg) };
+        slot.seq.store(seq.saturating_add(2), Ordering::Release);
+        self.next = n.saturating_add(1);
+        self.ring.header().head.store(self.next, Ordering::Release);
+        Ok(())
+    }
+}
+
+/// The receiving end of a broadcast ring. See [`RingSender`].
+///
+/// Any number of receivers, in any number of processes, can read from the same ring, each at
+/// its own pace.
+#[derive(Debug)]
+pub struct RingReceiver {
+    ring: Ring,
+    next: u64,
+}
+impl RingReceiver {
+    /// Opens an existing ring. Only messages sent after this point are received.
+    pub fn open(name: Name<'_>) -> io::Result<Self> {
+        let shm = SharedMemory::options().name(name).open()?;
+        if shm.len() < size_of::<Header>() {
+            return Err(invalid_ring());
+        }
+        // SAFETY: as in `Ring::header()`
+        let header = unsafe { &*shm.as_ptr().cast::<Header>() };
+        let start = Instant::now();
+        while header.ready.load(Ordering::Acquire) == 0 {
+            if start.elapsed() > INIT_TIMEOUT {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "the creator of the ring did not initialize it",
+                ));
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        let slot_size = usize::try_from(header.slot_size.load(Ordering::Relaxed))
+            .map_err(|_| invalid_ring())?;
+        let layout = Layout::new(slot_size, header.slot_count.load(Ordering::Relaxed))?;
+        match layout.total_size() {
+            Some(size) if size <= shm.len() => {}
+            _ => return Err(invalid_ring()),
+        }
+        let next = header.head.load(Ordering::Acquire);
+        Ok(Self { ring: Ring { shm, layout }, next })
+    }
+    /// Returns the largest message that fits into a slot.
+    #[inline]
+    pub fn slot_size(&self) -> usize { self.ring.layout.slot_size }
+
+    /// Receives the next message, if one has been sent. Never blocks.
+    ///
+    /// If the sender has overwritten messages that this receiver has not read yet, their number
+    /// is reported with [`TryRecvError::Lagged`], and the next call returns the oldest message
+    /// that is still in the ring.
+    pub fn try_recv(&mut self) -> Result<Vec<u8>, TryRecvError> {
+        if self.lag() == 0 {
+            return Err(TryRecvError::Empty);
+        }
+        let mut buf = vec![0; self.ring.layout.slot_size];
+        let len = self.try_recv_into(&mut buf)?;
+        buf.truncate(len);
+        Ok(buf)
+    }
+    /// Like [`.try_recv()`](Self::try_recv), but receives into the given buffer, which must be
+    /// at least as long as the [slot size](Self::slot_size), returning the length of the
+    /// message.
+    ///
+    /// # Panics
+    /// If the buffer is too short.
+    pub fn try_recv_into(&mut self, buf: &mut [u8]) -> Result<usize, TryRecvError> {
+        assert!(buf.len() >= self.ring.layout.slot_size, "buffer is shorter than a slot");
+        let head = self.ring.header().head.load(Ordering::Acquire);
+        if self.next >= head {
+            return Err(TryRecvError::Empty);
+        }
+        let oldest = head.saturating_sub(self.ring.layout.slot_count);
+        if self.next < oldest {
+            return Err(self.skip_to(oldest));
+        }
+
+        let n = self.next;
+        let (slot, data) = self.ring.slot(n);
+        let expected = n.saturating_add(1).saturating_mul(2);
+        if slot.seq.load(Ordering::Acquire) != expected {
+            // The sender has already started writing a newer message into the slot.
+            return Err(self.skip_to(n.saturating_add(1)));
+        }
+        let len = usize::try_from(slot.len.load(Ordering::Relaxed)).unwrap_or(usize::MAX);
+        let len = len.min(self.ring.layout.slot_size);
+        if let Some(dst) = buf.get_mut(..len) {
+            // SAFETY: the slot holds `len` bytes, rounded up to whole words
+            unsafe { load_words(data.cast_const(), dst) };
+        }
+        // Keeps the reads above from being reordered after the check below.
+        fence(Ordering::Acquire);
+        if slot.seq.load(Ordering::Relaxed) != expected {
+            return Err(self.skip_to(n.saturating_add(1)));
+        }
+        self.next = n.saturating_add(1);
+        Ok(len)
+    }
+    /// Returns how many messages have been sent but not yet received, including ones that
+    /// have been overwritten.
+    #[inline]
+    pub fn lag(&self) -> u64 {
+        self.ring.header().head.load(Ordering::Acquire).saturating_sub(self.next)
+    }
+
+    fn skip_to(&mut self, n: u64) -> TryRecvError {
+        let missed = n.saturating_sub(self.next);
+        self.next = n;
+        TryRecvError::Lagged(missed)
+    }
+}
+
+/// Copies the message into the slot data at `data` as a sequence of relaxed atomic stores of
+/// whole words, since receivers may be reading the slot at the same time. They discard what they
+/// read if the sequence number changes in the meantime, but the reads themselves must not race
+/// with plain writes.
+///
+/// # Safety
+/// `data` must be 8-byte aligned and valid for writes of the message's length rounded up to a
+/// multiple of 8 – slot data is padded to that.
+unsafe fn store_words(data: *mut u8, msg: &[u8]) {
+    for (i, chunk) in msg.chunks(size_of::<u64>()).enumerate() {
+        let mut word = [0; size_of::<u64>()];
+        word.iter_mut().zip(chunk).for_each(|(dst, src)| *dst = *src);
+        // SAFETY: guaranteed by the caller
+        let dst = unsafe { &*data.cast::<AtomicU64>().add(i) };
+        dst.store(u64::from_ne_bytes(word), Ordering::Relaxed);
+    }
+}
+/// The counterpart of [`store_words()`], filling `buf` from the slot data at `data`.
+///
+/// # Safety
+/// `data` must be 8-byte aligned and valid for reads of `buf.len()` rounded up to a multiple of
+/// 8.
+unsafe fn load_words(data: *const u8, buf: &mut [u8]) {
+    for (i, chunk) in buf.chunks_mut(size_of::<u64>()).enumerate() {
+        // SAFETY: guaranteed by the caller
+        let src = unsafe { &*data.cast::<AtomicU64>().add(i) };
+        let word = src.load(Ordering::Relaxed).to_ne_bytes();
+        chunk.iter_mut().zip(word).for_each(|(dst, src)| *dst = src);
+    }
+}
+
+fn invalid_ring() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "shared memory does not hold a valid ring")
+}
+
+/// Error type of [`RingReceiver::try_recv()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// No message has been sent since the last one that was received.
+    Empty,
+    /// The given number of messages was overwritten before it could be received.
+    Lagged(u64),
+}
+impl Display for TryRecvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("no message in the ring"),
+            Self::Lagged(n) => write!(f, "receiver lagged behind by {n} messages"),
+        }
+    }
+}
+impl Error for TryRecvError {}
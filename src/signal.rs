@@ -0,0 +1,171 @@
+//! Notification of requests to terminate the process.
+//!
+//! Daemons built around IPC need to shut down cleanly when asked to – removing socket files,
+//! flushing state into shared memory, telling clients goodbye. This module catches the ways in
+//! which a process is asked to terminate on each platform and reports them as [`Signal`]s,
+//! either to callbacks registered with [`on_signal()`] or through a [`Signals`] subscription,
+//! which can be waited on synchronously or, with the `tokio` feature, asynchronously.
+//!
+//! The handlers are installed the first time either is used, after which the signals no longer
+//! terminate the process – it is up to the program to exit once it has cleaned up.
+//!
+//! # Example
+//! ```no_run
+//! use interprocess::signal::Signals;
+//!
+//! let signals = Signals::new()?;
+//! // ...start serving clients on other threads...
+//! let signal = signals.recv();
+//! eprintln!("shutting down on {signal:?}");
+//! # std::io::Result::Ok(())
+//! ```
+//!
+//! # Platform-specific behavior
+//! ## Unix
+//! `SIGINT`, `SIGTERM` and `SIGHUP` are caught with `sigaction()`, and their handlers forward
+//! them to a helper thread through a pipe. Callbacks are called on that thread.
+//!
+//! ## Windows
+//! Console control events are caught with `SetConsoleCtrlHandler()`, and callbacks are called on
+//! the thread that the system creates for each event. The system terminates the process shortly
+//! after the handler returns from a [`Close`](Signal::Close) or [`Terminate`](Signal::Terminate)
+//! event, which only gives callbacks a chance to clean up.
+
+impmod! {signal, install}
+use std::{
+    collections::VecDeque,
+    io,
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError, Weak},
+    time::Duration,
+};
+
+/// A request for the process to terminate.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Signal {
+    /// The user pressed Ctrl+C – `SIGINT` on Unix, or `CTRL_C_EVENT` or `CTRL_BREAK_EVENT` on
+    /// Windows.
+    Interrupt,
+    /// The process is asked to terminate – `SIGTERM` on Unix, or `CTRL_LOGOFF_EVENT` or
+    /// `CTRL_SHUTDOWN_EVENT` on Windows.
+    Terminate,
+    /// The controlling terminal has gone away, or, by convention for daemons, the configuration
+    /// is to be reloaded – `SIGHUP`. Unix only.
+    Hangup,
+    /// The console window is being closed – `CTRL_CLOSE_EVENT`. Windows only.
+    Close,
+}
+
+type Callback = Box<dyn Fn(Signal) + Send + Sync>;
+
+struct Registry {
+    installed: bool,
+    callbacks: Vec<Callback>,
+    queues: Vec<Weak<Queue>>,
+}
+static REGISTRY: Mutex<Registry> =
+    Mutex::new(Registry { installed: false, callbacks: Vec::new(), queues: Vec::new() });
+
+fn registry() -> MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+/// Locks the registry, installing the handlers if that hasn't been done yet.
+fn installed_registry() -> io::Result<MutexGuard<'static, Registry>> {
+    let mut registry = registry();
+    if !registry.installed {
+        install(dispatch)?;
+        registry.installed = true;
+    }
+    Ok(registry)
+}
+
+fn dispatch(signal: Signal) {
+    let mut registry = registry();
+    registry.queues.retain(|queue| match queue.upgrade() {
+        Some(queue) => {
+            queue.push(signal);
+            true
+        }
+        None => false,
+    });
+    for callback in &registry.callbacks {
+        callback(signal);
+    }
+}
+
+/// Registers a callback which is called with every signal that arrives from now on.
+///
+/// Callbacks cannot be unregistered. They must not call `on_signal()` or create [`Signals`],
+/// which deadlocks.
+pub fn on_signal(callback: impl Fn(Signal) + Send + Sync + 'static) -> io::Result<()> {
+    installed_registry()?.callbacks.push(Box::new(callback));
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct Queue {
+    pending: Mutex<VecDeque<Signal>>,
+    condvar: Condvar,
+    #[cfg(feature = "tokio")]
+    notify: tokio::sync::Notify,
+}
+impl Queue {
+    fn pending(&self) -> MutexGuard<'_, VecDeque<Signal>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    fn push(&self, signal: Signal) {
+        self.pending().push_back(signal);
+        self.condvar.notify_all();
+        #[cfg(feature = "tokio")]
+        self.notify.notify_one();
+    }
+}
+
+/// A subscription to signals, which queues up every signal that arrives while it exists.
+///
+/// Any number of subscriptions can exist at once, and each receives every signal.
+#[derive(Debug)]
+pub struct Signals(Arc<Queue>);
+impl Signals {
+    /// Subscribes to signals.
+    pub fn new() -> io::Result<Self> {
+        let queue = Arc::new(Queue::default());
+        installed_registry()?.queues.push(Arc::downgrade(&queue));
+        Ok(Self(queue))
+    }
+    /// Returns the oldest signal that has not been received yet, if there is one.
+    #[inline]
+    pub fn try_recv(&self) -> Option<Signal> { self.0.pending().pop_front() }
+    /// Blocks until a signal arrives, unless one has already arrived and not yet been received.
+    pub fn recv(&self) -> Signal {
+        let mut pending = self.0.pending();
+        loop {
+            if let Some(signal) = pending.pop_front() {
+                return signal;
+            }
+            pending = self.0.condvar.wait(pending).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+    /// Like [`.recv()`](Self::recv), but gives up and returns `None` once the timeout expires.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Signal> {
+        let pending = self.0.pending();
+        let (mut pending, _) = self
+            .0
+            .condvar
+            .wait_timeout_while(pending, timeout, |pending| pending.is_empty())
+            .unwrap_or_else(PoisonError::into_inner);
+        pending.pop_front()
+    }
+    /// Waits asynchronously until a signal arrives, unless one has already arrived and not yet
+    /// been received.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+    pub async fn recv_async(&self) -> Signal {
+        loop {
+            if let Some(signal) = self.try_recv() {
+                return signal;
+            }
+            self.0.notify.notified().await;
+        }
+    }
+}
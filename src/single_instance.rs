@@ -0,0 +1,155 @@
+//! Detection of already running instances of an application, with forwarding of requests to
+//! them.
+//!
+//! A [`Guard`] is a local socket listener that serves as proof of an instance being the first one
+//! to run. Later instances find it already bound and connect to it instead, which lets them hand
+//! over their command-line arguments or other requests before exiting.
+//!
+//! # Examples
+//! ```no_run
+//! use {
+//!     interprocess::{
+//!         local_socket::{prelude::*, GenericNamespaced},
+//!         single_instance::{Acquired, Guard},
+//!     },
+//!     std::io::{prelude::*, BufReader},
+//! };
+//!
+//! let name = "example-app.sock".to_ns_name::<GenericNamespaced>()?;
+//! match Guard::acquire(name)? {
+//!     Acquired::Primary(guard) => {
+//!         for conn in guard.listener().incoming().filter_map(Result::ok) {
+//!             let mut line = String::new();
+//!             BufReader::new(conn).read_line(&mut line)?;
+//!             println!("Arguments forwarded by another instance: {line}");
+//!         }
+//!     }
+//!     Acquired::Secondary(mut conn) => {
+//!         let args = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+//!         conn.write_all(format!("{args}\n").as_bytes())?;
+//!     }
+//! }
+//! # std::io::Result::Ok(())
+//! ```
+
+use {
+    crate::local_socket::{prelude::*, Listener, ListenerOptions, Name, Stream},
+    std::io,
+};
+#[cfg(unix)]
+use {
+    crate::{
+        fs_lock::{FileLock, LockKind},
+        local_socket::NameInner,
+    },
+    std::fs,
+};
+
+/// The outcome of [`Guard::acquire()`].
+#[derive(Debug)]
+pub enum Acquired {
+    /// No other instance was running, and this one now holds the guard.
+    Primary(Guard),
+    /// Another instance holds the guard. The stream is connected to its listener.
+    Secondary(Stream),
+}
+
+/// Proof of an instance of an application being the only one that is running under a given name.
+///
+/// The guard is released when it's dropped, after which another instance can acquire it.
+#[derive(Debug)]
+pub struct Guard {
+    listener: Listener,
+}
+impl Guard {
+    /// Attempts to become the primary instance for the given name, connecting to the instance
+    /// which already is if that fails.
+    ///
+    /// The check is atomic: binding the name fails if another instance holds it, so two instances
+    /// starting at the same time cannot both become primary.
+    ///
+    /// # Stale names
+    /// On Unix, a filesystem path name can be left behind by an instance that crashed and thus
+    /// never got to perform [name reclamation](Listener#name-reclamation). If connecting to such
+    /// a name is refused, the socket file is deleted and binding is attempted again, once.
+    ///
+    /// Instances which find the same stale name take turns at this using an
+    /// [exclusive lock](crate::fs_lock) on a file named like the socket file with `.lock`
+    /// appended, and each of them checks that the name is still refusing connections once its
+    /// turn comes. This keeps one instance from deleting the socket file of another which has
+    /// just become primary. The lock file is left in place afterwards.
+    pub fn acquire(name: Name<'_>) -> io::Result<Acquired> {
+        match Self::bind(name.borrow()) {
+            Ok(guard) => return Ok(Acquired::Primary(guard)),
+            Err(e) if !is_taken(&e) => return Err(e),
+            Err(..) => {}
+        }
+        match Stream::connect(name.borrow()) {
+            Ok(conn) => Ok(Acquired::Secondary(conn)),
+            Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => Self::replace_stale(name),
+            Err(e) => Err(e),
+        }
+    }
+    /// Like [`.acquire()`](Self::acquire), but returns `None` instead of a stream if another
+    /// instance is running.
+    ///
+    /// The other instance may still see a connection that closes without sending anything, since
+    /// connecting is the only way to tell a running instance apart from a stale name.
+    #[inline]
+    pub fn try_acquire(name: Name<'_>) -> io::Result<Option<Self>> {
+        Self::acquire(name).map(|acq| match acq {
+            Acquired::Primary(guard) => Some(guard),
+            Acquired::Secondary(..) => None,
+        })
+    }
+
+    /// Borrows the listener, which can be used to accept connections from other instances.
+    #[inline]
+    pub fn listener(&self) -> &Listener { &self.listener }
+    /// Unwraps the listener. The guard remains held for as long as the listener exists.
+    #[inline]
+    pub fn into_listener(self) -> Listener { self.listener }
+
+    fn bind(name: Name<'_>) -> io::Result<Self> {
+        ListenerOptions::new().name(name).create_sync().map(|listener| Self { listener })
+    }
+    #[cfg(unix)]
+    fn replace_stale(name: Name<'_>) -> io::Result<Acquired> {
+        let NameInner::UdSocketPath(path) = &name.0 else {
+            return Err(io::ErrorKind::ConnectionRefused.into());
+        };
+        let mut lock_path = path.clone().into_owned();
+        lock_path.push(".lock");
+        let mut lock = FileLock::open(lock_path)?;
+        let _guard = lock.lock(LockKind::Exclusive)?;
+
+        // Another instance may have replaced the stale socket while we were waiting for the lock.
+        match Stream::connect(name.borrow()) {
+            Ok(conn) => return Ok(Acquired::Secondary(conn)),
+            Err(e) if e.kind() != io::ErrorKind::ConnectionRefused => return Err(e),
+            Err(..) => {}
+        }
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        // An instance which didn't go through the lock can only have bound the name after the
+        // stale socket file was deleted, in which case it is the primary one.
+        match Self::bind(name.borrow()) {
+            Ok(guard) => Ok(Acquired::Primary(guard)),
+            Err(e) if is_taken(&e) => Stream::connect(name).map(Acquired::Secondary),
+            Err(e) => Err(e),
+        }
+    }
+    #[cfg(not(unix))]
+    fn replace_stale(_: Name<'_>) -> io::Result<Acquired> {
+        Err(io::ErrorKind::ConnectionRefused.into())
+    }
+}
+
+/// Windows reports an existing pipe with the `FILE_FLAG_FIRST_PIPE_INSTANCE` flag as access being
+/// denied.
+fn is_taken(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::AddrInUse
+        || (cfg!(windows) && e.kind() == io::ErrorKind::PermissionDenied)
+}
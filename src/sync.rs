@@ -0,0 +1,349 @@
+//! Synchronization primitives shared between processes.
+//!
+//! The primitives are named with [local socket names](crate::local_socket::Name), like
+//! [shared memory](crate::shared_memory) is. One process creates a primitive, and others open it
+//! by its name. Names are shared between all kinds of primitives and shared memory, so each
+//! primitive needs a name of its own.
+//!
+//! On Unix, each primitive is stored in a shared memory object with the very name of the
+//! primitive, so that a [`Mutex`], a [`Condvar`], a [`ProcessBarrier`] and a [`SharedMemory`] of
+//! the same name are all the same object. Creating one fails with
+//! [`AlreadyExists`](std::io::ErrorKind::AlreadyExists) if any of the others exists, and opening
+//! one by the name of another reinterprets the latter's memory, with unspecified results. On
+//! Windows, the same goes for everything but [`Mutex`], which is a kernel object that cannot be
+//! created or opened under the name of a file mapping object.
+//!
+//! # Examples
+//! A [`Mutex`] and a [`Condvar`] guarding a counter in shared memory:
+//! ```no_run
+//! use {
+//!     interprocess::{
+//!         local_socket::{GenericNamespaced, ToNsName},
+//!         shared_memory::SharedMemory,
+//!         sync::{Condvar, Mutex},
+//!     },
+//!     std::sync::atomic::{AtomicU32, Ordering::Relaxed},
+//! };
+//!
+//! let shm_name = "example-counter".to_ns_name::<GenericNamespaced>()?;
+//! let shm = SharedMemory::options().name(shm_name).size(4).open()?;
+//! // SAFETY: shared memory is page-aligned
+//! let counter = unsafe { &*shm.as_ptr().cast::<AtomicU32>() };
+//! let mutex = Mutex::open("example-counter-mutex".to_ns_name::<GenericNamespaced>()?)?;
+//! let condvar = Condvar::open("example-counter-condvar".to_ns_name::<GenericNamespaced>()?)?;
+//!
+//! let mut guard = mutex.lock()?.into_guard();
+//! while counter.load(Relaxed) == 0 {
+//!     guard = condvar.wait(guard)?.into_guard();
+//! }
+//! counter.fetch_sub(1, Relaxed);
+//! # std::io::Result::Ok(())
+//! ```
+
+impmod! {condvar, Condvar as CondvarImpl}
+impmod! {mutex, Mutex as MutexImpl}
+impmod! {semaphore,
+    NamedSemaphore as SemaphoreImpl,
+    create_named as create_semaphore,
+    open_named as open_semaphore,
+}
+use {
+    crate::{
+        local_socket::Name,
+        shared_memory::{wait_until_ready, SharedMemory},
+    },
+    std::{
+        io,
+        marker::PhantomData,
+        mem::size_of,
+        sync::atomic::{AtomicU32, Ordering},
+        time::Duration,
+    },
+};
+
+/// A mutual exclusion lock shared between processes, which detects its owner having died while
+/// holding it.
+///
+/// The mutex protects no data of its own – it is meant to guard data structures in
+/// [shared memory](crate::shared_memory). If a process crashes while holding the lock, the next
+/// one to acquire it is told so by [`LockResult::OwnerDied`], and can repair the data structures
+/// that the dead process may have left in an inconsistent state.
+///
+/// # Platform-specific behavior
+/// ## Unix
+/// The mutex is a robust, process-shared pthread mutex in a shared memory object. Robust mutexes
+/// are only supported on Linux and FreeBSD; creating a mutex on other platforms fails with
+/// [`Unsupported`](io::ErrorKind::Unsupported). Locking a mutex which the current thread already
+/// holds deadlocks. The name stops referring to the mutex when the `Mutex` that created it is
+/// dropped.
+///
+/// ## Windows
+/// The mutex is a named kernel mutex in the session namespace. It can be locked recursively by
+/// the thread which holds it, which must then unlock it as many times. The name remains valid for
+/// as long as any process has the mutex open.
+#[derive(Debug)]
+pub struct Mutex(MutexImpl);
+impl Mutex {
+    /// Creates a mutex, failing with [`AlreadyExists`](io::ErrorKind::AlreadyExists) if one with
+    /// the same name exists.
+    #[inline]
+    pub fn create(name: Name<'_>) -> io::Result<Self> { MutexImpl::create(&name).map(Self) }
+    /// Opens an existing mutex.
+    #[inline]
+    pub fn open(name: Name<'_>) -> io::Result<Self> { MutexImpl::open(&name).map(Self) }
+
+    /// Acquires the lock, blocking until it's available.
+    pub fn lock(&self) -> io::Result<LockResult<'_>> {
+        self.lock_impl(None)?.ok_or_else(|| io::Error::other("unexpected timeout"))
+    }
+    /// Acquires the lock if it's available, returning `None` otherwise.
+    #[inline]
+    pub fn try_lock(&self) -> io::Result<Option<LockResult<'_>>> {
+        self.lock_impl(Some(Duration::ZERO))
+    }
+    /// Acquires the lock, blocking until it's available or until the timeout expires, in which
+    /// case `None` is returned.
+    #[inline]
+    pub fn lock_timeout(&self, timeout: Duration) -> io::Result<Option<LockResult<'_>>> {
+        self.lock_impl(Some(timeout))
+    }
+    fn lock_impl(&self, timeout: Option<Duration>) -> io::Result<Option<LockResult<'_>>> {
+        Ok(self.0.lock(timeout)?.map(|owner_died| LockResult::new(self, owner_died)))
+    }
+}
+
+/// The outcome of locking a [`Mutex`].
+#[derive(Debug)]
+pub enum LockResult<'m> {
+    /// The lock was acquired after having been released normally.
+    Acquired(MutexGuard<'m>),
+    /// The lock was acquired after its previous owner died while holding it. The data that it
+    /// protects may need to be repaired.
+    ///
+    /// The mutex itself is fully functional, and later owners will see
+    /// [`Acquired`](Self::Acquired) again.
+    OwnerDied(MutexGuard<'m>),
+}
+impl<'m> LockResult<'m> {
+    fn new(mutex: &'m Mutex, owner_died: bool) -> Self {
+        Self::from_guard(MutexGuard { mutex, _not_send: PhantomData }, owner_died)
+    }
+    fn from_guard(guard: MutexGuard<'m>, owner_died: bool) -> Self {
+        if owner_died {
+            Self::OwnerDied(guard)
+        } else {
+            Self::Acquired(guard)
+        }
+    }
+    /// Returns `true` if the previous owner died while holding the lock.
+    #[inline]
+    pub fn owner_died(&self) -> bool { matches!(self, Self::OwnerDied(..)) }
+    /// Returns the guard, discarding the information on whether the previous owner died.
+    #[inline]
+    pub fn into_guard(self) -> MutexGuard<'m> {
+        match self {
+            Self::Acquired(guard) | Self::OwnerDied(guard) => guard,
+        }
+    }
+}
+
+/// Proof of the current thread holding the lock of a [`Mutex`], which is released on drop.
+///
+/// The lock is owned by the thread that acquired it, which is why the guard cannot be sent to
+/// other threads.
+#[derive(Debug)]
+pub struct MutexGuard<'m> {
+    mutex: &'m Mutex,
+    _not_send: PhantomData<*const ()>,
+}
+// SAFETY: sharing references to the guard does not allow unlocking from other threads.
+unsafe impl Sync for MutexGuard<'_> {}
+impl MutexGuard<'_> {
+    /// Returns the mutex that the guard holds the lock of.
+    #[inline]
+    pub fn mutex(&self) -> &Mutex { self.mutex }
+}
+impl Drop for MutexGuard<'_> {
+    fn drop(&mut self) {
+        // Only fails if the current thread does not hold the lock, which the guard rules out.
+        let _ = self.mutex.0.unlock();
+    }
+}
+
+/// A condition variable shared between processes, for use with a [`Mutex`].
+///
+/// Like with any condition variable, wakeups can be spurious, and waiting is thus to be done in a
+/// loop that checks the condition.
+///
+/// # Platform-specific behavior
+/// ## Unix
+/// The condition variable is a process-shared pthread condition variable in a shared memory
+/// object. Timeouts are measured with `CLOCK_MONOTONIC`, except on Apple platforms, where
+/// `CLOCK_REALTIME` is used.
+///
+/// ## Windows
+/// The condition variable is made of a count of waiters in a shared memory object and a
+/// semaphore, with a name formed by appending `.condvar-sem` to that of the shared memory object.
+/// The mutex must not be held recursively while waiting.
+#[derive(Debug)]
+pub struct Condvar(CondvarImpl);
+impl Condvar {
+    /// Creates a condition variable, failing with [`AlreadyExists`](io::ErrorKind::AlreadyExists)
+    /// if one with the same name exists.
+    #[inline]
+    pub fn create(name: Name<'_>) -> io::Result<Self> { CondvarImpl::create(&name).map(Self) }
+    /// Opens an existing condition variable.
+    #[inline]
+    pub fn open(name: Name<'_>) -> io::Result<Self> { CondvarImpl::open(&name).map(Self) }
+
+    /// Releases the lock and blocks until notified, reacquiring the lock before returning.
+    ///
+    /// The result tells whether the previous owner of the lock died while this thread was
+    /// waiting to reacquire it.
+    pub fn wait<'m>(&self, guard: MutexGuard<'m>) -> io::Result<LockResult<'m>> {
+        let (owner_died, _) = self.0.wait(&guard.mutex.0, None)?;
+        Ok(LockResult::from_guard(guard, owner_died))
+    }
+    /// Like [`.wait()`](Self::wait), but also returns after the timeout expires, which is
+    /// indicated by the returned boolean being `true`.
+    pub fn wait_timeout<'m>(
+        &self,
+        guard: MutexGuard<'m>,
+        timeout: Duration,
+    ) -> io::Result<(LockResult<'m>, bool)> {
+        let (owner_died, timed_out) = self.0.wait(&guard.mutex.0, Some(timeout))?;
+        Ok((LockResult::from_guard(guard, owner_died), timed_out))
+    }
+    /// Wakes up one thread waiting on the condition variable, if there is any.
+    #[inline]
+    pub fn notify_one(&self) -> io::Result<()> { self.0.notify_one() }
+    /// Wakes up all threads waiting on the condition variable.
+    #[inline]
+    pub fn notify_all(&self) -> io::Result<()> { self.0.notify_all() }
+}
+
+/// Suffixes of the names of the two semaphores of a barrier, which successive rounds alternate
+/// between.
+const BARRIER_SEMAPHORE_SUFFIXES: [&str; 2] = [".barrier-sem0", ".barrier-sem1"];
+
+#[repr(C)]
+struct BarrierState {
+    ready: AtomicU32,
+    n: AtomicU32,
+    arrived: AtomicU32,
+    round: AtomicU32,
+}
+
+/// A barrier which blocks until a given number of processes (or threads) have arrived at it.
+///
+/// Every participant calls [`ProcessBarrier::new()`] with the same name and number of
+/// participants – whichever gets there first creates the barrier, and the rest open it. This
+/// makes barriers fit for coordinating the startup of a group of processes, such as the workers
+/// of a test harness, each of which waits at the barrier once it is ready. Barriers can be
+/// reused for any number of rounds.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::{
+///     local_socket::{GenericNamespaced, ToNsName},
+///     sync::ProcessBarrier,
+/// };
+///
+/// // In each of the four worker processes:
+/// let barrier = ProcessBarrier::new("example-startup".to_ns_name::<GenericNamespaced>()?, 4)?;
+/// // ...initialization...
+/// barrier.wait()?;
+/// // All four workers are ready by now.
+/// # std::io::Result::Ok(())
+/// ```
+///
+/// # Platform-specific behavior
+/// The barrier is made of a shared memory object and two semaphores – POSIX named semaphores on
+/// Unix and kernel semaphores on Windows – with names formed by appending `.barrier-sem0` and
+/// `.barrier-sem1` to that of the shared memory object.
+///
+/// ## Unix
+/// The names stop referring to the barrier when the `ProcessBarrier` that created it is dropped.
+/// Participants which have not opened it by then create a new one.
+#[derive(Debug)]
+pub struct ProcessBarrier {
+    shm: SharedMemory,
+    semaphores: [SemaphoreImpl; 2],
+    n: u32,
+}
+impl ProcessBarrier {
+    /// Creates or opens a barrier for `n` participants.
+    ///
+    /// Fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if `n` is zero or if the
+    /// existing barrier was created for a different number of participants.
+    pub fn new(name: Name<'_>, n: u32) -> io::Result<Self> {
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "barrier needs at least one participant",
+            ));
+        }
+        match Self::create(&name, n) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Self::open(&name, n),
+            rslt => rslt,
+        }
+    }
+    fn create(name: &Name<'_>, n: u32) -> io::Result<Self> {
+        let opts = SharedMemory::options().name(name.borrow()).size(size_of::<BarrierState>());
+        let shm = opts.create()?;
+        let [sfx0, sfx1] = BARRIER_SEMAPHORE_SUFFIXES;
+        let semaphores = [create_semaphore(name, sfx0)?, create_semaphore(name, sfx1)?];
+        let slf = Self { shm, semaphores, n };
+        let state = slf.state();
+        state.n.store(n, Ordering::Relaxed);
+        state.ready.store(1, Ordering::Release);
+        Ok(slf)
+    }
+    fn open(name: &Name<'_>, n: u32) -> io::Result<Self> {
+        let opts = SharedMemory::options().name(name.borrow()).size(size_of::<BarrierState>());
+        let shm = opts.open()?;
+        // SAFETY: as in `.state()`
+        let state = unsafe { &*shm.as_ptr().cast::<BarrierState>() };
+        wait_until_ready(&state.ready)?;
+        if state.n.load(Ordering::Relaxed) != n {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "barrier was created for a different number of participants",
+            ));
+        }
+        let [sfx0, sfx1] = BARRIER_SEMAPHORE_SUFFIXES;
+        let semaphores = [open_semaphore(name, sfx0)?, open_semaphore(name, sfx1)?];
+        Ok(Self { shm, semaphores, n })
+    }
+    fn state(&self) -> &BarrierState {
+        // SAFETY: the mapping is page-aligned and at least as big as the state
+        unsafe { &*self.shm.as_ptr().cast::<BarrierState>() }
+    }
+
+    /// Blocks until all participants have arrived at the barrier.
+    ///
+    /// Returns `true` for exactly one participant of each round, which is called the leader.
+    pub fn wait(&self) -> io::Result<bool> {
+        let state = self.state();
+        // The round can only change once this participant has arrived.
+        let round = state.round.load(Ordering::Acquire);
+        // Waking up the participants of a round does not race with the arrival of those of the
+        // next one, because the latter wait on the other semaphore.
+        let [even, odd] = &self.semaphores;
+        let semaphore = if round & 1 == 0 { even } else { odd };
+        let arrived = state.arrived.fetch_add(1, Ordering::AcqRel).saturating_add(1);
+        if arrived < self.n {
+            semaphore.wait()?;
+            return Ok(false);
+        }
+        state.arrived.store(0, Ordering::Relaxed);
+        state.round.store(round.wrapping_add(1), Ordering::Release);
+        for _ in 1..self.n {
+            semaphore.post()?;
+        }
+        Ok(true)
+    }
+    /// Returns the number of participants.
+    #[inline]
+    pub fn participants(&self) -> u32 { self.n }
+}
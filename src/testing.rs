@@ -0,0 +1,145 @@
+//! Ready-made servers for the integration tests of programs that use local sockets.
+//!
+//! Testing a client usually takes a server to talk to, and writing one for every test means
+//! picking a name that won't collide with other tests running in parallel, spawning a thread to
+//! accept connections and shutting it down at the end. [`TestServer`] does all of that: it
+//! listens on a [random name](random_name), serves every client on a thread of its own, and
+//! stops when dropped.
+//!
+//! This module is only available with the `test-util` feature, which is meant to be enabled in
+//! `[dev-dependencies]`.
+//!
+//! # Example
+//! ```no_run
+//! use {
+//!     interprocess::testing::TestServer,
+//!     std::io::{prelude::*, BufReader},
+//! };
+//!
+//! let server = TestServer::greeter("hello\n")?;
+//! let mut conn = BufReader::new(server.connect()?);
+//! let mut line = String::new();
+//! conn.read_line(&mut line)?;
+//! assert_eq!(line, "hello\n");
+//!
+//! conn.get_mut().write_all(b"ping\n")?;
+//! line.clear();
+//! conn.read_line(&mut line)?;
+//! assert_eq!(line, "ping\n");
+//! # std::io::Result::Ok(())
+//! ```
+
+use {
+    crate::local_socket::{prelude::*, GenericNamespaced, Listener, ListenerOptions, Name, Stream},
+    std::{
+        fmt::{self, Debug, Formatter},
+        io::{self, prelude::*},
+        process,
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+        thread::{self, JoinHandle},
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// How many names [`TestServer`] tries before giving up on finding one that isn't taken.
+const NAME_ATTEMPTS: usize = 16;
+
+/// Generates a namespaced local socket name which is unique within the process and unlikely to
+/// be used by any other process.
+pub fn random_name() -> io::Result<Name<'static>> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.subsec_nanos());
+    format!("interprocess-test-{}-{seq}-{nanos:08x}.sock", process::id())
+        .to_ns_name::<GenericNamespaced>()
+}
+
+/// A local socket server running on a background thread, which stops when dropped.
+///
+/// Every client is served on a thread of its own by sending back everything it sends, after an
+/// optional greeting. Clients that are still connected when the server is dropped keep being
+/// served until they hang up.
+pub struct TestServer {
+    name: Name<'static>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+impl TestServer {
+    /// Spawns a server which sends back everything that it receives.
+    #[inline]
+    pub fn echo() -> io::Result<Self> { Self::spawn(Vec::new()) }
+    /// Spawns a server which sends `greeting` to every client as soon as it connects, and then
+    /// sends back everything that it receives.
+    #[inline]
+    pub fn greeter(greeting: impl Into<Vec<u8>>) -> io::Result<Self> {
+        Self::spawn(greeting.into())
+    }
+    fn spawn(greeting: Vec<u8>) -> io::Result<Self> {
+        let (name, listener) = listen_on_random_name()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = thread::Builder::new().name("interprocess test server".to_owned()).spawn({
+            let stop = Arc::clone(&stop);
+            move || serve(listener, &stop, greeting.into())
+        })?;
+        Ok(Self { name, stop, thread: Some(thread) })
+    }
+
+    /// Returns the name that the server listens on.
+    #[inline]
+    pub fn name(&self) -> Name<'_> { self.name.borrow() }
+    /// Connects to the server.
+    #[inline]
+    pub fn connect(&self) -> io::Result<Stream> { Stream::connect(self.name()) }
+}
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wakes the server up from accepting, after which it sees the flag. If that fails, the
+        // thread is left to its own devices rather than waited for forever.
+        let woken = Stream::connect(self.name.borrow()).is_ok();
+        if let Some(thread) = self.thread.take().filter(|_| woken) {
+            let _ = thread.join();
+        }
+    }
+}
+impl Debug for TestServer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestServer").field("name", &self.name).finish_non_exhaustive()
+    }
+}
+
+fn listen_on_random_name() -> io::Result<(Name<'static>, Listener)> {
+    let mut last_err = None;
+    for _ in 0..NAME_ATTEMPTS {
+        let name = random_name()?;
+        match ListenerOptions::new().name(name.borrow()).create_sync() {
+            Ok(listener) => return Ok((name, listener)),
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => last_err = Some(e),
+            Err(e) => return Err(e),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| io::ErrorKind::AddrInUse.into()))
+}
+
+fn serve(listener: Listener, stop: &AtomicBool, greeting: Arc<[u8]>) {
+    for conn in listener.incoming() {
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(conn) = conn else { continue };
+        let greeting = Arc::clone(&greeting);
+        thread::spawn(move || {
+            let _ = echo(&conn, &greeting);
+        });
+    }
+}
+
+fn echo(conn: &Stream, greeting: &[u8]) -> io::Result<()> {
+    let (mut recver, mut sender) = (conn, conn);
+    sender.write_all(greeting)?;
+    io::copy(&mut recver, &mut sender)?;
+    Ok(())
+}
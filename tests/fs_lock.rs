@@ -0,0 +1,41 @@
+use {
+    crate::{
+        fs_lock::{FileLock, LockKind},
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::{fs, path::PathBuf, time::Duration},
+};
+
+fn lock_path(id: &str) -> PathBuf {
+    let rn = Xorshift32::from_id(id).next();
+    let name = format!("interprocess-test-{}-{rn:08x}.lock", std::process::id());
+    std::env::temp_dir().join(name)
+}
+
+fn test_exclusion() -> TestResult {
+    let path = lock_path(make_id!());
+    let mut a = FileLock::open(&path).opname("open")?;
+    let mut b = FileLock::open(&path).opname("open")?;
+
+    {
+        let _guard = a.lock(LockKind::Exclusive).opname("lock")?;
+        ensure!(b.try_lock(LockKind::Exclusive).opname("try_lock")?.is_none());
+        ensure!(b.try_lock(LockKind::Shared).opname("try_lock")?.is_none());
+        let timed = b.lock_timeout(LockKind::Shared, Duration::from_millis(30));
+        ensure!(timed.opname("lock_timeout")?.is_none());
+    }
+    {
+        let _shared_a = a.lock(LockKind::Shared).opname("lock")?;
+        let shared_b = b.try_lock(LockKind::Shared).opname("try_lock")?;
+        ensure!(shared_b.is_some());
+    }
+    ensure!(b.try_lock(LockKind::Exclusive).opname("try_lock")?.is_some());
+
+    drop((a, b));
+    fs::remove_file(&path).opname("remove")?;
+    Ok(())
+}
+
+#[test]
+fn fs_lock_exclusion() -> TestResult { test_wrapper(test_exclusion) }
@@ -5,19 +5,48 @@ mod util;
 mod os {
     #[cfg(any(unix, target_vendor = "wasmer"))]
     mod unix {
+        mod local_socket_audit;
+        mod local_socket_backlog;
+        mod local_socket_bind_name;
         mod local_socket_fake_ns;
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        mod local_socket_long_path;
         mod local_socket_mode;
+        mod local_socket_name_length;
+        mod local_socket_name_policy;
+        mod local_socket_sigpipe;
+        mod local_socket_socket_file;
+        mod local_socket_sockopt;
+        #[cfg(unix)]
+        mod semaphore;
+        #[cfg(unix)]
+        mod shared_memory;
     }
     #[cfg(windows)]
     mod windows {
+        mod event;
         mod local_socket_security_descriptor;
+        mod local_socket_verify_server;
+        mod local_socket_wait_timeout;
         mod named_pipe;
+        mod shared_memory;
         mod tokio_named_pipe;
     }
 }
 
+#[cfg(any(unix, windows))]
+mod fs_lock;
 mod local_socket;
-#[cfg(feature = "tokio")]
+mod process;
+#[cfg(any(unix, windows))]
+mod shared_memory;
+mod single_instance;
+#[cfg(unix)]
+mod signal;
+#[cfg(any(unix, windows))]
+mod sync;
+mod testing;
+#[cfg(feature = "local-socket-tokio")]
 mod tokio_local_socket;
 
 #[cfg(feature = "tokio")]
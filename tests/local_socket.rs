@@ -1,8 +1,40 @@
 // TODO(2.3.0) test various error conditions
 
+mod audit;
+mod broadcaster;
+mod buf_stream;
+mod buffer_size;
+mod bytes;
+mod channel;
+mod compressed;
+mod connect_nonblocking;
+mod fd_passing;
+mod flush_mode;
+mod framing;
+mod handshake;
+#[cfg(any(unix, windows))]
+mod hybrid;
+mod inheritable;
+mod local_name;
 mod no_client;
 mod no_server;
+mod nonblocking_toggle;
+mod peer_credentials;
+mod peer_info;
+mod polling;
+mod read_uninit;
+#[cfg(any(unix, windows))]
+mod region;
+#[cfg(any(unix, windows))]
+mod require_server;
+mod secure;
+mod static_name;
+mod stats;
 mod stream;
+mod throttled;
+mod try_clone;
+mod version;
+mod writer_handle;
 
 use crate::tests::util::*;
 
@@ -13,9 +45,34 @@ fn test_stream(id: &'static str, path: bool) -> TestResult {
     Ok(())
 }
 
+#[cfg(any(unix, windows))]
 use {
+    hybrid::run_and_verify as test_hybrid, region::run_and_verify as test_region,
+    require_server::run_and_verify as test_require_server,
+};
+use {
+    audit::run_and_verify as test_audit,
+    buf_stream::run_and_verify as test_buf_stream,
+    buffer_size::run_and_verify as test_buffer_size,
+    connect_nonblocking::run_and_verify as test_connect_nonblocking,
+    fd_passing::run_and_verify as test_fd_passing,
+    flush_mode::run_and_verify as test_flush_mode,
+    inheritable::run_and_verify as test_inheritable,
+    local_name::run_and_verify as test_local_name,
     no_client::run_and_verify_error as test_no_client,
     no_server::run_and_verify_error as test_no_server,
+    nonblocking_toggle::run_and_verify as test_nonblocking_toggle,
+    peer_credentials::run_and_verify as test_peer_credentials,
+    peer_info::{
+        run_and_verify as test_peer_info, run_and_verify_filtered as test_accept_filtered,
+    },
+    read_uninit::run_and_verify as test_read_uninit,
+    static_name::run_and_verify as test_static_name,
+    stats::run_and_verify as test_stats,
+    throttled::run_and_verify as test_throttled,
+    try_clone::run_and_verify as test_try_clone,
+    version::run_and_verify as test_version,
+    writer_handle::run_and_verify as test_writer_handle,
 };
 
 macro_rules! tests {
@@ -42,3 +99,116 @@ tests! {test_no_client
     no_client_file       true
     no_client_namespaced false
 }
+
+tests! {test_local_name
+    local_name_file       true
+    local_name_namespaced false
+}
+
+tests! {test_try_clone
+    try_clone_file       true
+    try_clone_namespaced false
+}
+
+tests! {test_throttled
+    throttled_file       true
+    throttled_namespaced false
+}
+
+tests! {test_buffer_size
+    buffer_size_file       true
+    buffer_size_namespaced false
+}
+
+tests! {test_read_uninit
+    read_uninit_file       true
+    read_uninit_namespaced false
+}
+
+tests! {test_peer_credentials
+    peer_credentials_file       true
+    peer_credentials_namespaced false
+}
+
+#[cfg(any(unix, windows))]
+tests! {test_hybrid
+    hybrid_file       true
+    hybrid_namespaced false
+}
+
+#[cfg(any(unix, windows))]
+tests! {test_region
+    region_file       true
+    region_namespaced false
+}
+
+tests! {test_connect_nonblocking
+    connect_nonblocking_file       true
+    connect_nonblocking_namespaced false
+}
+
+tests! {test_inheritable
+    inheritable_file       true
+    inheritable_namespaced false
+}
+
+tests! {test_version
+    version_file       true
+    version_namespaced false
+}
+
+tests! {test_flush_mode
+    flush_mode_file       true
+    flush_mode_namespaced false
+}
+
+tests! {test_peer_info
+    peer_info_file       true
+    peer_info_namespaced false
+}
+
+tests! {test_accept_filtered
+    accept_filtered_file       true
+    accept_filtered_namespaced false
+}
+
+tests! {test_stats
+    stats_file       true
+    stats_namespaced false
+}
+
+tests! {test_fd_passing
+    fd_passing_file       true
+    fd_passing_namespaced false
+}
+
+tests! {test_nonblocking_toggle
+    nonblocking_toggle_file       true
+    nonblocking_toggle_namespaced false
+}
+
+tests! {test_static_name
+    static_name_file       true
+    static_name_namespaced false
+}
+
+#[cfg(any(unix, windows))]
+tests! {test_require_server
+    require_server_file       true
+    require_server_namespaced false
+}
+
+tests! {test_audit
+    audit_file       true
+    audit_namespaced false
+}
+
+tests! {test_buf_stream
+    buf_stream_file       true
+    buf_stream_namespaced false
+}
+
+tests! {test_writer_handle
+    writer_handle_file       true
+    writer_handle_namespaced false
+}
@@ -0,0 +1,46 @@
+use {
+    crate::{
+        local_socket::{
+            audit::{self, Direction},
+            prelude::*,
+            ListenerOptions, Name, Stream,
+        },
+        tests::util::*,
+    },
+    std::sync::{Mutex, Once, PoisonError},
+};
+
+type Record = (Option<Name<'static>>, Direction, bool);
+static EVENTS: Mutex<Vec<Record>> = Mutex::new(Vec::new());
+
+/// The hook is shared by all tests running in the process, which pick out their own events by
+/// the name.
+fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        audit::set_hook(|event| {
+            let name = event.name.clone().map(Name::into_owned);
+            let record = (name, event.direction, event.result.is_ok());
+            EVENTS.lock().unwrap_or_else(PoisonError::into_inner).push(record);
+        })
+    });
+}
+fn events_of(name: &Name<'_>) -> Vec<(Direction, bool)> {
+    let events = EVENTS.lock().unwrap_or_else(PoisonError::into_inner);
+    events.iter().filter(|(n, ..)| n.as_ref() == Some(name)).map(|&(_, d, ok)| (d, ok)).collect()
+}
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    install_hook();
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let _client = Stream::connect(name.borrow()).opname("client connect")?;
+    let _server = listener.accept().opname("accept")?;
+    ensure_eq!(events_of(&name), [(Direction::Connect, true), (Direction::Accept, true)]);
+
+    drop(listener);
+    let _ = Stream::connect(name.borrow());
+    ensure_eq!(events_of(&name).last(), Some(&(Direction::Connect, false)));
+    Ok(())
+}
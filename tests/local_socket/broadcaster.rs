@@ -0,0 +1,42 @@
+use {
+    crate::{local_socket::Broadcaster, tests::util::*},
+    color_eyre::eyre::ensure,
+    std::io::{self, prelude::*},
+};
+
+#[derive(Debug, Default)]
+struct Peer {
+    received: Vec<u8>,
+    dead: bool,
+}
+impl Write for Peer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.dead {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        self.received.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+fn prunes_dead_peers() -> TestResult {
+    let mut broadcaster = (0..4).map(|_| Peer::default()).collect::<Broadcaster<_>>();
+    ensure!(broadcaster.broadcast(b"first").is_empty());
+
+    if let Some(peer) = broadcaster.peers_mut().get_mut(1) {
+        peer.dead = true;
+    }
+    let failed = broadcaster.broadcast(b"second");
+    ensure_eq!(failed.len(), 1);
+    ensure_eq!(failed.first().map(|(_, e)| e.kind()), Some(io::ErrorKind::BrokenPipe));
+    ensure_eq!(broadcaster.len(), 3);
+
+    for peer in broadcaster.peers() {
+        ensure_eq!(peer.received, b"firstsecond");
+    }
+    Ok(())
+}
+
+#[test]
+fn broadcaster_prunes_dead_peers() -> TestResult { test_wrapper(prunes_dead_peers) }
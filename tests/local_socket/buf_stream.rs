@@ -0,0 +1,59 @@
+use {
+    crate::{
+        local_socket::{prelude::*, BufStream, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::io::{prelude::*, BufReader},
+};
+
+const CAPACITY: usize = 64;
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let client = Stream::connect(name.borrow()).opname("connect")?;
+    let mut server = BufReader::new(listener.accept().opname("accept")?);
+    let mut client = BufStream::with_capacity(CAPACITY, CAPACITY, client);
+
+    // Small sends are held back until flushed.
+    client.write_all(b"first\n").opname("buffered send")?;
+    ensure_eq!(client.send_buffer(), b"first\n");
+    client.flush().opname("flush")?;
+    ensure!(client.send_buffer().is_empty(), "flush left data in the send buffer");
+    expect_line(&mut server, "first\n")?;
+
+    // Sends at least as large as the buffer go straight through.
+    let mut big = vec![b'x'; CAPACITY.saturating_mul(2)];
+    big.push(b'\n');
+    client.write_all(&big).opname("large send")?;
+    ensure!(client.send_buffer().is_empty(), "large send was buffered");
+    let mut line = Vec::new();
+    server.read_until(b'\n', &mut line).opname("server receive")?;
+    ensure_eq!(line, big);
+
+    // What the server sends in one piece is handed out line by line.
+    server.get_mut().write_all(b"one\ntwo\n").opname("server send")?;
+    let mut line = String::new();
+    client.read_line(&mut line).opname("receive")?;
+    ensure_eq!(line, "one\n");
+    line.clear();
+    client.read_line(&mut line).opname("receive")?;
+    ensure_eq!(line, "two\n");
+
+    // Unwrapping flushes, and so does dropping.
+    client.write_all(b"unwrapped\n").opname("buffered send")?;
+    let mut client = BufStream::new(client.into_inner().opname("into_inner")?);
+    expect_line(&mut server, "unwrapped\n")?;
+    client.write_all(b"dropped\n").opname("buffered send")?;
+    drop(client);
+    expect_line(&mut server, "dropped\n")
+}
+
+fn expect_line(server: &mut BufReader<Stream>, expected: &str) -> TestResult {
+    let mut line = String::new();
+    server.read_line(&mut line).opname("server receive")?;
+    ensure_eq!(line, expected);
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+};
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let client = Stream::connect(name.borrow()).opname("client connect")?;
+    let server = listener.accept().opname("accept")?;
+    for conn in [&client, &server] {
+        conn.send_buffer_size().opname("send buffer size query")?;
+        conn.recv_buffer_size().opname("receive buffer size query")?;
+    }
+    verify_set(&client)
+}
+
+#[cfg(unix)]
+fn verify_set(conn: &Stream) -> TestResult {
+    const SIZE: usize = 64 * 1024;
+    conn.set_send_buffer_size(SIZE).opname("send buffer size change")?;
+    conn.set_recv_buffer_size(SIZE).opname("receive buffer size change")?;
+    // The OS may round the sizes up (Linux doubles them), but shouldn't shrink them.
+    let send = conn.send_buffer_size().opname("send buffer size query")?;
+    ensure!(send >= SIZE, "send buffer shrunk to {send} bytes");
+    let recv = conn.recv_buffer_size().opname("receive buffer size query")?;
+    ensure!(recv >= SIZE, "receive buffer shrunk to {recv} bytes");
+    Ok(())
+}
+#[cfg(windows)]
+fn verify_set(conn: &Stream) -> TestResult {
+    let err = conn.set_send_buffer_size(4096).err().map(|e| e.kind());
+    ensure_eq!(err, Some(std::io::ErrorKind::Unsupported));
+    Ok(())
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "bytes")]
+
+use {
+    crate::{
+        local_socket::{prelude::*, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    ::bytes::{Buf, BytesMut},
+    color_eyre::eyre::ensure,
+};
+
+const MSG: &[u8] = b"Hello from the bytes ecosystem!";
+
+fn bytes(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let client = Stream::connect(name.borrow()).opname("client connect")?;
+    let server = listener.accept().opname("accept")?;
+
+    // A noncontiguous buffer, so that the vectored path gets exercised.
+    let (first, second) = MSG.split_at(MSG.len() / 2);
+    let mut sendbuf = first.chain(second);
+    while sendbuf.has_remaining() {
+        client.write_buf(&mut sendbuf).opname("send")?;
+    }
+
+    let mut recvbuf = BytesMut::new();
+    while recvbuf.len() < MSG.len() {
+        let n = server.read_bytes(&mut recvbuf).opname("receive")?;
+        ensure!(n > 0, "end of file before the whole message arrived");
+    }
+    ensure_eq!(&recvbuf[..], MSG);
+    Ok(())
+}
+
+#[test]
+fn bytes_file() -> TestResult { test_wrapper(|| bytes(make_id!(), true)) }
+#[test]
+fn bytes_namespaced() -> TestResult { test_wrapper(|| bytes(make_id!(), false)) }
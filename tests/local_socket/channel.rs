@@ -0,0 +1,34 @@
+#![cfg(feature = "serde")]
+
+use crate::{
+    local_socket::{channel, Connector},
+    tests::util::*,
+};
+
+type Msg = (u32, String);
+
+fn channel_roundtrip(id: &'static str, path: bool) -> TestResult {
+    let (_, (listener, connector)) =
+        listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+            channel::<Msg>(nm.borrow())
+        })?;
+    // A connector made from the name alone, as a client process would.
+    let mut client = Connector::<Msg>::new(connector.name().into_owned())
+        .connect()
+        .opname("client connect")?;
+    let mut server = listener.accept().opname("accept")?;
+
+    client.send(&(1, "ping".to_owned())).opname("client send")?;
+    ensure_eq!(server.recv().opname("server receive")?, Some((1, "ping".to_owned())));
+    server.send(&(2, "pong".to_owned())).opname("server send")?;
+    ensure_eq!(client.recv().opname("client receive")?, Some((2, "pong".to_owned())));
+
+    drop(client);
+    ensure_eq!(server.recv().opname("receive after close")?, None);
+    Ok(())
+}
+
+#[test]
+fn channel_file() -> TestResult { test_wrapper(|| channel_roundtrip(make_id!(), true)) }
+#[test]
+fn channel_namespaced() -> TestResult { test_wrapper(|| channel_roundtrip(make_id!(), false)) }
@@ -0,0 +1,45 @@
+#![cfg(feature = "compression")]
+
+use {
+    crate::{
+        local_socket::{prelude::*, CompressedStream, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    color_eyre::eyre::eyre,
+    std::{io::prelude::*, thread},
+};
+
+fn roundtrip(id: &'static str, path: bool, client_wants: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    // Highly compressible, and spanning several frames.
+    let msg = br#"{"metric": "cpu", "value": 0.25}"#.repeat(8192);
+
+    let server = thread::spawn(move || {
+        let conn = listener.accept().opname("accept")?;
+        let mut conn = CompressedStream::negotiate(conn, true).opname("server negotiate")?;
+        let mut buf = Vec::new();
+        conn.read_to_end(&mut buf).opname("server receive")?;
+        TestResult::Ok((conn.is_compressed(), buf))
+    });
+
+    let client = Stream::connect(name.borrow()).opname("connect")?;
+    let mut client = CompressedStream::negotiate(client, client_wants).opname("negotiate")?;
+    ensure_eq!(client.is_compressed(), client_wants);
+    client.write_all(&msg).opname("send")?;
+    drop(client);
+
+    let (server_compressed, received) =
+        server.join().map_err(|_| eyre!("server thread panicked"))??;
+    ensure_eq!(server_compressed, client_wants);
+    ensure_eq!(received, msg);
+    Ok(())
+}
+
+#[test]
+fn compressed_file() -> TestResult { test_wrapper(|| roundtrip(make_id!(), true, true)) }
+#[test]
+fn compressed_namespaced() -> TestResult { test_wrapper(|| roundtrip(make_id!(), false, true)) }
+#[test]
+fn compression_declined() -> TestResult { test_wrapper(|| roundtrip(make_id!(), false, false)) }
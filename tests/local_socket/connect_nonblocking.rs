@@ -0,0 +1,73 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ConnectStatus, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    color_eyre::eyre::bail,
+    std::{
+        io::{prelude::*, BufReader},
+        thread,
+        time::Duration,
+    },
+};
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+
+    let status = Stream::connect_nonblocking(name.borrow()).opname("connect")?;
+    let mut client = finish(status)?;
+
+    client.write_all(b"hi\n").opname("send")?;
+    let mut conn = BufReader::new(listener.accept().opname("accept")?);
+    let mut line = String::new();
+    conn.read_line(&mut line).opname("receive")?;
+    ensure_eq!(line, "hi\n");
+
+    #[cfg(target_os = "linux")]
+    backlog_full(id, path)?;
+    Ok(())
+}
+
+fn finish(mut status: ConnectStatus) -> TestResult<Stream> {
+    for _ in 0..1000 {
+        match status {
+            ConnectStatus::Connected(stream) => return Ok(stream),
+            ConnectStatus::InProgress(connecting) => {
+                thread::sleep(Duration::from_millis(1));
+                status = connecting.finish().opname("finish")?;
+            }
+        }
+    }
+    bail!("connection not established in time");
+}
+
+/// Linux doesn't start connection attempts while the backlog is full, leaving the socket
+/// writable and nothing to wait on.
+#[cfg(target_os = "linux")]
+fn backlog_full(id: &'static str, path: bool) -> TestResult {
+    use color_eyre::eyre::ensure;
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).backlog(0).create_sync()
+    })?;
+
+    // Kept alive for as long as they need to occupy the backlog.
+    let mut queued = Vec::new();
+    let connecting = 'fill: {
+        for _ in 0..16 {
+            match Stream::connect_nonblocking(name.borrow()).opname("connect")? {
+                ConnectStatus::Connected(stream) => queued.push(stream),
+                ConnectStatus::InProgress(connecting) => break 'fill connecting,
+            }
+        }
+        bail!("backlog never filled up");
+    };
+    ensure!(connecting.must_retry(), "full backlog reported as a connection in progress");
+    ensure!(!queued.is_empty(), "connection attempted with an empty backlog");
+
+    let _accepted = listener.accept().opname("accept")?;
+    let status = connecting.finish().opname("finish")?;
+    finish(status)?;
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    color_eyre::eyre::{ensure, eyre},
+    std::io,
+};
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let client = Stream::connect(name.borrow()).opname("client connect")?;
+    let server = listener.accept().opname("accept")?;
+
+    if !Stream::supports_fd_passing() {
+        let e = client.send_fds(b"x", &[]).err().ok_or_else(|| eyre!("send_fds succeeded"))?;
+        ensure_eq!(e.kind(), io::ErrorKind::Unsupported);
+        let e = server
+            .recv_fds(&mut [0], &mut Vec::new())
+            .err()
+            .ok_or_else(|| eyre!("recv_fds succeeded"))?;
+        ensure_eq!(e.kind(), io::ErrorKind::Unsupported);
+        return Ok(());
+    }
+    #[cfg(unix)]
+    {
+        pass_pipe(&client, &server)?;
+        excess_fds(&client, &server)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn pass_pipe(client: &Stream, server: &Stream) -> TestResult {
+    use std::{
+        fs::File,
+        io::prelude::*,
+        os::fd::{AsFd, FromRawFd, OwnedFd},
+    };
+    let mut fds = [0; 2];
+    ensure_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    let [rx, tx] = fds.map(|fd| unsafe { OwnedFd::from_raw_fd(fd) });
+
+    ensure_eq!(client.send_fds(b"x", &[tx.as_fd()]).opname("send_fds")?, 1);
+    drop(tx);
+    let mut buf = [0; 1];
+    let mut received = Vec::new();
+    ensure_eq!(server.recv_fds(&mut buf, &mut received).opname("recv_fds")?, 1);
+    ensure_eq!(&buf, b"x");
+    ensure_eq!(received.len(), 1);
+
+    let mut tx = File::from(received.pop().ok_or_else(|| eyre!("no fd received"))?);
+    tx.write_all(b"through the pipe").opname("write to passed pipe")?;
+    drop(tx);
+    let mut msg = String::new();
+    File::from(rx).read_to_string(&mut msg).opname("read from pipe")?;
+    ensure_eq!(msg, "through the pipe");
+    Ok(())
+}
+
+#[cfg(unix)]
+fn excess_fds(client: &Stream, server: &Stream) -> TestResult {
+    use std::os::fd::AsFd;
+    ensure_eq!(server.max_recv_fds(), 8);
+    server.set_max_recv_fds(100);
+    ensure_eq!(server.max_recv_fds(), 8);
+    server.set_max_recv_fds(1);
+
+    ensure!(!server.take_fds_truncated());
+    let fd = client.as_fd();
+    ensure_eq!(client.send_fds(b"yz", &[fd, fd]).opname("send_fds")?, 2);
+    let mut buf = [0; 2];
+    let mut received = Vec::new();
+    ensure_eq!(server.recv_fds(&mut buf, &mut received).opname("recv_fds")?, 2);
+    ensure_eq!(&buf, b"yz");
+    ensure_eq!(received.len(), 1);
+    ensure!(server.take_fds_truncated());
+    ensure!(!server.take_fds_truncated());
+
+    // The stream is still in sync after the excess file descriptors were dropped.
+    ensure_eq!(client.send_fds(b"w", &[]).opname("send_fds")?, 1);
+    ensure_eq!(server.recv_fds(&mut buf[..1], &mut received).opname("recv_fds")?, 1);
+    ensure_eq!(buf[0], b'w');
+    ensure!(!server.take_fds_truncated());
+    Ok(())
+}
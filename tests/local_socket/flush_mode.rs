@@ -0,0 +1,38 @@
+use {
+    crate::{
+        local_socket::{prelude::*, FlushMode, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    color_eyre::eyre::eyre,
+    std::{io::prelude::*, thread},
+};
+
+const MODES: [FlushMode; 3] = [FlushMode::Os, FlushMode::NoOp, FlushMode::OnClose];
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+
+    let server = thread::spawn(move || {
+        for mode in MODES {
+            let mut conn = listener.accept().opname("accept")?;
+            conn.set_flush_mode(mode);
+            conn.write_all(b"flushed").opname("send")?;
+            conn.flush().opname("flush")?;
+            // Waits for an acknowledgement, so that `NoOp` has no chance to discard anything.
+            let mut ack = [0; 1];
+            conn.read_exact(&mut ack).opname("receive acknowledgement")?;
+        }
+        TestResult::Ok(())
+    });
+
+    for mode in MODES {
+        let mut client = Stream::connect(name.borrow()).opname("connect")?;
+        let mut msg = [0; 7];
+        client.read_exact(&mut msg).opname("receive")?;
+        ensure_eq!(&msg, b"flushed", "mode {mode:?}");
+        client.write_all(b"k").opname("send acknowledgement")?;
+    }
+    server.join().map_err(|_| eyre!("server thread panicked"))?
+}
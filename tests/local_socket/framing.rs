@@ -0,0 +1,59 @@
+use {
+    crate::{
+        local_socket::framing::{Delimited, Framed, Framer, LengthPrefixed},
+        tests::util::*,
+    },
+    std::io::Cursor,
+};
+
+static MESSAGES: [&[u8]; 4] = [b"Hello", b"", b"from the framing test", &[0, 1, 2, 255]];
+
+fn roundtrip(framer: impl Framer + Copy, messages: &[&[u8]]) -> TestResult {
+    let mut sender = Framed::new(Vec::new(), framer);
+    for msg in messages {
+        sender.send(msg).opname("send")?;
+    }
+    let (wire, _) = sender.into_inner();
+
+    // Feed the data one byte at a time to make sure partial messages are handled.
+    let mut receiver = Framed::new(Trickle(Cursor::new(wire)), framer);
+    for msg in messages {
+        ensure_eq!(receiver.recv().opname("recv")?.as_deref(), Some(*msg));
+    }
+    ensure_eq!(receiver.recv().opname("recv at EOF")?, None);
+    Ok(())
+}
+
+struct Trickle(Cursor<Vec<u8>>);
+impl std::io::Read for Trickle {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(1);
+        self.0.read(buf.get_mut(..len).unwrap_or_default())
+    }
+}
+
+#[test]
+fn framing_length_prefixed() -> TestResult {
+    test_wrapper(|| roundtrip(LengthPrefixed::new(), &MESSAGES))
+}
+
+#[test]
+fn framing_delimited() -> TestResult {
+    test_wrapper(|| roundtrip(Delimited::lines(), &[b"Hello", b"", b"from the framing test"]))
+}
+
+#[test]
+fn framing_limits() -> TestResult { test_wrapper(limits) }
+fn limits() -> TestResult {
+    let mut sender = Framed::new(Vec::new(), LengthPrefixed::new().max_len(4));
+    ensure_eq!(sender.send(b"12345").unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    let mut sender = Framed::new(Vec::new(), Delimited::lines());
+    ensure_eq!(sender.send(b"a\nb").unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+
+    let framer = LengthPrefixed::new().max_len(4);
+    let mut receiver = Framed::new(Cursor::new(b"\0\0\0\x05hello".to_vec()), framer);
+    ensure_eq!(receiver.recv().unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    let mut receiver = Framed::new(Cursor::new(b"trunc".to_vec()), Delimited::lines());
+    ensure_eq!(receiver.recv().unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+    Ok(())
+}
@@ -0,0 +1,150 @@
+#![cfg(feature = "handshake")]
+
+use {
+    crate::{
+        local_socket::{
+            handshake::SharedSecret, prelude::*, ConnectOptions, ListenerOptions, Stream,
+        },
+        tests::util::*,
+    },
+    color_eyre::eyre::{ensure, eyre},
+    std::{
+        io::{self, prelude::*},
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+fn run(id: &'static str, path: bool, client_secret: &'static [u8]) -> TestResult {
+    let server_secret = SharedSecret::new(&b"correct horse battery staple"[..]);
+    let secret_matches = server_secret == SharedSecret::new(client_secret);
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new()
+            .name(nm.borrow())
+            .shared_secret(server_secret.clone())
+            .create_sync()
+    })?;
+
+    let server = thread::spawn(move || {
+        let mut conn = match listener.accept() {
+            Err(e) if !secret_matches => {
+                ensure_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+                return Ok(());
+            }
+            rslt => rslt.opname("accept")?,
+        };
+        conn.write_all(b"welcome").opname("server send")?;
+        Ok(())
+    });
+
+    let client = ConnectOptions::new()
+        .name(name.borrow())
+        .shared_secret(SharedSecret::new(client_secret))
+        .connect_sync();
+    match client {
+        Ok(mut client) => {
+            ensure!(secret_matches, "client with the wrong secret completed the handshake");
+            let mut msg = [0; 7];
+            client.read_exact(&mut msg).opname("client receive")?;
+            ensure_eq!(&msg, b"welcome");
+        }
+        Err(e) => {
+            ensure!(!secret_matches, "client handshake failed: {e}");
+            ensure_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+        }
+    }
+    server.join().map_err(|_| eyre!("server thread panicked"))?
+}
+
+#[test]
+fn handshake_file() -> TestResult {
+    test_wrapper(|| run(make_id!(), true, b"correct horse battery staple"))
+}
+#[test]
+fn handshake_namespaced() -> TestResult {
+    test_wrapper(|| run(make_id!(), false, b"correct horse battery staple"))
+}
+#[test]
+fn handshake_wrong_secret() -> TestResult { test_wrapper(|| run(make_id!(), false, b"guess")) }
+
+#[test]
+fn handshake_timeout() -> TestResult {
+    const TIMEOUT: Duration = Duration::from_millis(300);
+    test_wrapper(|| {
+        let secret = SharedSecret::new(&b"correct horse battery staple"[..]);
+        let (name, listener) =
+            listen_and_pick_name(&mut namegen_local_socket(make_id!(), false), |nm| {
+                ListenerOptions::new()
+                    .name(nm.borrow())
+                    .shared_secret(secret.clone())
+                    .handshake_timeout(TIMEOUT)
+                    .create_sync()
+            })?;
+        // Connects without taking part in the handshake, and stays connected until the server is
+        // done with it.
+        let silent = Stream::connect(name.borrow()).opname("connect")?;
+        let start = Instant::now();
+        let err = listener.accept().err().ok_or_else(|| eyre!("silent client was accepted"))?;
+        let elapsed = start.elapsed();
+        ensure_eq!(err.kind(), io::ErrorKind::TimedOut);
+        ensure!(elapsed >= TIMEOUT / 2, "handshake timed out too early, after {elapsed:?}");
+        ensure!(elapsed < TIMEOUT * 10, "handshake timed out too late, after {elapsed:?}");
+        drop(silent);
+        Ok(())
+    })
+}
+
+#[test]
+fn handshake_pending() -> TestResult {
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    test_wrapper(|| {
+        let secret = SharedSecret::new(&b"correct horse battery staple"[..]);
+        let (name, listener) =
+            listen_and_pick_name(&mut namegen_local_socket(make_id!(), false), |nm| {
+                ListenerOptions::new()
+                    .name(nm.borrow())
+                    .shared_secret(secret.clone())
+                    .handshake_timeout(TIMEOUT)
+                    .create_sync()
+            })?;
+        // Connects ahead of the real client and never takes part in the handshake.
+        let silent = Stream::connect(name.borrow()).opname("connect")?;
+        let client = thread::spawn({
+            let (name, secret) = (name.clone(), secret.clone());
+            move || {
+                let mut client = ConnectOptions::new()
+                    .name(name.borrow())
+                    .shared_secret(secret)
+                    .connect_sync()
+                    .opname("client connect")?;
+                let mut msg = [0; 7];
+                client.read_exact(&mut msg).opname("client receive")?;
+                ensure_eq!(&msg, b"welcome");
+                TestResult::Ok(())
+            }
+        });
+
+        let start = Instant::now();
+        let pending = [
+            listener.accept_pending().opname("accept")?,
+            listener.accept_pending().opname("accept")?,
+        ];
+        let finishers = pending.map(|pending| {
+            thread::spawn(move || pending.finish()?.write_all(b"welcome"))
+        });
+        client.join().map_err(|_| eyre!("client thread panicked"))??;
+        let elapsed = start.elapsed();
+        ensure!(elapsed < TIMEOUT / 2, "client held up by the silent one for {elapsed:?}");
+
+        drop(silent);
+        let succeeded = finishers
+            .into_iter()
+            .map(|finisher| finisher.join().map_err(|_| eyre!("finisher thread panicked")))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(Result::is_ok)
+            .count();
+        ensure_eq!(succeeded, 1);
+        Ok(())
+    })
+}
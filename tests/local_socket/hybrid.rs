@@ -0,0 +1,43 @@
+use {
+    crate::{
+        local_socket::{prelude::*, HybridStream, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    color_eyre::eyre::eyre,
+};
+
+const SMALL: &[u8] = b"Small enough to go over the socket";
+const LARGE_LEN: usize = 1024 * 1024;
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let mut client = HybridStream::new(Stream::connect(name.borrow()).opname("client connect")?);
+    let mut server = HybridStream::new(listener.accept().opname("accept")?);
+    let large = (0..=u8::MAX).cycle().take(LARGE_LEN).collect::<Vec<_>>();
+    let large_ref = large.as_slice();
+
+    // Interleaving the two kinds checks that shared memory objects stay matched to their headers.
+    for msg in [SMALL, large_ref, large_ref, SMALL, large_ref] {
+        client.send(msg).opname("client send")?;
+    }
+    for msg in [SMALL, large_ref, large_ref, SMALL, large_ref] {
+        ensure_eq!(server.recv().opname("server receive")?.as_deref(), Some(msg));
+    }
+
+    // A rejected message must not throw the following ones out of sync.
+    server.set_max_shared_len(LARGE_LEN - 1);
+    client.send(&large).opname("client send over limit")?;
+    client.send(SMALL).opname("client send after limit")?;
+    let err = server.recv().err().ok_or_else(|| eyre!("message over the limit was received"))?;
+    ensure_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    ensure_eq!(server.recv().opname("server receive after limit")?.as_deref(), Some(SMALL));
+
+    server.send(&large).opname("server send")?;
+    ensure_eq!(client.recv().opname("client receive")?, Some(large));
+
+    drop(client);
+    ensure_eq!(server.recv().opname("receive after close")?, None);
+    Ok(())
+}
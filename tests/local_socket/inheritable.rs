@@ -0,0 +1,47 @@
+use crate::{
+    local_socket::{prelude::*, ListenerOptions, Stream},
+    tests::util::*,
+};
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let client = Stream::connect(name.borrow()).opname("client connect")?;
+    let server = listener.accept().opname("accept")?;
+    for conn in [&client, &server] {
+        ensure_eq!(is_inheritable(conn)?, false);
+        conn.set_inheritable(true).opname("set_inheritable(true)")?;
+        ensure_eq!(is_inheritable(conn)?, true);
+        conn.set_inheritable(false).opname("set_inheritable(false)")?;
+        ensure_eq!(is_inheritable(conn)?, false);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn is_inheritable(conn: &Stream) -> TestResult<bool> {
+    use std::os::unix::io::{AsFd, AsRawFd};
+    let Stream::UdSocket(s) = conn;
+    let fd = s.as_fd().as_raw_fd();
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    if flags == -1 {
+        return Err(std::io::Error::last_os_error()).opname("fcntl");
+    }
+    Ok(flags & libc::FD_CLOEXEC == 0)
+}
+#[cfg(windows)]
+fn is_inheritable(conn: &Stream) -> TestResult<bool> {
+    use {
+        crate::os::windows::AsRawHandleExt as _,
+        std::os::windows::io::AsHandle,
+        windows_sys::Win32::Foundation::{GetHandleInformation, HANDLE_FLAG_INHERIT},
+    };
+    let Stream::NamedPipe(s) = conn;
+    let mut flags = 0;
+    let ok = unsafe { GetHandleInformation(s.as_handle().as_int_handle(), &mut flags) };
+    if ok == 0 {
+        return Err(std::io::Error::last_os_error()).opname("GetHandleInformation");
+    }
+    Ok(flags & HANDLE_FLAG_INHERIT != 0)
+}
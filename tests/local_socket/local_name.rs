@@ -0,0 +1,18 @@
+use crate::{
+    local_socket::{prelude::*, ListenerOptions, Stream},
+    tests::util::*,
+};
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    ensure_eq!(listener.local_name(), Some(name.borrow()));
+
+    // Pseudo-namespaced names are mapped to paths, which is what the peer address reports.
+    if path || name.is_namespaced() {
+        let client = Stream::connect(name.borrow()).opname("client connect")?;
+        ensure_eq!(client.peer_name(), Some(name.borrow().into_owned()));
+    }
+    Ok(())
+}
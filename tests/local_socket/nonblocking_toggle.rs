@@ -0,0 +1,36 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ListenerOptions, Stream},
+        tests::util::*,
+        TryClone,
+    },
+    color_eyre::eyre::eyre,
+    std::io::{self, prelude::*},
+};
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let client = Stream::connect(name.borrow()).opname("client connect")?;
+    let _server = listener.accept().opname("accept")?;
+
+    client.set_nonblocking(true).opname("set_nonblocking(true)")?;
+    client.set_nonblocking(true).opname("redundant set_nonblocking(true)")?;
+    ensure_would_block(&client)?;
+
+    // The clone shares the mode with the original, so changing it through one of them must not
+    // leave the other believing that the mode is still what it set it to.
+    let clone = client.try_clone().opname("try_clone")?;
+    clone.set_nonblocking(false).opname("clone set_nonblocking(false)")?;
+    client.set_nonblocking(true).opname("set_nonblocking(true) after clone")?;
+    ensure_would_block(&clone)?;
+    Ok(())
+}
+
+fn ensure_would_block(conn: &Stream) -> TestResult {
+    let mut conn = conn;
+    let e = conn.read(&mut [0]).err().ok_or_else(|| eyre!("receive did not fail"))?;
+    ensure_eq!(e.kind(), io::ErrorKind::WouldBlock);
+    Ok(())
+}
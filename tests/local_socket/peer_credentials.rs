@@ -0,0 +1,54 @@
+use crate::{
+    local_socket::{prelude::*, ListenerOptions, Stream},
+    tests::util::*,
+};
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let client = Stream::connect(name.borrow()).opname("client connect")?;
+    let server = listener.accept().opname("accept")?;
+    // Both ends are in this process, so they should see each other as such.
+    for conn in [&client, &server] {
+        let creds = conn.peer_credentials().opname("peer credentials query")?;
+        verify(creds)?;
+    }
+    Ok(())
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "haiku",
+    target_os = "netbsd",
+    target_os = "illumos",
+    target_os = "solaris",
+    windows,
+))]
+fn verify(creds: crate::local_socket::PeerCredentials) -> TestResult {
+    ensure_eq!(creds.pid, Some(std::process::id()));
+    verify_ids(creds)
+}
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "haiku",
+    target_os = "netbsd",
+    target_os = "illumos",
+    target_os = "solaris",
+    windows,
+)))]
+fn verify(creds: crate::local_socket::PeerCredentials) -> TestResult { verify_ids(creds) }
+
+#[cfg(unix)]
+fn verify_ids(creds: crate::local_socket::PeerCredentials) -> TestResult {
+    ensure_eq!(creds.uid, Some(unsafe { libc::geteuid() }));
+    ensure_eq!(creds.gid, Some(unsafe { libc::getegid() }));
+    Ok(())
+}
+#[cfg(windows)]
+fn verify_ids(creds: crate::local_socket::PeerCredentials) -> TestResult {
+    ensure_eq!(creds.uid, None);
+    Ok(())
+}
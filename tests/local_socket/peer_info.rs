@@ -0,0 +1,49 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    color_eyre::eyre::{ensure, eyre},
+    std::{io::prelude::*, thread},
+};
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let _client = Stream::connect(name.borrow()).opname("client connect")?;
+    let (server, info) = listener.accept_with_info().opname("accept")?;
+    // The information should be the same as what the stream reports while the client is alive.
+    let creds = server.peer_credentials().opname("peer credentials query")?;
+    ensure_eq!(info.credentials, creds);
+    ensure_eq!(info.name, server.peer_name());
+    Ok(())
+}
+
+pub fn run_and_verify_filtered(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let server = thread::spawn(move || {
+        // Both clients are in this process, so tell them apart by order of arrival.
+        let mut seen = 0_u32;
+        let (mut conn, _) = listener
+            .accept_filtered(|_| {
+                seen += 1;
+                seen == 2
+            })
+            .opname("accept")?;
+        conn.write_all(b"ok").opname("send")?;
+        TestResult::Ok(())
+    });
+
+    let mut rejected = Stream::connect(name.borrow()).opname("first client connect")?;
+    let mut buf = [0; 2];
+    // Depending on the platform, the closed connection reads as end of file or fails.
+    let rejected_read = rejected.read(&mut buf);
+    ensure!(!matches!(rejected_read, Ok(n) if n > 0), "rejected client received data");
+    let mut accepted = Stream::connect(name.borrow()).opname("second client connect")?;
+    accepted.read_exact(&mut buf).opname("receive")?;
+    ensure_eq!(&buf, b"ok");
+    server.join().map_err(|_| eyre!("server thread panicked"))?
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "polling")]
+
+use {
+    crate::{
+        local_socket::{prelude::*, Listener, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    ::polling::{Events, Poller},
+    std::time::Duration,
+};
+
+const KEY: usize = 42;
+
+#[cfg(unix)]
+fn register(poller: &Poller, listener: &Listener) -> TestResult {
+    use ::polling::Event;
+    unsafe { poller.add(listener, Event::readable(KEY)) }.opname("poller add")
+}
+#[cfg(unix)]
+fn deregister(poller: &Poller, listener: &Listener) -> TestResult {
+    poller.delete(listener).opname("poller delete")
+}
+
+#[cfg(windows)]
+fn register(poller: &Poller, listener: &Listener) -> TestResult {
+    use {crate::os::windows::local_socket::ListenerExt, ::polling::PollMode};
+    unsafe { listener.add_to_poller(poller, KEY, PollMode::Oneshot) }.opname("poller add")
+}
+#[cfg(windows)]
+fn deregister(poller: &Poller, listener: &Listener) -> TestResult {
+    use crate::os::windows::local_socket::ListenerExt;
+    listener.remove_from_poller(poller).opname("poller delete")
+}
+
+fn poll_listener(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let poller = Poller::new().opname("poller creation")?;
+    register(&poller, &listener)?;
+
+    let mut events = Events::new();
+    poller.wait(&mut events, Some(Duration::ZERO)).opname("idle wait")?;
+    ensure_eq!(events.len(), 0);
+
+    let _client = Stream::connect(name.borrow()).opname("client connect")?;
+    events.clear();
+    poller.wait(&mut events, Some(Duration::from_secs(5))).opname("wait")?;
+    ensure_eq!(events.iter().map(|ev| (ev.key, ev.readable)).collect::<Vec<_>>(), [(KEY, true)]);
+    let _server = listener.accept().opname("accept")?;
+
+    deregister(&poller, &listener)
+}
+
+#[test]
+fn polling_listener_file() -> TestResult { test_wrapper(|| poll_listener(make_id!(), true)) }
+#[test]
+fn polling_listener_namespaced() -> TestResult {
+    test_wrapper(|| poll_listener(make_id!(), false))
+}
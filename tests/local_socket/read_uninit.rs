@@ -0,0 +1,30 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::{io::Write, mem::MaybeUninit},
+};
+
+const MSG: &[u8] = b"Read me without zeroing me out first!";
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let mut client = Stream::connect(name.borrow()).opname("client connect")?;
+    let server = listener.accept().opname("accept")?;
+    client.write_all(MSG).opname("send")?;
+
+    let mut buf = [MaybeUninit::<u8>::uninit(); 128];
+    let mut received = Vec::with_capacity(MSG.len());
+    while received.len() < MSG.len() {
+        let n = server.read_to_uninit(&mut buf).opname("receive")?;
+        ensure!(n > 0, "end of file before the whole message arrived");
+        // SAFETY: read_to_uninit() has initialized the first n bytes
+        received.extend(buf.iter().take(n).map(|b| unsafe { b.assume_init() }));
+    }
+    ensure_eq!(received, MSG);
+    Ok(())
+}
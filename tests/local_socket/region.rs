@@ -0,0 +1,45 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ListenerOptions, Stream},
+        shared_memory::ShmRegion,
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::io::prelude::*,
+};
+
+const SIZE: usize = 1024 * 1024;
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let mut client = Stream::connect(name.borrow()).opname("client connect")?;
+    let mut server = listener.accept().opname("accept")?;
+
+    let mut region = ShmRegion::new(SIZE).opname("region creation")?;
+    ensure_eq!(region.len(), SIZE);
+    // SAFETY: the region has not been sent yet
+    let contents = unsafe { region.as_mut_slice() };
+    ensure!(contents.iter().all(|&b| b == 0));
+    contents.iter_mut().zip((0..=u8::MAX).cycle()).for_each(|(dst, src)| *dst = src);
+    client.send_region(region).opname("send region")?;
+    client.write_all(b"after").opname("client write")?;
+
+    let mut region = server.recv_region().opname("receive region")?;
+    ensure_eq!(region.len(), SIZE);
+    // SAFETY: the client has dropped its end of the region
+    let contents = unsafe { region.as_mut_slice() };
+    ensure!(contents.iter().zip((0..=u8::MAX).cycle()).all(|(&b, expected)| b == expected));
+    let mut after = [0; 5];
+    server.read_exact(&mut after).opname("server read")?;
+    ensure_eq!(&after, b"after");
+
+    // Send the region back to make sure that received regions are writable and transferable.
+    contents.fill(42);
+    server.send_region(region).opname("send region back")?;
+    let region = client.recv_region().opname("receive region back")?;
+    // SAFETY: as above
+    ensure!(unsafe { region.as_slice() }.iter().all(|&b| b == 42));
+    Ok(())
+}
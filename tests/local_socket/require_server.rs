@@ -0,0 +1,36 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ConnectOptions, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    color_eyre::eyre::eyre,
+    std::io,
+};
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let opts = || ConnectOptions::new().name(name.borrow());
+
+    // The server is in this process, so it's run by the same user as the client.
+    #[cfg(unix)]
+    let (right, wrong) = {
+        let _probe = Stream::connect(name.borrow()).opname("probe connect")?;
+        let server = listener.accept().opname("probe accept")?;
+        let uid = server.peer_credentials()?.uid.unwrap_or_default();
+        (Some(opts().require_server_uid(uid)), opts().require_server_uid(uid.wrapping_add(1)))
+    };
+    // S-1-0-0 is the Nobody SID, which no process runs as.
+    #[cfg(windows)]
+    let (right, wrong) = (None::<ConnectOptions<'_>>, opts().require_server_sid("S-1-0-0"));
+
+    if let Some(right) = right {
+        let _client = right.connect_sync().opname("connect to the right user")?;
+        let _server = listener.accept().opname("accept")?;
+    }
+    let e = wrong.connect_sync().err().ok_or_else(|| eyre!("connected to the wrong user"))?;
+    ensure_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+    let _server = listener.accept().opname("accept")?;
+    Ok(())
+}
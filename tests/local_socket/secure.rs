@@ -0,0 +1,60 @@
+#![cfg(feature = "noise")]
+
+use {
+    crate::{
+        local_socket::{prelude::*, ListenerOptions, SecureStream, Stream, StaticKeypair},
+        tests::util::*,
+    },
+    color_eyre::eyre::{ensure, eyre},
+    std::{
+        io::{self, prelude::*},
+        thread,
+    },
+};
+
+fn run(id: &'static str, path: bool, authorize_client: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let server_keys = StaticKeypair::generate().opname("generate server keys")?;
+    let client_keys = StaticKeypair::generate().opname("generate client keys")?;
+    let server_public = *server_keys.public_key();
+    let allowed = if authorize_client { vec![*client_keys.public_key()] } else { Vec::new() };
+
+    let server = thread::spawn(move || {
+        let conn = listener.accept().opname("accept")?;
+        let mut conn = match SecureStream::accept(conn, &server_keys, &allowed) {
+            Err(e) if !authorize_client => {
+                ensure_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+                return Ok(());
+            }
+            rslt => rslt.opname("server handshake")?,
+        };
+        let mut line = [0; 5];
+        conn.read_exact(&mut line).opname("server receive")?;
+        ensure_eq!(&line, b"hello");
+        conn.write_all(b"world").opname("server send")?;
+        Ok(())
+    });
+
+    let client = Stream::connect(name.borrow()).opname("connect")?;
+    match SecureStream::connect(client, &client_keys, &server_public) {
+        Ok(mut client) => {
+            ensure!(authorize_client, "unauthorized client completed the handshake");
+            ensure_eq!(client.remote_public_key(), &server_public);
+            client.write_all(b"hello").opname("client send")?;
+            let mut line = [0; 5];
+            client.read_exact(&mut line).opname("client receive")?;
+            ensure_eq!(&line, b"world");
+        }
+        Err(e) => ensure!(!authorize_client, "client handshake failed: {e}"),
+    }
+    server.join().map_err(|_| eyre!("server thread panicked"))?
+}
+
+#[test]
+fn secure_file() -> TestResult { test_wrapper(|| run(make_id!(), true, true)) }
+#[test]
+fn secure_namespaced() -> TestResult { test_wrapper(|| run(make_id!(), false, true)) }
+#[test]
+fn secure_unauthorized() -> TestResult { test_wrapper(|| run(make_id!(), false, false)) }
@@ -0,0 +1,37 @@
+use {
+    crate::{
+        local_socket::{
+            prelude::*, GenericFilePath, GenericNamespaced, ListenerOptions, StaticName, Stream,
+        },
+        tests::util::*,
+    },
+    std::process,
+};
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let string = if !path {
+        format!("interprocess-test-static-{id}-{}.sock", process::id())
+    } else if cfg!(windows) {
+        format!(r"\\.\pipe\interprocess-test-static-{id}-{}", process::id())
+    } else {
+        format!("/tmp/interprocess-test-static-{id}-{}.sock", process::id())
+    };
+    let string: &'static str = Box::leak(string.into_boxed_str());
+    let (name, expected) = if path {
+        (StaticName::path(string), string.to_fs_name::<GenericFilePath>()?)
+    } else {
+        (StaticName::namespaced(string), string.to_ns_name::<GenericNamespaced>()?)
+    };
+    ensure_eq!(name.as_str(), string);
+    ensure_eq!(name.name().opname("conversion")?, expected);
+
+    let listener = ListenerOptions::new()
+        .name(name.name()?)
+        .create_sync()
+        .opname("listener creation")?;
+    for _ in 0..2 {
+        let _client = Stream::connect(name.name()?).opname("client connect")?;
+        let _server = listener.accept().opname("accept")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ListenerNonblockingMode, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    std::io,
+};
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    ensure_eq!(listener.stats().accepted, 0);
+
+    for _ in 0..2 {
+        let _client = Stream::connect(name.borrow()).opname("connect")?;
+        let _conn = listener.accept().opname("accept")?;
+    }
+    ensure_eq!(listener.stats().accepted, 2);
+
+    // Having no client to accept is not an error.
+    listener.set_nonblocking(ListenerNonblockingMode::Accept).opname("set nonblocking")?;
+    let err = listener.accept().err();
+    ensure_eq!(err.map(|e| e.kind()), Some(io::ErrorKind::WouldBlock));
+    let stats = listener.stats();
+    ensure_eq!((stats.accepted, stats.accept_errors), (2, 0));
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ListenerOptions, Stream, Throttled},
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::{
+        io::prelude::*,
+        num::NonZeroU64,
+        time::{Duration, Instant},
+    },
+};
+
+const RATE: u64 = 1000;
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let client = Stream::connect(name.borrow()).opname("connect")?;
+    let mut conn = listener.accept().opname("accept")?;
+    let mut client = Throttled::with_rates(client, None, NonZeroU64::new(RATE));
+    ensure_eq!(client.send_rate(), NonZeroU64::new(RATE));
+
+    // The first second's worth goes through right away, the remaining half a second's worth has
+    // to wait for the bucket to refill.
+    let msg = [b'x'; 1500];
+    let start = Instant::now();
+    client.write_all(&msg).opname("send")?;
+    let elapsed = start.elapsed();
+    ensure!(elapsed >= Duration::from_millis(400), "sending was not throttled ({elapsed:?})");
+
+    let mut buf = [0; 1500];
+    conn.read_exact(&mut buf).opname("receive")?;
+    ensure_eq!(buf, msg);
+    Ok(())
+}
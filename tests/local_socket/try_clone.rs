@@ -0,0 +1,41 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ListenerOptions, Stream},
+        tests::util::*,
+        TryClone,
+    },
+    color_eyre::eyre::eyre,
+    std::{
+        io::{prelude::*, BufReader},
+        thread,
+    },
+};
+
+const MSG: &[u8] = b"Hello from a cloned stream!\n";
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let client = Stream::connect(name.borrow()).opname("client connect")?;
+    let server = listener.accept().opname("accept")?;
+
+    // Echo server: the client writes through one handle and reads through another.
+    let echo = thread::spawn(move || -> TestResult {
+        let mut server = BufReader::new(server);
+        let mut line = Vec::new();
+        server.read_until(b'\n', &mut line).opname("server receive")?;
+        server.get_mut().write_all(&line).opname("server send")
+    });
+
+    let mut sender = client.try_clone().opname("try_clone")?;
+    let writer = thread::spawn(move || sender.write_all(MSG).opname("client send"));
+
+    let mut received = Vec::new();
+    BufReader::new(client).read_until(b'\n', &mut received).opname("client receive")?;
+    ensure_eq!(received, MSG);
+
+    writer.join().map_err(|_| eyre!("client sender thread panicked"))??;
+    echo.join().map_err(|_| eyre!("server thread panicked"))??;
+    Ok(())
+}
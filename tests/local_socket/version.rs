@@ -0,0 +1,55 @@
+use {
+    crate::{
+        local_socket::{
+            negotiate_version, prelude::*, ListenerOptions, ProtocolVersion, Stream,
+            VersionMismatch,
+        },
+        tests::util::*,
+    },
+    color_eyre::eyre::{ensure, eyre},
+    std::thread,
+};
+
+fn run(id: &'static str, path: bool, client_major: u16) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let server_version = ProtocolVersion::new("test-daemon", 2, 3);
+    let client_version = ProtocolVersion::new("test-daemon", client_major, 1);
+    let compatible = server_version.is_compatible_with(&client_version);
+
+    let (sv, cv) = (server_version.clone(), client_version.clone());
+    let server = thread::spawn(move || {
+        let mut conn = listener.accept().opname("accept")?;
+        match negotiate_version(&mut conn, &sv) {
+            Ok(remote) => {
+                ensure!(compatible, "server accepted an incompatible version");
+                ensure_eq!(remote, cv);
+            }
+            Err(e) => {
+                let mismatch = VersionMismatch::find(&e).ok_or_else(|| eyre!("server: {e}"))?;
+                ensure_eq!(mismatch.remote, cv);
+            }
+        }
+        Ok(())
+    });
+
+    let mut client = Stream::connect(name.borrow()).opname("connect")?;
+    match negotiate_version(&mut client, &client_version) {
+        Ok(remote) => {
+            ensure!(compatible, "client accepted an incompatible version");
+            ensure_eq!(remote, server_version);
+        }
+        Err(e) => {
+            let mismatch = VersionMismatch::find(&e).ok_or_else(|| eyre!("client: {e}"))?;
+            ensure_eq!(mismatch.local, client_version);
+            ensure_eq!(mismatch.remote, server_version);
+        }
+    }
+    server.join().map_err(|_| eyre!("server thread panicked"))?
+}
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult { run(id, path, 2) }
+
+#[test]
+fn version_mismatch() -> TestResult { test_wrapper(|| run(make_id!(), false, 3)) }
@@ -0,0 +1,68 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    color_eyre::eyre::{ensure, eyre},
+    std::{
+        io::{prelude::*, BufReader},
+        thread,
+    },
+};
+
+const THREADS: usize = 4;
+const MESSAGES: usize = 64;
+const MSG_LEN: usize = 512;
+
+pub fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let client = Stream::connect(name.borrow()).opname("connect")?;
+    let server = listener.accept().opname("accept")?;
+
+    let receiver = thread::spawn(move || -> TestResult {
+        let mut server = BufReader::new(server);
+        let mut counts = [0; THREADS];
+        let mut line = Vec::new();
+        for _ in 0..MESSAGES * THREADS {
+            line.clear();
+            server.read_until(b'\n', &mut line).opname("server receive")?;
+            let &first = line.first().ok_or_else(|| eyre!("empty message"))?;
+            ensure!(line == message(first), "messages were interleaved");
+            let count = counts
+                .get_mut(usize::from(first.wrapping_sub(b'a')))
+                .ok_or_else(|| eyre!("unexpected message byte {first}"))?;
+            *count += 1;
+        }
+        ensure_eq!(counts, [MESSAGES; THREADS]);
+        Ok(())
+    });
+
+    let (_recver, handle) = client.writer_handle();
+    let senders = (b'a'..)
+        .take(THREADS)
+        .map(|byte| {
+            let handle = handle.clone();
+            let msg = message(byte);
+            thread::spawn(move || {
+                for _ in 0..MESSAGES {
+                    handle.send(&msg).opname("send")?;
+                }
+                TestResult::Ok(())
+            })
+        })
+        .collect::<Vec<_>>();
+    for sender in senders {
+        sender.join().map_err(|_| eyre!("sender thread panicked"))??;
+    }
+    receiver.join().map_err(|_| eyre!("receiver thread panicked"))??;
+    ensure!(handle.try_into_inner().is_ok(), "handle still shared after all clones were dropped");
+    Ok(())
+}
+
+fn message(byte: u8) -> Vec<u8> {
+    let mut msg = vec![byte; MSG_LEN];
+    msg.push(b'\n');
+    msg
+}
@@ -0,0 +1,53 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ConnectOptions, GenericFilePath, ListenerOptions},
+        os::unix::local_socket::{ConnectOptionsExt, ListenerOptionsExt},
+        tests::util::*,
+    },
+    std::{
+        fs::{self, Permissions},
+        io,
+        os::unix::prelude::*,
+        path::PathBuf,
+    },
+};
+
+fn test_audit() -> TestResult {
+    let rn = Xorshift32::from_id(make_id!()).next();
+    let mut dir = PathBuf::from(std::env::var_os("TMPDIR").unwrap_or_else(|| "/tmp".into()));
+    dir.push(format!("interprocess-test-{rn:08x}"));
+    fs::create_dir_all(&dir).opname("create directory")?;
+    let name = dir.join("s.sock").into_os_string().to_fs_name::<GenericFilePath>()?;
+    let listen = || {
+        ListenerOptions::new().name(name.borrow()).audit_directory(true).create_sync()
+    };
+
+    fs::set_permissions(&dir, Permissions::from_mode(0o777)).opname("chmod")?;
+    let e = listen().err().ok_or_else(|| color_eyre::eyre::eyre!("audit passed"))?;
+    ensure_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+
+    fs::set_permissions(&dir, Permissions::from_mode(0o1777)).opname("chmod")?;
+    let listener = listen().opname("listen with sticky bit")?;
+    let _ = ConnectOptions::new()
+        .name(name.borrow())
+        .audit_directory(true)
+        .connect_sync()
+        .opname("connect")?;
+
+    // A client must not fall for a directory that has since become unsafe.
+    fs::set_permissions(&dir, Permissions::from_mode(0o777)).opname("chmod")?;
+    let e = ConnectOptions::new()
+        .name(name.borrow())
+        .audit_directory(true)
+        .connect_sync()
+        .err()
+        .ok_or_else(|| color_eyre::eyre::eyre!("audit passed"))?;
+    ensure_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+
+    drop(listener);
+    fs::remove_dir(&dir).opname("remove directory")?;
+    Ok(())
+}
+
+#[test]
+fn local_socket_directory_audit() -> TestResult { test_wrapper(test_audit) }
@@ -0,0 +1,23 @@
+use crate::{
+    local_socket::{prelude::*, ListenerOptions, Stream},
+    tests::util::*,
+};
+
+fn test_backlog(path: bool) -> TestResult {
+    let (name, listener) =
+        listen_and_pick_name(&mut namegen_local_socket(make_id!(), path), |nm| {
+            ListenerOptions::new().name(nm.borrow()).backlog(16).create_sync()
+        })?;
+    ensure_eq!(listener.backlog(), Some(16));
+
+    listener.set_backlog(4).opname("set backlog")?;
+    ensure_eq!(listener.backlog(), Some(4));
+    let _client = Stream::connect(name.borrow()).opname("connect")?;
+    let _conn = listener.accept().opname("accept")?;
+    Ok(())
+}
+
+#[test]
+fn local_socket_backlog_file() -> TestResult { test_wrapper(|| test_backlog(true)) }
+#[test]
+fn local_socket_backlog_namespaced() -> TestResult { test_wrapper(|| test_backlog(false)) }
@@ -0,0 +1,57 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ConnectOptions, ListenerOptions, Name, NameInner},
+        os::unix::local_socket::ConnectOptionsExt,
+        tests::util::*,
+    },
+    color_eyre::eyre::{ensure, eyre},
+    std::{fs, io, path::Path},
+};
+
+#[test]
+fn local_socket_bind_name() -> TestResult {
+    test_wrapper(|| {
+        let (name, listener) =
+            listen_and_pick_name(&mut namegen_local_socket(make_id!(), true), |nm| {
+                ListenerOptions::new().name(nm.borrow()).create_sync()
+            })?;
+        let (client_name, _client) =
+            listen_and_pick_name(&mut namegen_local_socket(make_id!(), true), |nm| {
+                ConnectOptions::new().name(name.borrow()).bind_name(nm.borrow()).connect_sync()
+            })?;
+        let server_side = listener.accept().opname("accept")?;
+        let peer_name = server_side.peer_name();
+
+        if let Name(NameInner::UdSocketPath(path)) = &*client_name {
+            let _ = fs::remove_file(path);
+        }
+        ensure_eq!(peer_name, Some(client_name.borrow().into_owned()));
+        Ok(())
+    })
+}
+
+#[test]
+fn local_socket_bind_name_no_server() -> TestResult {
+    test_wrapper(|| {
+        let mut namegen = namegen_local_socket(make_id!(), true);
+        let (Some(Ok(name)), Some(Ok(client_name))) = (namegen.next(), namegen.next()) else {
+            unreachable!("name generation failed")
+        };
+        let Name(NameInner::UdSocketPath(client_path)) = &*client_name else {
+            unreachable!("not a path name")
+        };
+        // A failed attempt must not leave the socket file behind, which would make the next
+        // attempt fail with AddrInUse instead.
+        for _ in 0..2 {
+            let e = ConnectOptions::new()
+                .name(name.borrow())
+                .bind_name(client_name.borrow())
+                .connect_sync()
+                .err()
+                .ok_or_else(|| eyre!("connected to nonexistent server"))?;
+            ensure!(e.kind() != io::ErrorKind::AddrInUse, "bind name left taken: {e}");
+            ensure!(!Path::new(client_path).exists(), "socket file left behind");
+        }
+        Ok(())
+    })
+}
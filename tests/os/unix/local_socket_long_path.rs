@@ -0,0 +1,40 @@
+use {
+    crate::{
+        local_socket::{prelude::*, GenericFilePath, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::{
+        fs,
+        io::{prelude::*, BufReader},
+        path::PathBuf,
+    },
+};
+
+fn test_long_path() -> TestResult {
+    let rn = Xorshift32::from_id(make_id!()).next();
+    let mut dir = PathBuf::from(std::env::var_os("TMPDIR").unwrap_or_else(|| "/tmp".into()));
+    dir.push(format!("interprocess-test-{rn:08x}-{}", "d".repeat(120)));
+    fs::create_dir_all(&dir).opname("create directory")?;
+    let path = dir.join("s.sock");
+    ensure!(path.as_os_str().len() > 108);
+
+    let name = path.as_os_str().to_fs_name::<GenericFilePath>().opname("name")?;
+    let listener = ListenerOptions::new().name(name.borrow()).create_sync().opname("listen")?;
+    ensure!(path.exists(), "socket file not created at the long path");
+
+    let mut client = Stream::connect(name).opname("connect")?;
+    client.write_all(b"hi\n").opname("send")?;
+    let mut conn = BufReader::new(listener.accept().opname("accept")?);
+    let mut line = String::new();
+    conn.read_line(&mut line).opname("receive")?;
+    ensure_eq!(line, "hi\n");
+
+    drop(listener);
+    ensure!(!path.exists(), "socket file not unlinked");
+    fs::remove_dir(&dir).opname("remove directory")?;
+    Ok(())
+}
+
+#[test]
+fn local_socket_long_path() -> TestResult { test_wrapper(test_long_path) }
@@ -33,11 +33,10 @@ fn get_fd_mode(fd: BorrowedFd<'_>) -> TestResult<mode_t> {
     Ok(stat.st_mode & 0o777)
 }
 
-fn test_inner(path: bool) -> TestResult {
-    const MODE: libc::mode_t = 0o600;
+fn test_inner(path: bool, mode: mode_t) -> TestResult {
     let (name, listener) =
         listen_and_pick_name(&mut namegen_local_socket(make_id!(), path), |nm| {
-            ListenerOptions::new().name(nm.borrow()).mode(MODE).create_sync()
+            ListenerOptions::new().name(nm.borrow()).mode(mode).create_sync()
         })?;
     let name = Arc::try_unwrap(name).unwrap();
     let _ = Stream::connect(name.borrow()).opname("client connect")?;
@@ -52,15 +51,19 @@ fn test_inner(path: bool) -> TestResult {
     .opname("get mode")?;
     if actual_mode != 0 {
         // FreeBSD refuses to fstat sockets for reasons I cannot even begin to fathom
-        ensure_eq!(actual_mode, MODE);
+        ensure_eq!(actual_mode, mode);
     }
 
     Ok(())
 }
 
 #[test]
-fn local_socket_file_mode() -> TestResult { test_wrapper(|| test_inner(true)) }
+fn local_socket_file_mode() -> TestResult { test_wrapper(|| test_inner(true, 0o600)) }
+
+/// Group write permission is stripped by the usual umasks, and must be restored nonetheless.
+#[test]
+fn local_socket_file_mode_unmasked() -> TestResult { test_wrapper(|| test_inner(true, 0o660)) }
 
 #[cfg(any(target_os = "linux", target_os = "android"))]
 #[test]
-fn local_socket_namespaced_mode() -> TestResult { test_wrapper(|| test_inner(false)) }
+fn local_socket_namespaced_mode() -> TestResult { test_wrapper(|| test_inner(false, 0o600)) }
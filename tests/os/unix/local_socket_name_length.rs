@@ -0,0 +1,32 @@
+use {
+    crate::{
+        local_socket::{prelude::*, GenericFilePath, GenericNamespaced},
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::io,
+};
+
+fn ensure_too_long(rslt: io::Result<impl std::fmt::Debug>) -> TestResult {
+    let e = rslt.err().ok_or_else(|| color_eyre::eyre::eyre!("overlong name accepted"))?;
+    ensure_eq!(e.kind(), io::ErrorKind::InvalidInput);
+    ensure!(e.to_string().contains("exceeds the limit"), "undescriptive error: {e}");
+    Ok(())
+}
+
+#[test]
+fn local_socket_namespaced_name_length() -> TestResult {
+    test_wrapper(|| {
+        "a".repeat(64).to_ns_name::<GenericNamespaced>().opname("short name")?;
+        ensure_too_long("a".repeat(300).to_ns_name::<GenericNamespaced>())
+    })
+}
+
+#[test]
+fn local_socket_path_name_length() -> TestResult {
+    test_wrapper(|| {
+        // Too long for sun_path either directly or through /proc/self/fd.
+        let path = format!("/tmp/{}", "a".repeat(200));
+        ensure_too_long(path.to_fs_name::<GenericFilePath>())
+    })
+}
@@ -0,0 +1,65 @@
+use {
+    crate::{
+        local_socket::{Listener, ListenerOptions, Name, NameInner, NamePolicy},
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::{ffi::OsString, fs, path::PathBuf},
+};
+
+fn listen(id: &'static str, policy: NamePolicy) -> TestResult<(PathBuf, Listener)> {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, true), |nm| {
+        ListenerOptions::new().name(nm.borrow()).name_policy(policy).create_sync()
+    })?;
+    let Name(NameInner::UdSocketPath(path)) = &*name else { unreachable!() };
+    Ok((PathBuf::from(path.clone().into_owned()), listener))
+}
+fn relisten(path: &PathBuf, policy: NamePolicy) -> TestResult<Listener> {
+    let name = Name(NameInner::UdSocketPath(path.as_os_str().to_owned().into()));
+    ListenerOptions::new().name(name).name_policy(policy).create_sync().opname("relisten")
+}
+
+fn test_unlink_and_keep() -> TestResult {
+    let (path, listener) = listen(make_id!(), NamePolicy::Unlink)?;
+    drop(listener);
+    ensure!(!path.exists(), "socket file not unlinked");
+
+    let (path, listener) = listen(make_id!(), NamePolicy::Keep)?;
+    drop(listener);
+    ensure!(path.exists(), "socket file unlinked");
+    fs::remove_file(&path).opname("cleanup")?;
+    Ok(())
+}
+
+fn test_unlink_if_owned() -> TestResult {
+    let (path, first) = listen(make_id!(), NamePolicy::UnlinkIfOwned)?;
+    // Another listener takes over the path, as during a restart.
+    fs::remove_file(&path).opname("unlink")?;
+    let second = relisten(&path, NamePolicy::UnlinkIfOwned)?;
+    drop(first);
+    ensure!(path.exists(), "socket file of the other listener unlinked");
+    drop(second);
+    ensure!(!path.exists(), "socket file not unlinked");
+    Ok(())
+}
+
+fn test_rename_aside() -> TestResult {
+    let (path, listener) = listen(make_id!(), NamePolicy::RenameAside)?;
+    drop(listener);
+    let mut aside = OsString::from(path.as_os_str());
+    aside.push(".old");
+    ensure!(!path.exists(), "socket file not renamed");
+    fs::remove_file(&aside).opname("cleanup")?;
+    Ok(())
+}
+
+#[test]
+fn local_socket_name_policy_unlink_and_keep() -> TestResult {
+    test_wrapper(test_unlink_and_keep)
+}
+#[test]
+fn local_socket_name_policy_unlink_if_owned() -> TestResult {
+    test_wrapper(test_unlink_if_owned)
+}
+#[test]
+fn local_socket_name_policy_rename_aside() -> TestResult { test_wrapper(test_rename_aside) }
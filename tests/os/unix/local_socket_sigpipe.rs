@@ -0,0 +1,48 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ListenerOptions, Stream},
+        tests::util::*,
+    },
+    color_eyre::eyre::bail,
+    std::io::{self, prelude::*},
+};
+
+/// Restores the previous `SIGPIPE` disposition when dropped.
+struct SigpipeGuard(libc::sighandler_t);
+impl SigpipeGuard {
+    /// Rust executables ignore `SIGPIPE` by default, which would make the test pass trivially, so
+    /// the default disposition of terminating the process is restored for its duration.
+    fn default_disposition() -> Self {
+        Self(unsafe { libc::signal(libc::SIGPIPE, libc::SIG_DFL) })
+    }
+}
+impl Drop for SigpipeGuard {
+    fn drop(&mut self) { unsafe { libc::signal(libc::SIGPIPE, self.0) }; }
+}
+
+fn test_inner(path: bool) -> TestResult {
+    use io::ErrorKind::*;
+    let (name, listener) =
+        listen_and_pick_name(&mut namegen_local_socket(make_id!(), path), |nm| {
+            ListenerOptions::new().name(nm.borrow()).create_sync()
+        })?;
+    let mut client = Stream::connect(name.borrow()).opname("client connect")?;
+    drop(listener.accept().opname("accept")?);
+
+    let _guard = SigpipeGuard::default_disposition();
+    // The first send may still succeed by filling the buffer, but one of the following ones must
+    // notice that the peer is gone.
+    for _ in 0..16 {
+        match client.write_all(&[0; 4096]) {
+            Ok(()) => continue,
+            Err(e) if matches!(e.kind(), BrokenPipe | ConnectionReset) => return Ok(()),
+            Err(e) => return Err(e).opname("client send"),
+        }
+    }
+    bail!("sending to a disconnected peer kept succeeding")
+}
+
+#[test]
+fn local_socket_sigpipe_file() -> TestResult { test_wrapper(|| test_inner(true)) }
+#[test]
+fn local_socket_sigpipe_namespaced() -> TestResult { test_wrapper(|| test_inner(false)) }
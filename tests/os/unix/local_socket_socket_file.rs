@@ -0,0 +1,54 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ConnectOptions, GenericFilePath, ListenerOptions},
+        os::unix::local_socket::{check_socket_file, ConnectOptionsExt, ListenerOptionsExt},
+        tests::util::*,
+    },
+    std::{fs, io, os::unix::prelude::*, path::PathBuf},
+};
+
+fn test_socket_file() -> TestResult {
+    let rn = Xorshift32::from_id(make_id!()).next();
+    let mut path = PathBuf::from(std::env::var_os("TMPDIR").unwrap_or_else(|| "/tmp".into()));
+    path.push(format!("interprocess-test-{rn:08x}.sock"));
+    let name = path.as_os_str().to_fs_name::<GenericFilePath>()?;
+    let listener = ListenerOptions::new()
+        .name(name.borrow())
+        .mode(0o600)
+        .create_sync()
+        .opname("listen")?;
+    let uid = fs::metadata(&path).opname("stat")?.uid();
+
+    check_socket_file(name.borrow(), Some(uid), 0o077).opname("check")?;
+    let refused = |rslt: io::Result<()>| -> TestResult {
+        let e = rslt.err().ok_or_else(|| color_eyre::eyre::eyre!("check passed"))?;
+        ensure_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+        Ok(())
+    };
+    refused(check_socket_file(name.borrow(), Some(uid.wrapping_add(1)), 0))?;
+    refused(check_socket_file(name.borrow(), None, 0o200))?;
+
+    let _ = ConnectOptions::new()
+        .name(name.borrow())
+        .expect_socket_owner(Some(uid))
+        .forbid_socket_mode(0o077)
+        .connect_sync()
+        .opname("connect")?;
+    refused(
+        ConnectOptions::new()
+            .name(name.borrow())
+            .expect_socket_owner(Some(uid.wrapping_add(1)))
+            .connect_sync()
+            .map(drop),
+    )?;
+
+    // A regular file in place of the socket is an impostor too.
+    drop(listener);
+    fs::write(&path, b"").opname("create regular file")?;
+    refused(check_socket_file(name.borrow(), Some(uid), 0))?;
+    fs::remove_file(&path).opname("remove regular file")?;
+    Ok(())
+}
+
+#[test]
+fn local_socket_socket_file_check() -> TestResult { test_wrapper(test_socket_file) }
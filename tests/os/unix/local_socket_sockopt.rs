@@ -0,0 +1,33 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ListenerOptions, Stream},
+        os::unix::local_socket::sockopt::{RecvLowWatermark, SocketOptionExt},
+        tests::util::*,
+    },
+    libc::{c_int, SOL_SOCKET, SO_RCVLOWAT},
+};
+
+fn test_sockopt(path: bool) -> TestResult {
+    let (name, listener) =
+        listen_and_pick_name(&mut namegen_local_socket(make_id!(), path), |nm| {
+            ListenerOptions::new().name(nm.borrow()).create_sync()
+        })?;
+    let client = Stream::connect(name.borrow()).opname("connect")?;
+    let _conn = listener.accept().opname("accept")?;
+
+    client.set_socket_option::<RecvLowWatermark>(4).opname("set SO_RCVLOWAT")?;
+    ensure_eq!(client.get_socket_option::<RecvLowWatermark>().opname("get SO_RCVLOWAT")?, 4);
+
+    let mut raw = [0; std::mem::size_of::<c_int>()];
+    // SAFETY: SO_RCVLOWAT is an integer
+    let len = unsafe { client.get_socket_option_raw(SOL_SOCKET, SO_RCVLOWAT, &mut raw) }
+        .opname("get SO_RCVLOWAT raw")?;
+    ensure_eq!(len, raw.len());
+    ensure_eq!(c_int::from_ne_bytes(raw), 4);
+    Ok(())
+}
+
+#[test]
+fn local_socket_sockopt_file() -> TestResult { test_wrapper(|| test_sockopt(true)) }
+#[test]
+fn local_socket_sockopt_namespaced() -> TestResult { test_wrapper(|| test_sockopt(false)) }
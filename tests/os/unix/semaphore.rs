@@ -0,0 +1,53 @@
+use {
+    crate::{
+        os::unix::semaphore::{NamedSemaphore, SemaphoreOptions},
+        tests::util::*,
+    },
+    std::{io, time::Duration},
+};
+
+const SHORT: Duration = Duration::from_millis(10);
+
+fn test_named() -> TestResult {
+    let (name, creator) = listen_and_pick_name(&mut namegen_shm(make_id!()), |nm| {
+        SemaphoreOptions::new().initial_value(1).create(nm)
+    })?;
+    ensure_eq!(creator.try_wait().opname("try_wait")?, true);
+    ensure_eq!(creator.try_wait().opname("try_wait")?, false);
+
+    let opened = NamedSemaphore::open(&*name).opname("open")?;
+    opened.post().opname("post")?;
+    ensure_eq!(creator.wait_timeout(SHORT).opname("wait")?, true);
+    ensure_eq!(creator.wait_timeout(SHORT).opname("wait")?, false);
+
+    let again = SemaphoreOptions::new().create(&*name);
+    ensure_eq!(again.map(|_| ()).map_err(|e| e.kind()), Err(io::ErrorKind::AlreadyExists));
+
+    drop((creator, opened));
+    let reopened = NamedSemaphore::open(&*name).map(|_| ()).map_err(|e| e.kind());
+    ensure_eq!(reopened, Err(io::ErrorKind::NotFound));
+    Ok(())
+}
+
+#[cfg(feature = "tokio")]
+fn test_async() -> TestResult {
+    let (_, sem) = listen_and_pick_name(&mut namegen_shm(make_id!()), |nm| {
+        SemaphoreOptions::new().create(nm)
+    })?;
+    let rt = tokio::runtime::Builder::new_current_thread().build().opname("runtime creation")?;
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            std::thread::sleep(SHORT);
+            sem.post()
+        });
+        rt.block_on(sem.wait_async()).opname("async wait")
+    })?;
+    ensure_eq!(sem.try_wait().opname("try_wait")?, false);
+    Ok(())
+}
+
+#[test]
+fn semaphore_named() -> TestResult { test_wrapper(test_named) }
+#[cfg(feature = "tokio")]
+#[test]
+fn semaphore_async() -> TestResult { test_wrapper(test_async) }
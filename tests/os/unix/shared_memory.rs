@@ -0,0 +1,86 @@
+use {
+    crate::{
+        os::unix::shared_memory::{ShmObject, ShmOptions},
+        tests::util::*,
+        OrErrno,
+    },
+    color_eyre::eyre::ensure,
+    std::{ffi::CString, io, mem::zeroed, os::unix::prelude::*},
+};
+
+const SIZE: usize = 64 * 1024;
+
+fn fd_mode(fd: BorrowedFd<'_>) -> TestResult<libc::mode_t> {
+    let mut stat = unsafe { zeroed::<libc::stat>() };
+    unsafe { libc::fstat(fd.as_raw_fd(), &mut stat) != -1 }
+        .true_val_or_errno(())
+        .opname("fstat")?;
+    Ok(stat.st_mode & 0o777)
+}
+
+fn test_named() -> TestResult {
+    let (name, creator) = listen_and_pick_name(&mut namegen_shm(make_id!()), |nm| {
+        ShmOptions::new().mode(0o640).create(nm, SIZE)
+    })?;
+    ensure_eq!(creator.size(), SIZE);
+    ensure_eq!(fd_mode(creator.as_fd())?, 0o640);
+    let mut mapping = creator.map().opname("map")?;
+    // SAFETY: nobody else has opened the object yet
+    unsafe { mapping.as_mut_slice() }.fill(0xAA);
+
+    let opened = ShmObject::open(name.as_ref()).opname("open")?;
+    ensure_eq!(opened.size(), SIZE);
+    let other_mapping = opened.map().opname("map opened")?;
+    // SAFETY: no writes happen through the other mapping while this slice exists
+    ensure!(unsafe { other_mapping.as_slice() }.iter().all(|&b| b == 0xAA));
+
+    let again = ShmOptions::new().create(name.as_ref(), SIZE);
+    ensure_eq!(again.map(|_| ()).map_err(|e| e.kind()), Err(io::ErrorKind::AlreadyExists));
+
+    drop(creator);
+    let reopened = ShmObject::open(name.as_ref());
+    ensure_eq!(reopened.map(|_| ()).map_err(|e| e.kind()), Err(io::ErrorKind::NotFound));
+    Ok(())
+}
+
+fn test_no_unlink() -> TestResult {
+    let (name, mut creator) = listen_and_pick_name(&mut namegen_shm(make_id!()), |nm| {
+        ShmOptions::new().unlink_on_drop(false).create(nm, SIZE)
+    })?;
+    creator.do_not_unlink_on_drop();
+    drop(creator);
+    let opened = ShmObject::open(name.as_ref());
+    let cname = CString::new(name.as_bytes())?;
+    unsafe { libc::shm_unlink(cname.as_ptr()) != -1 }
+        .true_val_or_errno(())
+        .opname("shm_unlink")?;
+    ensure_eq!(opened.opname("open")?.size(), SIZE);
+    Ok(())
+}
+
+fn test_anonymous_inheritable() -> TestResult {
+    for inheritable in [false, true] {
+        let obj = ShmOptions::new()
+            .inheritable(inheritable)
+            .create_anonymous(SIZE)
+            .opname("create")?;
+        let flags = unsafe { libc::fcntl(obj.as_fd().as_raw_fd(), libc::F_GETFD) };
+        if flags == -1 {
+            return Err(io::Error::last_os_error()).opname("fcntl");
+        }
+        ensure_eq!(flags & libc::FD_CLOEXEC == 0, inheritable);
+        let mapping = obj.map().opname("map")?;
+        // SAFETY: the object is not shared with anyone
+        ensure!(unsafe { mapping.as_slice() }.iter().all(|&b| b == 0));
+    }
+    Ok(())
+}
+
+#[test]
+fn shared_memory_named() -> TestResult { test_wrapper(test_named) }
+#[test]
+fn shared_memory_no_unlink() -> TestResult { test_wrapper(test_no_unlink) }
+#[test]
+fn shared_memory_anonymous_inheritable() -> TestResult {
+    test_wrapper(test_anonymous_inheritable)
+}
@@ -0,0 +1,57 @@
+use {
+    crate::{
+        os::windows::event::{Event, EventOptions},
+        tests::util::*,
+    },
+    std::{io, thread, time::Duration},
+};
+
+const SHORT: Duration = Duration::from_millis(10);
+
+fn test_named() -> TestResult {
+    let (name, creator) = listen_and_pick_name(&mut namegen_shm(make_id!()), |nm| {
+        EventOptions::new().manual_reset(true).create_named(nm)
+    })?;
+    let opened = Event::open(name.as_ref()).opname("open")?;
+    ensure_eq!(creator.wait_timeout(SHORT).opname("wait")?, false);
+
+    opened.set().opname("set")?;
+    ensure_eq!(creator.wait_timeout(SHORT).opname("wait")?, true);
+    // Manual-reset events stay set.
+    ensure_eq!(creator.wait_timeout(SHORT).opname("wait")?, true);
+    opened.reset().opname("reset")?;
+    ensure_eq!(creator.wait_timeout(SHORT).opname("wait")?, false);
+
+    let again = EventOptions::new().create_named(name.as_ref());
+    ensure_eq!(again.map(|_| ()).map_err(|e| e.kind()), Err(io::ErrorKind::AlreadyExists));
+    Ok(())
+}
+
+fn test_auto_reset() -> TestResult {
+    let event = EventOptions::new().initially_set(true).create_anonymous().opname("create")?;
+    ensure_eq!(event.wait_timeout(SHORT).opname("wait")?, true);
+    ensure_eq!(event.wait_timeout(SHORT).opname("wait")?, false);
+    Ok(())
+}
+
+fn test_async() -> TestResult {
+    let event = Event::create_anonymous().opname("create")?;
+    let rt = tokio::runtime::Builder::new_current_thread().build().opname("runtime creation")?;
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(SHORT);
+            event.set()
+        });
+        rt.block_on(event.wait_async()).opname("async wait")
+    })?;
+    // The wait has consumed the signal.
+    ensure_eq!(event.wait_timeout(SHORT).opname("wait")?, false);
+    Ok(())
+}
+
+#[test]
+fn event_named() -> TestResult { test_wrapper(test_named) }
+#[test]
+fn event_auto_reset() -> TestResult { test_wrapper(test_auto_reset) }
+#[test]
+fn event_async() -> TestResult { test_wrapper(test_async) }
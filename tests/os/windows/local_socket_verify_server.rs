@@ -0,0 +1,39 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ConnectOptions, ListenerOptions},
+        tests::util::*,
+    },
+    color_eyre::eyre::eyre,
+    std::{env, io, process},
+};
+
+fn test_verify_server() -> TestResult {
+    let mut namegen = namegen_local_socket(make_id!(), false);
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_sync()
+    })?;
+    let exe = env::current_exe().opname("current_exe")?;
+
+    // The server is this very process.
+    let _client = ConnectOptions::new()
+        .name(name.borrow())
+        .verify_server(move |server| {
+            server.pid == process::id() && server.image_path.file_name() == exe.file_name()
+        })
+        .connect_sync()
+        .opname("connect to approved server")?;
+    let _server = listener.accept().opname("accept")?;
+
+    let e = ConnectOptions::new()
+        .name(name.borrow())
+        .verify_server(|_| false)
+        .connect_sync()
+        .err()
+        .ok_or_else(|| eyre!("connected to a rejected server"))?;
+    ensure_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+    let _server = listener.accept().opname("accept")?;
+    Ok(())
+}
+
+#[test]
+fn local_socket_verify_server() -> TestResult { test_wrapper(test_verify_server) }
@@ -0,0 +1,46 @@
+use {
+    crate::{
+        local_socket::{prelude::*, ConnectOptions, ListenerOptions},
+        os::windows::{local_socket::ListenerOptionsExt, named_pipe::WaitTimeout},
+        tests::util::*,
+    },
+    color_eyre::eyre::{ensure, eyre},
+    std::{
+        io,
+        time::{Duration, Instant},
+    },
+};
+
+const TIMEOUT: u32 = 500;
+
+fn test_wait_timeout() -> TestResult {
+    let mut namegen = namegen_local_socket(make_id!(), false);
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| {
+        ListenerOptions::new()
+            .name(nm.borrow())
+            .wait_timeout(WaitTimeout::from_raw(TIMEOUT))
+            .create_sync()
+    })?;
+
+    // Occupies the only instance, which stays busy since nobody accepts.
+    let _first =
+        ConnectOptions::new().name(name.borrow()).connect_sync().opname("first connect")?;
+
+    let start = Instant::now();
+    let e = ConnectOptions::new()
+        .name(name.borrow())
+        .connect_sync()
+        .err()
+        .ok_or_else(|| eyre!("connected while the only instance was busy"))?;
+    let waited = start.elapsed();
+    ensure_eq!(e.kind(), io::ErrorKind::TimedOut);
+    ensure!(
+        waited >= Duration::from_millis(u64::from(TIMEOUT / 2)),
+        "gave up after {waited:?}, much earlier than the server's wait timeout",
+    );
+    let _server = listener.accept().opname("accept")?;
+    Ok(())
+}
+
+#[test]
+fn local_socket_wait_timeout() -> TestResult { test_wrapper(test_wait_timeout) }
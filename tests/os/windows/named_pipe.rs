@@ -1,7 +1,12 @@
 #![cfg(windows)]
 
+mod buffer_size;
 mod bytes;
+mod connect_event;
+mod info;
 mod msg;
+mod overlapped;
+mod recycle;
 
 use {
     crate::{os::windows::named_pipe::PipeListenerOptions, tests::util::*},
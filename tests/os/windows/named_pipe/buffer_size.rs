@@ -0,0 +1,76 @@
+use {
+    crate::{
+        os::windows::named_pipe::{pipe_mode, DuplexPipeStream, PipeListenerOptions},
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::{path::Path, thread, time::Duration},
+};
+
+#[test]
+fn buffer_size_hints() -> TestResult {
+    test_wrapper(|| {
+        let (name, listener) = listen_and_pick_name(&mut namegen_named_pipe(make_id!()), |nm| {
+            PipeListenerOptions::new()
+                .path(Path::new(nm))
+                .input_buffer_size_hint(512)
+                .output_buffer_size_hint(512)
+                .create_duplex::<pipe_mode::Bytes>()
+        })?;
+        listener.set_buffer_size_hints(64 * 1024, 64 * 1024).opname("set buffer size hints")?;
+        ensure_eq!(listener.buffer_size_hints(), (64 * 1024, 64 * 1024));
+
+        // The instance that was waiting already keeps its buffers; the next one gets the new
+        // ones.
+        let accept = || {
+            let _client = DuplexPipeStream::<pipe_mode::Bytes>::connect_by_path(&*name)
+                .opname("client connect")?;
+            listener.accept().opname("accept")?.info().opname("query info")
+        };
+        let old = accept()?;
+        let new = accept()?;
+        ensure!(new.input_buffer_size > old.input_buffer_size, "{old:?} → {new:?}");
+        ensure!(new.output_buffer_size > old.output_buffer_size, "{old:?} → {new:?}");
+        Ok(())
+    })
+}
+
+#[test]
+fn accept_with_buffer_sizes() -> TestResult {
+    test_wrapper(|| {
+        let (name, listener) = listen_and_pick_name(&mut namegen_named_pipe(make_id!()), |nm| {
+            PipeListenerOptions::new()
+                .path(Path::new(nm))
+                .input_buffer_size_hint(512)
+                .output_buffer_size_hint(512)
+                .create_duplex::<pipe_mode::Bytes>()
+        })?;
+        let connect = || {
+            DuplexPipeStream::<pipe_mode::Bytes>::connect_by_path(&*name).opname("client connect")
+        };
+
+        // Occupy the regular instance first, so that the second client can only connect to the
+        // dedicated one.
+        let _regular_client = connect()?;
+        let dedicated = thread::scope(|scope| {
+            let server = scope.spawn(|| {
+                listener.accept_with_buffer_sizes(64 * 1024, 64 * 1024).opname("dedicated accept")
+            });
+            thread::sleep(Duration::from_millis(50));
+            let _dedicated_client = connect()?;
+            let stream = server.join().unwrap_or_else(|e| std::panic::resume_unwind(e))?;
+            stream.info().opname("query info")
+        })?;
+        let regular = listener.accept().opname("accept")?.info().opname("query info")?;
+        ensure!(
+            dedicated.input_buffer_size > regular.input_buffer_size,
+            "{regular:?} → {dedicated:?}"
+        );
+        ensure!(
+            dedicated.output_buffer_size > regular.output_buffer_size,
+            "{regular:?} → {dedicated:?}"
+        );
+        ensure_eq!(listener.buffer_size_hints(), (512, 512));
+        Ok(())
+    })
+}
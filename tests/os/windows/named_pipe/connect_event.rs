@@ -0,0 +1,39 @@
+use {
+    crate::{
+        os::windows::{
+            named_pipe::{pipe_mode, DuplexPipeStream, PipeListenerOptions},
+            AsRawHandleExt,
+        },
+        tests::util::*,
+    },
+    std::{os::windows::io::BorrowedHandle, path::Path},
+    windows_sys::Win32::{
+        Foundation::{WAIT_OBJECT_0, WAIT_TIMEOUT},
+        System::Threading::WaitForSingleObject,
+    },
+};
+
+fn wait(event: BorrowedHandle<'_>, ms: u32) -> u32 {
+    unsafe { WaitForSingleObject(event.as_int_handle(), ms) }
+}
+
+#[test]
+fn connect_event() -> TestResult {
+    test_wrapper(|| {
+        let (name, listener) = listen_and_pick_name(&mut namegen_named_pipe(make_id!()), |nm| {
+            PipeListenerOptions::new()
+                .path(Path::new(nm))
+                .create_duplex::<pipe_mode::Bytes>()
+        })?;
+        let event = listener.connect_event().opname("connect_event")?;
+        ensure_eq!(wait(event, 0), WAIT_TIMEOUT);
+
+        let _client = DuplexPipeStream::<pipe_mode::Bytes>::connect_by_path(&*name)
+            .opname("client connect")?;
+        ensure_eq!(wait(event, 5000), WAIT_OBJECT_0);
+        let _server = listener.accept().opname("accept")?;
+        // The event must be reset for the next instance, which nobody has connected to.
+        ensure_eq!(wait(event, 0), WAIT_TIMEOUT);
+        Ok(())
+    })
+}
@@ -0,0 +1,35 @@
+use {
+    crate::{
+        os::windows::named_pipe::{pipe_mode, DuplexPipeStream, PipeListenerOptions, PipeMode},
+        tests::util::*,
+    },
+    std::{num::NonZeroU8, path::Path},
+};
+
+#[test]
+fn pipe_info() -> TestResult {
+    test_wrapper(|| {
+        let (name, listener) = listen_and_pick_name(&mut namegen_named_pipe(make_id!()), |nm| {
+            PipeListenerOptions::new()
+                .path(Path::new(nm))
+                .mode(PipeMode::Messages)
+                .instance_limit(NonZeroU8::new(4))
+                .create_duplex::<pipe_mode::Messages>()
+        })?;
+        let client = DuplexPipeStream::<pipe_mode::Messages>::connect_by_path(&*name)
+            .opname("client connect")?;
+        let server = listener.accept().opname("accept")?;
+
+        let listener_info = listener.info().opname("listener info")?;
+        let server_info = server.info().opname("server info")?;
+        let client_info = client.info().opname("client info")?;
+        ensure_eq!(listener_info, server_info);
+        ensure_eq!(server_info.is_server, true);
+        ensure_eq!(server_info.mode, PipeMode::Messages);
+        ensure_eq!(server_info.instance_limit, NonZeroU8::new(4));
+        ensure_eq!(client_info.is_server, false);
+        ensure_eq!(client_info.mode, PipeMode::Messages);
+        ensure_eq!(client_info.instance_limit, server_info.instance_limit);
+        Ok(())
+    })
+}
@@ -0,0 +1,103 @@
+use {
+    crate::{
+        os::windows::named_pipe::{
+            overlapped::{self, CompletionPort, Operation},
+            PipeListenerOptions,
+        },
+        tests::util::*,
+    },
+    color_eyre::eyre::eyre,
+    std::{os::windows::io::AsHandle, path::Path, time::Duration},
+};
+
+const SERVER_KEY: usize = 1;
+const CLIENT_KEY: usize = 2;
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Drives a connection and an exchange of messages through the completion port.
+#[test]
+fn overlapped_completion_port() -> TestResult {
+    test_wrapper(|| {
+        let (name, server) = listen_and_pick_name(&mut namegen_named_pipe(make_id!()), |nm| {
+            overlapped::create_server(&PipeListenerOptions::new().path(Path::new(nm)), true)
+        })?;
+        let port = CompletionPort::new(0).opname("completion port creation")?;
+        port.associate(server.as_handle(), SERVER_KEY).opname("server association")?;
+
+        let connect = Operation::connect(server.as_handle()).opname("connect start")?;
+        let client = overlapped::connect(&*name).opname("client connect")?;
+        port.associate(client.as_handle(), CLIENT_KEY).opname("client association")?;
+        if let Some(connect) = connect {
+            let completion = port.wait(Some(TIMEOUT)).opname("wait for connect")?;
+            ensure_eq!(completion.key(), SERVER_KEY);
+            ensure_eq!(completion.operation(), connect.id());
+            connect.try_finish().map_err(|_| eyre!("connect still pending"))?.opname("connect")?;
+        }
+
+        let read = Operation::read(server.as_handle(), Vec::with_capacity(16)).opname("read")?;
+        let write = Operation::write(client.as_handle(), b"hello".to_vec()).opname("write")?;
+        let (mut read, mut write) = (Some(read), Some(write));
+        while read.is_some() || write.is_some() {
+            let completion = port.wait(Some(TIMEOUT)).opname("wait for transfer")?;
+            if let Some(op) = read.take_if_id(completion.operation()) {
+                let (buf, bytes) =
+                    op.try_finish().map_err(|_| eyre!("read still pending"))?.opname("read")?;
+                ensure_eq!(bytes, 5);
+                ensure_eq!(buf, b"hello");
+            } else if let Some(op) = write.take_if_id(completion.operation()) {
+                let (_, bytes) =
+                    op.try_finish().map_err(|_| eyre!("write still pending"))?.opname("write")?;
+                ensure_eq!(bytes, 5);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Waiting for one operation must not be satisfied by another one on the same handle finishing.
+#[test]
+fn overlapped_wait_per_operation() -> TestResult {
+    test_wrapper(|| {
+        let (name, server) = listen_and_pick_name(&mut namegen_named_pipe(make_id!()), |nm| {
+            overlapped::create_server(&PipeListenerOptions::new().path(Path::new(nm)), true)
+        })?;
+        let connect = Operation::connect(server.as_handle()).opname("connect start")?;
+        let client = overlapped::connect(&*name).opname("client connect")?;
+        if let Some(connect) = connect {
+            connect.wait().opname("connect")?;
+        }
+
+        // The client's read stays pending until the server replies, which it only does after
+        // receiving what the client writes in the meantime.
+        let reply = Operation::read(client.as_handle(), Vec::with_capacity(16)).opname("read")?;
+        Operation::write(client.as_handle(), b"ping".to_vec())
+            .opname("write")?
+            .wait()
+            .opname("write wait")?;
+        let (request, _) = Operation::read(server.as_handle(), Vec::with_capacity(16))
+            .opname("server read")?
+            .wait()
+            .opname("server read wait")?;
+        ensure_eq!(request, b"ping");
+        Operation::write(server.as_handle(), b"pong".to_vec())
+            .opname("server write")?
+            .wait()
+            .opname("server write wait")?;
+        let (reply, _) = reply.wait().opname("read wait")?;
+        ensure_eq!(reply, b"pong");
+        Ok(())
+    })
+}
+
+trait TakeIfId<'h> {
+    fn take_if_id(&mut self, id: overlapped::OperationId) -> Option<Operation<'h>>;
+}
+impl<'h> TakeIfId<'h> for Option<Operation<'h>> {
+    fn take_if_id(&mut self, id: overlapped::OperationId) -> Option<Operation<'h>> {
+        if self.as_ref().is_some_and(|op| op.id() == id) {
+            self.take()
+        } else {
+            None
+        }
+    }
+}
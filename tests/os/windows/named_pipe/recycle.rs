@@ -0,0 +1,42 @@
+use {
+    crate::{
+        os::windows::named_pipe::{pipe_mode, DuplexPipeStream, PipeListenerOptions},
+        tests::util::*,
+    },
+    std::{
+        io::{self, prelude::*},
+        num::NonZeroU8,
+        path::Path,
+    },
+};
+
+/// With room for only two instances, the listener can only keep accepting if it reuses the
+/// recycled one instead of creating a third.
+#[test]
+fn recycle() -> TestResult {
+    test_wrapper(|| {
+        let (name, listener) = listen_and_pick_name(&mut namegen_named_pipe(make_id!()), |nm| {
+            PipeListenerOptions::new()
+                .path(Path::new(nm))
+                .instance_limit(NonZeroU8::new(2))
+                .create_duplex::<pipe_mode::Bytes>()
+        })?;
+        for i in 0..4_u8 {
+            let mut client = DuplexPipeStream::<pipe_mode::Bytes>::connect_by_path(&*name)
+                .opname("client connect")?;
+            let server = listener.accept().opname("accept")?;
+            (&server).write_all(&[i]).opname("send")?;
+            let mut msg = [0; 1];
+            client.read_exact(&mut msg).opname("receive")?;
+            ensure_eq!(msg, [i]);
+            // Would wait for the client to receive the message if it hadn't already.
+            listener.recycle(server).opname("recycle")?;
+        }
+
+        let client = DuplexPipeStream::<pipe_mode::Bytes>::connect_by_path(&*name)
+            .opname("client connect")?;
+        let err = listener.recycle(client).err();
+        ensure_eq!(err.map(|e| e.kind()), Some(io::ErrorKind::InvalidInput));
+        Ok(())
+    })
+}
@@ -0,0 +1,60 @@
+use {
+    crate::{
+        os::windows::{
+            shared_memory::{FileMapping, FileMappingOptions},
+            AsRawHandleExt as _,
+        },
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::{io, os::windows::io::AsHandle},
+    windows_sys::Win32::Foundation::{GetHandleInformation, HANDLE_FLAG_INHERIT},
+};
+
+const SIZE: usize = 64 * 1024;
+
+fn test_named() -> TestResult {
+    let (name, creator) = listen_and_pick_name(&mut namegen_shm(make_id!()), |nm| {
+        FileMappingOptions::new().create_named(nm, SIZE)
+    })?;
+    ensure_eq!(creator.size(), SIZE);
+    let mut view = creator.map().opname("map")?;
+    // SAFETY: nobody else knows the name yet
+    unsafe { view.as_mut_slice() }.fill(0xAA);
+
+    let opened = FileMapping::open(name.as_ref()).opname("open")?;
+    ensure!(opened.size() >= SIZE);
+    let other_view = opened.map().opname("map opened")?;
+    // SAFETY: no writes happen through the other view while this slice exists
+    ensure!(unsafe { other_view.as_slice() }.iter().all(|&b| b == 0xAA));
+
+    let again = FileMappingOptions::new().create_named(name.as_ref(), SIZE);
+    ensure_eq!(again.map(|_| ()).map_err(|e| e.kind()), Err(io::ErrorKind::AlreadyExists));
+    Ok(())
+}
+
+fn test_anonymous_inheritable() -> TestResult {
+    for inheritable in [false, true] {
+        let mapping = FileMappingOptions::new()
+            .inheritable(inheritable)
+            .create_anonymous(SIZE)
+            .opname("create")?;
+        let mut flags = 0;
+        let ok = unsafe { GetHandleInformation(mapping.as_handle().as_int_handle(), &mut flags) };
+        if ok == 0 {
+            return Err(io::Error::last_os_error()).opname("GetHandleInformation");
+        }
+        ensure_eq!(flags & HANDLE_FLAG_INHERIT != 0, inheritable);
+        let view = mapping.map().opname("map")?;
+        // SAFETY: the object is not shared with anyone
+        ensure!(unsafe { view.as_slice() }.iter().all(|&b| b == 0));
+    }
+    Ok(())
+}
+
+#[test]
+fn shared_memory_named() -> TestResult { test_wrapper(test_named) }
+#[test]
+fn shared_memory_anonymous_inheritable() -> TestResult {
+    test_wrapper(test_anonymous_inheritable)
+}
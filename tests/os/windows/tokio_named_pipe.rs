@@ -1,6 +1,8 @@
-#![cfg(all(windows, feature = "tokio"))]
+#![cfg(all(windows, feature = "local-socket-tokio"))]
 
+mod blocking_strategy;
 mod bytes;
+mod parallel_accept;
 
 use {
     crate::{
@@ -40,6 +42,12 @@ matrix! {
     bytes stc    bytes_unidir_server_to_client
 }
 
+#[test]
+fn parallel_accept() -> TestResult { test_wrapper(parallel_accept::run(make_id!())) }
+
+#[test]
+fn blocking_strategy() -> TestResult { test_wrapper(blocking_strategy::run(make_id!())) }
+
 async fn drive_server<L: Debug, T: Future<Output = TestResult> + Send + 'static>(
     id: &str,
     name_sender: Sender<Arc<str>>,
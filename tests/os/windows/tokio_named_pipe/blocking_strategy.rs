@@ -0,0 +1,62 @@
+use {
+    crate::{
+        os::windows::named_pipe::{
+            pipe_mode,
+            tokio::{
+                set_blocking_strategy, BlockingStrategy, DuplexPipeStream, PipeListenerOptionsExt,
+            },
+            PipeListenerOptions,
+        },
+        tests::util::{listen_and_pick_name, namegen_named_pipe, TestResult, WrapErrExt},
+    },
+    color_eyre::eyre::{ensure, eyre},
+    std::{
+        num::NonZeroUsize,
+        path::Path,
+        sync::{
+            atomic::{AtomicUsize, Ordering::SeqCst},
+            Arc,
+        },
+    },
+    tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        task,
+    },
+};
+
+pub async fn run(id: &'static str) -> TestResult {
+    static RAN: AtomicUsize = AtomicUsize::new(0);
+    let BlockingStrategy::Executor(pool) =
+        BlockingStrategy::dedicated_pool(NonZeroUsize::new(4).unwrap()).opname("pool creation")?
+    else {
+        return Err(eyre!("dedicated pool is not an executor"));
+    };
+    set_blocking_strategy(BlockingStrategy::Executor(Arc::new(move |task| {
+        RAN.fetch_add(1, SeqCst);
+        pool(task)
+    })));
+    let rslt = flush_through_pool(id).await;
+    set_blocking_strategy(BlockingStrategy::default());
+    rslt?;
+    ensure!(RAN.load(SeqCst) > 0, "flush did not go through the dedicated pool");
+    Ok(())
+}
+
+async fn flush_through_pool(id: &'static str) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_named_pipe(id), |nm| {
+        PipeListenerOptions::new().path(Path::new(nm)).create_tokio_duplex::<pipe_mode::Bytes>()
+    })?;
+    let server = task::spawn(async move {
+        let mut conn = listener.accept().await.opname("accept")?;
+        let mut buf = [0; 4];
+        conn.read_exact(&mut buf).await.opname("receive")?;
+        TestResult::<[u8; 4]>::Ok(buf)
+    });
+
+    let mut client =
+        DuplexPipeStream::<pipe_mode::Bytes>::connect_by_path(&*name).await.opname("connect")?;
+    client.write_all(b"ping").await.opname("send")?;
+    client.flush().await.opname("flush")?;
+    ensure_eq!(server.await.opname("server task")??, *b"ping");
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use {
+    crate::{
+        os::windows::named_pipe::{
+            pipe_mode,
+            tokio::{DuplexPipeStream, PipeListenerOptionsExt},
+            PipeListenerOptions,
+        },
+        tests::util::{listen_and_pick_name, namegen_named_pipe, TestResult, WrapErrExt},
+    },
+    std::{num::NonZeroU8, path::Path, sync::Arc},
+    tokio::task,
+};
+
+const PARALLELISM: u8 = 4;
+
+pub async fn run(id: &'static str) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_named_pipe(id), |nm| {
+        PipeListenerOptions::new()
+            .path(Path::new(nm))
+            .accept_parallelism(NonZeroU8::new(PARALLELISM).unwrap())
+            .create_tokio_duplex::<pipe_mode::Bytes>()
+            .map(Arc::new)
+    })?;
+
+    // The first accept brings all of the instances into existence.
+    let first = task::spawn({
+        let listener = Arc::clone(&listener);
+        async move { listener.accept().await }
+    });
+    let mut clients = vec![DuplexPipeStream::<pipe_mode::Bytes>::connect_by_path(&*name)
+        .await
+        .opname("first connect")?];
+    first.await.opname("first accept task")?.opname("first accept")?;
+
+    // The remaining clients connect while nobody is accepting, each to an idle instance.
+    for _ in 1..PARALLELISM {
+        let client =
+            DuplexPipeStream::<pipe_mode::Bytes>::connect_by_path(&*name).await.opname("connect")?;
+        clients.push(client);
+    }
+    for _ in 1..PARALLELISM {
+        listener.accept().await.opname("accept")?;
+    }
+    Ok(())
+}
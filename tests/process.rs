@@ -0,0 +1,46 @@
+use {
+    crate::{
+        local_socket::Stream,
+        process::{spawn_with_channel, CHANNEL_ENV_VAR},
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::{
+        env,
+        io::{prelude::*, BufReader},
+        process::Command,
+    },
+};
+
+/// The test that the parent re-runs as the child process. When run as a regular test, without a
+/// channel to claim, it does nothing.
+#[test]
+fn spawn_with_channel_child() -> TestResult {
+    if env::var_os(CHANNEL_ENV_VAR).is_none() {
+        return Ok(());
+    }
+    let mut conn = BufReader::new(Stream::from_inherited().opname("claim")?);
+    ensure!(env::var_os(CHANNEL_ENV_VAR).is_none(), "channel variable left in the environment");
+    let mut line = String::new();
+    conn.read_line(&mut line).opname("receive")?;
+    ensure_eq!(line, "ping\n");
+    conn.get_mut().write_all(b"pong\n").opname("send")?;
+    Ok(())
+}
+
+#[test]
+fn spawn_with_channel_parent() -> TestResult {
+    test_wrapper(|| {
+        let mut cmd = Command::new(env::current_exe().opname("current_exe")?);
+        cmd.args(["tests::process::spawn_with_channel_child", "--exact", "--nocapture"]);
+        let (mut child, conn) = spawn_with_channel(cmd).opname("spawn")?;
+        let mut conn = BufReader::new(conn);
+        conn.get_mut().write_all(b"ping\n").opname("send")?;
+        let mut line = String::new();
+        conn.read_line(&mut line).opname("receive")?;
+        ensure_eq!(line, "pong\n");
+        let status = child.wait().opname("wait")?;
+        ensure!(status.success(), "child failed with {status}");
+        Ok(())
+    })
+}
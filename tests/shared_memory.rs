@@ -0,0 +1,84 @@
+use {
+    crate::{
+        shared_memory::{RingReceiver, RingSender, SharedMemory, TryRecvError},
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::io,
+};
+
+const SIZE: usize = 64 * 1024;
+
+fn test_create_and_open() -> TestResult {
+    let mut namegen = namegen_local_socket(make_id!(), false);
+    let (name, mut creator) = listen_and_pick_name(&mut namegen, |nm| {
+        SharedMemory::options().name(nm.borrow()).size(SIZE).create()
+    })?;
+    ensure_eq!(creator.len(), SIZE);
+    // SAFETY: nobody else has opened the object yet
+    unsafe { creator.as_mut_slice() }.fill(0x55);
+
+    let opened = SharedMemory::options().name(name.borrow()).open().opname("open")?;
+    ensure!(opened.len() >= SIZE);
+    // SAFETY: no writes happen through the creator while this slice exists
+    ensure!(unsafe { opened.as_slice() }.iter().take(SIZE).all(|&b| b == 0x55));
+
+    let partial = SharedMemory::options().name(name.borrow()).size(SIZE / 2).open();
+    ensure_eq!(partial.opname("open with size")?.len(), SIZE / 2);
+
+    let again = SharedMemory::options().name(name.borrow()).size(SIZE).create();
+    ensure_eq!(again.map(|_| ()).map_err(|e| e.kind()), Err(io::ErrorKind::AlreadyExists));
+    Ok(())
+}
+
+fn test_no_size() -> TestResult {
+    let name = namegen_local_socket(make_id!(), false).next().unwrap()?;
+    let rslt = SharedMemory::options().name(name.borrow()).create();
+    ensure_eq!(rslt.map(|_| ()).map_err(|e| e.kind()), Err(io::ErrorKind::InvalidInput));
+    Ok(())
+}
+
+fn test_ring() -> TestResult {
+    let mut namegen = namegen_local_socket(make_id!(), false);
+    let (name, mut sender) =
+        listen_and_pick_name(&mut namegen, |nm| RingSender::create(nm.borrow(), 16, 4))?;
+    let mut receiver = RingReceiver::open(name.borrow()).opname("open")?;
+    ensure_eq!(receiver.slot_size(), 16);
+    ensure_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+
+    sender.send(b"first").opname("send")?;
+    sender.send(b"second").opname("send")?;
+    ensure_eq!(receiver.try_recv().as_deref(), Ok(&b"first"[..]));
+    ensure_eq!(receiver.try_recv().as_deref(), Ok(&b"second"[..]));
+    ensure_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+
+    let too_long = sender.send(&[0; 17]).map_err(|e| e.kind());
+    ensure_eq!(too_long, Err(io::ErrorKind::InvalidInput));
+    Ok(())
+}
+
+fn test_ring_lagged() -> TestResult {
+    let mut namegen = namegen_local_socket(make_id!(), false);
+    let (name, mut sender) =
+        listen_and_pick_name(&mut namegen, |nm| RingSender::create(nm.borrow(), 8, 4))?;
+    let mut receiver = RingReceiver::open(name.borrow()).opname("open")?;
+    for i in 0..10_u8 {
+        sender.send(&[i]).opname("send")?;
+    }
+    ensure_eq!(receiver.lag(), 10);
+    ensure_eq!(receiver.try_recv(), Err(TryRecvError::Lagged(6)));
+    for i in 6..10_u8 {
+        ensure_eq!(receiver.try_recv(), Ok(vec![i]));
+    }
+    ensure_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    Ok(())
+}
+
+#[test]
+fn shared_memory_create_and_open() -> TestResult { test_wrapper(test_create_and_open) }
+#[test]
+fn shared_memory_no_size() -> TestResult { test_wrapper(test_no_size) }
+#[test]
+fn shared_memory_ring() -> TestResult { test_wrapper(test_ring) }
+#[test]
+fn shared_memory_ring_lagged() -> TestResult { test_wrapper(test_ring_lagged) }
@@ -0,0 +1,61 @@
+use {
+    crate::{
+        signal::{on_signal, Signal, Signals},
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::{
+        env,
+        process::Command,
+        sync::atomic::{AtomicBool, Ordering::SeqCst},
+        time::Duration,
+    },
+};
+
+/// Set in the environment of the child process that the parent re-runs the test in.
+const CHILD_ENV_VAR: &str = "INTERPROCESS_TEST_SIGNAL_CHILD";
+
+static CALLED: AtomicBool = AtomicBool::new(false);
+
+fn test_hangup() -> TestResult {
+    let signals = Signals::new().opname("subscription")?;
+    let other = Signals::new().opname("subscription")?;
+    on_signal(|signal| {
+        if signal == Signal::Hangup {
+            CALLED.store(true, SeqCst);
+        }
+    })
+    .opname("callback registration")?;
+    ensure_eq!(signals.try_recv(), None);
+
+    ensure_eq!(unsafe { libc::raise(libc::SIGHUP) }, 0);
+    let timeout = Duration::from_secs(5);
+    ensure_eq!(signals.recv_timeout(timeout), Some(Signal::Hangup));
+    ensure_eq!(other.recv_timeout(timeout), Some(Signal::Hangup));
+    ensure!(CALLED.load(SeqCst));
+    Ok(())
+}
+
+/// The test that the parent re-runs in a process of its own, since the handlers stay installed
+/// for the rest of the process's lifetime and would keep Ctrl+C from stopping the other tests.
+/// When run as a regular test, it does nothing.
+#[test]
+fn signal_hangup_child() -> TestResult {
+    if env::var_os(CHILD_ENV_VAR).is_none() {
+        return Ok(());
+    }
+    test_wrapper(test_hangup)
+}
+
+#[test]
+fn signal_hangup() -> TestResult {
+    test_wrapper(|| {
+        let status = Command::new(env::current_exe().opname("current_exe")?)
+            .args(["tests::signal::signal_hangup_child", "--exact", "--nocapture"])
+            .env(CHILD_ENV_VAR, "1")
+            .status()
+            .opname("run child")?;
+        ensure!(status.success(), "child failed with {status}");
+        Ok(())
+    })
+}
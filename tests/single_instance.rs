@@ -0,0 +1,109 @@
+use {
+    crate::{
+        local_socket::prelude::*,
+        single_instance::{Acquired, Guard},
+        tests::util::*,
+    },
+    color_eyre::eyre::{bail, ensure, eyre},
+    std::io::{prelude::*, BufReader},
+};
+
+fn test_forwarding(id: &'static str, path: bool) -> TestResult {
+    let mut namegen = namegen_local_socket(id, path);
+    let (name, guard) = loop {
+        let name = namegen.next().unwrap()?;
+        match Guard::acquire(name.borrow()).opname("first acquire")? {
+            Acquired::Primary(guard) => break (name, guard),
+            Acquired::Secondary(..) => continue,
+        }
+    };
+    let Acquired::Secondary(mut conn) = Guard::acquire(name.borrow()).opname("second acquire")?
+    else {
+        bail!("second instance became primary");
+    };
+    conn.write_all(b"forwarded\n").opname("send")?;
+    let mut line = String::new();
+    BufReader::new(guard.listener().accept().opname("accept")?)
+        .read_line(&mut line)
+        .opname("receive")?;
+    ensure_eq!(line, "forwarded\n");
+
+    drop(guard);
+    drop(conn);
+    ensure!(
+        matches!(Guard::acquire(name.borrow()).opname("reacquire")?, Acquired::Primary(..)),
+        "guard was not released on drop"
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn stale_file() -> TestResult {
+    test_wrapper(|| {
+        let name = namegen_local_socket(make_id!(), true).next().unwrap()?;
+        let guard = Guard::try_acquire(name.borrow()).opname("first acquire")?;
+        let mut listener = guard.ok_or_else(|| eyre!("name taken"))?.into_listener();
+        // Simulate a crash by leaving the socket file behind.
+        listener.do_not_reclaim_name_on_drop();
+        drop(listener);
+        ensure!(
+            crate::local_socket::Stream::connect(name.borrow()).is_err(),
+            "connecting to a stale name succeeded"
+        );
+        ensure!(
+            Guard::try_acquire(name.borrow()).opname("takeover")?.is_some(),
+            "stale name was not taken over"
+        );
+        Ok(())
+    })
+}
+
+#[cfg(unix)]
+#[test]
+fn stale_file_concurrent() -> TestResult {
+    use std::{
+        sync::{Arc, Barrier},
+        thread,
+    };
+    const INSTANCES: usize = 8;
+    test_wrapper(|| {
+        let name = namegen_local_socket(make_id!(), true).next().unwrap()?;
+        let guard = Guard::try_acquire(name.borrow()).opname("first acquire")?;
+        let mut listener = guard.ok_or_else(|| eyre!("name taken"))?.into_listener();
+        listener.do_not_reclaim_name_on_drop();
+        drop(listener);
+
+        let barrier = Arc::new(Barrier::new(INSTANCES));
+        let threads = (0..INSTANCES)
+            .map(|_| {
+                let (name, barrier) = (name.clone(), barrier.clone());
+                thread::spawn(move || {
+                    barrier.wait();
+                    Guard::acquire(name.borrow())
+                })
+            })
+            .collect::<Vec<_>>();
+        // Keep every outcome alive until all instances are done, so that a primary instance's
+        // socket file could not be mistaken for a stale one by a slower instance.
+        let mut outcomes = Vec::with_capacity(INSTANCES);
+        for thread in threads {
+            let acq = thread.join().map_err(|_| eyre!("instance panicked"))?;
+            outcomes.push(acq.opname("takeover")?);
+        }
+        let primaries = outcomes.iter().filter(|a| matches!(a, Acquired::Primary(..))).count();
+        ensure_eq!(primaries, 1);
+
+        if let crate::local_socket::NameInner::UdSocketPath(path) = &name.0 {
+            let mut lock_path = path.clone().into_owned();
+            lock_path.push(".lock");
+            let _ = std::fs::remove_file(lock_path);
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn forwarding_file() -> TestResult { test_wrapper(|| test_forwarding(make_id!(), true)) }
+#[test]
+fn forwarding_namespaced() -> TestResult { test_wrapper(|| test_forwarding(make_id!(), false)) }
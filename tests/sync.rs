@@ -0,0 +1,14 @@
+mod barrier;
+mod condvar;
+mod mutex;
+
+use crate::tests::util::*;
+
+#[test]
+fn mutex_lock() -> TestResult { test_wrapper(mutex::test_lock) }
+#[test]
+fn mutex_owner_died() -> TestResult { test_wrapper(mutex::test_owner_died) }
+#[test]
+fn condvar_notify() -> TestResult { test_wrapper(condvar::test_notify) }
+#[test]
+fn barrier_rounds() -> TestResult { test_wrapper(barrier::test_rounds) }
@@ -0,0 +1,45 @@
+use {
+    crate::{sync::ProcessBarrier, tests::util::*},
+    color_eyre::eyre::eyre,
+    std::{io, thread},
+};
+
+const PARTICIPANTS: u32 = 4;
+const ROUNDS: usize = 3;
+
+pub fn test_rounds() -> TestResult {
+    let mut namegen = namegen_local_socket(make_id!(), false);
+    let (name, barrier) = listen_and_pick_name(&mut namegen, |nm| {
+        ProcessBarrier::new(nm.borrow(), PARTICIPANTS)
+    })?;
+    ensure_eq!(barrier.participants(), PARTICIPANTS);
+
+    let mismatched = ProcessBarrier::new(name.borrow(), PARTICIPANTS + 1);
+    ensure_eq!(mismatched.map(|_| ()).map_err(|e| e.kind()), Err(io::ErrorKind::InvalidInput));
+
+    let others = (1..PARTICIPANTS)
+        .map(|_| ProcessBarrier::new(name.borrow(), PARTICIPANTS).opname("open"))
+        .collect::<TestResult<Vec<_>>>()?;
+    let leaders = thread::scope(|scope| {
+        let handles = others
+            .iter()
+            .map(|barrier| {
+                scope.spawn(move || {
+                    (0..ROUNDS).map(|_| barrier.wait()).collect::<io::Result<Vec<_>>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut leaders = vec![0; ROUNDS];
+        let mut tally = |results: Vec<bool>| {
+            leaders.iter_mut().zip(results).for_each(|(n, leader)| *n += usize::from(leader));
+        };
+        tally((0..ROUNDS).map(|_| barrier.wait()).collect::<io::Result<_>>().opname("wait")?);
+        for handle in handles {
+            let results = handle.join().map_err(|_| eyre!("participant thread panicked"))?;
+            tally(results.opname("wait")?);
+        }
+        TestResult::Ok(leaders)
+    })?;
+    ensure_eq!(leaders, vec![1; ROUNDS]);
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use {
+    super::mutex::create as create_mutex,
+    crate::{sync::Condvar, tests::util::*},
+    color_eyre::eyre::{ensure, eyre},
+    std::{
+        sync::atomic::{AtomicBool, Ordering::SeqCst},
+        thread,
+        time::Duration,
+    },
+};
+
+pub fn test_notify() -> TestResult {
+    let Some((mutex, opened_mutex)) = create_mutex(make_id!())? else { return Ok(()) };
+    let mut namegen = namegen_local_socket(make_id!(), false);
+    let (name, condvar) =
+        listen_and_pick_name(&mut namegen, |nm| Condvar::create(nm.borrow()))?;
+    let opened = Condvar::open(name.borrow()).opname("open")?;
+
+    let (guard, timed_out) = condvar
+        .wait_timeout(mutex.lock().opname("lock")?.into_guard(), Duration::from_millis(50))
+        .opname("wait_timeout")?;
+    ensure!(timed_out, "wait_timeout returned without a notification");
+    drop(guard);
+
+    let flag = AtomicBool::new(false);
+    let rslt = thread::scope(|scope| {
+        let waiter = scope.spawn(|| -> TestResult {
+            let mut guard = opened_mutex.lock().opname("lock in thread")?.into_guard();
+            while !flag.load(SeqCst) {
+                guard = opened.wait(guard).opname("wait")?.into_guard();
+            }
+            Ok(())
+        });
+        thread::sleep(Duration::from_millis(50));
+        {
+            let _guard = mutex.lock().opname("lock")?;
+            flag.store(true, SeqCst);
+        }
+        condvar.notify_one().opname("notify_one")?;
+        waiter.join().map_err(|_| eyre!("waiter panicked"))?
+    });
+    rslt?;
+    condvar.notify_all().opname("notify_all without waiters")?;
+    Ok(())
+}
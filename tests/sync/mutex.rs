@@ -0,0 +1,68 @@
+use {
+    crate::{
+        sync::{LockResult, Mutex},
+        tests::util::*,
+    },
+    color_eyre::eyre::{bail, ensure, eyre},
+    std::{io, mem, thread, time::Duration},
+};
+
+/// Creates a mutex under a fresh name, or returns `None` if the platform doesn't support them.
+pub fn create(id: &'static str) -> TestResult<Option<(Mutex, Mutex)>> {
+    let rslt = listen_and_pick_name(&mut namegen_local_socket(id, false), |nm| {
+        Mutex::create(nm.borrow())
+    });
+    let (name, mutex) = match rslt {
+        Ok(ok) => ok,
+        Err(e) if e.downcast_ref::<io::Error>().map(io::Error::kind)
+            == Some(io::ErrorKind::Unsupported) =>
+        {
+            eprintln!("Robust mutexes are not supported, skipping");
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+    let opened = Mutex::open(name.borrow()).opname("open")?;
+    Ok(Some((mutex, opened)))
+}
+
+pub fn test_lock() -> TestResult {
+    let Some((mutex, opened)) = create(make_id!())? else { return Ok(()) };
+    let LockResult::Acquired(guard) = mutex.lock().opname("lock")? else {
+        bail!("fresh mutex reported a dead owner");
+    };
+    let rslt = thread::scope(|scope| {
+        scope
+            .spawn(|| -> TestResult {
+                ensure!(opened.try_lock().opname("try_lock")?.is_none());
+                let timeout = Duration::from_millis(50);
+                ensure!(opened.lock_timeout(timeout).opname("lock_timeout")?.is_none());
+                Ok(())
+            })
+            .join()
+    });
+    rslt.map_err(|_| eyre!("thread panicked"))??;
+    drop(guard);
+    let relocked = opened.try_lock().opname("try_lock after unlock")?;
+    ensure!(matches!(relocked, Some(LockResult::Acquired(..))));
+    Ok(())
+}
+
+pub fn test_owner_died() -> TestResult {
+    let Some((mutex, opened)) = create(make_id!())? else { return Ok(()) };
+    let rslt = thread::scope(|scope| {
+        scope
+            .spawn(|| -> TestResult {
+                // Exiting the thread while holding the lock is as good as dying.
+                mem::forget(opened.lock().opname("lock in thread")?);
+                Ok(())
+            })
+            .join()
+    });
+    rslt.map_err(|_| eyre!("thread panicked"))??;
+    let rslt = mutex.lock().opname("lock after owner died")?;
+    ensure!(rslt.owner_died());
+    drop(rslt);
+    ensure!(matches!(mutex.lock().opname("lock after recovery")?, LockResult::Acquired(..)));
+    Ok(())
+}
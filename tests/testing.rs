@@ -0,0 +1,50 @@
+#![cfg(feature = "test-util")]
+
+use {
+    crate::{
+        local_socket::{prelude::*, Stream},
+        testing::TestServer,
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::io::prelude::*,
+};
+
+#[test]
+fn echo() -> TestResult {
+    test_wrapper(|| {
+        let server = TestServer::echo().opname("spawn")?;
+        let mut conns = (0..2).map(|_| server.connect()).collect::<Result<Vec<_>, _>>()?;
+        for (i, conn) in conns.iter_mut().enumerate() {
+            let msg = format!("message {i}");
+            conn.write_all(msg.as_bytes()).opname("send")?;
+            let mut buf = vec![0; msg.len()];
+            conn.read_exact(&mut buf).opname("receive")?;
+            ensure_eq!(buf, msg.as_bytes());
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn greeter() -> TestResult {
+    test_wrapper(|| {
+        let server = TestServer::greeter("hello").opname("spawn")?;
+        let mut conn = server.connect().opname("connect")?;
+        let mut buf = [0; 5];
+        conn.read_exact(&mut buf).opname("receive greeting")?;
+        ensure_eq!(&buf, b"hello");
+        Ok(())
+    })
+}
+
+#[test]
+fn shuts_down_on_drop() -> TestResult {
+    test_wrapper(|| {
+        let server = TestServer::echo().opname("spawn")?;
+        let name = server.name().into_owned();
+        drop(server);
+        ensure!(Stream::connect(name).is_err(), "server still accepting after being dropped");
+        Ok(())
+    })
+}
@@ -1,7 +1,15 @@
 // TODO(2.3.0) test various error conditions
 
+mod cancel;
+mod close;
+mod deadline;
+mod drain;
+mod framing;
+mod incoming;
 mod no_server;
+mod ready;
 mod stream;
+mod try_io;
 
 use {
     crate::{
@@ -60,3 +68,51 @@ matrix! {
 fn no_server_file() -> TestResult { test_wrapper(no_server::run_and_verify_error(true)) }
 #[test]
 fn no_server_namespaced() -> TestResult { test_wrapper(no_server::run_and_verify_error(false)) }
+#[test]
+fn incoming_file() -> TestResult {
+    test_wrapper(incoming::run_and_verify(make_id!(), true, None))
+}
+#[test]
+fn incoming_namespaced() -> TestResult {
+    test_wrapper(incoming::run_and_verify(make_id!(), false, None))
+}
+#[test]
+fn incoming_unreachable_limit() -> TestResult {
+    test_wrapper(incoming::run_and_verify(make_id!(), false, Some(std::num::NonZeroU32::MAX)))
+}
+#[test]
+fn drain_file() -> TestResult { test_wrapper(drain::run_and_verify(make_id!(), true)) }
+#[test]
+fn drain_namespaced() -> TestResult { test_wrapper(drain::run_and_verify(make_id!(), false)) }
+#[test]
+fn drain_expiry() -> TestResult {
+    test_wrapper(drain::run_and_verify_expiry(make_id!(), false))
+}
+#[test]
+fn framing_file() -> TestResult { test_wrapper(framing::run_and_verify(make_id!(), true)) }
+#[test]
+fn framing_namespaced() -> TestResult {
+    test_wrapper(framing::run_and_verify(make_id!(), false))
+}
+#[test]
+fn cancel_file() -> TestResult { test_wrapper(cancel::run_and_verify(make_id!(), true)) }
+#[test]
+fn cancel_namespaced() -> TestResult { test_wrapper(cancel::run_and_verify(make_id!(), false)) }
+#[test]
+fn deadline_file() -> TestResult { test_wrapper(deadline::run_and_verify(make_id!(), true)) }
+#[test]
+fn deadline_namespaced() -> TestResult {
+    test_wrapper(deadline::run_and_verify(make_id!(), false))
+}
+#[test]
+fn close_file() -> TestResult { test_wrapper(close::run_and_verify(make_id!(), true)) }
+#[test]
+fn close_namespaced() -> TestResult { test_wrapper(close::run_and_verify(make_id!(), false)) }
+#[test]
+fn ready_file() -> TestResult { test_wrapper(ready::run_and_verify(make_id!(), true)) }
+#[test]
+fn ready_namespaced() -> TestResult { test_wrapper(ready::run_and_verify(make_id!(), false)) }
+#[test]
+fn try_io_file() -> TestResult { test_wrapper(try_io::run_and_verify(make_id!(), true)) }
+#[test]
+fn try_io_namespaced() -> TestResult { test_wrapper(try_io::run_and_verify(make_id!(), false)) }
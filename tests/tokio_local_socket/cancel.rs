@@ -0,0 +1,38 @@
+use {
+    crate::{
+        error::Cancelled,
+        local_socket::{
+            tokio::{prelude::*, Stream},
+            ListenerOptions,
+        },
+        tests::util::*,
+    },
+    color_eyre::eyre::{bail, ensure},
+    std::{future, time::Duration},
+    tokio::time,
+};
+
+pub async fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_tokio()
+    })?;
+
+    let err = match listener.accept_cancellable(time::sleep(Duration::from_millis(20))).await {
+        Ok(..) => bail!("accept succeeded without a client"),
+        Err(e) => e,
+    };
+    ensure!(Cancelled::is(&err), "expected cancellation, received '{err}'");
+
+    // A connection made after a cancelled accept is picked up by the next one.
+    let _client = Stream::connect_cancellable(name.borrow(), future::pending::<()>())
+        .await
+        .opname("client connect")?;
+    listener.accept_cancellable(future::pending::<()>()).await.opname("accept")?;
+
+    let err = match Stream::connect_cancellable(name.borrow(), future::ready(())).await {
+        Ok(..) => bail!("connect succeeded despite being cancelled upfront"),
+        Err(e) => e,
+    };
+    ensure!(Cancelled::is(&err), "expected cancellation, received '{err}'");
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use {
+    crate::{
+        local_socket::{
+            tokio::{prelude::*, Stream},
+            ListenerOptions,
+        },
+        tests::util::*,
+    },
+    tokio::io::{AsyncReadExt, AsyncWriteExt},
+};
+
+const LEN: usize = 256 * 1024;
+
+pub async fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_tokio()
+    })?;
+
+    let server = async {
+        let mut conn = listener.accept().await.opname("accept")?;
+        let mut received = Vec::with_capacity(LEN);
+        conn.read_to_end(&mut received).await.opname("receive")?;
+        ensure_eq!(received.len(), LEN);
+        TestResult::Ok(())
+    };
+    let client = async {
+        let mut client = Stream::connect(name.borrow()).await.opname("connect")?;
+        client.write_all(&vec![0xa5; LEN]).await.opname("send")?;
+        client.close().await.opname("close")?;
+        TestResult::Ok(())
+    };
+    tokio::try_join!(server, client)?;
+    Ok(())
+}
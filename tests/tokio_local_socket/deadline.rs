@@ -0,0 +1,40 @@
+use {
+    crate::{
+        local_socket::{
+            tokio::{prelude::*, Stream},
+            ListenerOptions,
+        },
+        tests::util::*,
+    },
+    color_eyre::eyre::{bail, ensure},
+    std::{
+        io,
+        time::{Duration, Instant},
+    },
+    tokio::io::{AsyncReadExt, AsyncWriteExt},
+};
+
+pub async fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_tokio()
+    })?;
+    let mut client = Stream::connect(name.borrow()).await.opname("client connect")?;
+    let mut conn = listener.accept().await.opname("accept")?;
+
+    let deadline = Instant::now() + Duration::from_millis(20);
+    client.set_read_deadline(Some(deadline));
+    ensure_eq!(client.read_deadline(), Some(deadline));
+    let mut buf = [0; 1];
+    let err = match client.read(&mut buf).await {
+        Ok(..) => bail!("receive succeeded without anything having been sent"),
+        Err(e) => e,
+    };
+    ensure!(err.kind() == io::ErrorKind::TimedOut, "expected timeout, received '{err}'");
+    ensure!(Instant::now() >= deadline, "receive timed out before the deadline");
+
+    client.set_read_deadline(None);
+    conn.write_all(b"x").await.opname("send")?;
+    client.read_exact(&mut buf).await.opname("receive")?;
+    ensure_eq!(buf, *b"x");
+    Ok(())
+}
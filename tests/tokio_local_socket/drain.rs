@@ -0,0 +1,45 @@
+use {
+    crate::{
+        local_socket::{
+            tokio::{prelude::*, Stream},
+            ListenerOptions,
+        },
+        tests::util::*,
+    },
+    std::time::Duration,
+    tokio::{task, time},
+};
+
+pub async fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_tokio()
+    })?;
+    let listener = listener.tracking();
+
+    let _client1 = Stream::connect(name.borrow()).await.opname("client connect")?;
+    let conn1 = listener.accept().await.opname("accept")?;
+    let _client2 = Stream::connect(name.borrow()).await.opname("client connect")?;
+    let conn2 = listener.accept().await.opname("accept")?;
+    ensure_eq!(listener.open_connections(), 2);
+    drop(conn1);
+    ensure_eq!(listener.open_connections(), 1);
+
+    let closer = task::spawn(async move {
+        time::sleep(Duration::from_millis(20)).await;
+        drop(conn2);
+    });
+    ensure_eq!(listener.drain(Duration::from_secs(5)).await, 0);
+    closer.await.opname("closer task")?;
+    Ok(())
+}
+
+pub async fn run_and_verify_expiry(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_tokio()
+    })?;
+    let listener = listener.tracking();
+    let _client = Stream::connect(name.borrow()).await.opname("client connect")?;
+    let _conn = listener.accept().await.opname("accept")?;
+    ensure_eq!(listener.drain(Duration::from_millis(20)).await, 1);
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use {
+    crate::{
+        local_socket::{
+            tokio::{prelude::*, Stream},
+            ListenerOptions,
+        },
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    std::num::NonZeroU32,
+    tokio::task,
+};
+
+const QUEUE_DEPTH: usize = 2;
+// One more than fits in the queue. Any more than that and the clients on Windows would time out,
+// since the listener only has one spare instance of the pipe for them to connect to.
+const NUM_CLIENTS: usize = QUEUE_DEPTH + 1;
+
+pub async fn run_and_verify(
+    id: &'static str,
+    path: bool,
+    limit: Option<NonZeroU32>,
+) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_tokio()
+    })?;
+    let mut incoming = listener.incoming_with(limit, QUEUE_DEPTH);
+
+    let mut clients = Vec::with_capacity(NUM_CLIENTS);
+    for _ in 0..NUM_CLIENTS {
+        clients.push(Stream::connect(name.borrow()).await.opname("client connect")?);
+    }
+    // Let the accept loop run until it fills the queue.
+    for _ in 0..NUM_CLIENTS {
+        task::yield_now().await;
+    }
+    ensure!(incoming.queued() <= QUEUE_DEPTH, "accept loop overfilled the queue");
+
+    for _ in 0..NUM_CLIENTS {
+        incoming.next().await.opname("accept")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use {
+    crate::{
+        local_socket::{
+            tokio::{prelude::*, Stream},
+            ListenerOptions,
+        },
+        tests::util::*,
+    },
+    color_eyre::eyre::ensure,
+    tokio::io::{AsyncReadExt, AsyncWriteExt, Interest},
+};
+
+const MSG: &[u8] = b"ready when you are\n";
+
+pub async fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_tokio()
+    })?;
+
+    let server = async {
+        let mut conn = listener.accept().await.opname("accept")?;
+        conn.writable().await.opname("wait for writability")?;
+        conn.write_all(MSG).await.opname("send")?;
+        conn.flush().await.opname("flush")?;
+        TestResult::Ok(())
+    };
+    let client = async {
+        let mut client = Stream::connect(name.borrow()).await.opname("connect")?;
+        let ready = client.ready(Interest::READABLE).await.opname("wait for readiness")?;
+        ensure!(ready.is_readable() || ready.is_read_closed(), "not readable: {ready:?}");
+        client.readable().await.opname("wait for readability")?;
+        let mut received = vec![0; MSG.len()];
+        client.read_exact(&mut received).await.opname("receive")?;
+        ensure_eq!(received, MSG);
+        TestResult::Ok(())
+    };
+    tokio::try_join!(server, client)?;
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use {
+    crate::{
+        local_socket::{
+            tokio::{prelude::*, Stream},
+            ListenerOptions,
+        },
+        tests::util::*,
+    },
+    std::io::{self, IoSliceMut},
+    tokio::io::AsyncWriteExt,
+};
+
+const LEN: usize = 64 * 1024;
+
+pub async fn run_and_verify(id: &'static str, path: bool) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen_local_socket(id, path), |nm| {
+        ListenerOptions::new().name(nm.borrow()).create_tokio()
+    })?;
+
+    let server = async {
+        let mut conn = listener.accept().await.opname("accept")?;
+        let msg = vec![0xa5; LEN];
+        let mut sent = 0;
+        while let Some(rem) = msg.get(sent..).filter(|rem| !rem.is_empty()) {
+            conn.writable().await.opname("wait for writability")?;
+            match conn.try_write(rem) {
+                Ok(n) => sent += n,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e).opname("send"),
+            }
+        }
+        conn.flush().await.opname("flush")?;
+        TestResult::Ok(())
+    };
+    let client = async {
+        let client = Stream::connect(name.borrow()).await.opname("connect")?;
+        let (mut first, mut second) = ([0; 1024], [0; 1024]);
+        let mut received = 0;
+        loop {
+            client.readable().await.opname("wait for readability")?;
+            let rslt = if received % 2 == 0 {
+                client.try_read(&mut first)
+            } else {
+                let mut bufs = [IoSliceMut::new(&mut first), IoSliceMut::new(&mut second)];
+                client.try_read_vectored(&mut bufs)
+            };
+            match rslt {
+                Ok(0) => break,
+                Ok(n) => received += n,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e).opname("receive"),
+            }
+        }
+        ensure_eq!(received, LEN);
+        TestResult::Ok(())
+    };
+    tokio::try_join!(server, client)?;
+    Ok(())
+}
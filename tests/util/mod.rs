@@ -14,7 +14,7 @@ mod xorshift;
 #[allow(unused_imports)]
 pub use {drive::*, eyre::*, namegen::*, xorshift::*};
 
-#[cfg(feature = "tokio")]
+#[cfg(feature = "local-socket-tokio")]
 pub mod tokio;
 
 use {
@@ -66,7 +66,7 @@ pub fn listen_and_pick_name<L: Debug, N: Debug + ?Sized, F: FnMut(u32) -> NameRe
             };
             let l = match bindfn(&nm) {
                 Ok(l) => l,
-                Err(e) if matches!(e.kind(), AddrInUse | PermissionDenied) => {
+                Err(e) if matches!(e.kind(), AddrInUse | AlreadyExists | PermissionDenied) => {
                     eprintln!("\"{}\", skipping", e.kind());
                     return None;
                 }
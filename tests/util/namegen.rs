@@ -44,6 +44,18 @@ pub fn namegen_named_pipe(id: &str) -> NameGen<str, impl FnMut(u32) -> NameResul
     NameGen::new(id, move |rn| Ok(windows_path(rn).into()))
 }
 
+/// Names for named shared memory objects, in the format expected by the platform-specific APIs.
+pub fn namegen_shm(id: &str) -> NameGen<str, impl FnMut(u32) -> NameResult<str>> {
+    NameGen::new(id, move |rn| {
+        Ok(if cfg!(windows) {
+            format!(r"Local\interprocess-test-{rn:08x}")
+        } else {
+            format!("/interprocess-test-{rn:08x}")
+        }
+        .into())
+    })
+}
+
 fn windows_path(rn: u32) -> String { format!(r"\\.\pipe\interprocess-test-{rn:08x}") }
 fn unix_path(rn: u32) -> String {
     let tmpdir = std::env::var("TMPDIR").ok();